@@ -13,6 +13,7 @@ module.exports = {
   extraResources: [
     { from: 'resources/client', to: 'resources/client' },
     { from: 'resources/discord', to: 'discord' },
+    { from: 'resources/curseforge', to: 'curseforge' },
     { from: 'resources/icons/icon.png', to: 'icon.png' }
   ],
   asar: true,
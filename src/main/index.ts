@@ -2,20 +2,38 @@ import { app, BrowserWindow, clipboard, dialog, ipcMain, screen, shell } from 'e
 import { execFile } from 'node:child_process'
 import path from 'node:path'
 import { promisify } from 'node:util'
-import type { LoaderType } from './types'
+import type { CacheKind, LoaderType, LogLevel } from './types'
 import { store } from './services/store'
 import { login, logout, restore } from './services/account'
-import { createInstance, deleteInstance, getInstance, openInstanceFolder, updateInstance, copyLocalMod } from './services/instances'
-import { getLoaderVersions, getMinecraftVersions } from './services/versions'
-import { installMod, installModpack, installPack, listMods, listPacks, removeMod, removePack, searchContent, setModEnabled, setPackEnabled, updateAllMods, updateMod } from './services/modrinth'
-import { CLIENT_VERSION, launchInstance, openLaunchConsole } from './services/launcher'
+import { archiveInstance, clearCustomVersionJson, copyInstanceConfiguration, createInstance, deleteInstance, getInstance, getInstanceNotes, getInstanceStats, installVersionProfileFromUrl, listVersionProfiles, openInstanceFolder, selectVersionProfile, setCustomVersionJson, setInstanceNotes, raiseInstanceMemory, setInstanceUnstable, setProcessPriority, setRestartOnCrash, setSharedFolder, setVersionTracking, setWorldBackupPolicy, unarchiveInstance, updateInstance, importLocalMod } from './services/instances'
+import { getLoaderVersions, getMinecraftVersions, getVersionPatchNotes } from './services/versions'
+import { getNewsItems } from './services/news'
+import { getInstanceDiskUsage } from './services/storage'
+import { cleanupGameFiles } from './services/cleanup'
+import { clearCache, getCacheSize } from './services/cache'
+import { verifyInstance } from './services/repair'
+import { detectCurseForgeInstances, detectVanillaInstallation, exportInstancesToVanilla, firstRunVanillaImport, importCurseForgeInstance, importMultiMcInstance, importVanillaInstallation, importVanillaProfiles } from './services/importers'
+import { exportModList, exportModpack, getProjectDetails, importLocalPack, importModList, updateModpack, importModpackFile, installCollection, installMod, installModpack, installPack, listMods, listPacks, popularContent, removeMod, removePack, resolveInstallPlan, rollbackModUpdates, searchContent, setModEnabled, setPackEnabled, shaderLoaderRequirement, updateInstanceMods, updateMod } from './services/modrinth'
+import { CLIENT_VERSION, assertInstanceIdle, cancelLaunch, getConsoleLines, launchInstance, listRunningGames, openLaunchConsole, prepareInstance, stopGame } from './services/launcher'
+import { listLaunchLogs, readLaunchLog } from './services/launchLogs'
+import { searchLog } from './services/logSearch'
+import { cancelBisect, getBisect, startBisect } from './services/bisect'
+import { applyModSet, deleteModSet, listModSets, saveModSet } from './services/modSets'
 import { getProfileData, switchCape, updateSkin } from './services/profile'
-import { deleteWorld, downloadWorldZip, importWorldZip, listWorlds, worldFolder } from './services/worlds'
+import { backupWorld, deleteWorld, deleteWorldBackup, downloadWorldZip, importWorldZip, listWorldBackups, listWorlds, restoreWorldBackup, worldFolder } from './services/worlds'
 import { resourcePacksDirectory, shaderPacksDirectory } from './services/paths'
+import { copyScreenshot, listScreenshots, screenshotFile, screenshotsFolder, uploadScreenshot } from './services/screenshots'
 import { getPartnerServerStatus } from './services/servers'
 import { checkForUpdates, configureAutomaticUpdates, installReadyUpdate, notifyWindowFocused, setupUpdater, updaterState } from './services/updater'
 import { configureDiscordActivity, isDiscordActivityConfigured, showLauncherActivity, shutdownDiscordActivity } from './services/discordActivity'
 import { cleanupStaleProtectedClientArtifacts } from './services/clientPayload'
+import { LOG_LEVELS, logFile, logger, setLogLevel } from './services/logger'
+import { startBackgroundPreparation } from './services/backgroundPrepare'
+import { linkModrinthAccount, modrinthAccount, modrinthLibrary, restoreModrinthAccount, setProjectFollowed, unlinkModrinthAccount } from './services/modrinthAccount'
+import { checkContentUpdates, contentUpdateSummary, recheckInstanceUpdates, setContentUpdateSender, startContentUpdateChecks } from './services/contentUpdates'
+import { answerMeteredPrompt, setMeteredPromptSender } from './services/metered'
+import { connectivityStatus, probeConnectivity, setConnectivitySender, setOfflineMode } from './services/connectivity'
+import { listDownloads, pauseDownloads, resumeDownloads, setDownloadConcurrency, setDownloadEventSender, setDownloadSpeedLimit } from './services/downloads'
 
 const execFileAsync = promisify(execFile)
 
@@ -79,7 +97,7 @@ function loadRenderer(window: BrowserWindow, view: 'main' | 'splash'): void {
     : window.loadFile(path.join(__dirname, '../renderer/index.html'), { query: { view } })
 
   void loading.catch((error) => {
-    console.error(`[MegaClient] Failed to open the ${view} interface.`, error)
+    logger.error(`Failed to open the ${view} interface.`, { error })
   })
 }
 
@@ -168,21 +186,21 @@ function configureRendererWindow(window: BrowserWindow): void {
   window.setMenuBarVisibility(false)
   window.webContents.setWindowOpenHandler(() => ({ action: 'deny' }))
   window.webContents.on('preload-error', (_event, preloadPath, error) => {
-    console.error(`[MegaClient] Preload failed: ${preloadPath}`, error)
+    logger.error(`Preload failed: ${preloadPath}`, { error })
   })
   window.webContents.on('did-fail-load', (_event, code, description, validatedUrl) => {
-    console.error(`[MegaClient] Renderer failed to load (${code}): ${description} — ${validatedUrl}`)
+    logger.error(`Renderer failed to load (${code}): ${description} — ${validatedUrl}`)
   })
   window.on('unresponsive', () => {
-    console.warn('[MegaClient] Renderer became unresponsive.')
+    logger.warn('Renderer became unresponsive.')
     scheduleUnresponsiveRecovery(window)
   })
   window.on('responsive', () => {
     clearUnresponsiveTimer()
-    console.info('[MegaClient] Renderer is responsive again.')
+    logger.info('Renderer is responsive again.')
   })
   window.webContents.on('render-process-gone', (_event, details) => {
-    console.error('[MegaClient] Renderer process ended unexpectedly.', details)
+    logger.error('Renderer process ended unexpectedly.', { details })
     if (window !== mainWindow || window.isDestroyed() || details.reason === 'clean-exit') return
     if (rendererRecoveryAttempts >= 2) {
       void dialog.showMessageBox({
@@ -205,7 +223,7 @@ function configureRendererWindow(window: BrowserWindow): void {
     window.webContents.on('console-message', (_event, level, message, line, sourceId) => {
       const labels = ['verbose', 'info', 'warning', 'error']
       if (!message.includes('Download the React DevTools')) {
-        console.log(`[MegaClient renderer:${labels[level] ?? level}] ${message} (${sourceId}:${line})`)
+        logger.debug(message, { source: 'renderer', level: labels[level] ?? level, location: `${sourceId}:${line}` })
       }
     })
   }
@@ -360,6 +378,22 @@ function sendUpdate(payload: unknown): void {
   if (mainWindow && !mainWindow.isDestroyed()) mainWindow.webContents.send('updates:event', payload)
 }
 
+function sendDownloadEvent(payload: unknown): void {
+  if (mainWindow && !mainWindow.isDestroyed()) mainWindow.webContents.send('downloads:event', payload)
+}
+
+function sendConnectivity(payload: unknown): void {
+  if (mainWindow && !mainWindow.isDestroyed()) mainWindow.webContents.send('network:status', payload)
+}
+
+function sendMeteredPrompt(payload: unknown): void {
+  if (mainWindow && !mainWindow.isDestroyed()) mainWindow.webContents.send('network:metered-prompt', payload)
+}
+
+function sendContentUpdates(payload: unknown): void {
+  if (mainWindow && !mainWindow.isDestroyed()) mainWindow.webContents.send('updates:content', payload)
+}
+
 function registerIpc(): void {
   ipcMain.handle('window:minimize', () => requireWindow().minimize())
   ipcMain.handle('window:maximize', () => requireWindow().isMaximized() ? requireWindow().unmaximize() : requireWindow().maximize())
@@ -398,11 +432,17 @@ function registerIpc(): void {
       packaged: app.isPackaged
     }
   })
-  ipcMain.handle('versions:minecraft', (_event, snapshots: boolean) => getMinecraftVersions(Boolean(snapshots)))
+  ipcMain.handle('versions:minecraft', (_event, snapshots: boolean, legacy = false) => getMinecraftVersions(Boolean(snapshots), Boolean(legacy)))
+  ipcMain.handle('versions:patch-notes', (_event, version: string) => getVersionPatchNotes(String(version)))
+  ipcMain.handle('news:list', () => getNewsItems())
   ipcMain.handle('versions:loader', (_event, loader: LoaderType, version: string) => getLoaderVersions(loader, version))
   ipcMain.handle('settings:update', async (_event, patch) => {
     const before = store.getData().settings
     const next = await store.updateSettings(patch)
+    setLogLevel(next.logLevel)
+    setDownloadSpeedLimit(next.downloadSpeedLimit)
+    setDownloadConcurrency(next.downloadConcurrency)
+    setOfflineMode(next.offlineMode)
     if (next.checkUpdates !== before.checkUpdates) configureAutomaticUpdates(next.checkUpdates)
     if (next.discordActivity !== before.discordActivity) {
       await configureDiscordActivity(next.discordActivity)
@@ -413,6 +453,12 @@ function registerIpc(): void {
     }
     return next
   })
+  ipcMain.handle('logging:set-level', async (_event, level: LogLevel) => {
+    if (!LOG_LEVELS.includes(level)) throw new Error('Choose error, warn, info or debug.')
+    setLogLevel(level)
+    return (await store.updateSettings({ logLevel: level })).logLevel
+  })
+  ipcMain.handle('logging:open-file', () => shell.showItemInFolder(logFile()))
 
   ipcMain.handle('account:login', () => login(requireWindow()))
   ipcMain.handle('account:logout', () => logout())
@@ -430,24 +476,141 @@ function registerIpc(): void {
   ipcMain.handle('account:set-cape', (_event, capeId?: string) => switchCape(requireWindow(), capeId))
 
   ipcMain.handle('instances:create', (_event, input) => createInstance(input))
+  ipcMain.handle('instances:choose-linked-directory', async () => {
+    const result = await dialog.showOpenDialog(requireWindow(), {
+      title: 'Choose an existing Minecraft folder',
+      buttonLabel: 'Use this folder',
+      properties: ['openDirectory', 'dontAddToRecent']
+    })
+    return result.canceled ? null : result.filePaths[0] ?? null
+  })
   ipcMain.handle('instances:update', (_event, id: string, patch) => updateInstance(id, patch))
-  ipcMain.handle('instances:delete', (_event, id: string) => deleteInstance(id))
+  ipcMain.handle('instances:delete', (_event, id: string) => {
+    assertInstanceIdle(id)
+    return deleteInstance(id)
+  })
   ipcMain.handle('instances:select', async (_event, id: string) => {
     await store.selectInstance(id)
     showLauncherActivity(getInstance(id))
   })
+  ipcMain.handle('instances:get-notes', (_event, id: string) => getInstanceNotes(id))
+  ipcMain.handle('instances:set-notes', (_event, id: string, notes: string) => setInstanceNotes(id, notes))
+  ipcMain.handle('instances:stats', (_event, id: string) => getInstanceStats(id))
+  ipcMain.handle('instances:disk-usage', (_event, id: string) => getInstanceDiskUsage(id))
+  ipcMain.handle('storage:cleanup', (_event, dryRun: boolean) => cleanupGameFiles({ dryRun: Boolean(dryRun) }))
+  ipcMain.handle('cache:size', () => getCacheSize())
+  ipcMain.handle('cache:clear', (_event, kinds: CacheKind[]) => clearCache(Array.isArray(kinds) ? kinds : []))
+  ipcMain.handle('instances:copy-configuration', (_event, sourceId: string, targetId: string, items?: string[]) => copyInstanceConfiguration(sourceId, targetId, items))
+  ipcMain.handle('instances:import-multimc', async () => {
+    const result = await dialog.showOpenDialog(requireWindow(), {
+      title: 'Choose a MultiMC or Prism Launcher instance',
+      buttonLabel: 'Import instance',
+      properties: ['openDirectory', 'dontAddToRecent']
+    })
+    if (result.canceled || !result.filePaths[0]) return null
+    return importMultiMcInstance(result.filePaths[0])
+  })
+  ipcMain.handle('instances:import-mrpack', async () => {
+    const result = await dialog.showOpenDialog(requireWindow(), {
+      title: 'Choose a Modrinth modpack',
+      buttonLabel: 'Import modpack',
+      filters: [{ name: 'Modrinth modpacks', extensions: ['mrpack'] }],
+      properties: ['openFile', 'dontAddToRecent']
+    })
+    if (result.canceled || !result.filePaths[0]) return null
+    return importModpackFile(result.filePaths[0], progress)
+  })
+  ipcMain.handle('instances:export-mrpack', async (_event, id: string) => {
+    const instance = getInstance(id)
+    const result = await dialog.showSaveDialog(requireWindow(), {
+      title: `Export ${instance.name}`,
+      buttonLabel: 'Export modpack',
+      defaultPath: `${instance.name.replace(/[\\/:*?"<>|]+/g, '_')}.mrpack`,
+      filters: [{ name: 'Modrinth modpacks', extensions: ['mrpack'] }],
+      properties: ['showOverwriteConfirmation', 'dontAddToRecent']
+    })
+    if (result.canceled || !result.filePath) return null
+    return exportModpack(id, result.filePath, progress)
+  })
+  ipcMain.handle('mods:export-list', async (_event, id: string) => {
+    const instance = getInstance(id)
+    const result = await dialog.showSaveDialog(requireWindow(), {
+      title: `Export the mods in ${instance.name}`,
+      buttonLabel: 'Export mod list',
+      defaultPath: `${instance.name.replace(/[\\/:*?"<>|]+/g, '_')} mods.json`,
+      filters: [{ name: 'Mod lists', extensions: ['json'] }],
+      properties: ['showOverwriteConfirmation', 'dontAddToRecent']
+    })
+    if (result.canceled || !result.filePath) return null
+    return exportModList(id, result.filePath)
+  })
+  ipcMain.handle('mods:import-list', async (_event, id: string) => {
+    const result = await dialog.showOpenDialog(requireWindow(), {
+      title: 'Install mods from a list',
+      properties: ['openFile', 'dontAddToRecent'],
+      filters: [{ name: 'Mod lists', extensions: ['json'] }]
+    })
+    if (result.canceled || !result.filePaths[0]) return null
+    return importModList(id, result.filePaths[0], progress).finally(() => void recheckInstanceUpdates(id))
+  })
+  ipcMain.handle('instances:detect-vanilla', () => detectVanillaInstallation())
+  ipcMain.handle('instances:import-vanilla', (_event, profileId?: string) => importVanillaInstallation(profileId))
+  ipcMain.handle('instances:first-run-import', () => firstRunVanillaImport())
+  ipcMain.handle('instances:detect-curseforge', () => detectCurseForgeInstances())
+  ipcMain.handle('instances:import-curseforge', async (_event, folder?: string) => {
+    if (!folder) {
+      const result = await dialog.showOpenDialog(requireWindow(), {
+        title: 'Choose a CurseForge instance',
+        buttonLabel: 'Import instance',
+        properties: ['openDirectory', 'dontAddToRecent']
+      })
+      if (result.canceled || !result.filePaths[0]) return null
+      folder = result.filePaths[0]
+    }
+    return importCurseForgeInstance(folder, progress)
+  })
+  ipcMain.handle('instances:set-shared-folder', (_event, id: string, kind: 'saves' | 'resourcepacks', shared: boolean) => {
+    assertInstanceIdle(id)
+    return setSharedFolder(id, kind, Boolean(shared))
+  })
+  ipcMain.handle('instances:archive', (_event, id: string) => {
+    assertInstanceIdle(id)
+    return archiveInstance(id)
+  })
+  ipcMain.handle('instances:unarchive', (_event, id: string) => unarchiveInstance(id))
+  ipcMain.handle('instances:verify', (_event, id: string, repair = false) => verifyInstance(id, Boolean(repair), progress))
+  ipcMain.handle('instances:set-custom-version', async (_event, id: string) => {
+    const result = await dialog.showOpenDialog(requireWindow(), {
+      title: 'Choose a version profile',
+      buttonLabel: 'Use this profile',
+      properties: ['openFile', 'dontAddToRecent'],
+      filters: [{ name: 'Minecraft version JSON', extensions: ['json'] }]
+    })
+    if (result.canceled || !result.filePaths[0]) return null
+    return setCustomVersionJson(id, result.filePaths[0])
+  })
+  ipcMain.handle('instances:set-version-tracking', (_event, id: string, track: 'release' | 'snapshot' | null) => {
+    return setVersionTracking(id, track === 'release' || track === 'snapshot' ? track : null)
+  })
+  ipcMain.handle('instances:install-version-url', (_event, id: string, url: string) => installVersionProfileFromUrl(id, url))
+  ipcMain.handle('instances:clear-custom-version', (_event, id: string) => clearCustomVersionJson(id))
+  ipcMain.handle('instances:version-profiles', (_event, id: string) => listVersionProfiles(id))
+  ipcMain.handle('instances:select-version-profile', (_event, id: string, versionId: string) => selectVersionProfile(id, String(versionId)))
+  ipcMain.handle('instances:import-vanilla-profiles', (_event, ids: string[], link = false) => importVanillaProfiles(Array.isArray(ids) ? ids : [], Boolean(link)))
+  ipcMain.handle('instances:export-vanilla', (_event, ids?: string[]) => exportInstancesToVanilla(ids))
   ipcMain.handle('instances:open-folder', async (_event, id: string) => shell.openPath(await openInstanceFolder(id)))
   ipcMain.handle('instances:add-local-mod', async (_event, id: string) => {
     const result = await dialog.showOpenDialog(requireWindow(), { properties: ['openFile', 'multiSelections'], filters: [{ name: 'Minecraft mods', extensions: ['jar'] }] })
     if (result.canceled) return 0
-    for (const file of result.filePaths) await copyLocalMod(id, file)
+    for (const file of result.filePaths) await importLocalMod(id, file)
     return result.filePaths.length
   })
+  ipcMain.handle('instances:import-local-mod', (_event, id: string, file: string) => importLocalMod(id, file))
   ipcMain.handle('instances:launch', async (_event, id: string) => {
     const window = requireWindow()
     void launchInstance(window, id).catch((error) => {
       const message = error instanceof Error ? error.message : String(error)
-      if (!window.isDestroyed()) window.webContents.send('launch:error', { message })
+      if (!window.isDestroyed()) window.webContents.send('launch:error', { instanceId: id, message })
     })
     return true
   })
@@ -455,25 +618,105 @@ function registerIpc(): void {
     const window = requireWindow()
     void launchInstance(window, id, address).catch((error) => {
       const message = error instanceof Error ? error.message : String(error)
-      if (!window.isDestroyed()) window.webContents.send('launch:error', { message })
+      if (!window.isDestroyed()) window.webContents.send('launch:error', { instanceId: id, message })
+    })
+    return true
+  })
+  ipcMain.handle('instances:launch-world', async (_event, id: string, worldId: string) => {
+    const window = requireWindow()
+    void launchInstance(window, id, undefined, worldId).catch((error) => {
+      const message = error instanceof Error ? error.message : String(error)
+      if (!window.isDestroyed()) window.webContents.send('launch:error', { instanceId: id, message })
     })
     return true
   })
-  ipcMain.handle('instances:open-console', () => openLaunchConsole())
+  ipcMain.handle('instances:launch-safe-mode', async (_event, id: string) => {
+    const window = requireWindow()
+    void launchInstance(window, id, undefined, undefined, { safeMode: true }).catch((error) => {
+      const message = error instanceof Error ? error.message : String(error)
+      if (!window.isDestroyed()) window.webContents.send('launch:error', { instanceId: id, message })
+    })
+    return true
+  })
+  ipcMain.handle('instances:bisect-start', (_event, id: string) => {
+    assertInstanceIdle(id)
+    return startBisect(id)
+  })
+  ipcMain.handle('instances:bisect-status', (_event, id: string) => getBisect(id))
+  ipcMain.handle('instances:bisect-cancel', (_event, id: string) => cancelBisect(id))
+  ipcMain.handle('instances:prepare', (_event, id: string) => prepareInstance(requireWindow(), id))
+  ipcMain.handle('instances:cancel-launch', (_event, id: string) => cancelLaunch(id))
+  ipcMain.handle('instances:stop', (_event, id: string) => stopGame(id))
+  ipcMain.handle('instances:running', () => listRunningGames())
+  ipcMain.handle('instances:set-restart-on-crash', (_event, id: string, options) => setRestartOnCrash(id, options ?? null))
+  ipcMain.handle('instances:set-world-backups', (_event, id: string, policy) => setWorldBackupPolicy(id, policy ?? null))
+  ipcMain.handle('instances:clear-unstable', (_event, id: string) => setInstanceUnstable(id, null))
+  ipcMain.handle('instances:raise-memory', (_event, id: string) => raiseInstanceMemory(id))
+  ipcMain.handle('instances:set-priority', (_event, id: string, priority, affinity?: number[]) => setProcessPriority(id, priority, Array.isArray(affinity) ? affinity : []))
+  ipcMain.handle('instances:console-lines', (_event, id: string, since?: number) => getConsoleLines(id, Number(since) || 0))
+  ipcMain.handle('instances:launch-logs', (_event, id: string) => listLaunchLogs(id))
+  ipcMain.handle('instances:read-launch-log', (_event, id: string, name: string) => readLaunchLog(id, name))
+  ipcMain.handle('instances:search-log', (_event, id: string, options) => searchLog(id, options))
+  ipcMain.handle('instances:open-console', (_event, id?: string) => openLaunchConsole(id))
+
+  ipcMain.handle('downloads:list', () => listDownloads())
+  ipcMain.handle('downloads:pause', () => pauseDownloads())
+  ipcMain.handle('downloads:resume', () => resumeDownloads())
+  ipcMain.handle('network:status', () => connectivityStatus())
+  ipcMain.handle('network:probe', () => probeConnectivity())
+  ipcMain.handle('network:metered-answer', (_event, id: string, allowed: boolean) => answerMeteredPrompt(id, allowed))
 
   const progress = (message: string, value?: number): void => requireWindow().webContents.send('mods:progress', { message, progress: value })
   ipcMain.handle('mods:search', (_event, input) => searchContent(input))
-  ipcMain.handle('mods:install', (_event, instanceId: string, projectId: string) => installMod(instanceId, projectId, progress))
+  ipcMain.handle('mods:popular', (_event, input) => popularContent(input ?? {}))
+  ipcMain.handle('mods:install-collection', (_event, instanceId: string, input: string) => installCollection(instanceId, input, progress))
+  ipcMain.handle('mods:install-plan', (_event, projectId: string, instanceId: string) => resolveInstallPlan(projectId, instanceId))
+  ipcMain.handle('mods:project', (_event, idOrSlug: string, instanceId?: string) => getProjectDetails(idOrSlug, instanceId))
+  ipcMain.handle('mods:install', (_event, instanceId: string, projectId: string, replace?: boolean) => installMod(instanceId, projectId, progress, replace === true))
   ipcMain.handle('mods:install-modpack', (_event, instanceId: string, projectId: string) => installModpack(instanceId, projectId, progress))
+  ipcMain.handle('mods:update-modpack', (_event, instanceId: string) => {
+    assertInstanceIdle(instanceId)
+    return updateModpack(instanceId, progress).finally(() => void recheckInstanceUpdates(instanceId))
+  })
   ipcMain.handle('mods:list', (_event, instanceId: string) => listMods(instanceId))
   ipcMain.handle('mods:set-enabled', (_event, instanceId: string, fileName: string, enabled: boolean) => setModEnabled(instanceId, fileName, enabled))
   ipcMain.handle('mods:remove', (_event, instanceId: string, fileName: string) => removeMod(instanceId, fileName))
-  ipcMain.handle('mods:update', (_event, instanceId: string, projectId: string) => updateMod(instanceId, projectId, progress))
-  ipcMain.handle('mods:update-all', (_event, instanceId: string) => updateAllMods(instanceId, progress))
-
-  ipcMain.handle('packs:install', (_event, instanceId: string, projectId: string, type: 'resourcepack' | 'shader') => installPack(instanceId, projectId, type, progress))
+  ipcMain.handle('mods:update', (_event, instanceId: string, projectId: string) => updateMod(instanceId, projectId, progress).finally(() => void recheckInstanceUpdates(instanceId)))
+  ipcMain.handle('mods:update-all', (_event, instanceId: string, projectIds?: string[]) => updateInstanceMods(instanceId, projectIds, progress).finally(() => void recheckInstanceUpdates(instanceId)))
+  ipcMain.handle('mods:sets', (_event, instanceId: string) => listModSets(instanceId))
+  ipcMain.handle('mods:save-set', (_event, instanceId: string, name: string) => saveModSet(instanceId, name))
+  ipcMain.handle('mods:delete-set', (_event, instanceId: string, setId: string) => deleteModSet(instanceId, setId))
+  ipcMain.handle('mods:apply-set', (_event, instanceId: string, setId: string) => {
+    assertInstanceIdle(instanceId)
+    return applyModSet(instanceId, setId)
+  })
+  ipcMain.handle('mods:rollback-update', (_event, instanceId: string) => rollbackModUpdates(instanceId).finally(() => void recheckInstanceUpdates(instanceId)))
+  ipcMain.handle('updates:content-summary', () => contentUpdateSummary())
+  ipcMain.handle('updates:check-content', () => checkContentUpdates())
+  ipcMain.handle('modrinth:account', () => modrinthAccount())
+  ipcMain.handle('modrinth:link', (_event, token: string) => linkModrinthAccount(token))
+  ipcMain.handle('modrinth:unlink', () => unlinkModrinthAccount())
+  ipcMain.handle('modrinth:library', () => modrinthLibrary())
+  ipcMain.handle('modrinth:follow', (_event, projectId: string, followed: boolean) => setProjectFollowed(projectId, followed))
+
+  ipcMain.handle('packs:install', (_event, instanceId: string, projectId: string, type: 'resourcepack' | 'shader', installShaderLoader?: boolean) => installPack(instanceId, projectId, type, progress, installShaderLoader === true))
+  ipcMain.handle('packs:shader-loader', (_event, instanceId: string) => shaderLoaderRequirement(getInstance(instanceId)))
   ipcMain.handle('packs:list', (_event, instanceId: string, type?: 'resourcepack' | 'shader') => listPacks(instanceId, type))
-  ipcMain.handle('packs:set-enabled', (_event, instanceId: string, fileName: string, type: 'resourcepack' | 'shader', enabled: boolean) => setPackEnabled(instanceId, fileName, type, enabled))
+  ipcMain.handle('packs:set-enabled', (_event, instanceId: string, fileName: string, type: 'resourcepack' | 'shader', enabled: boolean) => {
+    // The game writes options.txt back when it closes, which would undo the change.
+    if (type === 'resourcepack') assertInstanceIdle(instanceId)
+    return setPackEnabled(instanceId, fileName, type, enabled)
+  })
+  ipcMain.handle('packs:add-local', async (_event, instanceId: string, type: 'resourcepack' | 'shader') => {
+    const result = await dialog.showOpenDialog(requireWindow(), {
+      title: type === 'resourcepack' ? 'Add resource packs' : 'Add shader packs',
+      properties: ['openFile', 'multiSelections', 'dontAddToRecent'],
+      filters: [{ name: type === 'resourcepack' ? 'Resource packs' : 'Shader packs', extensions: ['zip'] }]
+    })
+    if (result.canceled) return 0
+    for (const file of result.filePaths) await importLocalPack(instanceId, file, type)
+    return result.filePaths.length
+  })
   ipcMain.handle('packs:remove', (_event, instanceId: string, fileName: string, type: 'resourcepack' | 'shader') => removePack(instanceId, fileName, type))
   ipcMain.handle('packs:open-folder', async (_event, instanceId: string, type: 'resourcepack' | 'shader') => {
     const instance = getInstance(instanceId)
@@ -493,6 +736,26 @@ function registerIpc(): void {
   ipcMain.handle('worlds:download', (_event, instanceId: string, url: string) => downloadWorldZip(instanceId, url, progress))
   ipcMain.handle('worlds:delete', (_event, instanceId: string, worldId: string) => deleteWorld(instanceId, worldId))
   ipcMain.handle('worlds:open-folder', async (_event, instanceId: string, worldId?: string) => shell.openPath(await worldFolder(instanceId, worldId)))
+  ipcMain.handle('worlds:backups', (_event, instanceId: string, worldId?: string) => listWorldBackups(instanceId, worldId))
+  ipcMain.handle('worlds:backup', (_event, instanceId: string, worldId: string) => {
+    // A world the game is still writing could be saved half way through.
+    assertInstanceIdle(instanceId)
+    return backupWorld(instanceId, worldId, progress)
+  })
+  ipcMain.handle('worlds:restore-backup', (_event, instanceId: string, backupId: string) => restoreWorldBackup(instanceId, backupId))
+  ipcMain.handle('worlds:delete-backup', (_event, instanceId: string, backupId: string) => deleteWorldBackup(instanceId, backupId))
+
+  ipcMain.handle('screenshots:list', (_event, instanceId: string) => listScreenshots(instanceId))
+  ipcMain.handle('screenshots:copy', (_event, instanceId: string, fileName: string) => copyScreenshot(instanceId, fileName))
+  ipcMain.handle('screenshots:upload', (_event, instanceId: string, fileName: string) => uploadScreenshot(instanceId, fileName))
+  ipcMain.handle('screenshots:show', async (_event, instanceId: string, fileName: string) => shell.showItemInFolder(await screenshotFile(instanceId, fileName)))
+  ipcMain.handle('screenshots:open-folder', async (_event, instanceId: string) => shell.openPath(await screenshotsFolder(instanceId)))
+  ipcMain.handle('screenshots:upload-key', async () => Boolean(await store.loadScreenshotUploadKey()))
+  ipcMain.handle('screenshots:set-upload-key', async (_event, value: string | null) => {
+    const trimmed = typeof value === 'string' ? value.trim() : ''
+    if (trimmed) await store.saveScreenshotUploadKey(trimmed.slice(0, 4_000))
+    else await store.clearScreenshotUploadKey()
+  })
 
   ipcMain.handle('servers:copy-address', (_event, address: string) => clipboard.writeText(address))
   ipcMain.handle('servers:status', (_event, address: string, force = false) => getPartnerServerStatus(address, Boolean(force)))
@@ -525,17 +788,29 @@ async function startApplication(): Promise<void> {
     store.initialize(),
     hideProtectedInstallationResources()
   ])
+  await restoreModrinthAccount()
   // Let the lightweight splash renderer paint before the heavier main renderer
   // starts, while never holding startup indefinitely if that window cannot load.
   await Promise.race([splashReadyPromise, delay(900)])
 
   const data = store.getData()
+  setLogLevel(data.settings.logLevel)
+  setDownloadSpeedLimit(data.settings.downloadSpeedLimit)
+  setDownloadConcurrency(data.settings.downloadConcurrency)
+  setOfflineMode(data.settings.offlineMode)
   void cleanupStaleProtectedClientArtifacts(data.instances.map((instance) => instance.slug))
-    .catch((error) => console.warn('[MegaClient] Stale protected runtime cleanup could not finish.', error))
+    .catch((error) => logger.warn('Stale protected runtime cleanup could not finish.', { error }))
   setSplashProgress(34, 'Preparing the interface', 'Starting your library, updates and account services')
   registerIpc()
   createMainWindow()
   setupUpdater(sendUpdate, data.settings.checkUpdates)
+  setDownloadEventSender(sendDownloadEvent)
+  setConnectivitySender(sendConnectivity)
+  setMeteredPromptSender(sendMeteredPrompt)
+  setContentUpdateSender(sendContentUpdates)
+  if (!data.settings.offlineMode) void probeConnectivity()
+  startBackgroundPreparation(() => mainWindow)
+  startContentUpdateChecks()
 
   // Discord RPC is optional and must never delay the visible launcher startup.
   void configureDiscordActivity(data.settings.discordActivity)
@@ -543,7 +818,7 @@ async function startApplication(): Promise<void> {
       if (!data.settings.discordActivity) return
       showLauncherActivity(data.instances.find((instance) => instance.id === data.selectedInstanceId) ?? data.instances[0])
     })
-    .catch((error) => console.warn('[MegaClient] Discord activity could not start.', error))
+    .catch((error) => logger.warn('Discord activity could not start.', { error }))
 
   app.on('activate', () => { if (BrowserWindow.getAllWindows().length === 0) createWindows() })
 }
@@ -551,7 +826,7 @@ async function startApplication(): Promise<void> {
 app.whenReady().then(() => {
   void startApplication().catch(async (error: unknown) => {
     const message = error instanceof Error ? error.message : String(error)
-    console.error('[MegaClient] Startup failed:', error)
+    logger.error('Startup failed.', { error })
     setSplashProgress(100, 'MegaClient could not start', message)
     await delay(500)
     dialog.showErrorBox('MegaClient could not start', `${message}
@@ -561,8 +836,8 @@ Your Minecraft instances and files were not removed.`)
   })
 })
 
-process.on('unhandledRejection', (reason) => console.error('[MegaClient] Unhandled promise rejection:', reason))
-process.on('uncaughtException', (error) => console.error('[MegaClient] Uncaught main-process error:', error))
+process.on('unhandledRejection', (reason) => logger.error('Unhandled promise rejection.', { error: reason }))
+process.on('uncaughtException', (error) => logger.error('Uncaught main-process error.', { error }))
 
 app.on('before-quit', () => shutdownDiscordActivity())
 app.on('window-all-closed', () => { if (process.platform !== 'darwin') app.quit() })
@@ -2,6 +2,7 @@ import type { BrowserWindow } from 'electron'
 import { MicrosoftAuth, type Account } from 'eml-lib'
 import type { PublicAccount } from '../types'
 import { store } from './store'
+import { isOffline } from './connectivity'
 
 let refreshInFlight: Promise<Account> | null = null
 
@@ -79,6 +80,9 @@ export async function refreshAccount(mainWindow: BrowserWindow): Promise<Account
 
 export async function getValidAccount(mainWindow: BrowserWindow): Promise<Account> {
   const account = await getSavedAccount()
+  // Offline, the saved session is used as it is. Singleplayer works; servers
+  // will reject it until the launcher can refresh it again.
+  if (isOffline()) return account
   const auth = new MicrosoftAuth(mainWindow)
   try {
     if (await withTimeout(auth.validate(account), 12_000, 'Microsoft account validation timed out.')) return account
@@ -0,0 +1,68 @@
+import { powerMonitor, type BrowserWindow } from 'electron'
+import type { LauncherInstance } from '../types'
+import { isOffline } from './connectivity'
+import { isMeteredConnection } from './metered'
+import { listDownloads } from './downloads'
+import { hasActiveSessions, prepareInstanceInBackground } from './launcher'
+import { store } from './store'
+
+const CHECK_INTERVAL_MS = 60_000
+// How long the launcher must have had nothing to do before work starts.
+const IDLE_AFTER_MS = 2 * 60_000
+// A prepared instance is checked again after this long, in case files were
+// removed or a tracked version moved on.
+const PREPARED_FOR_MS = 6 * 60 * 60_000
+const FAILED_RETRY_MS = 30 * 60_000
+
+let timer: NodeJS.Timeout | null = null
+let idleSince = Date.now()
+let running = false
+// The last attempt per instance setup, keyed so a version or loader change
+// prepares the instance again straight away.
+const attempts = new Map<string, { at: number; prepared: boolean }>()
+
+function setupKey(instance: LauncherInstance): string {
+  return [instance.id, instance.minecraftVersion, instance.loader, instance.loaderVersion ?? '', instance.customVersionId ?? ''].join(':')
+}
+
+function launcherBusy(): boolean {
+  return hasActiveSessions() || listDownloads().items.some((item) => item.status === 'queued' || item.status === 'downloading')
+}
+
+async function tick(getWindow: () => BrowserWindow | null): Promise<void> {
+  const data = store.getData()
+  if (running || !data.settings.backgroundPrepare) return
+  if (launcherBusy()) {
+    idleSince = Date.now()
+    return
+  }
+  // Laptops on battery, metered connections and machines without internet
+  // are left alone.
+  if (Date.now() - idleSince < IDLE_AFTER_MS || isOffline() || powerMonitor.isOnBatteryPower() || await isMeteredConnection()) return
+  const instance = data.instances.find((item) => item.id === data.selectedInstanceId) ?? data.instances[0]
+  const window = getWindow()
+  if (!instance || instance.archivedAt || !window || window.isDestroyed()) return
+  const key = setupKey(instance)
+  const last = attempts.get(key)
+  if (last && Date.now() - last.at < (last.prepared ? PREPARED_FOR_MS : FAILED_RETRY_MS)) return
+
+  running = true
+  try {
+    const prepared = await prepareInstanceInBackground(window, instance.id)
+    // The setup may have changed while preparing, for example by following
+    // the latest release.
+    const current = store.getData().instances.find((item) => item.id === instance.id)
+    attempts.set(current ? setupKey(current) : key, { at: Date.now(), prepared })
+  } finally {
+    running = false
+    idleSince = Date.now()
+  }
+}
+
+// Starts the idle check. Whether anything is prepared is read from the
+// settings on every check, so the setting applies without a restart.
+export function startBackgroundPreparation(getWindow: () => BrowserWindow | null): void {
+  if (timer) return
+  timer = setInterval(() => void tick(getWindow), CHECK_INTERVAL_MS)
+  timer.unref()
+}
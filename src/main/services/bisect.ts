@@ -0,0 +1,83 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import type { BisectState, LauncherInstance } from '../types'
+import { getInstance } from './instances'
+import { metadataDirectory, modsDirectory } from './paths'
+import { disableModsForSafeMode } from './safeMode'
+
+function statePath(instance: LauncherInstance): string {
+  return path.join(metadataDirectory(instance.slug), 'bisect.json')
+}
+
+async function enabledMods(instance: LauncherInstance): Promise<string[]> {
+  return (await fs.readdir(modsDirectory(instance.slug)).catch(() => [] as string[]))
+    .filter((name) => name.endsWith('.jar') && !name.startsWith('mc-runtime-'))
+    .sort()
+}
+
+async function readState(instance: LauncherInstance): Promise<BisectState | null> {
+  try {
+    return JSON.parse(await fs.readFile(statePath(instance), 'utf8')) as BisectState
+  } catch {
+    return null
+  }
+}
+
+async function writeState(instance: LauncherInstance, state: BisectState): Promise<BisectState> {
+  await fs.mkdir(metadataDirectory(instance.slug), { recursive: true })
+  await fs.writeFile(statePath(instance), JSON.stringify(state, null, 2), 'utf8')
+  return state
+}
+
+// The last suspect is only named once a run with it as the only suspect
+// enabled has crashed. Otherwise that run is made first to confirm it.
+function nextRound(state: BisectState): BisectState {
+  const last = state.rounds.at(-1)
+  const [suspect] = state.candidates
+  if (!suspect) return { ...state, testing: [], inconclusive: true }
+  if (state.candidates.length === 1) {
+    const confirmed = last?.crashed && last.testing.length === 1 && last.testing[0] === suspect
+    return confirmed ? { ...state, testing: [], culprit: suspect } : { ...state, testing: [suspect] }
+  }
+  return { ...state, testing: state.candidates.slice(0, Math.ceil(state.candidates.length / 2)) }
+}
+
+export async function getBisect(instanceId: string): Promise<BisectState | null> {
+  return readState(getInstance(instanceId))
+}
+
+// Starts a binary search over the instance's enabled mods. Each launch while
+// it is active runs with only half of the remaining suspects enabled, and the
+// way the game exits decides which half keeps the blame.
+export async function startBisect(instanceId: string): Promise<BisectState> {
+  const instance = getInstance(instanceId)
+  const candidates = await enabledMods(instance)
+  if (candidates.length < 2) throw new Error('At least two enabled mods are needed to search for a crashing mod.')
+  return writeState(instance, nextRound({ candidates, testing: [], rounds: [], startedAt: new Date().toISOString() }))
+}
+
+export async function cancelBisect(instanceId: string): Promise<void> {
+  await fs.rm(statePath(getInstance(instanceId)), { force: true })
+}
+
+// Disables the suspects that are not part of this round. Mods that were ruled
+// out earlier stay enabled, since other mods may depend on them.
+export async function prepareBisectRound(instance: LauncherInstance): Promise<BisectState | null> {
+  const state = await readState(instance)
+  if (!state || state.culprit || state.inconclusive || !state.testing.length) return null
+  const benched = new Set(state.candidates.filter((name) => !state.testing.includes(name)))
+  await disableModsForSafeMode(instance, (await enabledMods(instance)).filter((name) => !benched.has(name)))
+  return state
+}
+
+export async function recordBisectRound(instanceId: string, crashed: boolean): Promise<BisectState | null> {
+  const instance = getInstance(instanceId)
+  const state = await readState(instance)
+  if (!state || state.culprit || state.inconclusive || !state.testing.length) return state
+  const candidates = crashed ? state.testing : state.candidates.filter((name) => !state.testing.includes(name))
+  return writeState(instance, nextRound({
+    ...state,
+    candidates,
+    rounds: [...state.rounds, { testing: state.testing, crashed, at: new Date().toISOString() }]
+  }))
+}
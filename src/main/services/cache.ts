@@ -0,0 +1,99 @@
+import { session } from 'electron'
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import type { CacheClearResult, CacheKind, CacheSizeReport } from '../types'
+import { listDownloads } from './downloads'
+import { emlRootDirectory, httpCacheDirectory, modIconCacheDirectory, newsCacheDirectory } from './paths'
+import { clearProfileCache, profileCacheFile } from './profile'
+import { clearServerStatusCache, serverStatusCacheSize } from './servers'
+import { directorySize } from './storage'
+import { logger } from './logger'
+
+export const CACHE_KINDS: readonly CacheKind[] = ['server-icons', 'avatars', 'metadata', 'partial-downloads']
+
+// Partial files from resumable downloads, and the temporary files a download
+// or link writes before renaming itself into place.
+const PARTIAL_FILE = /\.part$|\.\d+\.\d+\.(?:download|link)$/
+
+// Shared folders are reached through their own root rather than through each
+// instance's link, and worlds are skipped because they never hold downloads.
+async function partialFiles(directory: string): Promise<string[]> {
+  const files: string[] = []
+  for (const entry of await fs.readdir(directory, { withFileTypes: true }).catch(() => [])) {
+    const child = path.join(directory, entry.name)
+    if (entry.isDirectory() && entry.name !== 'saves') files.push(...await partialFiles(child))
+    else if (entry.isFile() && PARTIAL_FILE.test(entry.name)) files.push(child)
+  }
+  return files
+}
+
+function downloadsInProgress(): boolean {
+  return listDownloads().items.some((item) => item.status === 'queued' || item.status === 'downloading' || item.status === 'paused')
+}
+
+async function measure(kind: CacheKind): Promise<number> {
+  switch (kind) {
+    case 'server-icons':
+      return serverStatusCacheSize().bytes
+    case 'avatars':
+      // Avatars, skins and capes shown in the launcher sit in the window's
+      // HTTP cache, next to the saved skin and cape list.
+      return await session.defaultSession.getCacheSize() + await directorySize(profileCacheFile())
+    case 'metadata':
+      return await directorySize(httpCacheDirectory()) + await directorySize(newsCacheDirectory()) + await directorySize(modIconCacheDirectory())
+    case 'partial-downloads': {
+      let total = 0
+      for (const file of await partialFiles(emlRootDirectory())) total += await directorySize(file)
+      return total
+    }
+  }
+}
+
+export async function getCacheSize(): Promise<CacheSizeReport> {
+  const kinds = Object.fromEntries(await Promise.all(CACHE_KINDS.map(async (kind) => [kind, await measure(kind)] as const))) as Record<CacheKind, number>
+  return {
+    kinds,
+    totalBytes: Object.values(kinds).reduce((total, bytes) => total + bytes, 0),
+    measuredAt: new Date().toISOString()
+  }
+}
+
+// Clears the chosen caches so space can be reclaimed or a damaged cached
+// response thrown away. Everything cleared is fetched again when next needed.
+// Partial downloads are kept while any download is queued, running or paused,
+// because those resume from them.
+export async function clearCache(kinds: CacheKind[]): Promise<CacheClearResult> {
+  const selected = CACHE_KINDS.filter((kind) => kinds.includes(kind))
+  const skipped: CacheKind[] = []
+  let clearedBytes = 0
+  for (const kind of selected) {
+    if (kind === 'partial-downloads' && downloadsInProgress()) {
+      skipped.push(kind)
+      continue
+    }
+    const before = await measure(kind)
+    try {
+      switch (kind) {
+        case 'server-icons':
+          clearServerStatusCache()
+          break
+        case 'avatars':
+          await session.defaultSession.clearCache()
+          await clearProfileCache()
+          break
+        case 'metadata':
+          await fs.rm(httpCacheDirectory(), { recursive: true, force: true })
+          await fs.rm(newsCacheDirectory(), { recursive: true, force: true })
+          await fs.rm(modIconCacheDirectory(), { recursive: true, force: true })
+          break
+        case 'partial-downloads':
+          for (const file of await partialFiles(emlRootDirectory())) await fs.rm(file, { force: true })
+          break
+      }
+    } catch (error) {
+      logger.warn('A cache could not be cleared completely.', { kind, error })
+    }
+    clearedBytes += Math.max(0, before - await measure(kind))
+  }
+  return { clearedBytes, skipped, size: await getCacheSize() }
+}
@@ -0,0 +1,160 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import type { CleanupReport, LauncherInstance } from '../types'
+import { assertInstanceIdle } from './launcher'
+import { javaRequirement, runtimeDirectoryNames } from './java'
+import { instanceDirectory, runtimesDirectory, sharedDirectory } from './paths'
+import { store } from './store'
+import { mavenLibraryPath, readVersionJson, type VersionJson } from './versionJson'
+import { logger } from './logger'
+import { objectStoreBusy, unusedObjects } from './objectStore'
+import { directorySize } from './storage'
+
+// The profile the instance launches and everything it inherits from.
+async function versionChain(gameDirectory: string, instance: LauncherInstance): Promise<VersionJson[]> {
+  const start = instance.customVersionId
+    ?? (instance.loader === 'fabric' && instance.loaderVersion ? `fabric-loader-${instance.loaderVersion}-${instance.minecraftVersion}` : instance.minecraftVersion)
+  const chain: VersionJson[] = []
+  let next: string | undefined = start
+  while (next && chain.length < 16 && !chain.some((version) => version.id === next)) {
+    const version = await readVersionJson(gameDirectory, next)
+    if (!version) break
+    chain.push(version)
+    next = version.inheritsFrom
+  }
+  return chain
+}
+
+function referencedLibraries(chain: VersionJson[]): Set<string> {
+  const paths = new Set<string>()
+  for (const library of chain.flatMap((version) => version.libraries ?? [])) {
+    // Every platform's files are kept, in case the folder is synced between
+    // computers; only files no profile mentions at all are removed.
+    const downloads = library.downloads as { artifact?: { path?: string }; classifiers?: Record<string, { path?: string }> } | undefined
+    if (downloads?.artifact?.path) paths.add(downloads.artifact.path)
+    for (const artifact of Object.values(downloads?.classifiers ?? {})) if (artifact.path) paths.add(artifact.path)
+    const maven = mavenLibraryPath(library.name)
+    if (maven) paths.add(maven)
+  }
+  return paths
+}
+
+async function listFiles(root: string, relative = ''): Promise<string[]> {
+  const files: string[] = []
+  for (const entry of await fs.readdir(path.join(root, relative), { withFileTypes: true }).catch(() => [])) {
+    const child = relative ? `${relative}/${entry.name}` : entry.name
+    if (entry.isDirectory()) files.push(...await listFiles(root, child))
+    else if (entry.isFile()) files.push(child)
+  }
+  return files
+}
+
+async function removeEmptyDirectories(directory: string): Promise<void> {
+  for (const entry of await fs.readdir(directory, { withFileTypes: true }).catch(() => [])) {
+    if (entry.isDirectory()) await removeEmptyDirectories(path.join(directory, entry.name))
+  }
+  await fs.rmdir(directory).catch(() => undefined)
+}
+
+// Removes game files no instance uses any more: version folders left behind by
+// version changes, libraries only those versions needed, stale natives, Java
+// runtimes no instance requires, and stored mods and packs no instance links.
+// Forge and NeoForge installers write libraries no profile lists, so those
+// instances keep their versions, and the shared library store is left alone
+// while any of them uses it. Instances that are running or being prepared are
+// skipped, and shared libraries and runtimes are kept entirely while any game
+// is open.
+export async function cleanupGameFiles(options: { dryRun?: boolean } = {}): Promise<CleanupReport> {
+  const report: CleanupReport = { removed: [], reclaimedBytes: 0, skipped: [], dryRun: Boolean(options.dryRun) }
+  const remove = async (target: string, kind: CleanupReport['removed'][number]['kind'], label: string): Promise<void> => {
+    const bytes = await directorySize(target)
+    if (!options.dryRun) {
+      try {
+        await fs.rm(target, { recursive: true, force: true })
+      } catch (error) {
+        logger.warn('An unused game file could not be removed.', { target, error })
+        return
+      }
+    }
+    report.removed.push({ kind, path: label, bytes })
+    report.reclaimedBytes += bytes
+  }
+
+  const instances = store.getData().instances.filter((instance) => !instance.archivedAt)
+  const requiredRuntimes = new Set<string>()
+  // The shared library store is only cleaned when every instance using it
+  // could be checked.
+  const sharedLibraries = new Set<string>()
+  let sharedLibrariesKnown = true
+  let anyBusy = false
+  for (const instance of instances) {
+    try {
+      assertInstanceIdle(instance.id)
+    } catch {
+      anyBusy = true
+      report.skipped.push(instance.name)
+      continue
+    }
+    const gameDirectory = instanceDirectory(instance.slug)
+    const chain = await versionChain(gameDirectory, instance)
+    const launchId = chain[0]?.id
+    if (launchId) {
+      for (const name of runtimeDirectoryNames(await javaRequirement(gameDirectory, launchId))) requiredRuntimes.add(name)
+    }
+    // The user's own game folder is never cleaned.
+    if (instance.linkedDirectory) continue
+
+    const kept = new Set(chain.map((version) => version.id))
+    const nativesRoot = path.join(gameDirectory, 'natives')
+    for (const entry of await fs.readdir(nativesRoot).catch(() => [] as string[])) {
+      if (entry.endsWith('.tmp') || ![...kept].some((id) => entry.startsWith(`${id}-`))) {
+        await remove(path.join(nativesRoot, entry), 'natives', `${instance.name}/natives/${entry}`)
+      }
+    }
+
+    const librariesDirectory = path.join(gameDirectory, 'libraries')
+    const linked = Boolean((await fs.lstat(librariesDirectory).catch(() => null))?.isSymbolicLink())
+    if ((instance.loader !== 'vanilla' && instance.loader !== 'fabric') || !chain.length || chain.at(-1)!.inheritsFrom) {
+      if (linked) sharedLibrariesKnown = false
+      continue
+    }
+    for (const entry of await fs.readdir(path.join(gameDirectory, 'versions')).catch(() => [] as string[])) {
+      if (!kept.has(entry)) await remove(path.join(gameDirectory, 'versions', entry), 'version', `${instance.name}/versions/${entry}`)
+    }
+    const referenced = referencedLibraries(chain)
+    if (linked) {
+      for (const file of referenced) sharedLibraries.add(file)
+      continue
+    }
+    // Instances that have not launched since libraries became shared still
+    // keep their own copy.
+    for (const file of await listFiles(librariesDirectory)) {
+      if (!referenced.has(file)) await remove(path.join(librariesDirectory, file), 'library', `${instance.name}/libraries/${file}`)
+    }
+    if (!options.dryRun) await removeEmptyDirectories(librariesDirectory)
+  }
+
+  if (!anyBusy && sharedLibrariesKnown) {
+    const librariesDirectory = sharedDirectory('libraries')
+    for (const file of await listFiles(librariesDirectory)) {
+      if (!sharedLibraries.has(file)) await remove(path.join(librariesDirectory, file), 'library', `Shared libraries/${file}`)
+    }
+    if (!options.dryRun) {
+      await removeEmptyDirectories(librariesDirectory)
+      await fs.mkdir(librariesDirectory, { recursive: true })
+    }
+  }
+
+  // A mod install or launch may have stored an object it has not linked yet.
+  if (!anyBusy && !objectStoreBusy()) {
+    for (const file of await unusedObjects()) await remove(file, 'object', `Shared files/${path.basename(file)}`)
+  }
+
+  if (!anyBusy) {
+    for (const entry of await fs.readdir(runtimesDirectory()).catch(() => [] as string[])) {
+      // Leftover archives and staging folders from interrupted installs go too.
+      if (!requiredRuntimes.has(entry)) await remove(path.join(runtimesDirectory(), entry), 'runtime', `Java runtimes/${entry}`)
+    }
+  }
+  return report
+}
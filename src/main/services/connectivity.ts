@@ -0,0 +1,95 @@
+import { net } from 'electron'
+import type { ConnectivityStatus } from '../types'
+
+const PROBE_URL = 'https://piston-meta.mojang.com/mc/game/version_manifest_v2.json'
+const PROBE_TIMEOUT_MS = 5_000
+const RECHECK_INTERVAL_MS = 30_000
+
+let offlineMode = false
+let reachable = true
+let checkedAt: string | undefined
+let probeInFlight: Promise<ConnectivityStatus> | null = null
+let recheck: NodeJS.Timeout | null = null
+let sendStatus: (status: ConnectivityStatus) => void = () => undefined
+
+export class OfflineError extends Error {
+  constructor(message = 'MegaClient is offline. Connect to the internet, or turn off offline mode in Settings, and try again.') {
+    super(message)
+    this.name = 'OfflineError'
+  }
+}
+
+export function setConnectivitySender(sender: (status: ConnectivityStatus) => void): void {
+  sendStatus = sender
+}
+
+export function connectivityStatus(): ConnectivityStatus {
+  return { state: offlineMode ? 'offline-mode' : reachable ? 'online' : 'unreachable', checkedAt }
+}
+
+// True while requests are known to fail: offline mode is on, or the last
+// probe or request could not reach the internet.
+export function isOffline(): boolean {
+  return offlineMode || !reachable
+}
+
+// Offline mode is explicit: no request leaves the machine until it is turned
+// off, and everything that has a cached answer uses it.
+export function isOfflineMode(): boolean {
+  return offlineMode
+}
+
+export function assertOnline(): void {
+  if (offlineMode) throw new OfflineError()
+}
+
+export function setOfflineMode(enabled: boolean): void {
+  if (offlineMode === enabled) return
+  offlineMode = enabled
+  sendStatus(connectivityStatus())
+  if (!enabled) void probeConnectivity()
+}
+
+function setReachable(next: boolean): void {
+  checkedAt = new Date().toISOString()
+  if (recheck) clearTimeout(recheck)
+  recheck = null
+  if (!next) {
+    // Keep probing quietly so the launcher notices when the connection returns.
+    recheck = setTimeout(() => {
+      recheck = null
+      void probeConnectivity()
+    }, RECHECK_INTERVAL_MS)
+    recheck.unref()
+  }
+  if (reachable === next) return
+  reachable = next
+  sendStatus(connectivityStatus())
+}
+
+// Called by the HTTP client. A failed request may only mean one server is
+// down, so it triggers a probe rather than marking the launcher offline.
+export function noteRequestResult(succeeded: boolean): void {
+  if (succeeded) setReachable(true)
+  else if (reachable && Date.now() - (checkedAt ? Date.parse(checkedAt) : 0) > 10_000) void probeConnectivity()
+}
+
+export function probeConnectivity(): Promise<ConnectivityStatus> {
+  if (probeInFlight) return probeInFlight
+  probeInFlight = (async () => {
+    if (!net.isOnline()) {
+      setReachable(false)
+      return connectivityStatus()
+    }
+    try {
+      const response = await fetch(PROBE_URL, { method: 'HEAD', signal: AbortSignal.timeout(PROBE_TIMEOUT_MS) })
+      setReachable(response.status < 500)
+    } catch {
+      setReachable(false)
+    }
+    return connectivityStatus()
+  })().finally(() => {
+    probeInFlight = null
+  })
+  return probeInFlight
+}
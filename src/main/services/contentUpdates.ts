@@ -0,0 +1,85 @@
+import type { ContentUpdateSummary, InstanceUpdateSummary } from '../types'
+import { isOffline } from './connectivity'
+import { logger } from './logger'
+import { checkInstanceUpdates } from './modrinth'
+import { store } from './store'
+
+const TICK_MS = 10 * 60_000
+// Leaves startup to the launcher's own work before the first check.
+const FIRST_CHECK_AFTER_MS = 2 * 60_000
+
+let timer: NodeJS.Timeout | null = null
+let startedAt = Date.now()
+let running: Promise<ContentUpdateSummary> | null = null
+let summary: ContentUpdateSummary | null = null
+let send: ((summary: ContentUpdateSummary) => void) | null = null
+
+export function setContentUpdateSender(sender: (summary: ContentUpdateSummary) => void): void {
+  send = sender
+}
+
+export function contentUpdateSummary(): ContentUpdateSummary | null {
+  return summary
+}
+
+function publish(instances: InstanceUpdateSummary[]): ContentUpdateSummary {
+  const relevant = instances.filter((item) => item.mods.length || item.modpack)
+  summary = {
+    checkedAt: new Date().toISOString(),
+    total: relevant.reduce((total, item) => total + item.mods.length + (item.modpack ? 1 : 0), 0),
+    instances: relevant
+  }
+  send?.(summary)
+  return summary
+}
+
+// Checks every instance in turn. An instance that cannot be checked keeps
+// whatever the last check found for it.
+export async function checkContentUpdates(): Promise<ContentUpdateSummary> {
+  if (running) return running
+  running = (async () => {
+    const previous = new Map(summary?.instances.map((item) => [item.instanceId, item]))
+    const instances: InstanceUpdateSummary[] = []
+    for (const instance of store.getData().instances) {
+      if (instance.archivedAt || instance.customClient) continue
+      try {
+        instances.push(await checkInstanceUpdates(instance.id))
+      } catch (error) {
+        logger.warn('Content updates could not be checked for an instance.', { instanceId: instance.id, error })
+        const last = previous.get(instance.id)
+        if (last) instances.push(last)
+      }
+    }
+    return publish(instances)
+  })().finally(() => { running = null })
+  return running
+}
+
+// Refreshes one instance after its content changed, so the count shown does
+// not wait for the next scheduled check.
+export async function recheckInstanceUpdates(instanceId: string): Promise<void> {
+  if (!summary) return
+  const others = summary.instances.filter((item) => item.instanceId !== instanceId)
+  try {
+    publish([...others, await checkInstanceUpdates(instanceId)])
+  } catch (error) {
+    logger.debug('Content updates could not be rechecked.', { instanceId, error })
+  }
+}
+
+async function tick(): Promise<void> {
+  const hours = store.getData().settings.contentUpdateCheckHours
+  if (!hours || isOffline() || Date.now() - startedAt < FIRST_CHECK_AFTER_MS) return
+  if (summary && Date.now() - Date.parse(summary.checkedAt) < hours * 60 * 60_000) return
+  await checkContentUpdates().catch((error) => logger.warn('The scheduled content update check failed.', { error }))
+}
+
+// The interval is read from the settings on every tick, so turning checks on
+// or off applies without a restart.
+export function startContentUpdateChecks(): void {
+  if (timer) return
+  startedAt = Date.now()
+  timer = setInterval(() => void tick(), TICK_MS)
+  timer.unref()
+  setTimeout(() => void tick(), FIRST_CHECK_AFTER_MS + 1_000).unref()
+}
@@ -0,0 +1,89 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import type { CrashReport, JvmErrorLog } from '../types'
+
+const STACK_HEAD_LINES = 8
+
+// Jars that appear in every stack trace and never point at a culprit.
+const IGNORED_JARS = /^(?:minecraft|client|server|fabric-loader|forge|neoforge|fmlloader|fmlcore|javafmllanguage|mixin|sponge-mixin|java\.base|jdk\.|securejarhandler|bootstraplauncher|modlauncher)/i
+
+// Native libraries of the common OpenGL drivers. A JVM crash inside one of
+// them is almost always fixed by updating the graphics driver.
+const GPU_DRIVER_LIBRARIES = /^(?:atio6axx|atioglxx|amdxx|nvoglv(?:32|64)|ig\w*icd(?:32|64)|libnvidia-glcore|libgl(?:x|ap)?_nvidia|radeonsi_dri|iris_dri|i965_dri|appleintel|amdradeon|applemetal)/i
+
+async function newestFile(directory: string, since: number, matches: (name: string) => boolean): Promise<string | null> {
+  let newest: { file: string; modified: number } | null = null
+  for (const name of await fs.readdir(directory).catch(() => [] as string[])) {
+    if (!matches(name)) continue
+    const file = path.join(directory, name)
+    const stat = await fs.stat(file).catch(() => null)
+    if (!stat?.isFile() || stat.mtimeMs < since) continue
+    if (!newest || stat.mtimeMs > newest.modified) newest = { file, modified: stat.mtimeMs }
+  }
+  return newest?.file ?? null
+}
+
+export async function newestCrashReport(gameDirectory: string, since: number): Promise<string | null> {
+  return newestFile(path.join(gameDirectory, 'crash-reports'), since, (name) => name.endsWith('.txt'))
+}
+
+// The JVM writes hs_err_pid<pid>.log into its working directory when it dies
+// in native code, where Minecraft cannot write a crash report of its own.
+export async function newestJvmErrorLog(gameDirectory: string, since: number): Promise<string | null> {
+  return newestFile(gameDirectory, since, (name) => /^hs_err_pid\d+\.log$/.test(name))
+}
+
+export async function readJvmErrorLog(file: string): Promise<JvmErrorLog> {
+  const lines = (await fs.readFile(file, 'utf8')).split(/\r?\n/)
+  const header = lines.slice(0, 40).filter((line) => line.startsWith('#'))
+  const summary = header.map((line) => line.replace(/^#\s?/, '').trim()).filter((line) => line && !line.startsWith('If you would like') && !line.startsWith('See problematic'))
+  const frameIndex = header.findIndex((line) => line.startsWith('# Problematic frame:'))
+  const problematicFrame = frameIndex >= 0 ? header[frameIndex + 1]?.replace(/^#\s*/, '').trim() : undefined
+  // Frames look like C  [atio6axx.dll+0x1a2b3c] or C  [libGL.so.1+0x1234].
+  const library = problematicFrame ? /\[([^+\]]+)/.exec(problematicFrame)?.[1] : undefined
+  return { file, summary: summary.slice(0, 12), problematicFrame, library, gpuDriver: Boolean(library && GPU_DRIVER_LIBRARIES.test(library)) }
+}
+
+function suspectedMods(text: string, stackHead: string[]): string[] {
+  const mods = new Set<string>()
+  // Forge and NeoForge name the culprit directly; NONE means they could not tell.
+  for (const match of text.matchAll(/^\s*Suspected Mods?:\s*(.+)$/gim)) {
+    const value = match[1]!.trim()
+    if (value.toUpperCase() !== 'NONE') for (const mod of value.split(/,\s*/)) mods.add(mod.replace(/,?\s*Version:.*$/i, '').trim())
+  }
+  for (const match of text.matchAll(/Mixin (?:apply|transformation) (?:for|of) .*?\bmod ([\w-]+)/gi)) mods.add(match[1]!)
+  // Modded stack frames end in ~[jar-name.jar:?], which names the mod file.
+  for (const line of stackHead) {
+    const jar = /~?\[([^\]:/\\]+\.jar)(?::[^\]]*)?\]/.exec(line)?.[1]
+    if (jar && !IGNORED_JARS.test(jar)) mods.add(jar)
+  }
+  return [...mods].filter(Boolean).slice(0, 6)
+}
+
+// Reads the parts of a vanilla crash report that explain a crash: the
+// description, the exception line and the first frames of its stack trace.
+export function parseCrashReport(text: string): Omit<CrashReport, 'file'> {
+  const lines = text.split(/\r?\n/)
+  const description = lines.find((line) => line.startsWith('Description:'))?.slice('Description:'.length).trim()
+  const descriptionIndex = lines.findIndex((line) => line.startsWith('Description:'))
+  // Reports written by mods or older versions may have no description, so
+  // the exception is the first line a stack trace follows, or else the first
+  // line naming an exception or error.
+  const exceptionIndex = descriptionIndex >= 0
+    ? lines.findIndex((line, index) => index > descriptionIndex && line.trim() && !line.startsWith('\t') && !line.startsWith(' '))
+    : [
+        lines.findIndex((line, index) => line.trim() && !/^\s/.test(line) && /^\s+at\s/.test(lines[index + 1] ?? '')),
+        lines.findIndex((line) => /^(?:Caused by: )?[\w$.]+(?:Exception|Error)\b/.test(line.trim()))
+      ].find((index) => index >= 0) ?? -1
+  const exception = exceptionIndex >= 0 ? lines[exceptionIndex]!.trim() : undefined
+  const stackHead: string[] = []
+  for (const line of exceptionIndex >= 0 ? lines.slice(exceptionIndex + 1) : []) {
+    if (!/^\s+at\s/.test(line) || stackHead.length >= STACK_HEAD_LINES) break
+    stackHead.push(line.trim())
+  }
+  return { description, exception, stackHead, suspectedMods: suspectedMods(text, stackHead) }
+}
+
+export async function readCrashReport(file: string): Promise<CrashReport> {
+  return { file, ...parseCrashReport(await fs.readFile(file, 'utf8')) }
+}
@@ -0,0 +1,238 @@
+import { spawn } from 'node:child_process'
+import { EventEmitter } from 'node:events'
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import { app } from 'electron'
+import type { Account } from 'eml-lib'
+import { download } from './downloads'
+import { legacyAssetsRoot } from './legacyAssets'
+import { currentRuleEnvironment, type RuleEnvironment } from './rules'
+import { applicableArguments, applicableLibraries, baseMinecraftVersion, containedPath, mavenLibraryPath, resolveVersionJson, type LibraryArtifact, type VersionJson } from './versionJson'
+
+interface LoggingFile {
+  id: string
+  sha1?: string
+  size?: number
+  url?: string
+}
+
+export interface DirectLaunchOptions {
+  gameDirectory: string
+  versionId: string
+  javaPath: string
+  account: Account
+  memory: { min: number; max: number }
+  window: { width: number; height: number; fullscreen: boolean }
+  nativesDirectory: string | null
+  // Added after the profile's own JVM arguments, so they take precedence.
+  jvmArgs: string[]
+  // Added after the profile's own game arguments.
+  gameArgs: string[]
+  // Filled into the profile's own Quick Play arguments.
+  quickPlay?: QuickPlayTarget
+  signal?: AbortSignal
+}
+
+export interface QuickPlayTarget {
+  singleplayer?: string
+  multiplayer?: string
+}
+
+interface DirectLauncherEvents {
+  launch_debug: [line: string]
+  launch_launch: []
+  launch_data: [line: string]
+  launch_close: [code: number | null]
+}
+
+const LIBRARIES_URL = 'https://libraries.minecraft.net/'
+
+// What the official launcher adds for profiles from before 1.13, which only
+// carry a minecraftArguments string and leave the JVM arguments to it.
+const LEGACY_JVM_ARGUMENTS = [
+  ...process.platform === 'win32' ? ['-XX:HeapDumpPath=MojangTricksIntelDriversForPerformance_javaw.exe_minecraft.exe.heapdump'] : [],
+  '-Djava.library.path=${natives_directory}',
+  '-cp',
+  '${classpath}'
+]
+
+// Every library jar the profile puts on the classpath, downloaded when it is
+// missing. Vanilla libraries carry their own download; loader libraries such
+// as Fabric's only name a Maven repository, and the path comes from their
+// coordinates.
+async function libraryClasspath(version: VersionJson, librariesDirectory: string, environment: RuleEnvironment, signal?: AbortSignal): Promise<string[]> {
+  const files = new Map<string, string>()
+  for (const library of applicableLibraries(version, environment)) {
+    const artifact = (library.downloads as { artifact?: LibraryArtifact } | undefined)?.artifact
+    // Old profiles list natives-only libraries, which prepareNatives extracts.
+    if (!artifact && library.natives) continue
+    const relative = artifact?.path ?? mavenLibraryPath(library.name)
+    const file = relative ? containedPath(librariesDirectory, relative) : null
+    if (!relative || !file) throw new Error(`The library ${library.name} has an invalid path.`)
+    if (files.has(relative)) continue
+    files.set(relative, file)
+    if (await fs.stat(file).catch(() => null)) continue
+    const url = artifact?.url || `${(library.url || LIBRARIES_URL).replace(/\/?$/, '/')}${relative}`
+    await download(url, file, undefined, { signal, resume: true, sha1: artifact?.sha1 ?? library.sha1, size: artifact?.size ?? library.size })
+  }
+  return [...files.values()]
+}
+
+// Downloads the libraries a direct launch needs ahead of time, so a prepared
+// instance can later be launched offline.
+export async function installLaunchLibraries(gameDirectory: string, versionId: string, signal?: AbortSignal): Promise<void> {
+  const version = await resolveVersionJson(gameDirectory, versionId)
+  await libraryClasspath(version, path.join(gameDirectory, 'libraries'), currentRuleEnvironment(), signal)
+}
+
+// A profile with its own jar next to its JSON runs that jar; loader profiles
+// run the jar of the version they inherit from.
+async function clientJar(gameDirectory: string, version: VersionJson, versionId: string): Promise<string> {
+  const candidates = [versionId, typeof version.jar === 'string' ? version.jar : undefined, await baseMinecraftVersion(gameDirectory, versionId)]
+  for (const id of candidates) {
+    if (!id || !/^[\w.+-]{1,80}$/.test(id)) continue
+    const jar = path.join(gameDirectory, 'versions', id, `${id}.jar`)
+    if (await fs.stat(jar).catch(() => null)) return jar
+  }
+  throw new Error(`The game jar for ${versionId} is missing. Prepare the instance again to download it.`)
+}
+
+// Mojang's log4j configuration, which among other things keeps the game's
+// log free of the console colour codes. It is optional, so a configuration
+// that cannot be downloaded is left out.
+async function loggingArguments(version: VersionJson, assetsRoot: string, signal?: AbortSignal): Promise<string[]> {
+  const client = (version.logging as { client?: { argument?: string; file?: LoggingFile } } | undefined)?.client
+  if (!client?.argument || !client.file || !/^[\w.-]+$/.test(client.file.id)) return []
+  const file = path.join(assetsRoot, 'log_configs', client.file.id)
+  if (!await fs.stat(file).catch(() => null)) {
+    if (!client.file.url) return []
+    try {
+      await download(client.file.url, file, undefined, { signal, sha1: client.file.sha1, size: client.file.size })
+    } catch {
+      signal?.throwIfAborted()
+      return []
+    }
+  }
+  return [client.argument.replace('${path}', file)]
+}
+
+// Builds the Java command line the official launcher would run for the
+// resolved profile: its rule-filtered JVM and game arguments with every
+// placeholder filled in, the classpath, and the main class.
+export async function buildLaunchCommand(options: DirectLaunchOptions): Promise<string[]> {
+  const { gameDirectory, versionId, account } = options
+  const version = await resolveVersionJson(gameDirectory, versionId)
+  if (!version.mainClass) throw new Error(`The version profile ${versionId} does not name a main class.`)
+  const { quickPlay } = options
+  // The features the official launcher sets; arguments behind any other
+  // feature, such as the demo mode, are left out.
+  const environment = currentRuleEnvironment({
+    has_custom_resolution: true,
+    is_quick_play_singleplayer: Boolean(quickPlay?.singleplayer),
+    is_quick_play_multiplayer: Boolean(quickPlay?.multiplayer)
+  })
+  const librariesDirectory = path.join(gameDirectory, 'libraries')
+  const assetsRoot = path.join(gameDirectory, 'assets')
+  const classpath = [...await libraryClasspath(version, librariesDirectory, environment, options.signal), await clientJar(gameDirectory, version, versionId)]
+  const assetIndex = version.assetIndex?.id ?? version.assets ?? 'legacy'
+  const legacy = !version.arguments
+  const values: Record<string, string> = {
+    auth_player_name: account.name,
+    auth_uuid: account.uuid,
+    auth_access_token: account.accessToken,
+    auth_session: `token:${account.accessToken}:${account.uuid}`,
+    auth_xuid: '',
+    clientid: '',
+    user_type: 'msa',
+    user_properties: '{}',
+    version_name: versionId,
+    version_type: version.type ?? 'release',
+    game_directory: gameDirectory,
+    assets_root: assetsRoot,
+    assets_index_name: assetIndex,
+    game_assets: await legacyAssetsRoot(gameDirectory, assetIndex),
+    resolution_width: String(options.window.width),
+    resolution_height: String(options.window.height),
+    natives_directory: options.nativesDirectory ?? path.join(gameDirectory, 'natives'),
+    launcher_name: 'MegaClient',
+    launcher_version: app.getVersion(),
+    library_directory: librariesDirectory,
+    classpath: classpath.join(path.delimiter),
+    classpath_separator: path.delimiter,
+    quickPlaySingleplayer: quickPlay?.singleplayer ?? '',
+    quickPlayMultiplayer: quickPlay?.multiplayer ?? ''
+  }
+  const fill = (argument: string): string => argument.replace(/\$\{(\w+)\}/g, (match, key: string) => values[key] ?? match)
+
+  return [
+    ...(legacy ? LEGACY_JVM_ARGUMENTS : applicableArguments(version, 'jvm', environment)).map(fill),
+    ...await loggingArguments(version, assetsRoot, options.signal),
+    `-Xms${options.memory.min}M`,
+    `-Xmx${options.memory.max}M`,
+    ...options.jvmArgs,
+    version.mainClass,
+    ...applicableArguments(version, 'game', environment).map(fill),
+    // Legacy profiles have no resolution feature to opt into.
+    ...legacy ? ['--width', String(options.window.width), '--height', String(options.window.height)] : [],
+    ...options.window.fullscreen ? ['--fullscreen'] : [],
+    ...options.gameArgs
+  ]
+}
+
+const utf8 = new TextDecoder('utf-8', { fatal: true })
+const windows1252 = new TextDecoder('windows-1252')
+
+// Game output is UTF-8 with the arguments MegaClient adds, but mods and user
+// arguments can still print in the system code page, so a line that is not
+// valid UTF-8 is read as Windows-1252 rather than shown with broken characters.
+function decodeLine(bytes: Uint8Array): string {
+  try {
+    return utf8.decode(bytes)
+  } catch {
+    return windows1252.decode(bytes)
+  }
+}
+
+// Launches vanilla, Fabric and custom profiles with MegaClient's own command
+// line, so the profile that was verified and prepared is exactly the one that
+// runs. It reports through the same events as eml-lib's launcher.
+export class DirectLauncher extends EventEmitter<DirectLauncherEvents> {
+  constructor(private readonly options: DirectLaunchOptions) {
+    super()
+  }
+
+  async launch(): Promise<void> {
+    const args = await buildLaunchCommand(this.options)
+    this.options.signal?.throwIfAborted()
+    const token = this.options.account.accessToken
+    this.emit('launch_debug', `Launching Minecraft with args: ${args.map((arg) => token ? arg.replaceAll(token, '**********') : arg).join(' ')}`)
+
+    const child = spawn(this.options.javaPath, args, { cwd: this.options.gameDirectory, stdio: ['ignore', 'pipe', 'pipe'], windowsHide: true })
+    await new Promise<void>((resolve, reject) => {
+      child.once('spawn', resolve)
+      child.once('error', reject)
+    })
+    child.on('error', (error) => this.emit('launch_data', `[Launcher] ${error.message}`))
+    this.emitLines(child.stdout)
+    this.emitLines(child.stderr)
+    child.once('close', (code) => this.emit('launch_close', code))
+    this.emit('launch_launch')
+  }
+
+  // Splits the output on newline bytes before decoding, so a character split
+  // across two reads is decoded whole.
+  private emitLines(stream: NodeJS.ReadableStream): void {
+    let pending = Buffer.alloc(0)
+    stream.on('data', (chunk: Buffer) => {
+      pending = pending.length ? Buffer.concat([pending, chunk]) : chunk
+      let newline: number
+      while ((newline = pending.indexOf(0x0a)) !== -1) {
+        this.emit('launch_data', decodeLine(pending.subarray(0, newline)).replace(/\r$/, ''))
+        pending = pending.subarray(newline + 1)
+      }
+    })
+    stream.on('end', () => {
+      if (pending.length) this.emit('launch_data', decodeLine(pending))
+    })
+  }
+}
@@ -0,0 +1,198 @@
+import path from 'node:path'
+import { AsyncLocalStorage } from 'node:async_hooks'
+import type { DownloadItem } from '../types'
+import { downloadFile } from './net'
+
+type DownloadOptions = NonNullable<Parameters<typeof downloadFile>[3]> & { label?: string }
+
+interface QueuedDownload {
+  item: DownloadItem
+  url: string
+  destination: string
+  options: DownloadOptions
+  onProgress?: (downloaded: number, total: number) => void
+  pause: AbortController
+  start: () => void
+  background: boolean
+}
+
+const FINISHED_HISTORY = 200
+// Low-priority downloads never take more than this many slots.
+const BACKGROUND_ACTIVE = 2
+
+const queue: QueuedDownload[] = []
+const active = new Set<QueuedDownload>()
+const finished: DownloadItem[] = []
+let paused = false
+let maxActive = 6
+let nextId = 0
+let sendEvent: (item: DownloadItem) => void = () => undefined
+let bytesPerSecond = 0
+let throttledUntil = 0
+const lastProgressAt = new Map<string, number>()
+const lowPriority = new AsyncLocalStorage<boolean>()
+
+export function setDownloadEventSender(sender: (item: DownloadItem) => void): void {
+  sendEvent = sender
+}
+
+export function setDownloadConcurrency(limit: number): void {
+  maxActive = Math.max(1, Math.min(16, Math.floor(limit) || 6))
+  pump()
+}
+
+export function downloadConcurrency(): number {
+  return maxActive
+}
+
+export async function runWithConcurrency<T>(items: T[], limit: number, task: (item: T) => Promise<void>): Promise<void> {
+  let next = 0
+  await Promise.all(Array.from({ length: Math.min(limit, items.length) }, async () => {
+    while (next < items.length) await task(items[next++]!)
+  }))
+}
+
+// One budget shared by every active download. Up to a second of unused budget
+// can be spent at once, so short transfers are not slowed needlessly.
+export function setDownloadSpeedLimit(kilobytesPerSecond: number): void {
+  bytesPerSecond = Math.max(0, kilobytesPerSecond) * 1024
+}
+
+async function throttle(bytes: number): Promise<void> {
+  if (!bytesPerSecond) return
+  const now = Date.now()
+  throttledUntil = Math.max(throttledUntil, now - 1000) + (bytes / bytesPerSecond) * 1000
+  if (throttledUntil > now) await new Promise((resolve) => setTimeout(resolve, throttledUntil - now))
+}
+
+function publish(entry: QueuedDownload, status: DownloadItem['status'], error?: string): void {
+  entry.item = { ...entry.item, status, error, updatedAt: new Date().toISOString() }
+  if (status === 'completed' || status === 'failed' || status === 'cancelled') {
+    lastProgressAt.delete(entry.item.id)
+    finished.unshift(entry.item)
+    finished.splice(FINISHED_HISTORY)
+  }
+  sendEvent(entry.item)
+}
+
+// Queued downloads start in order, except that low-priority ones wait while
+// anything else is queued.
+function pump(): void {
+  while (!paused && active.size < maxActive && queue.length) {
+    const next = queue.findIndex((entry) => !entry.background)
+    if (next < 0 && [...active].filter((entry) => entry.background).length >= BACKGROUND_ACTIVE) break
+    const [entry] = queue.splice(Math.max(next, 0), 1)
+    active.add(entry!)
+    entry!.start()
+  }
+}
+
+// Downloads started anywhere inside the task, however deeply, are low
+// priority, so idle-time work can reuse the launch code unchanged.
+export function runAtLowPriority<T>(task: () => Promise<T>): Promise<T> {
+  return lowPriority.run(true, task)
+}
+
+// Every file MegaClient downloads itself goes through this queue: game files,
+// libraries, Java runtimes, mods, packs and worlds. Pausing stops resumable
+// transfers, which continue from their partial file later, and holds queued
+// ones; other transfers are short and finish normally. Files the bundled
+// launcher library downloads during a launch do not pass through here.
+export function download(
+  url: string,
+  destination: string,
+  onProgress?: (downloaded: number, total: number) => void,
+  options: DownloadOptions = {}
+): Promise<void> {
+  options.signal?.throwIfAborted()
+  const now = new Date().toISOString()
+  const entry: QueuedDownload = {
+    item: {
+      id: String(++nextId),
+      url,
+      fileName: path.basename(destination),
+      label: options.label,
+      status: 'queued',
+      downloaded: 0,
+      total: 0,
+      createdAt: now,
+      updatedAt: now
+    },
+    url,
+    destination,
+    options,
+    onProgress,
+    pause: new AbortController(),
+    start: () => undefined,
+    background: lowPriority.getStore() ?? false
+  }
+
+  return new Promise<void>((resolve, reject) => {
+    const cancelQueued = (): void => {
+      const index = queue.indexOf(entry)
+      if (index < 0) return
+      queue.splice(index, 1)
+      publish(entry, 'cancelled')
+      reject(options.signal!.reason)
+    }
+    options.signal?.addEventListener('abort', cancelQueued, { once: true })
+
+    entry.start = () => {
+      options.signal?.removeEventListener('abort', cancelQueued)
+      publish(entry, 'downloading')
+      const signals = [entry.pause.signal, ...(options.signal ? [options.signal] : [])]
+      void downloadFile(url, destination, (downloaded, total) => {
+        entry.item = { ...entry.item, downloaded, total }
+        onProgress?.(downloaded, total)
+        const at = Date.now()
+        if (at - (lastProgressAt.get(entry.item.id) ?? 0) < 250 && downloaded < total) return
+        lastProgressAt.set(entry.item.id, at)
+        sendEvent({ ...entry.item, updatedAt: new Date(at).toISOString() })
+      }, { ...options, signal: AbortSignal.any(signals), throttle }).then(() => {
+        active.delete(entry)
+        publish(entry, 'completed')
+        resolve()
+        pump()
+      }, (error: unknown) => {
+        active.delete(entry)
+        if (entry.pause.signal.aborted && !options.signal?.aborted) {
+          // Paused mid-transfer: queue it again at the front with a fresh
+          // controller, so it resumes first.
+          entry.pause = new AbortController()
+          queue.unshift(entry)
+          options.signal?.addEventListener('abort', cancelQueued, { once: true })
+          publish(entry, 'paused')
+        } else {
+          publish(entry, options.signal?.aborted ? 'cancelled' : 'failed', error instanceof Error ? error.message : String(error))
+          reject(error)
+        }
+        pump()
+      })
+    }
+
+    queue.push(entry)
+    publish(entry, paused ? 'paused' : 'queued')
+    pump()
+  })
+}
+
+export function pauseDownloads(): void {
+  if (paused) return
+  paused = true
+  for (const entry of queue) publish(entry, 'paused')
+  for (const entry of active) if (entry.options.resume) entry.pause.abort()
+}
+
+export function resumeDownloads(): void {
+  if (!paused) return
+  paused = false
+  for (const entry of queue) publish(entry, 'queued')
+  pump()
+}
+
+export function listDownloads(): { paused: boolean; items: DownloadItem[] } {
+  return {
+    paused,
+    items: [...[...active].map((entry) => entry.item), ...queue.map((entry) => entry.item), ...finished]
+  }
+}
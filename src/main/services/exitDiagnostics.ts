@@ -0,0 +1,104 @@
+import type { ExitExplanation } from '../types'
+
+interface LogSignature extends ExitExplanation {
+  pattern: RegExp
+}
+
+// Windows reports NTSTATUS codes as signed 32-bit exit codes; shells on macOS
+// and Linux report death by signal as 128 + the signal number.
+const EXIT_CODES = new Map<number, ExitExplanation>([
+  [-1073740791, {
+    id: 'stack-buffer-overrun',
+    title: 'The graphics driver crashed the game',
+    detail: 'Exit code -1073740791 (0xC0000409) is almost always raised inside the graphics driver. Update your GPU driver from the NVIDIA, AMD or Intel website.'
+  }],
+  [-1073741819, {
+    id: 'access-violation',
+    title: 'Native code crashed the game',
+    detail: 'Exit code -1073741819 (0xC0000005) is a memory access violation in a driver or native library. Update your GPU driver and remove overlays or injectors.'
+  }],
+  [-1073741571, {
+    id: 'stack-overflow',
+    title: 'The game ran out of stack space',
+    detail: 'Exit code -1073741571 (0xC00000FD) usually comes from a mod stuck in recursion. Try launching with mods disabled.'
+  }],
+  [-805306369, {
+    id: 'forced-close',
+    title: 'Windows closed the game',
+    detail: 'Exit code -805306369 means the game was ended while it was not responding.'
+  }],
+  [137, {
+    id: 'killed',
+    title: 'The game was killed',
+    detail: 'Exit code 137 means the process received SIGKILL, most often from the system running out of memory. Lower the memory given to the game or close other programs.'
+  }],
+  [134, {
+    id: 'aborted',
+    title: 'Java aborted',
+    detail: 'Exit code 134 means the JVM aborted, usually after a native crash. Look for an hs_err log in the instance folder.'
+  }],
+  [139, {
+    id: 'segfault',
+    title: 'Native code crashed the game',
+    detail: 'Exit code 139 is a segmentation fault in a driver or native library. Update your graphics driver.'
+  }]
+])
+
+const LOG_SIGNATURES: LogSignature[] = [
+  {
+    id: 'out-of-memory',
+    pattern: /java\.lang\.OutOfMemoryError/,
+    title: 'Minecraft ran out of memory',
+    detail: 'Give the instance more memory in its settings, or remove heavy mods and resource packs.'
+  },
+  {
+    id: 'heap-reservation',
+    pattern: /Could not reserve enough space for (?:object heap|\d+KB object heap)/,
+    title: 'Java could not reserve the requested memory',
+    detail: 'The maximum memory is larger than this computer or a 32-bit Java can provide. Lower it in the instance settings.'
+  },
+  {
+    id: 'missing-fabric-api',
+    pattern: /requires (?:any version of |version [^\n]* of )?(?:fabric-api|Fabric API)|depends on[^\n]*\bfabric(?:-api)?\b(?![-\w])/i,
+    title: 'Fabric API is missing',
+    detail: 'A mod needs Fabric API. Install it from the mod browser for this Minecraft version.'
+  },
+  {
+    id: 'incompatible-mods',
+    pattern: /Incompatible mods? found!|Mod resolution failed|Missing or unsupported mandatory dependencies/,
+    title: 'Some mods do not fit this instance',
+    detail: 'The loader refused to start because of a missing dependency or a mod for another version. The console lists the mods involved.'
+  },
+  {
+    id: 'wrong-java',
+    pattern: /UnsupportedClassVersionError|has been compiled by a more recent version of the Java Runtime/,
+    title: 'The Java version is too old',
+    detail: 'A mod or the game needs a newer Java. Switch Java back to automatic in Settings.'
+  },
+  {
+    id: 'opengl',
+    pattern: /Pixel format not accelerated|GLFW error 65542|WGL: The driver does not appear to support OpenGL|Failed to create the OpenGL context/,
+    title: 'OpenGL is not available',
+    detail: 'The graphics driver is missing or too old for this Minecraft version. Install the driver from your GPU vendor.'
+  },
+  {
+    id: 'mixin',
+    pattern: /Mixin apply(?:ing)? (?:for|of) .* failed|MixinApplyError|InvalidInjectionException/,
+    title: 'A mod failed to patch the game',
+    detail: 'Two mods conflict or a mod does not support this version. The crash details name the mod.'
+  }
+]
+
+// Looks up an exit code and scans the end of the game output for known
+// failure signatures, returning friendly explanations in that order.
+export function explainExit(code: number | null, lines: string[]): ExitExplanation[] {
+  if (code === 0) return []
+  const explanations: ExitExplanation[] = []
+  const known = code === null ? undefined : EXIT_CODES.get(code)
+  if (known) explanations.push(known)
+  const text = lines.slice(-1500).join('\n')
+  for (const { pattern, ...explanation } of LOG_SIGNATURES) {
+    if (pattern.test(text)) explanations.push(explanation)
+  }
+  return explanations
+}
@@ -0,0 +1,241 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import type { LauncherInstance, ProgressDetail } from '../types'
+import { fetchJson, hashFile } from './net'
+import { download, downloadConcurrency, runWithConcurrency } from './downloads'
+import { applicableLibraries, containedPath, installVersionJson, readVersionJson, resolveVersionJson, versionJsonPath, type LibraryArtifact } from './versionJson'
+import { minecraftVersionEntry } from './versions'
+import { logger } from './logger'
+
+export interface GameFileCheck {
+  checked: number
+  damaged: string[]
+  repaired: string[]
+}
+
+// Hashes by path, size and modification time, so launches after the first
+// only hash files that changed since.
+const fileHashCache = new Map<string, string>()
+
+async function fileMatches(file: string, sha1: string, size?: number): Promise<boolean> {
+  const stat = await fs.stat(file).catch(() => null)
+  if (!stat?.isFile()) return false
+  // A size mismatch is conclusive and avoids hashing large files needlessly.
+  if (size && stat.size !== size) return false
+  const key = `${file}:${stat.size}:${stat.mtimeMs}`
+  let hash = fileHashCache.get(key)
+  if (!hash) {
+    hash = (await hashFile(file, 'sha1')).toLowerCase()
+    fileHashCache.set(key, hash)
+    if (fileHashCache.size > 10_000) fileHashCache.delete(fileHashCache.keys().next().value!)
+  }
+  return hash === sha1.toLowerCase()
+}
+
+export interface GameFileOptions {
+  repair?: boolean
+  onProgress?: (message: string, progress?: number, detail?: ProgressDetail) => void
+  signal?: AbortSignal
+  // Told the size of a repair before it starts; throwing stops it.
+  confirmDownload?: (bytes: number) => Promise<void>
+}
+
+// Checks every library the version profile needs on this platform against the
+// SHA-1 Mojang and the loaders publish, and downloads a fresh copy of any file
+// that is missing or damaged. Libraries without a published hash are skipped.
+export async function verifyLibraries(gameDirectory: string, versionId: string, options: GameFileOptions = {}): Promise<GameFileCheck> {
+  const result: GameFileCheck = { checked: 0, damaged: [], repaired: [] }
+  const version = await resolveVersionJson(gameDirectory, versionId).catch(() => null)
+  if (!version) return result
+
+  const librariesDirectory = path.join(gameDirectory, 'libraries')
+  // Checked in parallel, so a library listed twice must not be fetched twice.
+  const artifacts = [...new Map(applicableLibraries(version)
+    .map((library) => (library.downloads as { artifact?: LibraryArtifact } | undefined)?.artifact)
+    .filter((artifact): artifact is Required<Pick<LibraryArtifact, 'path' | 'sha1'>> & LibraryArtifact => Boolean(artifact?.path && artifact.sha1))
+    .map((artifact) => [artifact.path, artifact])).values()]
+
+  await runWithConcurrency(artifacts, downloadConcurrency(), async (artifact) => {
+    options.signal?.throwIfAborted()
+    options.onProgress?.('Verifying libraries', artifacts.length ? result.checked / artifacts.length : undefined, { items: { current: result.checked, total: artifacts.length } })
+    result.checked++
+    const file = containedPath(librariesDirectory, artifact.path)
+    if (!file || await fileMatches(file, artifact.sha1, artifact.size)) return
+    result.damaged.push(artifact.path)
+    if (options.repair === false || !artifact.url) return
+    try {
+      await download(artifact.url, file, undefined, { signal: options.signal, resume: true, sha1: artifact.sha1, size: artifact.size })
+      result.repaired.push(artifact.path)
+    } catch (error) {
+      options.signal?.throwIfAborted()
+      logger.warn(`Library ${artifact.path} could not be repaired.`, { error })
+    }
+  })
+  return result
+}
+
+// Profiles installed before they were stored byte for byte were reformatted,
+// so one that differs from the published file only in whitespace is intact.
+// Without a connection it cannot be told apart and is taken as intact.
+async function versionJsonMatches(file: string, url: string, sha1: string): Promise<boolean> {
+  if (await fileMatches(file, sha1)) return true
+  const local = await fs.readFile(file, 'utf8').then((text) => JSON.stringify(JSON.parse(text))).catch(() => null)
+  if (!local) return false
+  const published = await fetchJson<unknown>(url).then((value) => JSON.stringify(value)).catch(() => null)
+  return published === null || local === published
+}
+
+// The vanilla version JSON is checked against the SHA-1 in Mojang's version
+// manifest and the client jar against the one in that JSON, and either is
+// downloaded again when it does not match. A custom profile's own jar may be
+// modified on purpose and is left alone.
+export async function verifyVersionFiles(gameDirectory: string, instance: LauncherInstance, options: GameFileOptions = {}): Promise<GameFileCheck> {
+  const result: GameFileCheck = { checked: 0, damaged: [], repaired: [] }
+  const id = instance.minecraftVersion
+  const jsonFile = versionJsonPath(gameDirectory, id)
+  const repairFile = async (relative: string, url: string, file: string, sha1: string, size?: number): Promise<void> => {
+    result.damaged.push(relative)
+    if (options.repair === false) return
+    try {
+      await download(url, file, undefined, { signal: options.signal, sha1, size })
+      result.repaired.push(relative)
+    } catch (error) {
+      options.signal?.throwIfAborted()
+      logger.warn(`${relative} could not be repaired.`, { error })
+    }
+  }
+
+  options.onProgress?.('Verifying the version profile')
+  const entry = await minecraftVersionEntry(id).catch(() => undefined)
+  if (entry?.sha1) {
+    result.checked++
+    if (!await versionJsonMatches(jsonFile, entry.url, entry.sha1)) await repairFile(`versions/${id}/${id}.json`, entry.url, jsonFile, entry.sha1)
+  }
+
+  const client = (await readVersionJson(gameDirectory, id))?.downloads?.client
+  if (client?.url && client.sha1 && instance.customVersionId !== id) {
+    options.onProgress?.(`Verifying Minecraft ${id}`)
+    result.checked++
+    const jar = path.join(path.dirname(jsonFile), `${id}.jar`)
+    if (!await fileMatches(jar, client.sha1, client.size)) await repairFile(`versions/${id}/${id}.jar`, client.url, jar, client.sha1, client.size)
+  }
+  return result
+}
+
+interface AssetIndexFile {
+  objects?: Record<string, { hash: string; size: number }>
+}
+
+const ASSET_BASE_URL = 'https://resources.download.minecraft.net'
+
+// Asset objects are named after their own SHA-1, so the index is the only
+// reference needed. Launches use the quick size check; a thorough check also
+// hashes every object and is used by instance verification.
+export async function verifyAssets(
+  gameDirectory: string,
+  versionId: string,
+  options: GameFileOptions & { thorough?: boolean } = {}
+): Promise<GameFileCheck> {
+  const result: GameFileCheck = { checked: 0, damaged: [], repaired: [] }
+  const version = await resolveVersionJson(gameDirectory, versionId).catch(() => null)
+  const indexId = version?.assetIndex?.id ?? version?.assets
+  if (!indexId) return result
+  let index: AssetIndexFile
+  try {
+    index = JSON.parse(await fs.readFile(path.join(gameDirectory, 'assets', 'indexes', `${indexId}.json`), 'utf8')) as AssetIndexFile
+  } catch {
+    return result
+  }
+
+  const objects = [...new Map(Object.values(index.objects ?? {}).map((object) => [object.hash, object])).values()]
+    .filter((object) => /^[0-9a-f]{40}$/i.test(object.hash))
+  const missing: typeof objects = []
+  await runWithConcurrency(objects, downloadConcurrency(), async (object) => {
+    options.signal?.throwIfAborted()
+    if (result.checked % 200 === 0) options.onProgress?.('Verifying assets', objects.length ? result.checked / objects.length : undefined, { items: { current: result.checked, total: objects.length } })
+    result.checked++
+    const relative = `${object.hash.slice(0, 2)}/${object.hash}`
+    const file = path.join(gameDirectory, 'assets', 'objects', object.hash.slice(0, 2), object.hash)
+    const stat = await fs.stat(file).catch(() => null)
+    const valid = stat?.isFile() && stat.size === object.size
+      && (!options.thorough || await fileMatches(file, object.hash, object.size))
+    if (valid) return
+    result.damaged.push(relative)
+    missing.push(object)
+  })
+  if (options.repair === false || !missing.length) return result
+
+  // A fresh install can be several hundred megabytes, so the whole repair is
+  // sized and confirmed before any of it is downloaded.
+  await options.confirmDownload?.(missing.reduce((total, object) => total + object.size, 0))
+  let downloaded = 0
+  await runWithConcurrency(missing, downloadConcurrency(), async (object) => {
+    options.signal?.throwIfAborted()
+    const relative = `${object.hash.slice(0, 2)}/${object.hash}`
+    const file = path.join(gameDirectory, 'assets', 'objects', object.hash.slice(0, 2), object.hash)
+    try {
+      await download(`${ASSET_BASE_URL}/${relative}`, file, undefined, { signal: options.signal, sha1: object.hash, size: object.size })
+      result.repaired.push(relative)
+    } catch (error) {
+      options.signal?.throwIfAborted()
+      logger.warn(`Asset ${relative} could not be repaired.`, { error })
+    }
+    downloaded++
+    if (downloaded % 50 === 0 || downloaded === missing.length) {
+      options.onProgress?.('Downloading assets', downloaded / missing.length, { items: { current: downloaded, total: missing.length } })
+    }
+  })
+  return result
+}
+
+async function ensureDownload(url: string, file: string, sha1?: string, size?: number, signal?: AbortSignal): Promise<boolean> {
+  if (sha1 ? await fileMatches(file, sha1, size) : await fs.stat(file).catch(() => null)) return false
+  await download(url, file, undefined, { signal, resume: true, sha1, size })
+  return true
+}
+
+// Installs the version metadata, client jar and asset index that the launch
+// would otherwise download, so an instance can be fully prepared ahead of
+// time. Forge and NeoForge still run their installers on the first launch.
+export async function installVersionFiles(
+  gameDirectory: string,
+  instance: LauncherInstance,
+  onProgress?: (message: string, progress?: number) => void,
+  signal?: AbortSignal
+): Promise<void> {
+  onProgress?.(`Downloading Minecraft ${instance.minecraftVersion} metadata`)
+  let vanilla = await readVersionJson(gameDirectory, instance.minecraftVersion)
+  if (!vanilla) {
+    const entry = await minecraftVersionEntry(instance.minecraftVersion)
+    if (!entry) throw new Error(`Minecraft ${instance.minecraftVersion} could not be found in Mojang's version manifest.`)
+    // Stored byte for byte, so it can later be checked against the manifest.
+    await download(entry.url, versionJsonPath(gameDirectory, instance.minecraftVersion), undefined, { signal, sha1: entry.sha1 })
+    vanilla = await readVersionJson(gameDirectory, instance.minecraftVersion)
+    if (!vanilla) throw new Error(`The Minecraft ${instance.minecraftVersion} version profile could not be read.`)
+  }
+
+  if (instance.loader === 'fabric' && instance.loaderVersion) {
+    const id = `fabric-loader-${instance.loaderVersion}-${instance.minecraftVersion}`
+    if (!await readVersionJson(gameDirectory, id)) {
+      onProgress?.(`Downloading Fabric ${instance.loaderVersion} metadata`)
+      await installVersionJson(gameDirectory, await fetchJson(
+        `https://meta.fabricmc.net/v2/versions/loader/${encodeURIComponent(instance.minecraftVersion)}/${encodeURIComponent(instance.loaderVersion)}/profile/json`,
+        { signal }
+      ))
+    }
+  }
+
+  const client = vanilla.downloads?.client
+  const jar = path.join(path.dirname(versionJsonPath(gameDirectory, vanilla.id)), `${vanilla.id}.jar`)
+  // A standalone custom profile may ship a modified jar, which is kept as is.
+  const customJar = instance.customVersionId === vanilla.id && await fs.stat(jar).catch(() => null)
+  if (client?.url && !customJar) {
+    onProgress?.(`Downloading Minecraft ${instance.minecraftVersion}`)
+    await ensureDownload(client.url, jar, client.sha1, client.size, signal)
+  }
+  const assetIndex = vanilla.assetIndex
+  if (assetIndex?.url && /^[\w.-]+$/.test(assetIndex.id)) {
+    onProgress?.('Downloading the asset index')
+    await ensureDownload(assetIndex.url, path.join(gameDirectory, 'assets', 'indexes', `${assetIndex.id}.json`), assetIndex.sha1, assetIndex.size, signal)
+  }
+}
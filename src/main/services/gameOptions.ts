@@ -0,0 +1,53 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import type { LauncherInstance } from '../types'
+import { instanceDirectory } from './paths'
+
+const RESOURCE_PACKS_KEY = 'resourcePacks'
+
+function optionsPath(instance: LauncherInstance): string {
+  return path.join(instanceDirectory(instance.slug), 'options.txt')
+}
+
+async function readLines(instance: LauncherInstance): Promise<string[]> {
+  const text = await fs.readFile(optionsPath(instance), 'utf8').catch(() => '')
+  return text ? text.split(/\r?\n/) : []
+}
+
+function listValue(lines: string[], key: string): string[] | null {
+  const line = lines.find((item) => item.startsWith(`${key}:`))
+  if (!line) return null
+  try {
+    const value = JSON.parse(line.slice(key.length + 1)) as unknown
+    return Array.isArray(value) ? value.filter((item): item is string => typeof item === 'string') : []
+  } catch {
+    return []
+  }
+}
+
+// The resource packs the game loads, lowest priority first, as options.txt
+// names them: "vanilla" and the like for built-in packs, "file/<name>" for
+// packs in the resourcepacks folder.
+export async function enabledResourcePacks(instance: LauncherInstance): Promise<string[]> {
+  return listValue(await readLines(instance), RESOURCE_PACKS_KEY) ?? []
+}
+
+// Adds or removes one pack from the list the game loads. A pack that is
+// turned on goes to the top, above every pack already enabled. The rest of
+// options.txt is written back unchanged.
+export async function setResourcePackEnabled(instance: LauncherInstance, fileName: string, enabled: boolean): Promise<void> {
+  const lines = await readLines(instance)
+  const entry = `file/${fileName}`
+  const current = listValue(lines, RESOURCE_PACKS_KEY) ?? []
+  if (current.includes(entry) === enabled) return
+  const next = enabled ? [...(current.length ? current : ['vanilla']), entry] : current.filter((item) => item !== entry)
+  const line = `${RESOURCE_PACKS_KEY}:${JSON.stringify(next)}`
+  const index = lines.findIndex((item) => item.startsWith(`${RESOURCE_PACKS_KEY}:`))
+  if (index >= 0) lines[index] = line
+  else {
+    while (lines.length && !lines[lines.length - 1]) lines.pop()
+    lines.push(line, '')
+  }
+  await fs.mkdir(path.dirname(optionsPath(instance)), { recursive: true })
+  await fs.writeFile(optionsPath(instance), lines.join('\n'), 'utf8')
+}
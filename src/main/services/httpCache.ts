@@ -0,0 +1,105 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import { createHash, randomBytes } from 'node:crypto'
+import { sendRequest, type RequestLimiter } from './net'
+import { logger } from './logger'
+import { isOfflineMode } from './connectivity'
+import { httpCacheDirectory } from './paths'
+
+interface CachedResponse {
+  url: string
+  etag?: string
+  lastModified?: string
+  fetchedAt: number
+  body: string
+}
+
+export interface CachedFetchOptions {
+  // How long a stored response is used without asking the server at all.
+  maxAgeMs?: number
+  // Serve the stored response, however old, when the server cannot be reached.
+  staleIfError?: boolean
+  signal?: AbortSignal
+  limiter?: RequestLimiter
+  retries?: number
+}
+
+const inFlight = new Map<string, Promise<string>>()
+
+function entryPath(url: string): string {
+  return path.join(httpCacheDirectory(), `${createHash('sha1').update(url).digest('hex')}.json`)
+}
+
+async function readEntry(url: string): Promise<CachedResponse | null> {
+  try {
+    const entry = JSON.parse(await fs.readFile(entryPath(url), 'utf8')) as CachedResponse
+    return entry.url === url && typeof entry.body === 'string' ? entry : null
+  } catch {
+    return null
+  }
+}
+
+async function writeEntry(entry: CachedResponse): Promise<void> {
+  const file = entryPath(entry.url)
+  await fs.mkdir(path.dirname(file), { recursive: true })
+  // Unique per write, since requests with a signal are not deduplicated and
+  // can write the same entry at once.
+  const temp = `${file}.${process.pid}.${randomBytes(6).toString('hex')}.tmp`
+  try {
+    await fs.writeFile(temp, JSON.stringify(entry), 'utf8')
+    await fs.rename(temp, file)
+  } finally {
+    await fs.rm(temp, { force: true }).catch(() => undefined)
+  }
+}
+
+async function revalidate(url: string, options: CachedFetchOptions): Promise<string> {
+  const cached = await readEntry(url)
+  if (cached && (isOfflineMode() || Date.now() - cached.fetchedAt < (options.maxAgeMs ?? 0))) return cached.body
+  const headers: Record<string, string> = {}
+  if (cached?.etag) headers['If-None-Match'] = cached.etag
+  if (cached?.lastModified) headers['If-Modified-Since'] = cached.lastModified
+  let response: Response
+  try {
+    response = await sendRequest(url, { headers, signal: options.signal }, { limiter: options.limiter, retries: options.retries })
+    if (response.status >= 500 && cached && options.staleIfError) throw new Error(`Request failed (${response.status})`)
+  } catch (error) {
+    options.signal?.throwIfAborted()
+    if (!cached || !options.staleIfError) throw error
+    logger.warn('Using a stored response because the server could not be reached.', { url, fetchedAt: new Date(cached.fetchedAt).toISOString(), error })
+    return cached.body
+  }
+  if (response.status === 304 && cached) {
+    await writeEntry({ ...cached, fetchedAt: Date.now() }).catch(() => undefined)
+    return cached.body
+  }
+  if (!response.ok) {
+    const text = await response.text().catch(() => '')
+    throw new Error(`Request failed (${response.status})${text ? `: ${text.slice(0, 240)}` : ''}`)
+  }
+  const body = await response.text()
+  await writeEntry({
+    url,
+    etag: response.headers.get('etag') ?? undefined,
+    lastModified: response.headers.get('last-modified') ?? undefined,
+    fetchedAt: Date.now(),
+    body
+  }).catch(() => undefined)
+  return body
+}
+
+// Metadata responses are kept on disk and revalidated with ETag and
+// Last-Modified, so a refresh that finds nothing new costs a 304 and repeated
+// loads within maxAgeMs cost no request at all.
+export async function fetchCachedText(url: string, options: CachedFetchOptions = {}): Promise<string> {
+  if (options.signal) return revalidate(url, options)
+  const existing = inFlight.get(url)
+  if (existing) return existing
+  const request = revalidate(url, options).finally(() => inFlight.delete(url))
+  inFlight.set(url, request)
+  return request
+}
+
+export async function fetchCachedJson<T>(url: string, options: CachedFetchOptions = {}): Promise<T> {
+  return JSON.parse(await fetchCachedText(url, options)) as T
+}
@@ -0,0 +1,604 @@
+import { app } from 'electron'
+import fs from 'node:fs/promises'
+import { constants } from 'node:fs'
+import path from 'node:path'
+import type { LauncherInstance, LoaderType, TrackedMod, VersionTrack } from '../types'
+import { createInstance, updateInstance } from './instances'
+import { trackImportedMods } from './modrinth'
+import { downloadObject } from './objectStore'
+import { fetchJson } from './net'
+import { instanceDirectory, modsDirectory, vanillaMinecraftDirectory } from './paths'
+import { store } from './store'
+import { readVersionJson, versionJsonPath } from './versionJson'
+import { getMinecraftVersions, resolveMinecraftVersionId, versionAliasTrack } from './versions'
+import { logger } from './logger'
+
+interface MultiMcComponent {
+  uid: string
+  version?: string
+  cachedVersion?: string
+}
+
+interface MultiMcPack {
+  formatVersion?: number
+  components?: MultiMcComponent[]
+}
+
+const MULTIMC_LOADERS = new Map<string, LoaderType>([
+  ['net.fabricmc.fabric-loader', 'fabric'],
+  ['net.minecraftforge', 'forge'],
+  ['net.neoforged', 'neoforge']
+])
+
+function parseInstanceConfig(text: string): Record<string, string> {
+  const values: Record<string, string> = {}
+  for (const line of text.split(/\r?\n/)) {
+    const match = line.match(/^\s*([^#;[][^=]*?)\s*=\s*(.*)$/)
+    if (match) values[match[1]!] = match[2]!.trim()
+  }
+  return values
+}
+
+async function firstDirectory(candidates: string[]): Promise<string | null> {
+  for (const candidate of candidates) {
+    const stat = await fs.stat(candidate).catch(() => null)
+    if (stat?.isDirectory()) return candidate
+  }
+  return null
+}
+
+async function copyGameDirectory(source: string, instance: LauncherInstance): Promise<void> {
+  const destination = instanceDirectory(instance.slug)
+  await fs.mkdir(destination, { recursive: true })
+  for (const entry of await fs.readdir(source, { withFileTypes: true })) {
+    // Other launchers' own caches and logs are not useful in a fresh instance.
+    if (['logs', 'crash-reports', '.fabric', '.cache'].includes(entry.name)) continue
+    await fs.cp(path.join(source, entry.name), path.join(destination, entry.name), { recursive: true, force: true })
+  }
+}
+
+export async function importMultiMcInstance(folder: string): Promise<LauncherInstance> {
+  const configText = await fs.readFile(path.join(folder, 'instance.cfg'), 'utf8').catch(() => null)
+  if (configText == null) throw new Error('This folder is not a MultiMC or Prism Launcher instance (instance.cfg is missing).')
+  const packText = await fs.readFile(path.join(folder, 'mmc-pack.json'), 'utf8').catch(() => '{}')
+  let pack: MultiMcPack
+  try {
+    pack = JSON.parse(packText) as MultiMcPack
+  } catch {
+    throw new Error("The instance's mmc-pack.json is damaged.")
+  }
+  const config = parseInstanceConfig(configText)
+  const components = Array.isArray(pack.components) ? pack.components : []
+
+  const minecraft = components.find((component) => component.uid === 'net.minecraft')
+  const minecraftVersion = minecraft?.version ?? minecraft?.cachedVersion ?? config.IntendedVersion
+  if (!minecraftVersion) throw new Error('The instance does not declare a Minecraft version.')
+
+  let loader: LoaderType = 'vanilla'
+  let loaderVersion: string | undefined
+  for (const component of components) {
+    const mapped = MULTIMC_LOADERS.get(component.uid)
+    if (!mapped) {
+      if (component.uid === 'org.quiltmc.quilt-loader') throw new Error('Quilt instances are not supported by MegaClient yet.')
+      continue
+    }
+    loader = mapped
+    const version = component.version ?? component.cachedVersion
+    loaderVersion = mapped === 'forge' && version && !version.startsWith(`${minecraftVersion}-`) ? `${minecraftVersion}-${version}` : version
+  }
+
+  const gameDirectory = await firstDirectory([path.join(folder, '.minecraft'), path.join(folder, 'minecraft')])
+  let instance = await createInstance({
+    name: config.name?.trim() || path.basename(folder),
+    minecraftVersion,
+    loader,
+    loaderVersion
+  })
+  if (gameDirectory) await copyGameDirectory(gameDirectory, instance)
+
+  const overrides: Partial<LauncherInstance> = {}
+  if (config.OverrideMemory === 'true') {
+    overrides.memoryMin = Number(config.MinMemAlloc) || undefined
+    overrides.memoryMax = Number(config.MaxMemAlloc) || undefined
+  }
+  if (config.OverrideJavaArgs === 'true' && config.JvmArgs) overrides.jvmArgs = config.JvmArgs
+  if (config.notes) overrides.notes = config.notes.replace(/\\n/g, '\n')
+  if (Object.keys(overrides).length) instance = await updateInstance(instance.id, overrides)
+  return instance
+}
+
+interface VanillaLauncherProfile {
+  name?: string
+  type?: string
+  lastVersionId?: string
+  gameDir?: string
+  javaArgs?: string
+  lastUsed?: string
+}
+
+export interface VanillaProfileSummary {
+  id: string
+  name: string
+  minecraftVersion?: string
+  loader: LoaderType
+  loaderVersion?: string
+  lastUsed?: string
+  track?: VersionTrack
+}
+
+export interface VanillaInstallation {
+  directory: string
+  profiles: VanillaProfileSummary[]
+}
+
+const VANILLA_IMPORT_ITEMS = ['saves', 'resourcepacks', 'shaderpacks', 'options.txt', 'servers.dat']
+
+function neoForgeMinecraftVersion(version: string): string | undefined {
+  const [major, minor] = version.split(/[.-]/).map(Number)
+  if (!Number.isFinite(major) || !Number.isFinite(minor)) return undefined
+  if (major! >= 25) return `${major}.${minor}`
+  return minor ? `1.${major}.${minor}` : `1.${major}`
+}
+
+function mapVersionId(versionId: string): Pick<VanillaProfileSummary, 'minecraftVersion' | 'loader' | 'loaderVersion'> {
+  const fabric = versionId.match(/^fabric-loader-([^-]+)-(.+)$/)
+  if (fabric) return { loader: 'fabric', loaderVersion: fabric[1], minecraftVersion: fabric[2] }
+  const forge = versionId.match(/^(\d[^-]*)-forge-(.+)$/)
+  if (forge) return { loader: 'forge', loaderVersion: `${forge[1]}-${forge[2]}`, minecraftVersion: forge[1] }
+  const neoforge = versionId.match(/^neoforge-(.+)$/)
+  if (neoforge) return { loader: 'neoforge', loaderVersion: neoforge[1], minecraftVersion: neoForgeMinecraftVersion(neoforge[1]!) }
+  return { loader: 'vanilla', minecraftVersion: versionId }
+}
+
+async function readVanillaProfiles(directory: string): Promise<Record<string, VanillaLauncherProfile>> {
+  for (const name of ['launcher_profiles.json', 'launcher_profiles_microsoft_store.json']) {
+    try {
+      const parsed = JSON.parse(await fs.readFile(path.join(directory, name), 'utf8')) as { profiles?: Record<string, VanillaLauncherProfile> }
+      if (parsed.profiles && typeof parsed.profiles === 'object') return parsed.profiles
+    } catch {
+      // Try the next profile file. Fresh installations may have neither.
+    }
+  }
+  return {}
+}
+
+export async function detectVanillaInstallation(): Promise<VanillaInstallation | null> {
+  const directory = vanillaMinecraftDirectory()
+  const stat = await fs.stat(directory).catch(() => null)
+  if (!stat?.isDirectory()) return null
+  const latest = {
+    release: await resolveMinecraftVersionId('latest-release').catch(() => undefined),
+    snapshot: await resolveMinecraftVersionId('latest-snapshot').catch(() => undefined)
+  }
+  const profiles = Object.entries(await readVanillaProfiles(directory))
+    .filter(([id]) => !id.startsWith('megaclient-'))
+    .map(([id, profile]): VanillaProfileSummary => {
+      const track = versionAliasTrack(profile.type ?? '') ?? versionAliasTrack(profile.lastVersionId ?? '')
+      return {
+        id,
+        name: profile.name?.trim() || (track ? `Latest ${track}` : profile.lastVersionId ?? id),
+        lastUsed: profile.lastUsed,
+        track,
+        ...(track ? { loader: 'vanilla' as const, minecraftVersion: latest[track] } : mapVersionId(profile.lastVersionId ?? ''))
+      }
+    })
+    .filter((profile) => profile.minecraftVersion)
+  profiles.sort((a, b) => Date.parse(b.lastUsed ?? '') - Date.parse(a.lastUsed ?? '') || a.name.localeCompare(b.name))
+  return { directory, profiles }
+}
+
+// The official launcher is offered for import once, on the first start of a
+// launcher with no instances. Later imports go through the import dialog.
+export async function firstRunVanillaImport(): Promise<VanillaInstallation | null> {
+  const data = store.getData()
+  if (data.firstRunImportOffered || data.instances.length) return null
+  await store.markFirstRunImportOffered()
+  return detectVanillaInstallation()
+}
+
+export async function importVanillaInstallation(profileId?: string): Promise<LauncherInstance> {
+  const installation = await detectVanillaInstallation()
+  if (!installation) throw new Error('No official Minecraft Launcher installation was found.')
+  const profiles = await readVanillaProfiles(installation.directory)
+  const summary = profileId ? installation.profiles.find((profile) => profile.id === profileId) : installation.profiles[0]
+  if (profileId && !summary) throw new Error('That launcher profile could not be found.')
+  const minecraftVersion = summary?.minecraftVersion ?? (await getMinecraftVersions(false))[0]?.id
+  if (!minecraftVersion) throw new Error('MegaClient could not determine a Minecraft version to import.')
+
+  const source = summary && profiles[summary.id]?.gameDir ? profiles[summary.id]!.gameDir! : installation.directory
+  let instance = await createInstance({
+    name: summary ? `${summary.name} (imported)` : 'Minecraft (imported)',
+    minecraftVersion: summary?.track ? `latest-${summary.track}` : minecraftVersion,
+    loader: summary?.loader ?? 'vanilla',
+    loaderVersion: summary?.loaderVersion
+  })
+  for (const item of VANILLA_IMPORT_ITEMS) {
+    const from = path.join(source, item)
+    const stat = await fs.stat(from).catch(() => null)
+    if (!stat) continue
+    await fs.cp(from, path.join(instanceDirectory(instance.slug), item), { recursive: stat.isDirectory(), force: true })
+  }
+  // Memory is managed by MegaClient's own settings, so heap flags are dropped.
+  const javaArgs = summary
+    ? profiles[summary.id]?.javaArgs?.split(/\s+/).filter((arg) => arg && !/^-Xm[sx]/i.test(arg)).join(' ')
+    : undefined
+  if (javaArgs) instance = await updateInstance(instance.id, { jvmArgs: javaArgs })
+  return instance
+}
+
+interface CurseForgeAddon {
+  addonID?: number
+  name?: string
+  installedFile?: { id?: number; fileName?: string; displayName?: string; downloadUrl?: string }
+}
+
+interface CurseForgeInstanceManifest {
+  name?: string
+  gameVersion?: string
+  baseModLoader?: { name?: string; minecraftVersion?: string; forgeVersion?: string } | null
+  installedAddons?: CurseForgeAddon[]
+}
+
+export interface CurseForgeInstanceSummary {
+  folder: string
+  name: string
+  minecraftVersion: string
+  loader: LoaderType
+  loaderVersion?: string
+  modCount: number
+}
+
+function curseForgeInstanceRoots(): string[] {
+  const home = app.getPath('home')
+  const roots = [path.join(home, 'curseforge', 'minecraft', 'Instances')]
+  if (process.platform === 'win32') roots.push(path.join(app.getPath('documents'), 'Curse', 'Minecraft', 'Instances'))
+  return roots
+}
+
+function curseForgeLoader(manifest: CurseForgeInstanceManifest, minecraftVersion: string): { loader: LoaderType; loaderVersion?: string } {
+  const name = manifest.baseModLoader?.name ?? ''
+  const fabric = name.match(/^fabric-([^-]+)(?:-|$)/i)
+  if (fabric) return { loader: 'fabric', loaderVersion: fabric[1] }
+  const neoforge = name.match(/^neoforge-(.+)$/i)
+  if (neoforge) return { loader: 'neoforge', loaderVersion: neoforge[1] }
+  const forge = name.match(/^forge-(.+)$/i)
+  if (forge) return { loader: 'forge', loaderVersion: `${minecraftVersion}-${forge[1]}` }
+  if (/^quilt-/i.test(name)) throw new Error('Quilt instances are not supported by MegaClient yet.')
+  return { loader: 'vanilla' }
+}
+
+async function readCurseForgeManifest(folder: string): Promise<CurseForgeInstanceManifest | null> {
+  try {
+    return JSON.parse(await fs.readFile(path.join(folder, 'minecraftinstance.json'), 'utf8')) as CurseForgeInstanceManifest
+  } catch {
+    return null
+  }
+}
+
+interface CurseForgeApiFile {
+  id: number
+  modId: number
+  fileName: string
+  fileLength?: number
+  downloadUrl?: string | null
+  hashes?: Array<{ value: string; algo: number }>
+  fileFingerprint?: number
+}
+
+interface CurseForgeApiMod {
+  id: number
+  links?: { websiteUrl?: string }
+}
+
+// A mod the import could not download, with the page it can be fetched from
+// by hand when its author has turned off downloads by other launchers.
+export interface CurseForgeSkippedFile {
+  name: string
+  fileName: string
+  reason: string
+  pageUrl?: string
+}
+
+export interface CurseForgeImportResult {
+  instance: LauncherInstance
+  skipped: CurseForgeSkippedFile[]
+}
+
+const CURSEFORGE_API = 'https://api.curseforge.com/v1'
+// CurseForge's hash algorithm number for SHA-1.
+const CURSEFORGE_SHA1 = 1
+
+function curseForgeKeyCandidates(): string[] {
+  return [
+    path.join(process.resourcesPath, 'curseforge', 'api-key.txt'),
+    path.join(app.getAppPath(), 'resources', 'curseforge', 'api-key.txt')
+  ]
+}
+
+// The CurseForge API only answers requests that carry a key issued to the
+// launcher, configured like the Discord application ID.
+async function readCurseForgeApiKey(): Promise<string> {
+  const fromEnvironment = process.env.MEGACLIENT_CURSEFORGE_API_KEY?.trim()
+  if (fromEnvironment) return fromEnvironment
+  for (const candidate of curseForgeKeyCandidates()) {
+    const value = (await fs.readFile(candidate, 'utf8').catch(() => '')).trim()
+    if (value) return value
+  }
+  return ''
+}
+
+async function curseForgePost<T>(apiKey: string, endpoint: string, body: unknown): Promise<T[]> {
+  const response = await fetchJson<{ data?: T[] }>(`${CURSEFORGE_API}${endpoint}`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', Accept: 'application/json', 'x-api-key': apiKey },
+    body: JSON.stringify(body)
+  })
+  return response.data ?? []
+}
+
+// Looks up every file the manifest names, in batches, along with the project
+// pages that opted-out files are listed with.
+async function resolveCurseForgeFiles(apiKey: string, fileIds: number[]): Promise<{ files: Map<number, CurseForgeApiFile>; pages: Map<number, string> }> {
+  const files = new Map<number, CurseForgeApiFile>()
+  const pages = new Map<number, string>()
+  for (let start = 0; start < fileIds.length; start += 200) {
+    for (const file of await curseForgePost<CurseForgeApiFile>(apiKey, '/mods/files', { fileIds: fileIds.slice(start, start + 200) })) files.set(file.id, file)
+  }
+  const modIds = [...new Set([...files.values()].map((file) => file.modId))]
+  for (let start = 0; start < modIds.length; start += 200) {
+    for (const mod of await curseForgePost<CurseForgeApiMod>(apiKey, '/mods', { modIds: modIds.slice(start, start + 200) })) {
+      if (mod.links?.websiteUrl) pages.set(mod.id, mod.links.websiteUrl)
+    }
+  }
+  return { files, pages }
+}
+
+// CurseForge's file fingerprint: 32-bit MurmurHash2 with seed 1 over the file
+// with tab, line feed, carriage return and space bytes left out.
+function curseForgeFingerprint(data: Buffer): number {
+  const bytes = data.filter((byte) => byte !== 9 && byte !== 10 && byte !== 13 && byte !== 32)
+  const m = 0x5bd1e995
+  let hash = (1 ^ bytes.length) >>> 0
+  let index = 0
+  for (; index + 4 <= bytes.length; index += 4) {
+    let k = bytes[index]! | (bytes[index + 1]! << 8) | (bytes[index + 2]! << 16) | (bytes[index + 3]! << 24)
+    k = Math.imul(k, m)
+    k ^= k >>> 24
+    k = Math.imul(k, m)
+    hash = Math.imul(hash, m) ^ k
+  }
+  const remaining = bytes.length - index
+  if (remaining >= 3) hash ^= bytes[index + 2]! << 16
+  if (remaining >= 2) hash ^= bytes[index + 1]! << 8
+  if (remaining >= 1) hash = Math.imul(hash ^ bytes[index]!, m)
+  hash ^= hash >>> 13
+  hash = Math.imul(hash, m)
+  hash ^= hash >>> 15
+  return hash >>> 0
+}
+
+// Downloads one resolved file, checked against its SHA-1 where CurseForge
+// publishes one and against its fingerprint otherwise.
+async function downloadCurseForgeFile(file: CurseForgeApiFile, target: string): Promise<void> {
+  const sha1 = file.hashes?.find((hash) => hash.algo === CURSEFORGE_SHA1)?.value
+  await downloadObject(file.downloadUrl!, target, sha1, undefined, { size: file.fileLength || undefined })
+  if (sha1 || file.fileFingerprint == null) return
+  if (curseForgeFingerprint(await fs.readFile(target)) !== file.fileFingerprint >>> 0) {
+    await fs.rm(target, { force: true })
+    throw new Error('The download did not match its CurseForge fingerprint.')
+  }
+}
+
+export async function detectCurseForgeInstances(): Promise<CurseForgeInstanceSummary[]> {
+  const summaries: CurseForgeInstanceSummary[] = []
+  for (const root of curseForgeInstanceRoots()) {
+    const entries = await fs.readdir(root, { withFileTypes: true }).catch(() => [])
+    for (const entry of entries.filter((item) => item.isDirectory())) {
+      const folder = path.join(root, entry.name)
+      const manifest = await readCurseForgeManifest(folder)
+      const minecraftVersion = manifest?.baseModLoader?.minecraftVersion ?? manifest?.gameVersion
+      if (!manifest || !minecraftVersion) continue
+      try {
+        summaries.push({
+          folder,
+          name: manifest.name?.trim() || entry.name,
+          minecraftVersion,
+          modCount: manifest.installedAddons?.length ?? 0,
+          ...curseForgeLoader(manifest, minecraftVersion)
+        })
+      } catch {
+        // Unsupported loaders are left out of the detected list.
+      }
+    }
+  }
+  return summaries.sort((a, b) => a.name.localeCompare(b.name))
+}
+
+export async function importCurseForgeInstance(
+  folder: string,
+  onProgress?: (message: string, progress?: number) => void
+): Promise<CurseForgeImportResult> {
+  const manifest = await readCurseForgeManifest(folder)
+  if (!manifest) throw new Error('This folder is not a CurseForge instance (minecraftinstance.json is missing).')
+  const minecraftVersion = manifest.baseModLoader?.minecraftVersion ?? manifest.gameVersion
+  if (!minecraftVersion) throw new Error('The CurseForge instance does not declare a Minecraft version.')
+
+  const instance = await createInstance({
+    name: manifest.name?.trim() || path.basename(folder),
+    minecraftVersion,
+    ...curseForgeLoader(manifest, minecraftVersion)
+  })
+  onProgress?.('Copying instance files', 0)
+  await copyGameDirectory(folder, instance)
+  await fs.rm(path.join(instanceDirectory(instance.slug), 'minecraftinstance.json'), { force: true })
+
+  const addons = (manifest.installedAddons ?? []).filter((addon) => addon.installedFile?.fileName)
+  const present = async (target: string): Promise<boolean> => Boolean(await fs.stat(target).catch(() => null) ?? await fs.stat(`${target}.disabled`).catch(() => null))
+  const missing: CurseForgeAddon[] = []
+  for (const addon of addons) {
+    const fileName = path.basename(addon.installedFile!.fileName!)
+    if (fileName.endsWith('.jar') && !await present(path.join(modsDirectory(instance.slug), fileName))) missing.push(addon)
+  }
+
+  // Files are resolved through the API rather than guessed from the CDN, so
+  // authors who turned off third-party downloads are respected and every file
+  // can be checked against its published hash.
+  const skipped: CurseForgeSkippedFile[] = []
+  let resolved: Awaited<ReturnType<typeof resolveCurseForgeFiles>> = { files: new Map(), pages: new Map() }
+  const apiKey = missing.length ? await readCurseForgeApiKey() : ''
+  if (missing.length && apiKey) {
+    onProgress?.('Looking up mods on CurseForge', 0)
+    resolved = await resolveCurseForgeFiles(apiKey, missing.map((addon) => addon.installedFile!.id).filter((id): id is number => Boolean(id)))
+      .catch((error) => {
+        logger.warn('CurseForge files could not be resolved.', { error })
+        return resolved
+      })
+  }
+
+  let completed = 0
+  for (const addon of missing) {
+    const fileName = path.basename(addon.installedFile!.fileName!)
+    const name = addon.name?.trim() || fileName
+    const file = addon.installedFile!.id ? resolved.files.get(addon.installedFile!.id) : undefined
+    const pageUrl = file ? resolved.pages.get(file.modId) : undefined
+    onProgress?.(`Downloading ${name}`, completed++ / missing.length)
+    if (!file) {
+      skipped.push({ name, fileName, reason: apiKey ? 'CurseForge did not return this file.' : 'CurseForge downloads are not available in this build of MegaClient.' })
+    } else if (!file.downloadUrl) {
+      skipped.push({ name, fileName, pageUrl: pageUrl && `${pageUrl}/files/${file.id}`, reason: 'The author only allows downloads from CurseForge itself.' })
+    } else {
+      await downloadCurseForgeFile(file, path.join(modsDirectory(instance.slug), fileName)).catch((error) => {
+        logger.warn(`${fileName} could not be downloaded.`, { error })
+        skipped.push({ name, fileName, pageUrl: pageUrl && `${pageUrl}/files/${file.id}`, reason: error instanceof Error ? error.message : String(error) })
+      })
+    }
+  }
+
+  // Only files that are actually in the folder are tracked.
+  const tracked: TrackedMod[] = []
+  for (const addon of addons) {
+    const file = addon.installedFile!
+    const fileName = path.basename(file.fileName!)
+    const target = path.join(modsDirectory(instance.slug), fileName)
+    if (!fileName.endsWith('.jar') || !await present(target)) continue
+    tracked.push({
+      title: addon.name?.trim() || fileName.replace(/\.jar$/i, ''),
+      fileName,
+      enabled: !(await fs.stat(`${target}.disabled`).catch(() => null)),
+      versionNumber: file.displayName,
+      installedAt: new Date().toISOString(),
+      source: 'curseforge',
+      curseforge: addon.addonID && file.id ? { projectId: addon.addonID, fileId: file.id } : undefined
+    })
+  }
+  await trackImportedMods(instance.id, tracked)
+  onProgress?.(`Imported ${instance.name}`, 1)
+  return { instance, skipped }
+}
+
+export async function importVanillaProfiles(profileIds: string[], linkCustomGameDirectories = false): Promise<LauncherInstance[]> {
+  const installation = await detectVanillaInstallation()
+  if (!installation) throw new Error('No official Minecraft Launcher installation was found.')
+  const profiles = await readVanillaProfiles(installation.directory)
+  const imported: LauncherInstance[] = []
+  for (const id of new Set(profileIds)) {
+    const summary = installation.profiles.find((profile) => profile.id === id)
+    if (!summary?.minecraftVersion) continue
+    const gameDir = profiles[id]?.gameDir
+    if (linkCustomGameDirectories && gameDir) {
+      // The official launcher keeps using this folder too, so link it rather
+      // than copying it and letting the two copies drift apart.
+      imported.push(await createInstance({
+        name: summary.name,
+        minecraftVersion: summary.track ? `latest-${summary.track}` : summary.minecraftVersion,
+        loader: summary.loader,
+        loaderVersion: summary.loaderVersion,
+        linkedDirectory: gameDir
+      }))
+    } else {
+      imported.push(await importVanillaInstallation(id))
+    }
+  }
+  return imported
+}
+
+function vanillaVersionId(instance: LauncherInstance): string {
+  if (instance.customVersionId) return instance.customVersionId
+  if (instance.loader === 'fabric' && instance.loaderVersion) return `fabric-loader-${instance.loaderVersion}-${instance.minecraftVersion}`
+  if (instance.loader === 'forge' && instance.loaderVersion) {
+    const forgeVersion = instance.loaderVersion.startsWith(`${instance.minecraftVersion}-`)
+      ? instance.loaderVersion.slice(instance.minecraftVersion.length + 1)
+      : instance.loaderVersion
+    return `${instance.minecraftVersion}-forge-${forgeVersion}`
+  }
+  if (instance.loader === 'neoforge' && instance.loaderVersion) return `neoforge-${instance.loaderVersion}`
+  return instance.minecraftVersion
+}
+
+// The official launcher only lists profiles whose version JSON is in its own
+// versions folder, so the launched profile and every profile it inherits from
+// are copied there. Vanilla jars are downloaded by the official launcher, but
+// a custom profile's own jar is copied with it. Files already there are kept.
+async function copyProfileVersions(instance: LauncherInstance, minecraftDirectory: string): Promise<void> {
+  const gameDirectory = instance.linkedDirectory ?? instanceDirectory(instance.slug)
+  if (path.resolve(gameDirectory) === path.resolve(minecraftDirectory)) return
+  const visited = new Set<string>()
+  let id: string | undefined = vanillaVersionId(instance)
+  while (id && !visited.has(id) && visited.size < 16) {
+    visited.add(id)
+    const version = await readVersionJson(gameDirectory, id)
+    if (!version) {
+      logger.warn(`The ${id} version profile is not installed in ${instance.name} yet, so the official launcher will download it if it can.`)
+      break
+    }
+    const destination = versionJsonPath(minecraftDirectory, id)
+    await fs.mkdir(path.dirname(destination), { recursive: true })
+    await fs.copyFile(versionJsonPath(gameDirectory, id), destination, constants.COPYFILE_EXCL).catch((error) => {
+      if ((error as NodeJS.ErrnoException).code !== 'EEXIST') throw error
+    })
+    if (id === instance.customVersionId) {
+      const jar = path.join(path.dirname(versionJsonPath(gameDirectory, id)), `${id}.jar`)
+      await fs.copyFile(jar, path.join(path.dirname(destination), `${id}.jar`), constants.COPYFILE_EXCL).catch((error) => {
+        if (!['EEXIST', 'ENOENT'].includes((error as NodeJS.ErrnoException).code ?? '')) throw error
+      })
+    }
+    id = version.inheritsFrom
+  }
+}
+
+export async function exportInstancesToVanilla(instanceIds?: string[]): Promise<number> {
+  const directory = vanillaMinecraftDirectory()
+  const file = path.join(directory, 'launcher_profiles.json')
+  let document: { profiles?: Record<string, unknown>; [key: string]: unknown }
+  try {
+    document = JSON.parse(await fs.readFile(file, 'utf8'))
+  } catch (error) {
+    if ((error as NodeJS.ErrnoException).code !== 'ENOENT') throw new Error('The official launcher profile file could not be read, so it was left unchanged.')
+    document = { profiles: {}, version: 3 }
+  }
+
+  const instances = store.getData().instances
+    .filter((instance) => !instance.customClient && !instance.archivedAt)
+    .filter((instance) => !instanceIds || instanceIds.includes(instance.id))
+  const profiles = { ...(document.profiles ?? {}) }
+  for (const instance of instances) {
+    await copyProfileVersions(instance, directory)
+    profiles[`megaclient-${instance.id}`] = {
+      name: `${instance.name} (MegaClient)`,
+      type: 'custom',
+      icon: 'Grass',
+      created: instance.createdAt,
+      lastUsed: instance.lastPlayedAt ?? instance.updatedAt,
+      lastVersionId: vanillaVersionId(instance),
+      gameDir: instance.linkedDirectory ?? instanceDirectory(instance.slug),
+      ...(instance.jvmArgs ? { javaArgs: instance.jvmArgs } : {})
+    }
+  }
+
+  await fs.mkdir(directory, { recursive: true })
+  await fs.copyFile(file, `${file}.megaclient.bak`).catch(() => undefined)
+  const temp = `${file}.${process.pid}.${Date.now()}.tmp`
+  await fs.writeFile(temp, JSON.stringify({ ...document, profiles }, null, 2), 'utf8')
+  await fs.rename(temp, file)
+  return instances.length
+}
@@ -1,38 +1,103 @@
 import fs from 'node:fs/promises'
+import os from 'node:os'
 import path from 'node:path'
 import { randomUUID } from 'node:crypto'
-import type { LauncherInstance, LoaderType } from '../types'
+import AdmZip from 'adm-zip'
+import type { InstanceStats, LauncherInstance, LoaderType, LocalModInfo, ProcessPriority, RestartOnCrash, SharedFolderKind, UnstableFlag, VersionProfileInfo, VersionTrack, WorldBackupPolicy } from '../types'
+import { fetchWithTimeout, readLimitedText } from './net'
 import { store } from './store'
-import { instanceDirectory, metadataDirectory, modsDirectory } from './paths'
+import { baseMinecraftVersion, installVersionJson, readVersionJson, validateVersionJson, versionJsonPath } from './versionJson'
+import { minecraftVersionType, resolveMinecraftVersionId, versionAliasTrack } from './versions'
+import { archiveDirectory, instanceDirectory, metadataDirectory, modsDirectory, sharedDirectory } from './paths'
+import { inspectModJar, readModDeclaration } from './modSecurity'
+import { zipFolder } from './zipFolder'
+
+const COPYABLE_INSTANCE_ITEMS = [
+  'options.txt', 'optionsof.txt', 'optionsshaders.txt', 'config', 'servers.dat',
+  'resourcepacks', 'shaderpacks', 'schematics', 'screenshots'
+] as const
+const DEFAULT_COPIED_ITEMS = ['options.txt', 'optionsof.txt', 'optionsshaders.txt', 'config']
 
 function slugify(value: string): string {
   const base = value.toLowerCase().trim().replace(/\s+/g, '-').replace(/[^a-z0-9-]/g, '').replace(/-+/g, '-')
   return base || `instance-${Date.now()}`
 }
 
+async function linkDirectory(target: string, link: string): Promise<void> {
+  await fs.mkdir(path.dirname(link), { recursive: true })
+  // Junctions do not need administrator rights or Developer Mode on Windows.
+  await fs.symlink(target, link, process.platform === 'win32' ? 'junction' : 'dir')
+}
+
+// Libraries are stored by Maven path and asset objects by hash, so the same
+// path always holds the same file and every instance can link one copy.
+const SHARED_GAME_FILES = ['libraries', 'assets'] as const
+
+async function mergeDirectory(source: string, target: string): Promise<void> {
+  await fs.mkdir(target, { recursive: true })
+  for (const entry of await fs.readdir(source, { withFileTypes: true })) {
+    const from = path.join(source, entry.name)
+    const to = path.join(target, entry.name)
+    if (entry.isDirectory()) await mergeDirectory(from, to)
+    else if (entry.isFile() && !await fs.stat(to).catch(() => null)) await fs.rename(from, to).catch(() => fs.copyFile(from, to))
+  }
+}
+
+// Points the instance's libraries and assets at the shared store, moving in
+// whatever the instance had downloaded on its own before.
+export async function ensureSharedGameFiles(instance: LauncherInstance): Promise<void> {
+  if (instance.linkedDirectory) return
+  const directory = instanceDirectory(instance.slug)
+  for (const kind of SHARED_GAME_FILES) {
+    const local = path.join(directory, kind)
+    const stat = await fs.lstat(local).catch(() => null)
+    if (stat?.isSymbolicLink()) continue
+    if (stat?.isDirectory()) {
+      await mergeDirectory(local, sharedDirectory(kind))
+      await fs.rm(local, { recursive: true, force: true })
+    }
+    await fs.mkdir(sharedDirectory(kind), { recursive: true })
+    await linkDirectory(sharedDirectory(kind), local)
+  }
+}
+
 export async function createInstance(input: {
   name: string
   minecraftVersion: string
-  loader: LoaderType
+  loader?: LoaderType
   loaderVersion?: string
   customClient?: boolean
+  linkedDirectory?: string
 }): Promise<LauncherInstance> {
   const data = store.getData()
+  const loader = input.loader ?? data.settings.defaultLoader
   let slug = slugify(input.name)
   let suffix = 2
   while (data.instances.some((instance) => instance.slug === slug)) slug = `${slugify(input.name)}-${suffix++}`
   const now = new Date().toISOString()
+  const trackLatest = input.customClient ? undefined : versionAliasTrack(input.minecraftVersion)
+  const minecraftVersion = input.customClient ? '26.2' : await resolveMinecraftVersionId(input.minecraftVersion)
   const instance: LauncherInstance = {
     id: randomUUID(),
     name: input.name.trim(),
     slug,
-    minecraftVersion: input.customClient ? '26.2' : input.minecraftVersion,
-    loader: input.customClient ? 'fabric' : input.loader,
-    loaderVersion: input.loaderVersion,
+    minecraftVersion,
+    versionType: await minecraftVersionType(minecraftVersion).catch(() => undefined),
+    loader: input.customClient ? 'fabric' : loader,
+    loaderVersion: trackLatest ? undefined : input.loaderVersion,
+    trackLatest,
     createdAt: now,
     updatedAt: now,
     customClient: Boolean(input.customClient)
   }
+  if (input.linkedDirectory) {
+    if (input.customClient) throw new Error('MegaClient 26.2 instances must use their own isolated folder.')
+    const target = path.resolve(input.linkedDirectory)
+    const stat = await fs.stat(target).catch(() => null)
+    if (!stat?.isDirectory()) throw new Error('The linked game folder does not exist.')
+    await linkDirectory(target, instanceDirectory(slug))
+    instance.linkedDirectory = target
+  }
   await fs.mkdir(modsDirectory(slug), { recursive: true })
   await fs.mkdir(metadataDirectory(slug), { recursive: true })
   await store.setInstances([...data.instances, instance])
@@ -53,7 +118,13 @@ export async function deleteInstance(id: string): Promise<void> {
   const data = store.getData()
   const instance = data.instances.find((item) => item.id === id)
   if (!instance) return
-  await fs.rm(instanceDirectory(instance.slug), { recursive: true, force: true })
+  for (const kind of [...instance.sharedFolders ?? [], ...SHARED_GAME_FILES]) {
+    await fs.unlink(path.join(instanceDirectory(instance.slug), kind)).catch(() => undefined)
+  }
+  await fs.rm(instanceArchivePath(instance), { force: true })
+  // A linked instance only owns the link; the external game folder is kept.
+  if (instance.linkedDirectory) await fs.unlink(instanceDirectory(instance.slug)).catch(() => undefined)
+  else await fs.rm(instanceDirectory(instance.slug), { recursive: true, force: true })
   await store.setInstances(data.instances.filter((item) => item.id !== id))
 }
 
@@ -63,16 +134,328 @@ export function getInstance(id: string): LauncherInstance {
   return instance
 }
 
+export async function setVersionTracking(id: string, track: VersionTrack | null): Promise<LauncherInstance> {
+  const instance = getInstance(id)
+  if (track && instance.customClient) throw new Error('MegaClient 26.2 instances always use Minecraft 26.2.')
+  if (track && instance.customVersionId) throw new Error('Remove the custom version profile before tracking the latest Minecraft version.')
+  return updateInstance(id, { trackLatest: track ?? undefined })
+}
+
+export async function setProcessPriority(id: string, priority: ProcessPriority, affinity: number[] = []): Promise<LauncherInstance> {
+  if (!['below-normal', 'normal', 'above-normal'].includes(priority)) throw new Error('Choose below normal, normal or above normal priority.')
+  const cores = os.cpus().length
+  const cpuAffinity = [...new Set(affinity.map(Number))].filter((core) => Number.isInteger(core) && core >= 0 && core < cores).sort((a, b) => a - b)
+  if (cpuAffinity.length && process.platform !== 'win32') throw new Error('CPU affinity can only be set on Windows.')
+  return updateInstance(id, {
+    processPriority: priority === 'normal' ? undefined : priority,
+    cpuAffinity: cpuAffinity.length && cpuAffinity.length < cores ? cpuAffinity : undefined
+  })
+}
+
+export async function setRestartOnCrash(id: string, options: RestartOnCrash | null): Promise<LauncherInstance> {
+  if (!options) return updateInstance(id, { restartOnCrash: undefined })
+  const maxRetries = Math.floor(Number(options.maxRetries))
+  const withinSeconds = Math.floor(Number(options.withinSeconds))
+  if (!(maxRetries >= 1 && maxRetries <= 10)) throw new Error('Choose between 1 and 10 restarts.')
+  if (!(withinSeconds >= 10 && withinSeconds <= 3600)) throw new Error('Choose a crash window between 10 seconds and an hour.')
+  return updateInstance(id, { restartOnCrash: { maxRetries, withinSeconds } })
+}
+
+export async function setWorldBackupPolicy(id: string, policy: WorldBackupPolicy | null): Promise<LauncherInstance> {
+  if (!policy) return updateInstance(id, { worldBackups: undefined })
+  const keepLast = Math.floor(Number(policy.keepLast))
+  const keepDays = Math.floor(Number(policy.keepDays))
+  if (!(keepLast >= 1 && keepLast <= 100)) throw new Error('Keep between 1 and 100 backups of each world.')
+  if (!(keepDays >= 0 && keepDays <= 3650)) throw new Error('Choose how many days to keep backups, or 0 to keep them regardless of age.')
+  return updateInstance(id, { worldBackups: { keepLast, keepDays } })
+}
+
+export async function setInstanceUnstable(id: string, unstable: UnstableFlag | null): Promise<LauncherInstance> {
+  return updateInstance(id, { unstable: unstable ?? undefined })
+}
+
+// Half as much again, rounded to 512 MB, while leaving 2 GB of the computer's
+// memory to the system. Returns null when there is no room to grow.
+export function suggestedMemoryMax(currentMb: number): number | null {
+  const systemMb = Math.floor(os.totalmem() / 1024 / 1024)
+  const ceiling = Math.min(32768, Math.floor((systemMb - 2048) / 512) * 512)
+  const suggested = Math.min(ceiling, Math.ceil((currentMb * 1.5) / 512) * 512)
+  return suggested > currentMb ? suggested : null
+}
+
+// Applies the increase offered after the game ran out of memory. It takes
+// effect at the next launch.
+export async function raiseInstanceMemory(id: string): Promise<LauncherInstance> {
+  const instance = getInstance(id)
+  const current = Number(instance.memoryMax) || store.getData().settings.memoryMax
+  const memoryMax = suggestedMemoryMax(current)
+  if (!memoryMax) throw new Error(`${instance.name} already has as much memory as this computer can spare.`)
+  return updateInstance(id, { memoryMax })
+}
+
+export function getInstanceNotes(id: string): string {
+  return getInstance(id).notes ?? ''
+}
+
+export async function setInstanceNotes(id: string, notes: string): Promise<string> {
+  const value = String(notes ?? '').replace(/\r\n/g, '\n').slice(0, 20_000)
+  const updated = await updateInstance(id, { notes: value.trim() ? value : undefined })
+  return updated.notes ?? ''
+}
+
+export function getInstanceStats(id: string): InstanceStats {
+  const instance = getInstance(id)
+  return {
+    lastPlayedAt: instance.lastPlayedAt,
+    lastExitedAt: instance.lastExitedAt,
+    totalPlaytimeMs: instance.totalPlaytimeMs ?? 0,
+    launchCount: instance.launchCount ?? 0
+  }
+}
+
+export async function recordPlaySession(id: string, startedAt: number, endedAt = Date.now()): Promise<InstanceStats> {
+  const current = getInstance(id)
+  // Clock changes or a missing start event should never subtract playtime.
+  const duration = Math.max(0, endedAt - startedAt)
+  await updateInstance(id, {
+    lastPlayedAt: new Date(startedAt).toISOString(),
+    lastExitedAt: new Date(endedAt).toISOString(),
+    totalPlaytimeMs: (current.totalPlaytimeMs ?? 0) + duration,
+    launchCount: (current.launchCount ?? 0) + 1
+  })
+  return getInstanceStats(id)
+}
+
+export async function copyInstanceConfiguration(sourceId: string, targetId: string, items: string[] = DEFAULT_COPIED_ITEMS): Promise<string[]> {
+  const source = getInstance(sourceId)
+  const target = getInstance(targetId)
+  if (source.id === target.id) throw new Error('Choose a different instance to copy settings into.')
+  const allowed = new Set<string>(COPYABLE_INSTANCE_ITEMS)
+  const copied: string[] = []
+  for (const item of new Set(items)) {
+    if (!allowed.has(item)) throw new Error(`${item} cannot be copied between instances.`)
+    const from = path.join(instanceDirectory(source.slug), item)
+    const stat = await fs.stat(from).catch(() => null)
+    if (!stat) continue
+    const to = path.join(instanceDirectory(target.slug), item)
+    // options.txt holds keybinds alongside video settings, so it is replaced as
+    // a whole. Folders are merged so target-only files are left in place.
+    await fs.cp(from, to, { recursive: stat.isDirectory(), force: true })
+    copied.push(item)
+  }
+  return copied
+}
+
+export async function ensureLinkedDirectory(instance: LauncherInstance): Promise<void> {
+  if (!instance.linkedDirectory) return
+  const stat = await fs.stat(instance.linkedDirectory).catch(() => null)
+  if (!stat?.isDirectory()) throw new Error(`The linked game folder ${instance.linkedDirectory} is no longer available.`)
+  const link = instanceDirectory(instance.slug)
+  const current = await fs.readlink(link).catch(() => null)
+  if (current && path.resolve(current) === path.resolve(instance.linkedDirectory)) return
+  await fs.rm(link, { force: true }).catch(() => undefined)
+  await linkDirectory(instance.linkedDirectory, link)
+}
+
+async function moveIntoShared(source: string, shared: string, suffix: string): Promise<void> {
+  for (const entry of await fs.readdir(source).catch(() => [] as string[])) {
+    let destination = path.join(shared, entry)
+    if (await fs.stat(destination).catch(() => null)) {
+      const extension = path.extname(entry)
+      destination = path.join(shared, `${path.basename(entry, extension)} (${suffix})${extension}`)
+    }
+    await fs.cp(path.join(source, entry), destination, { recursive: true, errorOnExist: true, force: false })
+  }
+  await fs.rm(source, { recursive: true, force: true })
+}
+
+export async function setSharedFolder(id: string, kind: SharedFolderKind, shared: boolean): Promise<LauncherInstance> {
+  if (kind !== 'saves' && kind !== 'resourcepacks') throw new Error('Only saves and resource packs can be shared.')
+  const instance = getInstance(id)
+  if (instance.linkedDirectory) throw new Error('Linked instances already use an external game folder.')
+  const current = new Set(instance.sharedFolders ?? [])
+  if (current.has(kind) === shared) return instance
+
+  const local = path.join(instanceDirectory(instance.slug), kind)
+  const target = sharedDirectory(kind)
+  await fs.mkdir(target, { recursive: true })
+  if (shared) {
+    await moveIntoShared(local, target, instance.slug)
+    await linkDirectory(target, local)
+    current.add(kind)
+  } else {
+    // Leave the shared copy for other instances and give this one its own copy.
+    await fs.unlink(local).catch(() => undefined)
+    await fs.cp(target, local, { recursive: true })
+    current.delete(kind)
+  }
+  return updateInstance(id, { sharedFolders: [...current] })
+}
+
+function instanceArchivePath(instance: LauncherInstance): string {
+  return path.join(archiveDirectory(), `${instance.slug}.zip`)
+}
+
+export async function archiveInstance(id: string): Promise<LauncherInstance> {
+  const instance = getInstance(id)
+  if (instance.archivedAt) return instance
+  if (instance.linkedDirectory) throw new Error('Linked instances use an external folder and cannot be archived.')
+  const directory = instanceDirectory(instance.slug)
+  await ensureSharedGameFiles(instance)
+  const shared = new Set<string>([...instance.sharedFolders ?? [], ...SHARED_GAME_FILES])
+  await fs.mkdir(archiveDirectory(), { recursive: true })
+  const destination = instanceArchivePath(instance)
+  const temp = `${destination}.${process.pid}.${Date.now()}.tmp`
+  try {
+    // Shared folders belong to every instance using them, so only the link is
+    // recreated on restore and the shared content itself stays in place.
+    await zipFolder(directory, temp, { skipTopLevel: [...shared] })
+    await fs.rename(temp, destination)
+  } finally {
+    await fs.rm(temp, { force: true }).catch(() => undefined)
+  }
+  for (const kind of shared) await fs.unlink(path.join(directory, kind)).catch(() => undefined)
+  await fs.rm(directory, { recursive: true, force: true, maxRetries: 5, retryDelay: 250 })
+  return updateInstance(id, { archivedAt: new Date().toISOString() })
+}
+
+export async function unarchiveInstance(id: string): Promise<LauncherInstance> {
+  const instance = getInstance(id)
+  if (!instance.archivedAt) return instance
+  const archive = instanceArchivePath(instance)
+  if (!await fs.stat(archive).catch(() => null)) throw new Error('The archive for this instance could not be found.')
+  const directory = instanceDirectory(instance.slug)
+  await fs.mkdir(directory, { recursive: true })
+  await new Promise<void>((resolve, reject) => {
+    new AdmZip(archive).extractAllToAsync(directory, true, false, (error) => error ? reject(error) : resolve())
+  })
+  for (const kind of instance.sharedFolders ?? []) {
+    await fs.mkdir(sharedDirectory(kind), { recursive: true })
+    await linkDirectory(sharedDirectory(kind), path.join(directory, kind)).catch(() => undefined)
+  }
+  await ensureSharedGameFiles(instance)
+  await fs.rm(archive, { force: true })
+  return updateInstance(id, { archivedAt: undefined })
+}
+
+async function selectCustomVersion(instance: LauncherInstance, versionId: string): Promise<LauncherInstance> {
+  const minecraftVersion = await baseMinecraftVersion(instanceDirectory(instance.slug), versionId)
+  return updateInstance(instance.id, { customVersionId: versionId, minecraftVersion, loader: 'vanilla', loaderVersion: undefined })
+}
+
+// A jar given with the profile is installed next to it, so the profile runs
+// that jar instead of the one of the version it inherits from.
+async function applyCustomVersion(instance: LauncherInstance, parsed: unknown, jar?: string): Promise<LauncherInstance> {
+  if (instance.customClient) throw new Error('MegaClient 26.2 instances cannot use a custom version profile.')
+  const directory = instanceDirectory(instance.slug)
+  const version = await installVersionJson(directory, parsed)
+  if (jar) {
+    const destination = path.join(path.dirname(versionJsonPath(directory, version.id)), `${version.id}.jar`)
+    if (path.resolve(jar) !== path.resolve(destination)) await fs.copyFile(jar, destination)
+  }
+  return selectCustomVersion(instance, version.id)
+}
+
+export async function setCustomVersionJson(id: string, file: string): Promise<LauncherInstance> {
+  const instance = getInstance(id)
+  let parsed: unknown
+  try {
+    parsed = JSON.parse(await fs.readFile(file, 'utf8'))
+  } catch {
+    throw new Error('The selected file is not a valid version JSON.')
+  }
+  const jar = file.replace(/\.json$/i, '.jar')
+  return applyCustomVersion(instance, parsed, jar !== file && await fs.stat(jar).catch(() => null) ? jar : undefined)
+}
+
+// Every valid profile in the instance's versions folder, including ones copied
+// there by hand.
+export async function listVersionProfiles(id: string): Promise<VersionProfileInfo[]> {
+  const directory = instanceDirectory(getInstance(id).slug)
+  const profiles: VersionProfileInfo[] = []
+  for (const entry of await fs.readdir(path.join(directory, 'versions'), { withFileTypes: true }).catch(() => [])) {
+    if (!entry.isDirectory() || !/^[\w.+-]{1,80}$/.test(entry.name)) continue
+    const version = await readVersionJson(directory, entry.name)
+    if (!version || version.id !== entry.name) continue
+    try {
+      validateVersionJson(version)
+    } catch {
+      continue
+    }
+    const hasJar = Boolean(await fs.stat(path.join(directory, 'versions', entry.name, `${entry.name}.jar`)).catch(() => null))
+    profiles.push({ id: version.id, inheritsFrom: version.inheritsFrom, hasJar })
+  }
+  return profiles.sort((a, b) => a.id.localeCompare(b.id))
+}
+
+export async function selectVersionProfile(id: string, versionId: string): Promise<LauncherInstance> {
+  const instance = getInstance(id)
+  if (instance.customClient) throw new Error('MegaClient 26.2 instances cannot use a custom version profile.')
+  if (!(await listVersionProfiles(id)).some((profile) => profile.id === versionId)) {
+    throw new Error('That version profile could not be found in this instance.')
+  }
+  return selectCustomVersion(instance, versionId)
+}
+
+export async function installVersionProfileFromUrl(id: string, sourceUrl: string): Promise<LauncherInstance> {
+  const instance = getInstance(id)
+  let parsed: URL
+  try { parsed = new URL(sourceUrl.trim()) } catch { throw new Error('Enter a valid version profile link.') }
+  if (parsed.protocol !== 'https:') throw new Error('Version profiles must be downloaded over a secure HTTPS link.')
+  if (parsed.username || parsed.password) throw new Error('Version profile links cannot contain embedded sign-in details.')
+  const response = await fetchWithTimeout(parsed.toString())
+  const text = await readLimitedText(response, 4 * 1024 * 1024, 'The version profile is larger than MegaClient can safely install.')
+  let profile: unknown
+  try {
+    profile = JSON.parse(text)
+  } catch {
+    throw new Error('The link did not return a valid version JSON.')
+  }
+  return applyCustomVersion(instance, profile)
+}
+
+export async function clearCustomVersionJson(id: string): Promise<LauncherInstance> {
+  return updateInstance(id, { customVersionId: undefined })
+}
+
 export async function openInstanceFolder(id: string): Promise<string> {
   const instance = getInstance(id)
+  if (instance.linkedDirectory) return instance.linkedDirectory
   await fs.mkdir(instanceDirectory(instance.slug), { recursive: true })
   return instanceDirectory(instance.slug)
 }
 
-export async function copyLocalMod(id: string, source: string): Promise<void> {
+// Adds a mod jar from disk, for example one dropped onto the mods list. The
+// jar is read and scanned for blocked clients before it is copied, and what
+// it declares is returned so the list can show it straight away.
+export async function importLocalMod(id: string, source: string): Promise<LocalModInfo> {
   const instance = getInstance(id)
   const extension = path.extname(source).toLowerCase()
   if (extension !== '.jar') throw new Error('Only .jar mod files can be added.')
-  await fs.mkdir(modsDirectory(instance.slug), { recursive: true })
-  await fs.copyFile(source, path.join(modsDirectory(instance.slug), path.basename(source)))
+  if (!(await fs.stat(source).catch(() => null))?.isFile()) throw new Error(`${path.basename(source)} could not be found.`)
+  const declaration = await readModDeclaration(source)
+  if (!declaration) throw new Error(`${path.basename(source)} is not a valid jar file.`)
+  const finding = await inspectModJar(source)
+  if (finding) throw new Error(finding.detail)
+
+  const warnings: string[] = []
+  if (!declaration.loaders.length) warnings.push('This jar does not declare itself as a Fabric, Quilt, Forge or NeoForge mod.')
+  else if (instance.loader === 'vanilla') warnings.push('Vanilla does not load mods. Use a Fabric, Forge or NeoForge instance.')
+  else if (!declaration.loaders.includes(instance.loader) && !(instance.loader === 'neoforge' && declaration.loaders.includes('forge'))) {
+    warnings.push(`This is a ${declaration.loaders.join(' or ')} mod, but the instance uses ${instance.loader}.`)
+  }
+  const directory = modsDirectory(instance.slug)
+  await fs.mkdir(directory, { recursive: true })
+  const fileName = path.basename(source)
+  await fs.copyFile(source, path.join(directory, fileName))
+  await fs.rm(path.join(directory, `${fileName}.disabled`), { force: true })
+  return {
+    fileName,
+    title: declaration.name ?? fileName.replace(/\.jar$/i, ''),
+    version: declaration.version,
+    description: declaration.description,
+    modIds: declaration.ids,
+    loaders: declaration.loaders,
+    warnings
+  }
 }
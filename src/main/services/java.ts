@@ -0,0 +1,280 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import { execFile } from 'node:child_process'
+import { promisify } from 'node:util'
+import AdmZip from 'adm-zip'
+import type { ProgressDetail } from '../types'
+import { fetchJson, hashFile } from './net'
+import { fetchCachedJson } from './httpCache'
+import { isOffline } from './connectivity'
+import { download, downloadConcurrency, runWithConcurrency } from './downloads'
+import { runtimesDirectory } from './paths'
+import { baseMinecraftVersion, readVersionJson, resolveVersionJson } from './versionJson'
+import { minecraftReleaseTime, minecraftVersionJson } from './versions'
+import { hasArm64Natives } from './natives'
+import { logger } from './logger'
+
+const execFileAsync = promisify(execFile)
+
+interface AdoptiumAsset {
+  binary?: {
+    package?: { link?: string; name?: string; checksum?: string; size?: number }
+  }
+  version?: { openjdk_version?: string }
+}
+
+export interface JavaRuntime {
+  major: number
+  arch: JavaArch
+  executable: string
+}
+
+export type JavaArch = 'x64' | 'aarch64'
+
+export interface JavaRequirement {
+  major: number
+  arch: JavaArch
+  component?: string
+}
+
+interface MojangRuntimeEntry {
+  manifest: { sha1: string; size: number; url: string }
+  version: { name: string }
+}
+
+interface MojangRuntimeFile {
+  type: 'file' | 'directory' | 'link'
+  executable?: boolean
+  target?: string
+  downloads?: { raw?: { sha1: string; size: number; url: string } }
+}
+
+const MOJANG_RUNTIMES_URL = 'https://piston-meta.mojang.com/v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json'
+const RUNTIME_STAMP = '.megaclient-runtime'
+// The runtime list changes a few times a year, so an hour-old copy is fine.
+const RUNTIMES_MAX_AGE_MS = 60 * 60_000
+
+// The first snapshot that required each Java release. Snapshots move to a new
+// Java before the release they lead to, so the version number alone is not
+// enough to tell.
+const JAVA_BY_RELEASE_TIME: Array<[number, number]> = [
+  [Date.parse('2025-12-16T00:00:00Z'), 25],
+  [Date.parse('2024-04-03T00:00:00Z'), 21],
+  [Date.parse('2021-09-15T00:00:00Z'), 17],
+  [Date.parse('2021-05-19T00:00:00Z'), 16]
+]
+
+// For profiles without a javaVersion, or when none could be read, the release
+// time of the vanilla version answers for snapshots and releases alike.
+async function javaMajorByReleaseTime(gameDirectory: string, versionId: string): Promise<number> {
+  const base = await baseMinecraftVersion(gameDirectory, versionId, minecraftVersionJson).catch(() => versionId)
+  const vanilla = await readVersionJson(gameDirectory, base)
+  const releaseTime = vanilla?.releaseTime ? Date.parse(vanilla.releaseTime) : await minecraftReleaseTime(base).catch(() => undefined)
+  return JAVA_BY_RELEASE_TIME.find(([since]) => releaseTime && releaseTime >= since)?.[1] ?? 8
+}
+
+// Before the first launch the vanilla profile is not on disk yet, so it is
+// read from Mojang's manifest instead.
+export async function javaRequirement(gameDirectory: string, versionId: string): Promise<JavaRequirement> {
+  const version = await resolveVersionJson(gameDirectory, versionId, async (id) => await readVersionJson(gameDirectory, id) ?? await minecraftVersionJson(id))
+    .catch(() => null)
+  const major = version?.javaVersion?.majorVersion ?? await javaMajorByReleaseTime(gameDirectory, versionId)
+  // An ARM64 JVM can only load ARM64 natives. Versions whose LWJGL has none
+  // run on the x64 runtime through Rosetta or Windows' x64 emulation instead.
+  const arch: JavaArch = process.arch === 'arm64' && (!version || hasArm64Natives(version)) ? 'aarch64' : 'x64'
+  return { major, arch, component: version?.javaVersion?.component ?? (version ? 'jre-legacy' : undefined) }
+}
+
+function mojangRuntimePlatform(arch: JavaArch): string | null {
+  if (process.platform === 'win32') return arch === 'aarch64' ? 'windows-arm64' : 'windows-x64'
+  if (process.platform === 'darwin') return arch === 'aarch64' ? 'mac-os-arm64' : 'mac-os'
+  if (process.platform === 'linux') return arch === 'aarch64' ? null : 'linux'
+  return null
+}
+
+function mojangJavaExecutable(home: string): string {
+  if (process.platform === 'darwin') return path.join(home, 'jre.bundle', 'Contents', 'Home', 'bin', 'java')
+  return path.join(home, 'bin', process.platform === 'win32' ? 'javaw.exe' : 'java')
+}
+
+// Installs the exact runtime component a version profile names (for example
+// java-runtime-delta) from Mojang's runtime manifest, file by file, so the game
+// runs on the JVM build it was tested against. The manifest's SHA-1 is kept
+// as a stamp so an unchanged runtime is not checked again.
+async function ensureMojangJava(
+  { major, arch, component }: JavaRequirement,
+  onProgress?: (message: string, progress?: number, detail?: ProgressDetail) => void,
+  signal?: AbortSignal,
+  confirmDownload?: (bytes: number) => Promise<void>
+): Promise<JavaRuntime | null> {
+  const platform = mojangRuntimePlatform(arch)
+  if (!component || !platform || !/^[\w-]+$/.test(component)) return null
+  const directory = path.join(runtimesDirectory(), `mojang-${component}-${platform}`)
+  const executable = mojangJavaExecutable(directory)
+  const stamp = (await fs.readFile(path.join(directory, RUNTIME_STAMP), 'utf8').catch(() => '')).trim()
+  const installed = Boolean(stamp) && Boolean(await fs.stat(executable).catch(() => null))
+  // A finished install is used as it is while offline; the manifest is only
+  // needed to notice that Mojang has published a newer build.
+  if (installed && isOffline()) return { major, arch, executable }
+  let runtimes: Record<string, Record<string, MojangRuntimeEntry[]>>
+  try {
+    runtimes = await fetchCachedJson(MOJANG_RUNTIMES_URL, { maxAgeMs: RUNTIMES_MAX_AGE_MS, staleIfError: true, signal })
+  } catch (error) {
+    signal?.throwIfAborted()
+    if (!installed) throw error
+    logger.warn('Mojang\'s Java runtime list could not be fetched, using the installed runtime.', { component, error })
+    return { major, arch, executable }
+  }
+  const entry = runtimes[platform]?.[component]?.[0]
+  if (!entry) return installed ? { major, arch, executable } : null
+  if (installed && stamp === entry.manifest.sha1) return { major, arch, executable }
+
+  onProgress?.(`Downloading Java ${entry.version.name}`)
+  const manifest = await fetchJson<{ files: Record<string, MojangRuntimeFile> }>(entry.manifest.url, { signal })
+  const files = Object.entries(manifest.files ?? {})
+  const root = path.resolve(directory)
+  const target = (name: string): string => {
+    const destination = path.resolve(root, name)
+    if (!destination.startsWith(`${root}${path.sep}`)) throw new Error(`The Java runtime manifest contains an unsafe path: ${name}`)
+    return destination
+  }
+
+  for (const [name, file] of files) {
+    if (file.type === 'directory') await fs.mkdir(target(name), { recursive: true })
+  }
+  const downloads = files.filter(([, file]) => file.type === 'file' && file.downloads?.raw)
+  if (confirmDownload) {
+    // Sized from the files that are missing or the wrong length; damaged ones
+    // are found by their hash below and are rare enough not to count.
+    let bytes = 0
+    for (const [name, file] of downloads) {
+      if ((await fs.stat(target(name)).catch(() => null))?.size !== file.downloads!.raw!.size) bytes += file.downloads!.raw!.size
+    }
+    await confirmDownload(bytes)
+  }
+  let completed = 0
+  await runWithConcurrency(downloads, downloadConcurrency(), async ([name, file]) => {
+    const raw = file.downloads!.raw!
+    const destination = target(name)
+    const stat = await fs.stat(destination).catch(() => null)
+    if (!stat || stat.size !== raw.size || (await hashFile(destination, 'sha1')).toLowerCase() !== raw.sha1.toLowerCase()) {
+      await download(raw.url, destination, undefined, { signal, resume: true, sha1: raw.sha1, size: raw.size })
+    }
+    if (file.executable && process.platform !== 'win32') await fs.chmod(destination, 0o755)
+    completed++
+    onProgress?.(`Downloading Java ${entry.version.name}`, completed / downloads.length, { items: { current: completed, total: downloads.length } })
+  })
+  for (const [name, file] of files) {
+    if (file.type !== 'link' || !file.target || process.platform === 'win32') continue
+    const link = target(name)
+    target(path.join(path.dirname(name), file.target))
+    await fs.rm(link, { force: true })
+    await fs.symlink(file.target, link)
+  }
+
+  await fs.writeFile(path.join(directory, RUNTIME_STAMP), entry.manifest.sha1, 'utf8')
+  return { major, arch, executable }
+}
+
+function adoptiumOs(): string {
+  if (process.platform === 'darwin') return 'mac'
+  if (process.platform === 'win32') return 'windows'
+  return 'linux'
+}
+
+function runtimeKey(major: number, arch: JavaArch): string {
+  return `temurin-${major}-${adoptiumOs()}-${arch}`
+}
+
+// The folders under .runtimes that can satisfy a requirement: Mojang's own
+// runtime where one exists for this platform, and the Temurin fallback.
+export function runtimeDirectoryNames({ major, arch, component }: JavaRequirement): string[] {
+  const platform = mojangRuntimePlatform(arch)
+  return [runtimeKey(major, arch), ...(component && platform ? [`mojang-${component}-${platform}`] : [])]
+}
+
+function javaExecutable(home: string): string {
+  if (process.platform === 'darwin') return path.join(home, 'Contents', 'Home', 'bin', 'java')
+  return path.join(home, 'bin', process.platform === 'win32' ? 'javaw.exe' : 'java')
+}
+
+async function installedRuntime(directory: string): Promise<string | null> {
+  // Archives contain a single versioned top-level folder such as jdk-17.0.9+9-jre.
+  for (const entry of await fs.readdir(directory, { withFileTypes: true }).catch(() => [])) {
+    if (!entry.isDirectory()) continue
+    const executable = javaExecutable(path.join(directory, entry.name))
+    if (await fs.stat(executable).catch(() => null)) return executable
+  }
+  return null
+}
+
+async function extractArchive(archive: string, destination: string): Promise<void> {
+  await fs.mkdir(destination, { recursive: true })
+  if (archive.endsWith('.zip')) {
+    await new Promise<void>((resolve, reject) => new AdmZip(archive).extractAllToAsync(destination, true, false, (error) => error ? reject(error) : resolve()))
+    return
+  }
+  await execFileAsync('tar', ['-xzf', archive, '-C', destination], { windowsHide: true })
+}
+
+// confirmDownload is told the size of a runtime before it is downloaded, and
+// stops the install by throwing.
+export async function ensureManagedJava(
+  requirement: JavaRequirement,
+  onProgress?: (message: string, progress?: number, detail?: ProgressDetail) => void,
+  signal?: AbortSignal,
+  confirmDownload?: (bytes: number) => Promise<void>
+): Promise<JavaRuntime> {
+  try {
+    const runtime = await ensureMojangJava(requirement, onProgress, signal, confirmDownload)
+    if (runtime) return runtime
+  } catch (error) {
+    signal?.throwIfAborted()
+    logger.warn('Mojang\'s Java runtime could not be installed, falling back to Temurin.', { error })
+  }
+  return ensureAdoptiumJava(requirement, onProgress, signal, confirmDownload)
+}
+
+// Downloads the Eclipse Temurin JRE for the running OS and the requested CPU
+// architecture into .runtimes/temurin-<major>-<os>-<arch>, verifying the
+// published SHA-256.
+async function ensureAdoptiumJava(
+  { major, arch }: JavaRequirement,
+  onProgress?: (message: string, progress?: number, detail?: ProgressDetail) => void,
+  signal?: AbortSignal,
+  confirmDownload?: (bytes: number) => Promise<void>
+): Promise<JavaRuntime> {
+  const directory = path.join(runtimesDirectory(), runtimeKey(major, arch))
+  const existing = await installedRuntime(directory)
+  if (existing) return { major, arch, executable: existing }
+
+  onProgress?.(`Downloading Java ${major} (${arch})`)
+  const query = new URLSearchParams({ architecture: arch, image_type: 'jre', os: adoptiumOs(), vendor: 'eclipse' })
+  const assets = await fetchJson<AdoptiumAsset[]>(`https://api.adoptium.net/v3/assets/latest/${major}/hotspot?${query}`, { signal })
+  const binary = assets.find((asset) => asset.binary?.package?.link)?.binary?.package
+  if (!binary?.link || !binary.name) throw new Error(`Java ${major} is not available for this computer.`)
+
+  // Temurin JREs are around 50 MB when the API leaves the size out.
+  await confirmDownload?.(binary.size ?? 50 * 1024 * 1024)
+  const archive = path.join(runtimesDirectory(), binary.name)
+  const staging = `${directory}.tmp`
+  try {
+    await download(binary.link, archive, (downloaded, total) => onProgress?.(`Downloading Java ${major}`, total ? downloaded / total : undefined, total ? { bytes: { current: downloaded, total } } : undefined), { signal, resume: true })
+    if (binary.checksum && (await hashFile(archive, 'sha256')).toLowerCase() !== binary.checksum.toLowerCase()) {
+      throw new Error(`The Java ${major} download was damaged. Please try again.`)
+    }
+    onProgress?.(`Installing Java ${major}`)
+    await fs.rm(staging, { recursive: true, force: true })
+    await extractArchive(archive, staging)
+    await fs.rm(directory, { recursive: true, force: true })
+    await fs.rename(staging, directory)
+  } finally {
+    await fs.rm(archive, { force: true }).catch(() => undefined)
+    await fs.rm(staging, { recursive: true, force: true }).catch(() => undefined)
+  }
+
+  const executable = await installedRuntime(directory)
+  if (!executable) throw new Error(`Java ${major} was downloaded but could not be found.`)
+  if (process.platform !== 'win32') await fs.chmod(executable, 0o755).catch(() => undefined)
+  return { major, arch, executable }
+}
@@ -0,0 +1,94 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import type { LaunchLogSummary } from '../types'
+import { getInstance } from './instances'
+import { launchLogsDirectory } from './paths'
+import { logger } from './logger'
+
+const LAUNCH_LOG_LIMIT = 20
+// Attached files beyond this are cut short, which no hs_err log reaches.
+const ATTACHMENT_LIMIT = 4 * 1024 * 1024
+const LOG_NAME = /^\d{4}-\d{2}-\d{2}T\d{2}-\d{2}-\d{2}-\d{3}Z\.log$/
+
+export interface LaunchLogWriter {
+  name: string
+  write(line: string): void
+  flush(): Promise<void>
+  attach(title: string, source: string): Promise<void>
+}
+
+async function pruneLaunchLogs(directory: string): Promise<void> {
+  const names = (await fs.readdir(directory).catch(() => [] as string[])).filter((name) => LOG_NAME.test(name)).sort()
+  for (const name of names.slice(0, Math.max(0, names.length - LAUNCH_LOG_LIMIT))) {
+    await fs.rm(path.join(directory, name), { force: true }).catch(() => undefined)
+  }
+}
+
+// Each launch gets its own timestamped file. Lines are batched and appended in
+// order, so the log keeps growing after the game has exited, for example when
+// a crash report is read, without the writer needing to be closed.
+export function createLaunchLog(slug: string, startedAt = new Date()): LaunchLogWriter {
+  const directory = launchLogsDirectory(slug)
+  const name = `${startedAt.toISOString().replace(/[:.]/g, '-')}.log`
+  const file = path.join(directory, name)
+  let pending: string[] = []
+  let timer: NodeJS.Timeout | null = null
+  let writing = fs.mkdir(directory, { recursive: true })
+    .then(() => pruneLaunchLogs(directory))
+    .catch((error) => logger.warn('The launch log folder could not be prepared.', { error }))
+
+  const flush = (): Promise<void> => {
+    if (timer) clearTimeout(timer)
+    timer = null
+    if (!pending.length) return writing
+    const text = `${pending.join('\n')}\n`
+    pending = []
+    writing = writing.then(() => fs.appendFile(file, text, 'utf8'))
+      .catch((error) => logger.warn('The launch log could not be written.', { error }))
+    return writing
+  }
+
+  // Appends a whole file, such as the JVM's hs_err log, after the lines
+  // written so far, so the launch log alone is enough for a bug report.
+  const attach = (title: string, source: string): Promise<void> => {
+    void flush()
+    writing = writing.then(async () => {
+      const content = await fs.readFile(source)
+      const text = content.subarray(0, ATTACHMENT_LIMIT).toString('utf8')
+      const truncated = content.length > ATTACHMENT_LIMIT ? `(cut short after ${ATTACHMENT_LIMIT} bytes)\n` : ''
+      await fs.appendFile(file, `----- ${title} -----\n${text.replace(/\n?$/, '\n')}${truncated}----- End of ${title} -----\n`, 'utf8')
+    }).catch((error) => logger.warn('A file could not be attached to the launch log.', { error }))
+    return writing
+  }
+
+  return {
+    name,
+    write(line) {
+      pending.push(line)
+      if (!timer) timer = setTimeout(() => void flush(), 500)
+    },
+    flush,
+    attach
+  }
+}
+
+export async function listLaunchLogs(instanceId: string): Promise<LaunchLogSummary[]> {
+  const directory = launchLogsDirectory(getInstance(instanceId).slug)
+  const names = (await fs.readdir(directory).catch(() => [] as string[])).filter((name) => LOG_NAME.test(name))
+  const logs = await Promise.all(names.map(async (name) => {
+    const stat = await fs.stat(path.join(directory, name)).catch(() => null)
+    if (!stat?.isFile()) return null
+    // The file name is the launch time with : and . replaced.
+    const startedAt = name.replace(/T(\d{2})-(\d{2})-(\d{2})-(\d{3})Z\.log$/, 'T$1:$2:$3.$4Z')
+    return { name, startedAt, size: stat.size, modifiedAt: stat.mtime.toISOString() } satisfies LaunchLogSummary
+  }))
+  return logs.filter((log): log is LaunchLogSummary => Boolean(log)).sort((a, b) => b.name.localeCompare(a.name))
+}
+
+export async function readLaunchLog(instanceId: string, name: string): Promise<string> {
+  if (!LOG_NAME.test(name)) throw new Error('Launch log not found.')
+  const file = path.join(launchLogsDirectory(getInstance(instanceId).slug), name)
+  return fs.readFile(file, 'utf8').catch(() => {
+    throw new Error('Launch log not found.')
+  })
+}
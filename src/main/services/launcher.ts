@@ -3,8 +3,10 @@ import fs from 'node:fs/promises'
 import path from 'node:path'
 import semver from 'semver'
 import { Launcher, type Account } from 'eml-lib'
-import type { LaunchProgress, LauncherInstance } from '../types'
+import type { ExitExplanation, LaunchProgress, LaunchProgressEvent, LaunchStage, LaunchStageEntry, LaunchStateStage, LauncherInstance, LauncherSettings, LauncherVisibility, ProgressDetail, RunningGame } from '../types'
 import { getValidAccount } from './account'
+import { OfflineError, isOffline } from './connectivity'
+import { confirmMeteredDownload, isMeteredConnection } from './metered'
 import {
   MINIMUM_PROTECTED_CLIENT_LOADER,
   PROTECTED_CLIENT_VERSION,
@@ -13,11 +15,27 @@ import {
   validatePreparedClientPayloadSync,
   type PreparedClientPayload
 } from './clientPayload'
-import { getInstance, updateInstance } from './instances'
-import { instanceDirectory, modsDirectory } from './paths'
+import { ensureLinkedDirectory, ensureSharedGameFiles, getInstance, recordPlaySession, setInstanceUnstable, suggestedMemoryMax, updateInstance } from './instances'
+import { instanceDirectory, modsDirectory, savesDirectory } from './paths'
 import { store } from './store'
-import { getLoaderVersions } from './versions'
-import { installMod, setModEnabled } from './modrinth'
+import { getLoaderVersions, minecraftReleaseTime, minecraftVersionType, resolveMinecraftVersionId } from './versions'
+import { applicableArguments, readVersionJson, resolveVersionJson } from './versionJson'
+import { FABRIC_API_PROJECT, installMod, setModEnabled, trackedContent } from './modrinth'
+import { materializeLegacyAssets } from './legacyAssets'
+import { createLaunchLog, type LaunchLogWriter } from './launchLogs'
+import { tailLogFile, type LogTail } from './logTail'
+import { cpuPercent, sampleProcesses, type ProcessSample } from './processStats'
+import { applyProcessPriority } from './processPriority'
+import { explainExit } from './exitDiagnostics'
+import { disableModsForSafeMode, restoreSafeModeMods } from './safeMode'
+import { prepareBisectRound, recordBisectRound } from './bisect'
+import { newestCrashReport, newestJvmErrorLog, readCrashReport, readJvmErrorLog } from './crashReports'
+import { installVersionFiles, verifyAssets, verifyLibraries } from './gameFiles'
+import { runAtLowPriority } from './downloads'
+import { backupPlayedWorlds } from './worlds'
+import { nativesJvmArguments, prepareNatives } from './natives'
+import { DirectLauncher, installLaunchLibraries, type QuickPlayTarget } from './directLaunch'
+import { ensureManagedJava, javaRequirement, type JavaRequirement } from './java'
 import { showLauncherActivity, showLaunchingActivity, showPlayingActivity } from './discordActivity'
 import {
   findGameJavaProcesses,
@@ -26,8 +44,10 @@ import {
   scanLoadedModules,
   scanRunningTools,
   secureChildEnvironment,
-  terminateProcesses
+  terminateProcesses,
+  userJvmArguments
 } from './security'
+import { logger, type Logger } from './logger'
 
 const MINIMUM_CLIENT_LOADER = MINIMUM_PROTECTED_CLIENT_LOADER
 export const CLIENT_VERSION = PROTECTED_CLIENT_VERSION
@@ -36,14 +56,71 @@ const ESCAPED_CLIENT_VERSION = CLIENT_VERSION.replace(/[.*+?^${}()|[\]\\]/g, '\\
 const CLIENT_VERSION_PATTERN = new RegExp(`(?:^|[\\s\\-:])megaclient(?:[\\s\\-:@]|$)[^\\r\\n]{0,80}\\b${ESCAPED_CLIENT_VERSION}\\b`, 'im')
 const CLIENT_MOD_PATTERN = new RegExp(`mod\\s+megaclient\\s+${ESCAPED_CLIENT_VERSION}`, 'im')
 
-let activeLauncher: Launcher | null = null
+type ConsoleEntry = { seq: number; line: string; kind: 'info' | 'error' | 'game' | 'muted' | 'success' }
+
+const CONSOLE_BUFFER_LINES = 5000
+
+// Everything that belongs to one launch or preparation. Sessions are keyed by
+// instance id, so several instances can download and run side by side without
+// mixing their console output, timers or events.
+interface LaunchSession {
+  instanceId: string
+  name: string
+  mainWindow: BrowserWindow
+  abort: AbortController
+  launcher: Launcher | DirectLauncher | null
+  gameStarted: boolean
+  processIds: number[]
+  instance: LauncherInstance | null
+  startedAt: number | null
+  stopping: boolean
+  safeMode: boolean
+  bisecting: boolean
+  outOfMemory: boolean
+  consoleLines: ConsoleEntry[]
+  consoleSequence: number
+  log: LaunchLogWriter | null
+  logTails: LogTail[]
+  gameMessages: Set<string>
+  trace: Logger
+  stage: LaunchStage | 'start'
+  stages: LaunchStageEntry[]
+  consoleState: string
+  securityTimer: NodeJS.Timeout | null
+  securityCheckRunning: boolean
+  clientVerificationTimer: NodeJS.Timeout | null
+  statsTimer: NodeJS.Timeout | null
+  // Idle-time preparation, which reports nothing to the renderer.
+  background: boolean
+}
+
+const sessions = new Map<string, LaunchSession>()
+// Idle-time preparations still running, so a launch can wait for one it stops.
+const backgroundRuns = new Map<string, Promise<void>>()
+// World backups taken after a game exits. The instance stays busy until they
+// finish, so the worlds are not opened or changed while being read.
+const worldBackupRuns = new Map<string, Promise<void>>()
+// Automatic restarts in a row per instance, reset by a run that outlives the
+// instance's crash window.
+const restartAttempts = new Map<string, number>()
+// Crash restarts waiting to launch, which keep a closing launcher running.
+let pendingRestarts = 0
+// Set by the "close" visibility: the window is hidden while the game runs and
+// the launcher quits once no launch, game, restart or world backup is left.
+let quitWhenIdle = false
+// Crashes in a row per instance, used to offer a mod bisect.
+const crashStreaks = new Map<string, number>()
+// When each instance recently crashed, to spot a crash loop.
+const recentCrashes = new Map<string, number[]>()
+const CRASH_LOOP_LIMIT = 3
+const CRASH_LOOP_WINDOW_MS = 10 * 60_000
+// The last finished session of each instance, so its console can still be
+// read after the game has exited.
+const finishedSessions = new Map<string, LaunchSession>()
 let consoleWindow: BrowserWindow | null = null
+let consoleSession: LaunchSession | null = null
 let gameTray: Tray | null = null
-let securityTimer: NodeJS.Timeout | null = null
-let clientVerificationTimer: NodeJS.Timeout | null = null
-let securityCheckRunning = false
-let consoleLines: Array<{ line: string; kind: 'info' | 'error' | 'game' | 'muted' | 'success' }> = []
-let consolePending: Array<{ line: string; kind: 'info' | 'error' | 'game' | 'muted' | 'success' }> = []
+let consolePending: ConsoleEntry[] = []
 let consoleFlushTimer: NodeJS.Timeout | null = null
 
 function appIconPath(): string {
@@ -56,6 +133,67 @@ function emit(mainWindow: BrowserWindow, event: string, payload: unknown): void
   if (!mainWindow.isDestroyed()) mainWindow.webContents.send(event, payload)
 }
 
+// Launch events carry the instance id so the renderer can tell concurrent
+// launches apart.
+function emitLaunch(session: LaunchSession, event: string, payload: object): void {
+  if (session.background) return
+  emit(session.mainWindow, event, { instanceId: session.instanceId, ...payload })
+}
+
+const STAGE_LABELS: Record<LaunchStateStage, string> = {
+  'resolving-version': 'resolving the version',
+  'downloading-assets': 'downloading game files',
+  'ensuring-java': 'preparing Java',
+  spawning: 'starting Minecraft',
+  running: 'running',
+  exited: 'exited',
+  failed: 'failed',
+  cancelled: 'cancelled'
+}
+
+function currentLaunchStage(session: LaunchSession): LaunchStateStage | undefined {
+  return session.stages.at(-1)?.stage
+}
+
+// The coarse launch state machine behind mc:stage. Every transition is
+// timestamped and the whole history is sent along, so a step list can be drawn
+// from any single event and a failure names the stage it happened in.
+function setLaunchStage(session: LaunchSession, stage: LaunchStateStage): void {
+  const previous = currentLaunchStage(session)
+  if (previous === stage || previous === 'exited' || previous === 'failed' || previous === 'cancelled') return
+  session.stages.push({ stage, at: new Date().toISOString() })
+  if (stage === 'failed' && previous) appendConsole(session, `[MegaClient] The launch failed while ${STAGE_LABELS[previous]}`, 'error')
+  else session.trace.info(`Launch state ${stage}`, { previous })
+  emitLaunch(session, 'mc:stage', { stage, previous, at: session.stages.at(-1)!.at, stages: session.stages })
+}
+
+// Progress goes out twice: as the typed mc:progress event, and in the older
+// launch:progress shape that the library view still reads.
+function emitProgress(session: LaunchSession, stage: LaunchStage, message: string, progress?: number, detail: ProgressDetail = {}): void {
+  if (stage !== session.stage) {
+    session.stage = stage
+    session.trace = session.trace.child({ stage })
+    session.trace.info('Launch stage started')
+  }
+  if (session.background) return
+  const fraction = progress ?? (detail.bytes?.total ? detail.bytes.current / detail.bytes.total : detail.items?.total ? detail.items.current / detail.items.total : undefined)
+  const percent = fraction === undefined ? undefined : Math.round(Math.max(0, Math.min(1, fraction)) * 1000) / 10
+  emit(session.mainWindow, 'mc:progress', { instanceId: session.instanceId, stage, message, percent, ...detail } satisfies LaunchProgressEvent)
+  emitLaunch(session, 'launch:progress', {
+    phase: stage, message, progress: fraction,
+    downloaded: detail.bytes?.current, total: detail.bytes?.total, speed: detail.speed
+  } satisfies LaunchProgress)
+}
+
+// Any launch, preparation or running game, including idle-time preparation.
+export function hasActiveSessions(): boolean {
+  return sessions.size > 0
+}
+
+function anyGameRunning(except?: LaunchSession): boolean {
+  return [...sessions.values()].some((session) => session !== except && session.gameStarted)
+}
+
 function showMainWindow(mainWindow: BrowserWindow): void {
   if (mainWindow.isDestroyed()) return
   if (mainWindow.isMinimized()) mainWindow.restore()
@@ -83,6 +221,32 @@ function destroyGameTray(): void {
   gameTray = null
 }
 
+function quitIfIdle(): void {
+  if (quitWhenIdle && !sessions.size && !worldBackupRuns.size && !pendingRestarts) app.quit()
+}
+
+// Applied once the game has started. Closing only hides the window, so the
+// game's exit, playtime and world backups are still handled before the
+// launcher quits. Showing the window again keeps it open.
+function hideLauncherForGame(mainWindow: BrowserWindow, visibility: LauncherVisibility): void {
+  if (mainWindow.isDestroyed()) return
+  if (visibility === 'minimize') mainWindow.minimize()
+  else if (visibility === 'tray') {
+    createGameTray(mainWindow)
+    mainWindow.hide()
+  } else if (visibility === 'close') {
+    quitWhenIdle = true
+    mainWindow.hide()
+    mainWindow.once('show', () => { quitWhenIdle = false })
+  }
+}
+
+function restoreLauncherAfterGame(mainWindow: BrowserWindow, visibility: LauncherVisibility): void {
+  destroyGameTray()
+  if (quitWhenIdle) return
+  if (visibility !== 'keep-open') showMainWindow(mainWindow)
+}
+
 function consoleHtml(iconDataUrl: string): string {
   return `<!doctype html><html><head><meta charset="utf-8"><title>MegaClient Console</title><style>
   :root{color-scheme:dark}*{box-sizing:border-box}html,body{width:100%;height:100%}body{margin:0;background:#090a0f;color:#d8dbe4;font:12px/1.58 ui-monospace,SFMono-Regular,Consolas,monospace;overflow:hidden;border:1px solid #272b36;border-radius:12px}.bar{-webkit-app-region:drag;height:50px;display:flex;align-items:center;padding:0 6px 0 14px;border-bottom:1px solid #232631;background:linear-gradient(180deg,#141620,#0f1118)}.brand{display:flex;align-items:center;gap:9px}.brand img{width:26px;height:26px;object-fit:contain}.title{font:650 12px Inter,Segoe UI,sans-serif;color:#f5f6f8}.subtitle{display:block;color:#747b8b;font:9px Inter,Segoe UI,sans-serif;margin-top:1px}.state{margin-left:auto;color:#9ca3b2;font:10px Inter,Segoe UI,sans-serif;padding-right:10px}.controls{-webkit-app-region:no-drag;display:flex;align-self:stretch}.controls button{width:42px;border:0;background:transparent;color:#aeb4c1;cursor:pointer;font:15px Segoe UI,sans-serif}.controls button:hover{background:#20232d;color:#fff}.controls button.close:hover{background:#d83b52}#log{height:calc(100vh - 51px);padding:14px 16px 18px;overflow:auto;white-space:pre-wrap;word-break:break-word;scrollbar-width:thin;scrollbar-color:#343947 transparent}.line{padding:1px 0}.info{color:#cfd3dc}.error{color:#ff6c83}.success{color:#73d99a}.game{color:#b8c2ff}.muted{color:#737b8d}</style></head><body><div class="bar"><div class="brand"><img src="${iconDataUrl}"/><span><span class="title">MegaClient Console</span><span class="subtitle">Minecraft launch and game output</span></span></div><span id="state" class="state">Preparing</span><div class="controls"><button aria-label="Minimise" onclick="window.mega.consoleWindow.minimize()">−</button><button aria-label="Maximise" onclick="window.mega.consoleWindow.maximize()">□</button><button class="close" aria-label="Close" onclick="window.mega.consoleWindow.close()">×</button></div></div><div id="log"></div></body></html>`
@@ -100,7 +264,7 @@ function flushConsole(): void {
   void consoleWindow.webContents.executeJavaScript(script).catch(() => undefined)
 }
 
-function queueConsole(entries: typeof consolePending): void {
+function queueConsole(entries: ConsoleEntry[]): void {
   // Keep the bounded history, but do not build a second unbounded live queue
   // while the optional console window is closed. Reopening replays consoleLines.
   if (!consoleWindow || consoleWindow.isDestroyed()) return
@@ -110,10 +274,24 @@ function queueConsole(entries: typeof consolePending): void {
 }
 
 function replayConsole(): void {
-  queueConsole(consoleLines)
+  if (!consoleSession || !consoleWindow || consoleWindow.isDestroyed()) return
+  const state = JSON.stringify(consoleStateText(consoleSession))
+  void consoleWindow.webContents.executeJavaScript(`document.getElementById('log').replaceChildren();document.getElementById('state').textContent=${state}`)
+    .catch(() => undefined)
+  consolePending = []
+  queueConsole(consoleSession.consoleLines)
+}
+
+function consoleStateText(session: LaunchSession): string {
+  return sessions.size > 1 ? `${session.name} · ${session.consoleState}` : session.consoleState
 }
 
-function showConsole(): BrowserWindow {
+// The console shows one session at a time; switching replays its history.
+function showConsole(session?: LaunchSession | null): BrowserWindow {
+  if (session && session !== consoleSession) {
+    consoleSession = session
+    replayConsole()
+  }
   if (consoleWindow && !consoleWindow.isDestroyed()) {
     consoleWindow.show()
     consoleWindow.focus()
@@ -155,16 +333,61 @@ function showConsole(): BrowserWindow {
   return consoleWindow
 }
 
-function appendConsole(line: string, kind: 'info' | 'error' | 'game' | 'muted' | 'success' = 'info'): void {
-  const entry = { line, kind }
-  consoleLines.push(entry)
-  if (consoleLines.length > 3000) consoleLines = consoleLines.slice(-2400)
-  queueConsole([entry])
+function appendConsole(session: LaunchSession, line: string, kind: ConsoleEntry['kind'] = 'info'): void {
+  const entry = { seq: ++session.consoleSequence, line, kind }
+  session.consoleLines.push(entry)
+  session.log?.write(line)
+  if (kind !== 'game') session.trace[kind === 'error' ? 'error' : kind === 'muted' ? 'debug' : 'info'](line)
+  if (session.consoleLines.length > CONSOLE_BUFFER_LINES * 1.2) session.consoleLines = session.consoleLines.slice(-CONSOLE_BUFFER_LINES)
+  if (session === consoleSession) queueConsole([entry])
 }
 
-function setConsoleState(state: string): void {
-  if (!consoleWindow || consoleWindow.isDestroyed()) return
-  void consoleWindow.webContents.executeJavaScript(`document.getElementById('state').textContent=${JSON.stringify(state)}`).catch(() => undefined)
+// The part of a log4j line after the timestamp, thread and level, which is the
+// same on stdout and in the log files even though their layouts differ.
+function logMessage(line: string): string {
+  const index = line.indexOf(']: ')
+  return (index >= 0 ? line.slice(index + 3) : line).trim()
+}
+
+function rememberGameMessage(session: LaunchSession, message: string): void {
+  session.gameMessages.add(message)
+  if (session.gameMessages.size > 4000) session.gameMessages.delete(session.gameMessages.values().next().value!)
+}
+
+// Reported once per run, on whichever of stdout or the log files shows the
+// error first, with a larger -Xmx the player can accept for the next launch.
+function watchForOutOfMemory(session: LaunchSession, line: string): void {
+  if (session.outOfMemory || !line.includes('java.lang.OutOfMemoryError')) return
+  session.outOfMemory = true
+  const instance = session.instance ?? getInstance(session.instanceId)
+  const memoryMaxMb = effectiveMemory(instance, store.getData().settings).max
+  const suggestedMaxMb = suggestedMemoryMax(memoryMaxMb)
+  appendConsole(session, suggestedMaxMb
+    ? `[MegaClient] Minecraft ran out of its ${memoryMaxMb} MB of memory. Raising it to ${suggestedMaxMb} MB may help.`
+    : `[MegaClient] Minecraft ran out of its ${memoryMaxMb} MB of memory, which is already as much as this computer can spare. Remove heavy mods or resource packs.`, 'error')
+  emitLaunch(session, 'mc:out-of-memory', { line: line.trim().slice(0, 600), memoryMaxMb, suggestedMaxMb })
+}
+
+// Errors during early startup, for example from a mod loader before it hooks
+// stdout, sometimes only reach the game's own log files. Their lines are shown
+// unless the same message already arrived on stdout.
+function startGameLogTails(session: LaunchSession, instance: LauncherInstance, includeDebug: boolean): void {
+  const logs = path.join(instanceDirectory(instance.slug), 'logs')
+  const follow = (name: string, kind: ConsoleEntry['kind']): LogTail => tailLogFile(path.join(logs, name), (line) => {
+    const message = logMessage(line)
+    if (!message || session.gameMessages.has(message)) return
+    rememberGameMessage(session, message)
+    watchForOutOfMemory(session, line)
+    appendConsole(session, `[${name}] ${line}`, kind)
+  })
+  session.logTails.push(follow('latest.log', 'game'))
+  if (includeDebug) session.logTails.push(follow('debug.log', 'muted'))
+}
+
+function setConsoleState(session: LaunchSession, state: string): void {
+  session.consoleState = state
+  if (session !== consoleSession || !consoleWindow || consoleWindow.isDestroyed()) return
+  void consoleWindow.webContents.executeJavaScript(`document.getElementById('state').textContent=${JSON.stringify(consoleStateText(session))}`).catch(() => undefined)
 }
 
 async function resolveLoader(instance: LauncherInstance): Promise<LauncherInstance> {
@@ -195,7 +418,7 @@ async function resolveLoader(instance: LauncherInstance): Promise<LauncherInstan
   return updateInstance(instance.id, { loaderVersion: versions[0] })
 }
 
-async function prepareCustomClient(instance: LauncherInstance, mainWindow: BrowserWindow): Promise<PreparedClientPayload | null> {
+async function prepareCustomClient(session: LaunchSession, instance: LauncherInstance): Promise<PreparedClientPayload | null> {
   if (!instance.customClient) return null
 
   const mods = modsDirectory(instance.slug)
@@ -207,28 +430,41 @@ async function prepareCustomClient(instance: LauncherInstance, mainWindow: Brows
 
   // Resolve required Modrinth dependencies before staging the private runtime.
   // This keeps the decrypted client on disk for the shortest possible time.
-  emit(mainWindow, 'launch:progress', { phase: 'client', message: 'Installing required client files' } satisfies LaunchProgress)
-  appendConsole('[MegaClient] Resolving required Fabric dependencies', 'muted')
-  try {
-    const fabricApi = await installMod(instance.id, 'P7dR8mSH', (message, progress) => {
-      emit(mainWindow, 'launch:progress', { phase: 'client', message, progress } satisfies LaunchProgress)
-    })
-    if (!fabricApi.enabled) {
-      await setModEnabled(instance.id, fabricApi.fileName, true)
+  emitProgress(session, 'client', 'Installing required client files')
+  appendConsole(session, '[MegaClient] Resolving required Fabric dependencies', 'muted')
+  if (isOffline()) {
+    // Modrinth cannot be asked for the newest build, so the copy installed by
+    // an earlier launch is used as it is.
+    const fabricApi = (await trackedContent(instance.id)).mods.find((mod) => mod.projectId === FABRIC_API_PROJECT)
+    const present = fabricApi && await fs.access(path.join(mods, fabricApi.enabled ? fabricApi.fileName : `${fabricApi.fileName}.disabled`)).then(() => true, () => false)
+    if (!fabricApi || !present) {
+      throw new OfflineError('MegaClient needs Fabric API, which has not been downloaded for this instance yet. Connect to the internet and launch once to install it.')
+    }
+    if (!fabricApi.enabled) await setModEnabled(instance.id, fabricApi.fileName, true)
+    appendConsole(session, `[MegaClient] Offline: using the installed Fabric API ${fabricApi.versionNumber ?? ''}`.trimEnd(), 'muted')
+  } else {
+    try {
+      // The client needs this build, so any other copy in the folder is replaced.
+      const fabricApi = await installMod(instance.id, FABRIC_API_PROJECT, (message, progress) => {
+        emitProgress(session, 'client', message, progress)
+      }, true)
+      if (!fabricApi.enabled) {
+        await setModEnabled(instance.id, fabricApi.fileName, true)
+      }
+      appendConsole(session, `[MegaClient] Fabric API ${fabricApi.versionNumber ?? 'compatible build'} is ready`, 'success')
+    } catch (error) {
+      const message = error instanceof Error ? error.message : String(error)
+      appendConsole(session, `[MegaClient] Dependency setup failed: ${message}`, 'error')
+      throw new Error(`MegaClient could not install its required Fabric API dependency. ${message}`)
     }
-    appendConsole(`[MegaClient] Fabric API ${fabricApi.versionNumber ?? 'compatible build'} is ready`, 'success')
-  } catch (error) {
-    const message = error instanceof Error ? error.message : String(error)
-    appendConsole(`[MegaClient] Dependency setup failed: ${message}`, 'error')
-    throw new Error(`MegaClient could not install its required Fabric API dependency. ${message}`)
   }
 
-  emit(mainWindow, 'launch:progress', { phase: 'client', message: 'Preparing MegaClient' } satisfies LaunchProgress)
-  appendConsole('[MegaClient] Decrypting and verifying the protected runtime', 'muted')
+  emitProgress(session, 'client', 'Preparing MegaClient')
+  appendConsole(session, '[MegaClient] Decrypting and verifying the protected runtime', 'muted')
   const payload = await prepareClientPayload(instance.slug)
   try {
     validatePreparedClientPayloadSync(payload)
-    appendConsole(`[MegaClient] Protected runtime prepared for Fabric Loader ${instance.loaderVersion}`, 'success')
+    appendConsole(session, `[MegaClient] Protected runtime prepared for Fabric Loader ${instance.loaderVersion}`, 'success')
     return payload
   } catch (error) {
     await payload.cleanup()
@@ -236,23 +472,57 @@ async function prepareCustomClient(instance: LauncherInstance, mainWindow: Brows
   }
 }
 
-function stopSecurityMonitor(): void {
-  if (securityTimer) clearInterval(securityTimer)
-  securityTimer = null
-  securityCheckRunning = false
+function stopResourceMonitor(session: LaunchSession): void {
+  if (session.statsTimer) clearInterval(session.statsTimer)
+  session.statsTimer = null
+}
+
+// Samples the game's memory and CPU use every few seconds for mc:stats, next to
+// the -Xmx the instance was given so over- or under-allocation is visible.
+function startResourceMonitor(session: LaunchSession, instance: LauncherInstance, memoryMaxMb: number): void {
+  stopResourceMonitor(session)
+  let previous: { sample: ProcessSample; at: number } | null = null
+  let sampling = false
+  session.statsTimer = setInterval(() => {
+    if (sampling) return
+    sampling = true
+    void (async () => {
+      const processIds = session.processIds.length ? session.processIds : await trackGameProcesses(session, instance)
+      const sample = await sampleProcesses(processIds)
+      if (!sample) return
+      const at = Date.now()
+      emitLaunch(session, 'mc:stats', {
+        memoryBytes: sample.memoryBytes,
+        memoryMaxBytes: memoryMaxMb * 1024 * 1024,
+        cpuPercent: previous ? cpuPercent(previous.sample, sample, at - previous.at) : null,
+        uptimeSeconds: session.startedAt ? Math.floor((at - session.startedAt) / 1000) : 0
+      })
+      previous = { sample, at }
+    })().catch(() => undefined).finally(() => {
+      sampling = false
+    })
+  }, 5_000)
+}
+
+function stopSecurityMonitor(session: LaunchSession): void {
+  if (session.securityTimer) clearInterval(session.securityTimer)
+  session.securityTimer = null
+  session.securityCheckRunning = false
 }
 
-function stopClientVerification(): void {
-  if (clientVerificationTimer) clearTimeout(clientVerificationTimer)
-  clientVerificationTimer = null
+function stopClientVerification(session: LaunchSession): void {
+  if (session.clientVerificationTimer) clearTimeout(session.clientVerificationTimer)
+  session.clientVerificationTimer = null
 }
 
-function startSecurityMonitor(mainWindow: BrowserWindow, instance: LauncherInstance): void {
-  stopSecurityMonitor()
+function startSecurityMonitor(session: LaunchSession, instance: LauncherInstance): void {
+  stopSecurityMonitor(session)
+  // Launch protection is Windows-only, where the scans it relies on exist.
+  if (process.platform !== 'win32') return
   let nextModuleScanAt = 0
-  securityTimer = setInterval(() => {
-    if (securityCheckRunning) return
-    securityCheckRunning = true
+  session.securityTimer = setInterval(() => {
+    if (session.securityCheckRunning) return
+    session.securityCheckRunning = true
     void (async () => {
       const gameProcesses = await findGameJavaProcesses(instance)
       if (!gameProcesses.length) return
@@ -271,13 +541,13 @@ function startSecurityMonitor(mainWindow: BrowserWindow, instance: LauncherInsta
       if (!finding) return
 
       const message = `${finding.title}: ${finding.detail}`
-      appendConsole(`[Security] ${message}`, 'error')
-      setConsoleState('Launch protection stopped the game')
-      emit(mainWindow, 'launch:error', { message })
+      appendConsole(session, `[Security] ${message}`, 'error')
+      setConsoleState(session, 'Launch protection stopped the game')
+      emitLaunch(session, 'launch:error', { message })
       await terminateProcesses(processIds)
-      stopSecurityMonitor()
-    })().catch((error) => appendConsole(`[Security] Monitor warning: ${error instanceof Error ? error.message : String(error)}`, 'muted')).finally(() => {
-      securityCheckRunning = false
+      stopSecurityMonitor(session)
+    })().catch((error) => appendConsole(session, `[Security] Monitor warning: ${error instanceof Error ? error.message : String(error)}`, 'muted')).finally(() => {
+      session.securityCheckRunning = false
     })
   }, 30_000)
 }
@@ -336,36 +606,36 @@ async function readClientMarker(payload: PreparedClientPayload): Promise<boolean
 }
 
 function startClientVerification(
-  mainWindow: BrowserWindow,
+  session: LaunchSession,
   instance: LauncherInstance,
   payload: PreparedClientPayload,
   verificationStartedAt: number,
   hasSeenClient: () => boolean,
   markClientSeen: () => void
 ): void {
-  stopClientVerification()
+  stopClientVerification(session)
   const deadline = verificationStartedAt + 240_000
   const logFallbackAt = verificationStartedAt + 15_000
   const latestLog = path.join(instanceDirectory(instance.slug), 'logs', 'latest.log')
 
   const finishSuccess = (source: 'marker' | 'log'): void => {
-    stopClientVerification()
+    stopClientVerification(session)
     markClientSeen()
-    appendConsole(
+    appendConsole(session, 
       source === 'marker'
         ? `[MegaClient] MegaClient ${CLIENT_VERSION} was verified inside the running Fabric client`
         : `[MegaClient] MegaClient ${CLIENT_VERSION} was confirmed in Fabric's active mod output`,
       'success'
     )
-    emit(mainWindow, 'launch:progress', { phase: 'client', message: `MegaClient ${CLIENT_VERSION} loaded`, progress: 1 } satisfies LaunchProgress)
+    emitProgress(session, 'client', `MegaClient ${CLIENT_VERSION} loaded`, 1)
   }
 
   const failClosed = async (detail: string): Promise<void> => {
-    stopClientVerification()
+    stopClientVerification(session)
     const message = `MegaClient ${CLIENT_VERSION} did not load correctly. ${detail}`
-    appendConsole(`[MegaClient] ${message}`, 'error')
-    setConsoleState('MegaClient failed to load')
-    emit(mainWindow, 'launch:error', { message })
+    appendConsole(session, `[MegaClient] ${message}`, 'error')
+    setConsoleState(session, 'MegaClient failed to load')
+    emitLaunch(session, 'launch:error', { message })
     const processes = await findGameJavaProcesses(instance)
     await terminateProcesses(processes.map((item) => item.ProcessId ?? 0))
   }
@@ -400,15 +670,58 @@ function startClientVerification(
       return
     }
 
-    clientVerificationTimer = setTimeout(() => void poll().catch((error) => {
-      appendConsole(`[MegaClient] Verification warning: ${error instanceof Error ? error.message : String(error)}`, 'muted')
-      clientVerificationTimer = setTimeout(() => void poll(), 2_000)
+    session.clientVerificationTimer = setTimeout(() => void poll().catch((error) => {
+      appendConsole(session, `[MegaClient] Verification warning: ${error instanceof Error ? error.message : String(error)}`, 'muted')
+      session.clientVerificationTimer = setTimeout(() => void poll(), 2_000)
     }), 1_500)
   }
 
-  clientVerificationTimer = setTimeout(() => void poll(), 1_500)
+  session.clientVerificationTimer = setTimeout(() => void poll(), 1_500)
 }
-function serverGameArgs(address?: string): string[] {
+// Instances that track the latest release or snapshot move to it at every
+// launch. Offline, the last resolved version is launched instead.
+async function followTrackedVersion(session: LaunchSession, instance: LauncherInstance): Promise<LauncherInstance> {
+  const track = instance.trackLatest!
+  const previous = instance.minecraftVersion
+  const latest = await resolveMinecraftVersionId(`latest-${track}`).catch(() => null)
+  if (latest && latest !== previous) {
+    instance = await updateInstance(instance.id, {
+      minecraftVersion: latest,
+      versionType: await minecraftVersionType(latest).catch(() => undefined),
+      loaderVersion: undefined
+    })
+    appendConsole(session, `[MegaClient] Updated from Minecraft ${previous} to the latest ${track}, ${latest}`, 'success')
+  } else {
+    appendConsole(session, `[MegaClient] Tracking the latest ${track}: Minecraft ${instance.minecraftVersion}`, 'muted')
+  }
+  emitLaunch(session, 'launch:version', { instanceId: instance.id, track, minecraftVersion: instance.minecraftVersion, previous })
+  return instance
+}
+
+function effectiveMemory(instance: LauncherInstance, settings: LauncherSettings): { min: number; max: number } {
+  // Instances without their own overrides follow the global defaults, so a
+  // change in Settings applies to every instance that has not been customised.
+  const min = Math.max(512, Number(instance.memoryMin) || settings.memoryMin)
+  const max = Math.max(min + 512, Number(instance.memoryMax) || settings.memoryMax)
+  return { min, max }
+}
+
+// Quick Play arrived in 23w14a (1.20). Later versions ignore --server, so the
+// join flag must match what the version's own profile declares.
+const QUICK_PLAY_RELEASE_TIME = Date.parse('2023-04-05T00:00:00Z')
+
+async function supportsQuickPlay(instance: LauncherInstance): Promise<boolean> {
+  const profile = await resolveVersionJson(instanceDirectory(instance.slug), launchVersionId(instance)).catch(() => null)
+  if (profile?.arguments?.game) {
+    return profile.arguments.game.some((argument) => typeof argument !== 'string'
+      && (argument.rules as Array<{ features?: Record<string, boolean> }> | undefined)?.some((rule) => rule.features?.is_quick_play_multiplayer))
+  }
+  if (profile) return false
+  const releaseTime = await minecraftReleaseTime(instance.minecraftVersion).catch(() => undefined)
+  return releaseTime ? releaseTime >= QUICK_PLAY_RELEASE_TIME : /^(?:1\.(?:2\d|[3-9]\d)|[2-9]\d\.)/.test(instance.minecraftVersion)
+}
+
+function serverGameArgs(address: string | undefined, quickPlay: boolean): string[] {
   const value = address?.trim()
   if (!value) return []
   const match = value.match(/^(.+?)(?::(\d{1,5}))?$/)
@@ -416,132 +729,770 @@ function serverGameArgs(address?: string): string[] {
   const host = match[1]!
   const port = match[2] ? Number(match[2]) : undefined
   if (port != null && (port < 1 || port > 65535)) throw new Error('The partner server port is invalid.')
+  if (quickPlay) return ['--quickPlayMultiplayer', port ? `${host}:${port}` : host]
   return ['--server', host, ...(port ? ['--port', String(port)] : [])]
 }
 
-export async function launchInstance(mainWindow: BrowserWindow, instanceId: string, serverAddress?: string): Promise<void> {
-  if (activeLauncher) throw new Error('Minecraft is already being launched by MegaClient.')
-  const settings = store.getData().settings
-  consoleLines = []
-  consolePending = []
-  if (settings.showConsole) showConsole()
-  appendConsole(`[MegaClient] Starting ${new Date().toLocaleString()}`, 'muted')
-  if (serverAddress) appendConsole(`[MegaClient] Direct connection: ${serverAddress}`, 'muted')
-  setConsoleState('Preparing')
+function quickPlayTargetOf(args: string[]): QuickPlayTarget | undefined {
+  const [flag, value] = args
+  if (flag === '--quickPlayMultiplayer') return { multiplayer: value }
+  if (flag === '--quickPlaySingleplayer') return { singleplayer: value }
+  return undefined
+}
+
+// LWJGL 3 must own the main thread on macOS. Current profiles add the flag
+// through an osx rule, but older and third-party profiles often omit it.
+async function platformJvmArguments(instance: LauncherInstance): Promise<string[]> {
+  if (process.platform !== 'darwin') return []
+  const profile = await resolveVersionJson(instanceDirectory(instance.slug), instance.customVersionId ?? instance.minecraftVersion).catch(() => null)
+  const usesLwjgl3 = profile
+    ? (profile.libraries ?? []).some((library) => /^org\.lwjgl:lwjgl:3\./.test(library.name))
+    : !/^1\.(?:[0-9]|1[0-2])(?:\.|$)/.test(instance.minecraftVersion)
+  if (!usesLwjgl3) return []
+  if (profile && applicableArguments(profile, 'jvm').includes('-XstartOnFirstThread')) return []
+  return ['-XstartOnFirstThread']
+}
 
+// Java otherwise writes stdout and its log files in the system code page on
+// Windows, which mangles non-ASCII player names and chat in the console.
+// stdout.encoding is read by Java 19+, sun.stdout.encoding by older releases.
+// sun.jnu.encoding is left alone, since it decides how file paths are encoded.
+function utf8JvmArguments(userArgs: string[]): string[] {
+  const defaults = ['file.encoding', 'stdout.encoding', 'stderr.encoding', 'sun.stdout.encoding', 'sun.stderr.encoding']
+  return defaults
+    .filter((key) => !userArgs.some((arg) => arg.startsWith(`-D${key}=`)))
+    .map((key) => `-D${key}=UTF-8`)
+}
+
+// On a metered connection a large download waits for the user's answer. Saying
+// no cancels the launch rather than failing it, so it can be retried later.
+function confirmDownload(session: LaunchSession, what: string): (bytes: number) => Promise<void> {
+  return async (bytes) => {
+    const signal = session.abort.signal
+    // Idle-time preparation never asks; it waits for another connection.
+    const allowed = session.background
+      ? !await isMeteredConnection()
+      : await confirmMeteredDownload({ instanceId: session.instanceId, name: session.name, what, bytes }, signal)
+    if (allowed) return
+    appendConsole(session, `[MegaClient] Downloading ${what} was put off because the connection is metered`, 'muted')
+    session.abort.abort(new Error(`Downloading ${what} was put off because the connection is metered.`))
+    signal.throwIfAborted()
+  }
+}
+
+async function managedJavaExecutable(session: LaunchSession, requirement: JavaRequirement, signal: AbortSignal): Promise<string> {
+  const runtime = await ensureManagedJava(requirement, (message, value, detail) => {
+    emitProgress(session, 'java', message, value, detail)
+  }, signal, confirmDownload(session, `Java ${requirement.major}`))
+  appendConsole(session, `[MegaClient] Using managed Java ${runtime.major} (${runtime.arch}) at ${runtime.executable}`, 'muted')
+  return runtime.executable
+}
+
+// Versions before Quick Play get the older --world argument instead, which
+// some of them and several mods read.
+async function worldGameArgs(instance: LauncherInstance, worldId: string | undefined, quickPlay: boolean): Promise<string[]> {
+  if (!worldId) return []
+  const saves = savesDirectory(instance.slug)
+  const world = path.resolve(saves, worldId)
+  if (path.dirname(world) !== path.resolve(saves) || !await fs.stat(path.join(world, 'level.dat')).catch(() => null)) {
+    throw new Error('That world could not be found in this instance.')
+  }
+  return [quickPlay ? '--quickPlaySingleplayer' : '--world', path.basename(world)]
+}
+
+// The version profile that is launched: a custom profile, the Fabric profile
+// for the loader version, or the vanilla version. Forge and NeoForge profiles
+// are written by their installers at launch.
+function launchVersionId(instance: LauncherInstance): string {
+  return instance.customVersionId ?? (instance.loader === 'fabric' && instance.loaderVersion
+    ? `fabric-loader-${instance.loaderVersion}-${instance.minecraftVersion}`
+    : instance.minecraftVersion)
+}
+
+// Vanilla, Fabric and custom profiles are launched by MegaClient itself from
+// the resolved profile. Forge and NeoForge need their installers, which only
+// eml-lib runs, so they are launched through it.
+async function launchesDirectly(instance: LauncherInstance): Promise<boolean> {
+  if (!instance.customVersionId && (instance.loader === 'forge' || instance.loader === 'neoforge')) return false
+  const profile = await resolveVersionJson(instanceDirectory(instance.slug), launchVersionId(instance)).catch(() => null)
+  return Boolean(profile?.arguments || profile?.minecraftArguments)
+}
+
+async function readyInstance(session: LaunchSession, instanceId: string): Promise<LauncherInstance> {
+  setLaunchStage(session, 'resolving-version')
   let instance = getInstance(instanceId)
+  if (instance.archivedAt) throw new Error(`${instance.name} is archived. Restore it before playing.`)
   if (instance.customClient && (instance.minecraftVersion !== CLIENT_MINECRAFT_VERSION || instance.loader !== 'fabric')) {
     instance = await updateInstance(instance.id, { minecraftVersion: CLIENT_MINECRAFT_VERSION, loader: 'fabric' })
   }
-  instance = await resolveLoader(instance)
+  if (isOffline()) {
+    // Offline, an installed instance launches exactly as it was last prepared:
+    // the version manifest and loader metadata are not consulted.
+    if (!await readVersionJson(instanceDirectory(instance.slug), launchVersionId(instance)) || (instance.loader !== 'vanilla' && !instance.loaderVersion)) {
+      throw new OfflineError(`${instance.name} has not been downloaded yet. Connect to the internet once to prepare it for offline play.`)
+    }
+    appendConsole(session, '[MegaClient] Offline: launching the installed files without checking for updates', 'info')
+  } else {
+    if (instance.trackLatest && !instance.customClient && !instance.customVersionId) {
+      instance = await followTrackedVersion(session, instance)
+    }
+    instance = await resolveLoader(instance)
+  }
+  await ensureLinkedDirectory(instance)
+  await ensureSharedGameFiles(instance)
+  return instance
+}
+
+// Downloads everything a launch needs without starting the game, reporting
+// through the same launch:progress events, so the instance can later be
+// played offline. Resolves to false when the preparation is cancelled.
+export async function prepareInstance(mainWindow: BrowserWindow, instanceId: string): Promise<boolean> {
+  await stopBackgroundPreparation()
+  const session = beginSession(mainWindow, instanceId)
+  const signal = session.abort.signal
+  try {
+    const instance = await readyInstance(session, instanceId)
+    if (instance.customVersionId && !await readVersionJson(instanceDirectory(instance.slug), instance.customVersionId)) {
+      throw new Error(`The custom version profile ${instance.customVersionId} is missing from this instance.`)
+    }
+    await prepareGameFiles(session, instance, store.getData().settings, signal)
+    setLaunchStage(session, 'exited')
+    setConsoleState(session, 'Ready to play')
+    emitProgress(session, 'prepared', `${instance.name} is ready to play`, 1)
+    return true
+  } catch (error) {
+    if (!signal.aborted) {
+      setLaunchStage(session, 'failed')
+      throw error
+    }
+    setLaunchStage(session, 'cancelled')
+    emitLaunch(session, 'launch:cancelled', {})
+    return false
+  } finally {
+    endSession(session)
+  }
+}
+
+// Idle-time preparation of the selected instance: the same work as preparing
+// it by hand, downloaded at low priority and without progress events, so Play
+// finds everything in place. A launch or preparation of any instance stops it.
+export async function prepareInstanceInBackground(mainWindow: BrowserWindow, instanceId: string): Promise<boolean> {
+  if (sessions.size) return false
+  const session = beginSession(mainWindow, instanceId, true)
+  const signal = session.abort.signal
+  const run = runAtLowPriority(async () => {
+    const instance = await readyInstance(session, instanceId)
+    if (instance.customVersionId && !await readVersionJson(instanceDirectory(instance.slug), instance.customVersionId)) return
+    await prepareGameFiles(session, instance, store.getData().settings, signal)
+  })
+  backgroundRuns.set(instanceId, run.then(() => undefined, () => undefined))
+  try {
+    await run
+    session.trace.info('Prepared in the background')
+    return true
+  } catch (error) {
+    if (!signal.aborted) session.trace.warn('Background preparation failed', { error })
+    return false
+  } finally {
+    backgroundRuns.delete(instanceId)
+    endSession(session)
+  }
+}
+
+async function stopBackgroundPreparation(): Promise<void> {
+  for (const session of sessions.values()) {
+    if (session.background) session.abort.abort(new Error('Background preparation gave way to a launch.'))
+  }
+  await Promise.all(backgroundRuns.values())
+}
+
+// Sessions double as the per-instance lock: an instance is launched or
+// prepared at most once at a time, and is not deleted while it is in use.
+export function assertInstanceIdle(instanceId: string): void {
+  const session = sessions.get(instanceId)
+  if (!session && worldBackupRuns.has(instanceId)) throw new Error(`${getInstance(instanceId).name} is still backing up its worlds. Try again in a moment.`)
+  if (!session) return
+  if (session.gameStarted) throw new Error(`${session.name} is already running.`)
+  throw new Error(`${session.name} is already being prepared or launched.`)
+}
+
+function beginSession(mainWindow: BrowserWindow, instanceId: string, background = false): LaunchSession {
+  assertInstanceIdle(instanceId)
+  const session: LaunchSession = {
+    instanceId,
+    name: getInstance(instanceId).name,
+    mainWindow,
+    abort: new AbortController(),
+    launcher: null,
+    gameStarted: false,
+    processIds: [],
+    instance: null,
+    startedAt: null,
+    stopping: false,
+    safeMode: false,
+    bisecting: false,
+    outOfMemory: false,
+    consoleLines: [],
+    consoleSequence: 0,
+    log: null,
+    logTails: [],
+    gameMessages: new Set(),
+    trace: logger.child({ instanceId }),
+    stage: 'start',
+    stages: [],
+    consoleState: 'Preparing',
+    securityTimer: null,
+    securityCheckRunning: false,
+    clientVerificationTimer: null,
+    statsTimer: null,
+    background
+  }
+  sessions.set(instanceId, session)
+  return session
+}
+
+function endSession(session: LaunchSession): void {
+  stopSecurityMonitor(session)
+  stopResourceMonitor(session)
+  stopClientVerification(session)
+  for (const tail of session.logTails.splice(0)) void tail.stop()
+  void session.log?.flush()
+  if (sessions.get(session.instanceId) === session) sessions.delete(session.instanceId)
+  if (!session.background) finishedSessions.set(session.instanceId, session)
+  // Deferred, so a world backup started by the same exit is counted.
+  setImmediate(quitIfIdle)
+}
+
+// Console lines after the sequence number `since`, so a log view opened
+// mid-launch can backfill and then poll with the returned cursor.
+export function getConsoleLines(instanceId: string, since = 0): { lines: ConsoleEntry[]; next: number; running: boolean } {
+  const session = sessions.get(instanceId) ?? finishedSessions.get(instanceId)
+  if (!session) return { lines: [], next: since, running: false }
+  return {
+    lines: session.consoleLines.filter((entry) => entry.seq > since),
+    next: session.consoleSequence,
+    running: sessions.get(instanceId) === session
+  }
+}
+
+// Stops a launch or preparation that is still downloading or checking files.
+// A game that has already started is left running.
+export function cancelLaunch(instanceId: string): boolean {
+  const session = sessions.get(instanceId)
+  if (!session || session.gameStarted || session.abort.signal.aborted) return false
+  session.abort.abort(new Error('The launch was cancelled.'))
+  // Everything up to a direct launch stops at once. Forge and NeoForge are
+  // handed to eml-lib, whose installer and downloads cannot be interrupted.
+  if (session.launcher instanceof Launcher) {
+    const message = `${session.name} is finishing its Forge or NeoForge downloads and will be closed as soon as it starts.`
+    appendConsole(session, `[MegaClient] ${message}`, 'muted')
+    emitLaunch(session, 'launch:warning', { message })
+  }
+  return true
+}
+
+async function trackGameProcesses(session: LaunchSession, instance: LauncherInstance): Promise<number[]> {
+  const found = (await findGameJavaProcesses(instance)).map((item) => item.ProcessId ?? 0).filter((id) => id > 0)
+  session.processIds = [...new Set([...session.processIds, ...found])]
+  return session.processIds
+}
+
+// Force-stops a running game, for example one that no longer responds. The
+// process ids are looked up again so a game that restarted itself is caught.
+export async function stopGame(instanceId: string): Promise<boolean> {
+  const session = sessions.get(instanceId)
+  if (!session) return false
+  if (!session.gameStarted) return cancelLaunch(instanceId)
+  const instance = getInstance(instanceId)
+  const processIds = await trackGameProcesses(session, instance)
+  if (!processIds.length) throw new Error(`${instance.name} is running, but its Minecraft process could not be found.`)
+  session.stopping = true
+  appendConsole(session, '[MegaClient] Stopping Minecraft', 'muted')
+  setConsoleState(session, 'Stopping')
+  await terminateProcesses(processIds)
+  return true
+}
+
+export interface LaunchOptions {
+  safeMode?: boolean
+}
+
+// Awaited before the session ends, so nothing else touches the mods folder
+// while the disabled mods are renamed back.
+async function restoreSafeMode(session: LaunchSession, instance: LauncherInstance): Promise<void> {
+  if (!session.safeMode) return
+  session.safeMode = false
+  try {
+    const restored = await restoreSafeModeMods(instance)
+    appendConsole(session, `[MegaClient] Safe mode ended, ${restored} mods enabled again`, 'muted')
+  } catch (error) {
+    appendConsole(session, `[MegaClient] Mods disabled for safe mode could not be restored: ${error instanceof Error ? error.message : String(error)}`, 'error')
+  }
+}
+
+// Moves an active mod bisect on by the result of the run that just ended, or
+// offers one once an instance keeps crashing.
+async function recordCrashResult(session: LaunchSession, crashed: boolean): Promise<void> {
+  if (!session.bisecting) {
+    const streak = crashed ? (crashStreaks.get(session.instanceId) ?? 0) + 1 : 0
+    crashStreaks.set(session.instanceId, streak)
+    if (streak >= 2) emitLaunch(session, 'mc:bisect-offer', { crashes: streak })
+    return
+  }
+  crashStreaks.delete(session.instanceId)
+  // A run stopped from MegaClient says nothing about the mods it tested.
+  if (session.stopping) {
+    appendConsole(session, '[MegaClient] Mod bisect: the run was stopped, so it does not count. Launch again to repeat it.', 'info')
+    return
+  }
+  const state = await recordBisectRound(session.instanceId, crashed)
+  if (!state) return
+  if (state.culprit) {
+    appendConsole(session, `[MegaClient] Mod bisect finished after ${state.rounds.length} runs: ${state.culprit} triggers the crash`, 'success')
+  } else if (state.inconclusive) {
+    appendConsole(session, `[MegaClient] Mod bisect finished after ${state.rounds.length} runs without a crash from any single mod. The crash may need several mods together.`, 'info')
+  } else if (state.candidates.length === 1) {
+    appendConsole(session, `[MegaClient] Mod bisect: ${state.candidates[0]} is the last suspect. Launch again to confirm it.`, 'info')
+  } else {
+    appendConsole(session, `[MegaClient] Mod bisect: ${state.candidates.length} suspects remain. Launch again to test the next half.`, 'info')
+  }
+  emitLaunch(session, 'mc:bisect', state)
+}
+
+// Returns how many times the instance crashed within the crash-loop window once
+// that passes the limit, otherwise 0.
+function noteCrash(instanceId: string): number {
+  const now = Date.now()
+  const times = [...(recentCrashes.get(instanceId) ?? []), now].filter((at) => now - at <= CRASH_LOOP_WINDOW_MS)
+  recentCrashes.set(instanceId, times)
+  return times.length >= CRASH_LOOP_LIMIT ? times.length : 0
+}
+
+// Relaunches an instance whose game crashed soon after starting, up to the
+// instance's retry limit. Returns whether a restart was scheduled.
+function scheduleCrashRestart(
+  session: LaunchSession,
+  instance: LauncherInstance,
+  code: number | null,
+  serverAddress?: string,
+  worldId?: string,
+  options: LaunchOptions = {}
+): boolean {
+  const restart = instance.restartOnCrash
+  const ranFor = session.startedAt ? Date.now() - session.startedAt : 0
+  if (!restart || getInstance(instance.id).unstable || code === 0 || session.stopping || ranFor > restart.withinSeconds * 1000) {
+    restartAttempts.delete(instance.id)
+    return false
+  }
+  const attempt = (restartAttempts.get(instance.id) ?? 0) + 1
+  if (attempt > restart.maxRetries) {
+    restartAttempts.delete(instance.id)
+    appendConsole(session, `[MegaClient] Minecraft crashed ${restart.maxRetries} times in a row, so it will not be restarted again`, 'error')
+    return false
+  }
+  restartAttempts.set(instance.id, attempt)
+  appendConsole(session, `[MegaClient] Restarting after a crash (attempt ${attempt} of ${restart.maxRetries})`, 'info')
+  emitLaunch(session, 'launch:restarting', { attempt, maxRetries: restart.maxRetries, code })
+  pendingRestarts++
+  setTimeout(() => {
+    pendingRestarts--
+    void launchInstance(session.mainWindow, instance.id, serverAddress, worldId, options).catch((error) => {
+      emit(session.mainWindow, 'launch:error', { instanceId: instance.id, message: error instanceof Error ? error.message : String(error) })
+    }).finally(quitIfIdle)
+  }, 3_000)
+  return true
+}
+
+// Turns a non-zero exit into an mc:crashed event, plus mc:native-crash when
+// the JVM itself died. Only reports written since the game started count;
+// without one only the exit code is known.
+async function reportCrash(
+  session: LaunchSession,
+  instance: LauncherInstance,
+  startedAt: number,
+  code: number | null,
+  explanations: ExitExplanation[],
+  crashLoop: number
+): Promise<void> {
+  const file = await newestCrashReport(instanceDirectory(instance.slug), startedAt)
+  const report = file ? await readCrashReport(file).catch(() => null) : null
+  if (report) {
+    appendConsole(session, `[Crash] ${report.description ?? 'Minecraft crashed'}: ${report.exception ?? 'unknown exception'}`, 'error')
+    if (report.suspectedMods.length) appendConsole(session, `[Crash] Suspected mods: ${report.suspectedMods.join(', ')}`, 'error')
+    appendConsole(session, `[Crash] Report saved to ${report.file}`, 'muted')
+  }
+  emitLaunch(session, 'mc:crashed', { code, report })
+  if (crashLoop) {
+    const summary = report?.exception ?? report?.description ?? explanations[0]?.title
+    const unstable = { since: new Date().toISOString(), crashes: crashLoop, summary }
+    await setInstanceUnstable(instance.id, unstable)
+    appendConsole(session, `[MegaClient] ${instance.name} crashed ${crashLoop} times in ${CRASH_LOOP_WINDOW_MS / 60_000} minutes and is marked unstable. Automatic restarts are off until it runs cleanly.`, 'error')
+    emitLaunch(session, 'mc:crash-loop', { ...unstable, code, report, explanations })
+  }
+
+  const jvmLog = await newestJvmErrorLog(instanceDirectory(instance.slug), startedAt)
+  const nativeCrash = jvmLog ? await readJvmErrorLog(jvmLog).catch(() => null) : null
+  if (!nativeCrash) return
+  appendConsole(session, `[JVM] Java crashed in native code. The full log is ${nativeCrash.file} and is attached to this launch log`, 'error')
+  for (const line of nativeCrash.summary) appendConsole(session, `[JVM] ${line}`, 'error')
+  await session.log?.attach(path.basename(nativeCrash.file), nativeCrash.file)
+  if (nativeCrash.gpuDriver) appendConsole(session, `[JVM] ${nativeCrash.library} belongs to the graphics driver. Updating the driver usually fixes this crash.`, 'info')
+  emitLaunch(session, 'mc:native-crash', nativeCrash)
+}
+
+// Backs up the worlds played during the session, for instances that opted
+// in. Failures are reported without stopping the other worlds.
+async function backUpPlayedWorlds(session: LaunchSession, instanceId: string, startedAt: number): Promise<void> {
+  const policy = getInstance(instanceId).worldBackups
+  if (!policy) return
+  const result = await backupPlayedWorlds(instanceId, startedAt, policy)
+  for (const backup of result.backups) appendConsole(session, `[MegaClient] Backed up ${backup.worldFolder}`, 'success')
+  if (result.removed) appendConsole(session, `[MegaClient] Removed ${result.removed} old automatic world backup${result.removed === 1 ? '' : 's'}`, 'muted')
+  for (const failure of result.failed) appendConsole(session, `[MegaClient] ${failure.worldId} could not be backed up: ${failure.error}`, 'error')
+  if (result.failed.length) {
+    emitLaunch(session, 'launch:warning', { message: `${result.failed.map((failure) => failure.worldId).join(', ')} could not be backed up. See the console for details.` })
+  }
+}
+
+export async function listRunningGames(): Promise<RunningGame[]> {
+  const running = [...sessions.values()].filter((session) => session.gameStarted && session.instance && session.startedAt)
+  return Promise.all(running.map(async (session) => {
+    const instance = session.instance!
+    const processIds = session.processIds.length ? session.processIds : await trackGameProcesses(session, instance).catch(() => [])
+    return {
+      instanceId: session.instanceId,
+      name: instance.name,
+      pid: processIds[0],
+      processIds,
+      startedAt: new Date(session.startedAt!).toISOString(),
+      uptimeSeconds: Math.floor((Date.now() - session.startedAt!) / 1000),
+      minecraftVersion: instance.minecraftVersion,
+      loader: instance.loader,
+      loaderVersion: instance.loaderVersion
+    }
+  }))
+}
+
+// Shared by launches and by preparing an instance ahead of time: installs the
+// version files, verifies libraries and assets, caches natives and installs the
+// managed Java runtime.
+async function prepareGameFiles(
+  session: LaunchSession,
+  instance: LauncherInstance,
+  settings: LauncherSettings,
+  signal: AbortSignal
+): Promise<{ nativesDirectory: string | null; managedJava: string | null; direct: boolean }> {
+  const gameDirectory = instanceDirectory(instance.slug)
+  setLaunchStage(session, 'downloading-assets')
+  await installVersionFiles(gameDirectory, instance, (message, value) => {
+    emitProgress(session, 'download', message, value)
+  }, signal).catch((error) => {
+    signal.throwIfAborted()
+    // The launch itself can still download these, for example through a mirror.
+    appendConsole(session, `[MegaClient] Version files could not be prepared: ${error instanceof Error ? error.message : String(error)}`, 'muted')
+  })
+
+  const libraries = await verifyLibraries(gameDirectory, instance.customVersionId ?? instance.minecraftVersion, {
+    signal,
+    onProgress: (message, value, detail) => emitProgress(session, 'prepare', message, value, detail)
+  })
+  if (libraries.repaired.length) appendConsole(session, `[MegaClient] Downloaded ${libraries.repaired.length} damaged or missing libraries again`, 'success')
+  for (const file of libraries.damaged.filter((item) => !libraries.repaired.includes(item))) {
+    appendConsole(session, `[MegaClient] Library ${file} failed verification and could not be repaired`, 'error')
+  }
+  const assets = await verifyAssets(gameDirectory, instance.customVersionId ?? instance.minecraftVersion, {
+    signal,
+    confirmDownload: confirmDownload(session, 'the game assets'),
+    onProgress: (message, value, detail) => emitProgress(session, 'assets', message, value, detail)
+  })
+  if (assets.repaired.length) appendConsole(session, `[MegaClient] Downloaded ${assets.repaired.length} damaged or missing asset files again`, 'success')
+  if (assets.damaged.length > assets.repaired.length) {
+    appendConsole(session, `[MegaClient] ${assets.damaged.length - assets.repaired.length} asset files could not be repaired`, 'error')
+  }
+  const directProfile = await launchesDirectly(instance)
+  if (directProfile) {
+    emitProgress(session, 'prepare', 'Downloading libraries')
+    await installLaunchLibraries(gameDirectory, launchVersionId(instance), signal)
+  }
+
+  setLaunchStage(session, 'ensuring-java')
+  const javaTarget = settings.javaMode === 'manual'
+    ? null
+    : await javaRequirement(gameDirectory, instance.customVersionId ?? instance.minecraftVersion)
+  // eml-lib extracts the natives itself, so they are only cached for direct launches.
+  const nativesDirectory = !directProfile ? null : await prepareNatives(gameDirectory, launchVersionId(instance), (library) => {
+    appendConsole(session, `[MegaClient] ${library} publishes no native library for ${process.platform}, so the game may fail to start`, 'error')
+  }, javaTarget?.arch === 'x64' ? 'x86_64' : undefined)
+    .catch((error) => {
+      signal.throwIfAborted()
+      appendConsole(session, `[MegaClient] Native libraries could not be cached: ${error instanceof Error ? error.message : String(error)}`, 'muted')
+      return null
+    })
+
+  // Automatic Java uses the runtime Mojang ships for the version. Where that
+  // fails, macOS and ARM64 still need MegaClient's own runtime because the
+  // bundled downloader has no matching builds; elsewhere it takes over.
+  const managedJava = javaTarget
+    ? await managedJavaExecutable(session, javaTarget, signal).catch((error) => {
+      if (signal.aborted || process.platform === 'darwin' || javaTarget.arch === 'aarch64') throw error
+      appendConsole(session, `[MegaClient] Managed Java is unavailable, using the bundled downloader: ${error instanceof Error ? error.message : String(error)}`, 'muted')
+      return null
+    })
+    : null
+  // A direct launch needs a known Java; without one eml-lib finds it.
+  const direct = directProfile && Boolean(settings.javaMode === 'manual' ? settings.javaPath : managedJava)
+  return { nativesDirectory, managedJava, direct }
+}
+
+export async function launchInstance(
+  mainWindow: BrowserWindow,
+  instanceId: string,
+  serverAddress?: string,
+  worldId?: string,
+  options: LaunchOptions = {}
+): Promise<void> {
+  await stopBackgroundPreparation()
+  const session = beginSession(mainWindow, instanceId)
+  try {
+    await runLaunch(session, serverAddress, worldId, options)
+  } catch (error) {
+    if (!session.abort.signal.aborted) {
+      setLaunchStage(session, 'failed')
+      throw error
+    }
+    setLaunchStage(session, 'cancelled')
+    appendConsole(session, '[MegaClient] Launch cancelled', 'muted')
+    setConsoleState(session, 'Cancelled')
+    emitLaunch(session, 'launch:cancelled', {})
+  } finally {
+    // A running game keeps its session until it exits.
+    if (!session.gameStarted) {
+      await restoreSafeMode(session, getInstance(instanceId))
+      endSession(session)
+    }
+  }
+}
+
+async function runLaunch(session: LaunchSession, serverAddress?: string, worldId?: string, options: LaunchOptions = {}): Promise<void> {
+  const { mainWindow, instanceId } = session
+  const signal = session.abort.signal
+  const settings = store.getData().settings
+  if (settings.showConsole) showConsole(session)
+  session.log = createLaunchLog(getInstance(instanceId).slug)
+  appendConsole(session, `[MegaClient] Starting ${new Date().toLocaleString()}`, 'muted')
+  if (serverAddress) appendConsole(session, `[MegaClient] Direct connection: ${serverAddress}`, 'muted')
+  if (worldId) appendConsole(session, `[MegaClient] Opening world: ${worldId}`, 'muted')
+  setConsoleState(session, 'Preparing')
+
+  let instance = await readyInstance(session, instanceId)
+  if (!instance.versionType && !isOffline()) {
+    const versionType = await minecraftVersionType(instance.minecraftVersion).catch(() => undefined)
+    if (versionType) instance = await updateInstance(instance.id, { versionType })
+  }
+  if (instance.versionType === 'snapshot') {
+    const message = `Minecraft ${instance.minecraftVersion} is a snapshot. Snapshots can corrupt worlds, so keep backups of anything important.`
+    appendConsole(session, `[MegaClient] ${message}`, 'muted')
+    emitLaunch(session, 'launch:warning', { message })
+  }
   showLaunchingActivity(instance, serverAddress)
 
-  emit(mainWindow, 'launch:progress', { phase: 'security', message: 'Running enforced launch protection' } satisfies LaunchProgress)
-  appendConsole('[Security] Checking high-confidence blocked identities without restricting legitimate native mods', 'muted')
+  emitProgress(session, 'security', 'Running enforced launch protection')
+  appendConsole(session, '[Security] Checking high-confidence blocked identities without restricting legitimate native mods', 'muted')
   await runPreflightSecurity(instance)
 
+  if (instance.customVersionId) {
+    if (!await readVersionJson(instanceDirectory(instance.slug), instance.customVersionId)) {
+      throw new Error(`The custom version profile ${instance.customVersionId} is missing from this instance.`)
+    }
+    appendConsole(session, `[MegaClient] Using custom version profile ${instance.customVersionId} (based on ${instance.minecraftVersion})`, 'muted')
+  }
+
+  const { nativesDirectory, managedJava, direct } = await prepareGameFiles(session, instance, settings, signal)
+
+  const quickPlay = Boolean(serverAddress || worldId) && await supportsQuickPlay(instance)
+  const joinArgs = [
+    ...serverGameArgs(serverAddress, quickPlay),
+    ...await worldGameArgs(instance, serverAddress ? undefined : worldId, quickPlay)
+  ]
+  if (worldId && !serverAddress && !quickPlay) {
+    appendConsole(session, `[MegaClient] Minecraft ${instance.minecraftVersion} has no Quick Play, so ${worldId} is passed with --world. If it does not open, choose it from Singleplayer`, 'muted')
+  }
+  // A direct launch fills the profile's own Quick Play arguments instead.
+  const quickPlayTarget = direct && quickPlay ? quickPlayTargetOf(joinArgs) : undefined
+  const gameArgs = quickPlayTarget ? [] : joinArgs
+
+  const platformArgs = await platformJvmArguments(instance)
+  signal.throwIfAborted()
+  if (options.safeMode) {
+    session.safeMode = true
+    const disabled = await disableModsForSafeMode(instance)
+    appendConsole(session, `[MegaClient] Safe mode: ${disabled.length} mods are disabled until the game exits`, 'info')
+  } else {
+    // Mods left disabled by a safe-mode launch that never finished.
+    const restored = await restoreSafeModeMods(instance)
+    if (restored) appendConsole(session, `[MegaClient] Enabled ${restored} mods left disabled by an earlier safe-mode launch`, 'muted')
+    const bisect = await prepareBisectRound(instance)
+    if (bisect) {
+      session.safeMode = session.bisecting = true
+      appendConsole(session, `[MegaClient] Mod bisect run ${bisect.rounds.length + 1}: testing ${bisect.testing.length} of ${bisect.candidates.length} suspected mods`, 'info')
+    }
+  }
   const account: Account = await getValidAccount(mainWindow)
-  const clientPayload = await prepareCustomClient(instance, mainWindow)
+  const clientPayload = await prepareCustomClient(session, instance)
+  const userArgs = userJvmArguments(instance.jvmArgs?.trim() ? instance.jvmArgs : settings.defaultJvmArgs)
   const javaArgs = [
+    ...userArgs,
+    ...utf8JvmArguments(userArgs),
     '-Dmegaclient.launcher=true',
-    '-Dfabric.debug.disableModShuffle=true'
+    '-Dfabric.debug.disableModShuffle=true',
+    ...(direct && nativesDirectory ? nativesJvmArguments(nativesDirectory) : []),
+    ...platformArgs
   ]
   if (clientPayload) {
     javaArgs.push(`-Dmegaclient.payload.sha256=${clientPayload.sha256}`)
     javaArgs.push(`-Dmegaclient.payload.path=${clientPayload.jarPath.replaceAll('\\', '/')}`)
     javaArgs.push(`-Dmegaclient.marker=${clientPayload.markerPath}`)
     javaArgs.push(`-Dmegaclient.marker.nonce=${clientPayload.markerNonce}`)
-    appendConsole(`[MegaClient] Staged the protected client in this isolated instance (${clientPayload.sha256.slice(0, 12)}…)`, 'success')
+    appendConsole(session, `[MegaClient] Staged the protected client in this isolated instance (${clientPayload.sha256.slice(0, 12)}…)`, 'success')
   }
 
   if (instance.customClient) {
     await fs.rm(path.join(instanceDirectory(instance.slug), 'logs', 'latest.log'), { force: true }).catch(() => undefined)
   }
 
-  const launcher = new Launcher({
+  const memory = effectiveMemory(instance, settings)
+  const windowSize = { width: settings.width, height: settings.height, fullscreen: settings.fullscreen }
+  const directLauncher = direct
+    ? new DirectLauncher({
+      gameDirectory: instanceDirectory(instance.slug),
+      versionId: launchVersionId(instance),
+      javaPath: settings.javaMode === 'manual' ? settings.javaPath : managedJava!,
+      account,
+      memory,
+      window: windowSize,
+      nativesDirectory,
+      jvmArgs: javaArgs,
+      gameArgs,
+      quickPlay: quickPlayTarget,
+      signal
+    })
+    : null
+  const launcher = directLauncher ? null : new Launcher({
     root: 'megaclient',
     storage: 'isolated',
     profile: {
       slug: instance.slug,
       minecraft: {
-        version: instance.minecraftVersion,
+        version: instance.customVersionId ?? instance.minecraftVersion,
         loader: instance.loader === 'vanilla'
           ? { loader: 'vanilla' }
           : { loader: instance.loader, version: instance.loaderVersion },
-        args: serverGameArgs(serverAddress)
+        args: gameArgs
       }
     },
     cleaning: { enabled: false },
     account,
-    memory: { min: settings.memoryMin, max: settings.memoryMax },
-    window: { width: settings.width, height: settings.height, fullscreen: settings.fullscreen },
+    memory,
+    window: windowSize,
     java: settings.javaMode === 'manual'
       ? { install: 'manual', absolutePath: settings.javaPath, args: javaArgs }
-      : { install: 'auto', args: javaArgs }
+      : managedJava
+        ? { install: 'manual', absolutePath: managedJava, args: javaArgs }
+        : { install: 'auto', args: javaArgs }
   })
-  activeLauncher = launcher
+  session.launcher = directLauncher ?? launcher
+  if (directLauncher) appendConsole(session, `[MegaClient] Launching ${launchVersionId(instance)} from its version profile`, 'muted')
 
   let payloadCleaned = false
   let clientObserved = false
-  let gameStartedAt = Date.now()
   const cleanupPayload = async (): Promise<void> => {
     if (payloadCleaned) return
     payloadCleaned = true
     await clientPayload?.cleanup()
   }
 
+  const prepareLegacyAssets = async (): Promise<void> => {
+    try {
+      const copied = await materializeLegacyAssets(instanceDirectory(instance.slug), instance.minecraftVersion)
+      if (copied) appendConsole(session, `[MegaClient] Prepared ${copied} legacy asset files`, 'muted')
+    } catch (error) {
+      appendConsole(session, `[MegaClient] Legacy assets could not be prepared: ${error instanceof Error ? error.message : String(error)}`, 'muted')
+    }
+  }
+
   let lastProgressSent = 0
-  const progress = (phase: string, message: string, value?: number): void => {
-    const payload: LaunchProgress = { phase, message, progress: value }
-    emit(mainWindow, 'launch:progress', payload)
-    appendConsole(`[MegaClient] ${message}`, 'info')
-    setConsoleState(message)
-  }
-
-  launcher.on('launch_compute_download', () => progress('prepare', 'Checking game files'))
-  launcher.on('launch_download', ({ total }) => progress('download', `Preparing ${total.amount} downloads`))
-  launcher.on('download_progress', ({ downloaded, total, speed }) => {
-    const now = Date.now()
-    if (now - lastProgressSent < 120 && downloaded.size < total.size) return
-    lastProgressSent = now
-    const value = total.size ? downloaded.size / total.size : undefined
-    emit(mainWindow, 'launch:progress', {
-      phase: 'download', message: 'Downloading game files', progress: value,
-      downloaded: downloaded.size, total: total.size, speed
-    } satisfies LaunchProgress)
-    setConsoleState('Downloading')
-  })
-  launcher.on('download_error', ({ filename, message }) => appendConsole(`[Download] ${filename}: ${String(message)}`, 'error'))
-  launcher.on('launch_install_loader', ({ type, minecraftVersion, loaderVersion }) => progress('loader', `Installing ${type === 'VANILLA' ? 'Minecraft' : `${type} ${loaderVersion}`} for ${minecraftVersion}`))
-  launcher.on('launch_check_java', () => progress('java', 'Checking the required Java runtime'))
-  launcher.on('java_info', ({ version, arch }) => appendConsole(`[Java] ${version} (${arch})`, 'muted'))
-  launcher.on('launch_copy_assets', () => progress('assets', 'Preparing Minecraft assets'))
-  launcher.on('launch_extract_natives', () => progress('natives', 'Extracting native libraries'))
-  launcher.on('launch_patch_loader', () => progress('loader', 'Finalising the mod loader'))
-  launcher.on('launch_launch', () => {
+  const progress = (stage: LaunchStage, message: string, value?: number): void => {
+    emitProgress(session, stage, message, value)
+    appendConsole(session, `[MegaClient] ${message}`, 'info')
+    setConsoleState(session, message)
+  }
+
+  if (launcher) {
+    launcher.on('launch_compute_download', () => progress('prepare', 'Checking game files'))
+    launcher.on('launch_download', ({ total }) => progress('download', `Preparing ${total.amount} downloads`))
+    launcher.on('download_progress', ({ downloaded, total, speed }) => {
+      const now = Date.now()
+      if (now - lastProgressSent < 120 && downloaded.size < total.size) return
+      lastProgressSent = now
+      emitProgress(session, 'download', 'Downloading game files', undefined, {
+        bytes: { current: downloaded.size, total: total.size },
+        items: { current: downloaded.amount, total: total.amount },
+        speed
+      })
+      setConsoleState(session, 'Downloading')
+    })
+    launcher.on('download_error', ({ filename, message }) => appendConsole(session, `[Download] ${filename}: ${String(message)}`, 'error'))
+    launcher.on('launch_install_loader', ({ type, minecraftVersion, loaderVersion }) => progress('loader', `Installing ${type === 'VANILLA' ? 'Minecraft' : `${type} ${loaderVersion}`} for ${minecraftVersion}`))
+    launcher.on('launch_check_java', () => progress('java', 'Checking the required Java runtime'))
+    launcher.on('java_info', ({ version, arch }) => appendConsole(session, `[Java] ${version} (${arch})`, 'muted'))
+    launcher.on('launch_copy_assets', () => progress('assets', 'Preparing Minecraft assets'))
+    launcher.on('launch_extract_natives', () => {
+      progress('natives', 'Extracting native libraries')
+      // Assets are complete once natives are extracted, so newly downloaded
+      // legacy assets are laid out before the game reads them.
+      void prepareLegacyAssets()
+    })
+    launcher.on('launch_patch_loader', () => progress('loader', 'Finalising the mod loader'))
+  }
+
+  const onGameLaunched = (): void => {
+    // eml-lib cannot be interrupted, so a Forge or NeoForge launch cancelled
+    // while it was downloading is stopped as soon as the game starts.
+    if (signal.aborted) {
+      void findGameJavaProcesses(instance).then((processes) => terminateProcesses(processes.map((item) => item.ProcessId ?? 0)))
+      return
+    }
     if (clientPayload) {
       validatePreparedClientPayloadSync(clientPayload)
-      appendConsole('[MegaClient] Fabric can read the verified runtime JARs', 'success')
+      appendConsole(session, '[MegaClient] Fabric can read the verified runtime JARs', 'success')
     }
-    gameStartedAt = Date.now()
-    progress('launch', serverAddress ? 'Joining partner server' : 'Minecraft is running', 1)
-    setConsoleState('Minecraft running')
-    showPlayingActivity(instance, serverAddress, gameStartedAt)
-    startSecurityMonitor(mainWindow, instance)
+    session.gameStarted = true
+    setLaunchStage(session, 'running')
+    session.instance = instance
+    const startedAt = session.startedAt = Date.now()
+    void trackGameProcesses(session, instance).then((processIds) => {
+      if (!instance.processPriority && !instance.cpuAffinity?.length) return
+      return applyProcessPriority(processIds, instance.processPriority ?? 'normal', instance.cpuAffinity)
+        .then(() => appendConsole(session, `[MegaClient] Process priority set to ${instance.processPriority ?? 'normal'}${instance.cpuAffinity?.length ? ` on cores ${instance.cpuAffinity.join(', ')}` : ''}`, 'muted'))
+    }).catch((error) => appendConsole(session, `[MegaClient] Process priority could not be changed: ${error instanceof Error ? error.message : String(error)}`, 'muted'))
+    progress('launch', serverAddress ? 'Joining partner server' : worldId ? `Opening ${worldId}` : 'Minecraft is running', 1)
+    setConsoleState(session, 'Minecraft running')
+    showPlayingActivity(instance, serverAddress, startedAt)
+    startSecurityMonitor(session, instance)
+    startResourceMonitor(session, instance, memory.max)
     if (instance.customClient && clientPayload) {
-      startClientVerification(mainWindow, instance, clientPayload, Date.now(), () => clientObserved, () => { clientObserved = true })
-    }
-    if (settings.minimizeToTrayOnLaunch) {
-      createGameTray(mainWindow)
-      mainWindow.hide()
+      startClientVerification(session, instance, clientPayload, Date.now(), () => clientObserved, () => { clientObserved = true })
     }
-  })
-  launcher.on('launch_data', (line) => {
-    const cleaned = line.replace(/\r?\n$/, '')
+    hideLauncherForGame(mainWindow, settings.launcherVisibility)
+  }
+  const onGameData = (line: string): void => {
+    const cleaned = line.replace(/^\uFEFF/, '').replace(/\r?\n$/, '')
     if (clientLoadedInText(cleaned)) clientObserved = true
-    appendConsole(cleaned, 'game')
-  })
-  launcher.on('launch_debug', (line) => {
+    rememberGameMessage(session, logMessage(cleaned))
+    watchForOutOfMemory(session, cleaned)
+    appendConsole(session, cleaned, 'game')
+  }
+  const onGameDebug = (line: string): void => {
     const isFinalCommand = line.startsWith('Launching Minecraft with args:')
     if (isFinalCommand && instance.customClient && clientPayload) {
       const normalisedLine = line.replaceAll('\\', '/')
@@ -553,49 +1504,111 @@ export async function launchInstance(mainWindow: BrowserWindow, instanceId: stri
       if (!hasMarker) {
         throw new Error('The final Java command did not contain the protected MegaClient verification arguments.')
       }
-      appendConsole('[MegaClient] Confirmed protected runtime verification arguments in the final Java command', 'success')
-      appendConsole('[Launcher] Final Java command prepared (protected paths hidden)', 'muted')
+      appendConsole(session, '[MegaClient] Confirmed protected runtime verification arguments in the final Java command', 'success')
+      appendConsole(session, '[Launcher] Final Java command prepared (protected paths hidden)', 'muted')
       return
     }
-    appendConsole(line, 'muted')
-  })
-  launcher.on('launch_crash', (data) => {
-    appendConsole(`[Crash] ${JSON.stringify(data)}`, 'error')
-    emit(mainWindow, 'launch:crash', data)
-  })
-  launcher.on('launch_close', (code) => {
-    stopSecurityMonitor()
-    stopClientVerification()
-    setConsoleState(code === 0 ? 'Finished' : `Exited (${code ?? 'unknown'})`)
-    emit(mainWindow, 'launch:closed', { code })
-    destroyGameTray()
-    showMainWindow(mainWindow)
-    showLauncherActivity(instance)
+    appendConsole(session, line, 'muted')
+  }
+  const onGameClosed = (code: number | null): void => {
+    const gameStarted = session.gameStarted
+    setLaunchStage(session, 'exited')
+    setConsoleState(session, session.stopping ? 'Stopped' : code === 0 ? 'Finished' : `Exited (${code ?? 'unknown'})`)
+    emitLaunch(session, 'launch:closed', { code, stopped: session.stopping })
+    const explanations = session.stopping ? [] : explainExit(code, session.consoleLines.map((entry) => entry.line))
+    for (const explanation of explanations) appendConsole(session, `[MegaClient] ${explanation.title}. ${explanation.detail}`, 'error')
+    emitLaunch(session, 'mc:exited', { code, stopped: session.stopping, explanations })
+    const crashed = gameStarted && code !== 0 && !session.stopping
+    // Crashes are expected while bisecting mods, so they do not count.
+    const crashLoop = crashed && !session.bisecting ? noteCrash(instance.id) : 0
+    const restarting = gameStarted && !crashLoop && scheduleCrashRestart(session, instance, code, serverAddress, worldId, options)
+    // The session keeps the instance locked until the safe mode mods are back
+    // and the bisect has moved on, since both rename mods.
+    void restoreSafeMode(session, instance)
+      .then(() => gameStarted
+        ? recordCrashResult(session, crashed).catch((error) => session.trace.warn('The mod bisect result could not be recorded.', { error }))
+        : undefined)
+      .finally(() => endSession(session))
+    if (!anyGameRunning(session) && !restarting) {
+      restoreLauncherAfterGame(mainWindow, settings.launcherVisibility)
+      showLauncherActivity(instance)
+    }
     void cleanupPayload()
-  })
+    if (crashed) {
+      void reportCrash(session, instance, session.startedAt ?? Date.now(), code, explanations, crashLoop)
+        .catch((error) => session.trace.warn('The crash report could not be read.', { error }))
+    } else if (gameStarted && code === 0) {
+      recentCrashes.delete(instance.id)
+      if (getInstance(instance.id).unstable) {
+        void setInstanceUnstable(instance.id, null).catch((error) => session.trace.warn('The unstable flag could not be cleared.', { error }))
+      }
+    }
+    if (gameStarted) {
+      void recordPlaySession(instance.id, session.startedAt ?? Date.now())
+        .catch((error) => session.trace.warn('Playtime could not be recorded.', { error }))
+      // Skipped when the game is about to restart, since it would open the
+      // world again while the backup is still reading it.
+      if (!restarting) {
+        const backup = backUpPlayedWorlds(session, instance.id, session.startedAt ?? Date.now())
+          .catch((error) => {
+            const message = error instanceof Error ? error.message : String(error)
+            session.trace.warn('Worlds could not be backed up.', { error })
+            appendConsole(session, `[MegaClient] Worlds could not be backed up: ${message}`, 'error')
+            emitLaunch(session, 'launch:warning', { message: `Worlds could not be backed up: ${message}` })
+          })
+          .finally(() => {
+            worldBackupRuns.delete(instance.id)
+            quitIfIdle()
+          })
+        worldBackupRuns.set(instance.id, backup)
+      }
+    }
+  }
 
+  if (directLauncher) {
+    directLauncher.on('launch_launch', onGameLaunched)
+    directLauncher.on('launch_data', onGameData)
+    directLauncher.on('launch_debug', onGameDebug)
+    directLauncher.on('launch_close', onGameClosed)
+  } else if (launcher) {
+    launcher.on('launch_launch', onGameLaunched)
+    launcher.on('launch_data', onGameData)
+    launcher.on('launch_debug', onGameDebug)
+    launcher.on('launch_crash', (data) => {
+      appendConsole(session, `[Crash] ${JSON.stringify(data)}`, 'error')
+      emitLaunch(session, 'launch:crash', typeof data === 'object' && data ? data : { data })
+    })
+    launcher.on('launch_close', onGameClosed)
+  }
+
+  setLaunchStage(session, 'spawning')
+  startGameLogTails(session, instance, settings.logLevel === 'debug')
   const restoreEnvironment = secureChildEnvironment()
   try {
-    await launcher.launch()
+    await prepareLegacyAssets()
+    await (directLauncher ?? launcher)?.launch()
+    signal.throwIfAborted()
     await updateInstance(instance.id, { lastPlayedAt: new Date().toISOString() })
   } catch (error) {
-    stopSecurityMonitor()
-    stopClientVerification()
-    destroyGameTray()
-    showMainWindow(mainWindow)
-    showLauncherActivity(instance)
-    const message = error instanceof Error ? error.message : String(error)
-    appendConsole(`[Error] ${message}`, 'error')
-    setConsoleState('Launch failed')
-    emit(mainWindow, 'launch:error', { message })
+    stopSecurityMonitor(session)
+    stopClientVerification(session)
+    if (!anyGameRunning(session)) {
+      restoreLauncherAfterGame(mainWindow, settings.launcherVisibility)
+      showLauncherActivity(instance)
+    }
+    if (!signal.aborted) {
+      const message = error instanceof Error ? error.message : String(error)
+      appendConsole(session, `[Error] ${message}`, 'error')
+      setConsoleState(session, 'Launch failed')
+      emitLaunch(session, 'launch:error', { message })
+    }
     throw error
   } finally {
     restoreEnvironment()
     await cleanupPayload()
-    activeLauncher = null
   }
 }
 
-export function openLaunchConsole(): void {
-  showConsole()
+export function openLaunchConsole(instanceId?: string): void {
+  showConsole(instanceId ? sessions.get(instanceId) : consoleSession ?? [...sessions.values()].at(-1))
 }
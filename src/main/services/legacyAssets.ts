@@ -0,0 +1,58 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import { containedPath, readJsonFile, resolveVersionJson } from './versionJson'
+
+interface AssetIndex {
+  virtual?: boolean
+  map_to_resources?: boolean
+  objects?: Record<string, { hash: string; size: number }>
+}
+
+async function assetIndexId(gameDirectory: string, versionId: string): Promise<string | undefined> {
+  const version = await resolveVersionJson(gameDirectory, versionId).catch(() => null)
+  return version?.assetIndex?.id ?? version?.assets
+}
+
+// Versions before 1.7.2 read assets by their original names rather than from
+// the hashed object store. Their asset index marks this with `virtual` (files
+// live under assets/virtual/<index>) or `map_to_resources` (files live in the
+// game directory's resources folder, used by versions before 1.6).
+export async function materializeLegacyAssets(gameDirectory: string, minecraftVersion: string): Promise<number> {
+  const assetsDirectory = path.join(gameDirectory, 'assets')
+  const id = await assetIndexId(gameDirectory, minecraftVersion)
+  if (!id) return 0
+  const index = await readJsonFile<AssetIndex>(path.join(assetsDirectory, 'indexes', `${id}.json`))
+  if (!index?.objects || (!index.virtual && !index.map_to_resources)) return 0
+
+  const targets = [
+    ...(index.virtual ? [path.join(assetsDirectory, 'virtual', id)] : []),
+    ...(index.map_to_resources ? [path.join(gameDirectory, 'resources')] : [])
+  ]
+  let copied = 0
+  for (const [name, object] of Object.entries(index.objects)) {
+    if (!/^[0-9a-f]{40}$/i.test(object.hash)) continue
+    const source = path.join(assetsDirectory, 'objects', object.hash.slice(0, 2), object.hash)
+    const sourceStat = await fs.stat(source).catch(() => null)
+    if (!sourceStat) continue
+    for (const root of targets) {
+      const destination = containedPath(root, name)
+      if (!destination) continue
+      const existing = await fs.stat(destination).catch(() => null)
+      if (existing?.size === object.size) continue
+      await fs.mkdir(path.dirname(destination), { recursive: true })
+      await fs.copyFile(source, destination)
+      copied++
+    }
+  }
+  return copied
+}
+
+// The folder a legacy version reads its assets from, filled into the
+// ${game_assets} placeholder of its minecraftArguments.
+export async function legacyAssetsRoot(gameDirectory: string, indexId: string): Promise<string> {
+  const assetsDirectory = path.join(gameDirectory, 'assets')
+  const index = await readJsonFile<AssetIndex>(path.join(assetsDirectory, 'indexes', `${indexId}.json`))
+  if (index?.map_to_resources) return path.join(gameDirectory, 'resources')
+  if (index?.virtual) return path.join(assetsDirectory, 'virtual', indexId)
+  return assetsDirectory
+}
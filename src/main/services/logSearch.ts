@@ -0,0 +1,78 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import { promisify } from 'node:util'
+import { gunzip } from 'node:zlib'
+import type { LogSearchLevel, LogSearchMatch, LogSearchOptions, LogSearchResult } from '../types'
+import { getInstance } from './instances'
+import { readLaunchLog } from './launchLogs'
+import { instanceDirectory } from './paths'
+
+const gunzipAsync = promisify(gunzip)
+const GAME_LOG_NAME = /^(?:latest|debug|\d{4}-\d{2}-\d{2}-\d+)\.log(?:\.gz)?$/
+// [12:34:56] [Render thread/WARN]: and [Error] from the launcher's own lines.
+const LEVEL_PATTERN = /(?:\/|\[)(FATAL|ERROR|WARN(?:ING)?|INFO|DEBUG|TRACE)\]/i
+
+async function readGameLog(instanceId: string, name: string): Promise<string> {
+  if (!GAME_LOG_NAME.test(name)) throw new Error('Game log not found.')
+  const file = path.join(instanceDirectory(getInstance(instanceId).slug), 'logs', name)
+  const data = await fs.readFile(file).catch(() => {
+    throw new Error('Game log not found.')
+  })
+  return (name.endsWith('.gz') ? await gunzipAsync(data) : data).toString('utf8')
+}
+
+function lineLevel(line: string): LogSearchLevel | undefined {
+  const level = LEVEL_PATTERN.exec(line)?.[1]?.toUpperCase()
+  if (!level) return undefined
+  if (level === 'FATAL') return 'ERROR'
+  if (level === 'WARNING') return 'WARN'
+  return level === 'TRACE' ? 'DEBUG' : level as LogSearchLevel
+}
+
+function matcher(query: string, regex: boolean, caseSensitive: boolean): (line: string) => boolean {
+  if (!query) return () => true
+  if (regex) {
+    let pattern: RegExp
+    try {
+      pattern = new RegExp(query, caseSensitive ? '' : 'i')
+    } catch {
+      throw new Error('The search pattern is not a valid regular expression.')
+    }
+    return (line) => pattern.test(line)
+  }
+  const needle = caseSensitive ? query : query.toLowerCase()
+  return (line) => (caseSensitive ? line : line.toLowerCase()).includes(needle)
+}
+
+// Searches one launch or game log in the main process and returns only the
+// matching lines with some context. Lines without a level, such as stack
+// trace frames, count as the level of the entry they continue.
+export async function searchLog(instanceId: string, options: LogSearchOptions): Promise<LogSearchResult> {
+  const text = options.source === 'game'
+    ? await readGameLog(instanceId, options.name)
+    : await readLaunchLog(instanceId, options.name)
+  const lines = text.split(/\r?\n/)
+  const matches = matcher(String(options.query ?? ''), Boolean(options.regex), Boolean(options.caseSensitive))
+  const levels = options.levels?.length ? new Set(options.levels) : null
+  const context = Math.max(0, Math.min(10, Math.floor(Number(options.context) || 0)))
+  const limit = Math.max(1, Math.min(1000, Math.floor(Number(options.limit) || 200)))
+
+  const results: LogSearchMatch[] = []
+  let total = 0
+  let level: LogSearchLevel | undefined
+  lines.forEach((line, index) => {
+    level = lineLevel(line) ?? (/^\s/.test(line) ? level : undefined)
+    if (levels && (!level || !levels.has(level))) return
+    if (!matches(line)) return
+    total++
+    if (results.length >= limit) return
+    results.push({
+      line: index + 1,
+      text: line,
+      level,
+      before: lines.slice(Math.max(0, index - context), index),
+      after: lines.slice(index + 1, index + 1 + context)
+    })
+  })
+  return { matches: results, total, truncated: total > results.length }
+}
@@ -0,0 +1,73 @@
+import fs from 'node:fs/promises'
+import { StringDecoder } from 'node:string_decoder'
+
+export interface LogTail {
+  stop(): Promise<void>
+}
+
+// Follows a log file the game writes itself. Whatever the file held before the
+// tail started belongs to an earlier run and is skipped; when log4j rolls the
+// file over on startup, the new file is read from its beginning.
+export function tailLogFile(file: string, onLine: (line: string) => void, interval = 1000): LogTail {
+  let offset = -1
+  let inode: number | null = null
+  let partial = ''
+  // Keeps a character split across two reads intact; invalid bytes become
+  // U+FFFD instead of failing the line.
+  let decoder = new StringDecoder('utf8')
+  let reading: Promise<void> = Promise.resolve()
+  let stopped = false
+
+  const read = async (flush = false): Promise<void> => {
+    const stat = await fs.stat(file).catch(() => null)
+    if (!stat) {
+      if (offset < 0) offset = 0
+      return
+    }
+    if (offset < 0) {
+      offset = stat.size
+      inode = stat.ino
+      return
+    }
+    if (stat.ino !== inode || stat.size < offset) {
+      inode = stat.ino
+      offset = 0
+      partial = ''
+      decoder = new StringDecoder('utf8')
+    }
+    if (stat.size > offset) {
+      const handle = await fs.open(file, 'r')
+      try {
+        const buffer = Buffer.alloc(Math.min(stat.size - offset, 4 * 1024 * 1024))
+        const { bytesRead } = await handle.read(buffer, 0, buffer.length, offset)
+        offset += bytesRead
+        partial += decoder.write(buffer.subarray(0, bytesRead))
+      } finally {
+        await handle.close()
+      }
+    }
+    if (flush) partial += decoder.end()
+    const lines = partial.split(/\r?\n/)
+    partial = flush ? '' : lines.pop() ?? ''
+    for (const line of lines) if (line) onLine(line)
+  }
+
+  const poll = (flush = false): Promise<void> => {
+    reading = reading.then(() => read(flush)).catch(() => undefined)
+    return reading
+  }
+  void poll()
+  const timer = setInterval(() => {
+    if (!stopped) void poll()
+  }, interval)
+
+  return {
+    async stop() {
+      if (stopped) return reading
+      stopped = true
+      clearInterval(timer)
+      // The last lines are usually the ones explaining why the game exited.
+      return poll(true)
+    }
+  }
+}
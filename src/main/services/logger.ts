@@ -0,0 +1,94 @@
+import fs from 'node:fs'
+import path from 'node:path'
+import type { LogLevel } from '../types'
+import { dataDirectory } from './paths'
+
+type LogFields = Record<string, unknown>
+
+export interface Logger {
+  debug(message: string, fields?: LogFields): void
+  info(message: string, fields?: LogFields): void
+  warn(message: string, fields?: LogFields): void
+  error(message: string, fields?: LogFields): void
+  child(fields: LogFields): Logger
+}
+
+export const LOG_LEVELS: readonly LogLevel[] = ['error', 'warn', 'info', 'debug']
+const LOG_FILE_LIMIT = 5 * 1024 * 1024
+const LOG_FILE_HISTORY = 3
+
+let threshold: LogLevel = 'info'
+let stream: fs.WriteStream | null = null
+let written = 0
+
+export function setLogLevel(level: LogLevel): void {
+  if (LOG_LEVELS.includes(level)) threshold = level
+}
+
+export function logFile(): string {
+  return path.join(dataDirectory(), 'logs', 'megaclient.jsonl')
+}
+
+// Errors are not enumerable, so they are flattened before JSON.stringify.
+function serialise(value: unknown): unknown {
+  if (value instanceof Error) return { name: value.name, message: value.message, stack: value.stack }
+  return value
+}
+
+function rotate(file: string): void {
+  stream?.end()
+  stream = null
+  for (let index = LOG_FILE_HISTORY - 1; index >= 1; index--) {
+    const from = index === 1 ? file : `${file}.${index - 1}`
+    try {
+      fs.renameSync(from, `${file}.${index}`)
+    } catch {
+      // Nothing to rotate yet.
+    }
+  }
+}
+
+function writeLine(line: string): void {
+  const file = logFile()
+  try {
+    if (!stream) {
+      fs.mkdirSync(path.dirname(file), { recursive: true })
+      written = fs.statSync(file, { throwIfNoEntry: false })?.size ?? 0
+      stream = fs.createWriteStream(file, { flags: 'a' })
+      stream.on('error', () => { stream = null })
+    }
+    stream.write(line)
+    written += Buffer.byteLength(line)
+    if (written > LOG_FILE_LIMIT) rotate(file)
+  } catch {
+    stream = null
+  }
+}
+
+function write(level: LogLevel, message: string, fields: LogFields): void {
+  if (LOG_LEVELS.indexOf(level) > LOG_LEVELS.indexOf(threshold)) return
+  const entry: LogFields = { time: new Date().toISOString(), level, message }
+  for (const [key, value] of Object.entries(fields)) entry[key] = serialise(value)
+  writeLine(`${JSON.stringify(entry)}\n`)
+
+  const context = Object.entries(fields).filter(([key]) => key !== 'error')
+  const prefix = `[MegaClient]${context.map(([key, value]) => ` ${key}=${String(value)}`).join('')}`
+  const method = level === 'debug' ? console.debug : level === 'info' ? console.info : level === 'warn' ? console.warn : console.error
+  if (fields.error !== undefined) method(`${prefix} ${message}`, fields.error)
+  else method(`${prefix} ${message}`)
+}
+
+// Every entry is one JSON object per line in logs/megaclient.jsonl, carrying
+// the fields of the logger it came from, for example the instance and launch
+// stage of a launch.
+function createLogger(base: LogFields): Logger {
+  return {
+    debug: (message, fields = {}) => write('debug', message, { ...base, ...fields }),
+    info: (message, fields = {}) => write('info', message, { ...base, ...fields }),
+    warn: (message, fields = {}) => write('warn', message, { ...base, ...fields }),
+    error: (message, fields = {}) => write('error', message, { ...base, ...fields }),
+    child: (fields) => createLogger({ ...base, ...fields })
+  }
+}
+
+export const logger = createLogger({})
@@ -0,0 +1,86 @@
+import { execFile } from 'node:child_process'
+import { promisify } from 'node:util'
+import type { MeteredPrompt } from '../types'
+import { store } from './store'
+import { logger } from './logger'
+
+const execFileAsync = promisify(execFile)
+
+// Downloads smaller than this go ahead without asking.
+const PROMPT_THRESHOLD_BYTES = 50 * 1024 * 1024
+const DETECT_CACHE_MS = 60_000
+// One answer covers the rest of a launch, which may ask about assets and Java.
+const APPROVAL_MS = 10 * 60_000
+
+let detected: { metered: boolean; at: number } | null = null
+let approvedUntil = 0
+let nextId = 0
+let sendPrompt: (prompt: MeteredPrompt) => void = () => undefined
+const pending = new Map<string, (allowed: boolean) => void>()
+
+export function setMeteredPromptSender(sender: (prompt: MeteredPrompt) => void): void {
+  sendPrompt = sender
+}
+
+// Windows reports whether the current internet connection is metered, as
+// phone hotspots and connections marked metered in Settings are. Other
+// platforms publish nothing comparable, so only the setting applies there.
+async function detectMetered(): Promise<boolean> {
+  if (process.platform !== 'win32') return false
+  if (detected && Date.now() - detected.at < DETECT_CACHE_MS) return detected.metered
+  const command = [
+    "$ErrorActionPreference='Stop'",
+    '$connection=[Windows.Networking.Connectivity.NetworkInformation,Windows.Networking.Connectivity,ContentType=WindowsRuntime]::GetInternetConnectionProfile()',
+    'if ($connection) { $cost=$connection.GetConnectionCost(); "$($cost.NetworkCostType)|$($cost.Roaming)|$($cost.OverDataLimit)" }'
+  ].join(';')
+  let metered = false
+  try {
+    const { stdout } = await execFileAsync('powershell.exe', ['-NoProfile', '-NonInteractive', '-ExecutionPolicy', 'Bypass', '-Command', command], { windowsHide: true, timeout: 10_000 })
+    const [costType, roaming, overLimit] = stdout.trim().split('|')
+    metered = costType === 'Fixed' || costType === 'Variable' || roaming === 'True' || overLimit === 'True'
+  } catch (error) {
+    logger.debug('The connection cost could not be read.', { error })
+  }
+  detected = { metered, at: Date.now() }
+  return metered
+}
+
+export async function isMeteredConnection(): Promise<boolean> {
+  const setting = store.getData().settings.meteredConnection
+  if (setting !== 'auto') return setting === 'metered'
+  return detectMetered()
+}
+
+// Asks the renderer before a large download on a metered connection and
+// resolves with the answer. Small downloads, unmetered connections and
+// downloads within a few minutes of an earlier approval go ahead at once.
+export async function confirmMeteredDownload(
+  request: Omit<MeteredPrompt, 'id' | 'closed'>,
+  signal?: AbortSignal
+): Promise<boolean> {
+  if (request.bytes < PROMPT_THRESHOLD_BYTES || Date.now() < approvedUntil || !await isMeteredConnection()) return true
+  signal?.throwIfAborted()
+  const id = String(++nextId)
+  const allowed = await new Promise<boolean>((resolve, reject) => {
+    const cancel = (): void => {
+      pending.delete(id)
+      sendPrompt({ id, ...request, closed: true })
+      reject(signal!.reason)
+    }
+    signal?.addEventListener('abort', cancel, { once: true })
+    pending.set(id, (answer) => {
+      signal?.removeEventListener('abort', cancel)
+      resolve(answer)
+    })
+    sendPrompt({ id, ...request })
+  })
+  if (allowed) approvedUntil = Date.now() + APPROVAL_MS
+  return allowed
+}
+
+export function answerMeteredPrompt(id: string, allowed: boolean): void {
+  const resolve = pending.get(id)
+  if (!resolve) return
+  pending.delete(id)
+  resolve(Boolean(allowed))
+}
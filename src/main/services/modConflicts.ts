@@ -0,0 +1,68 @@
+import { readModDeclaration } from './modSecurity'
+
+// Pairs that load without declaring a conflict but crash or break rendering
+// together. Declared `breaks` and `conflicts` entries are read from the jars.
+const KNOWN_INCOMPATIBLE: Array<[string, string]> = [
+  ['sodium', 'optifabric'],
+  ['iris', 'optifabric'],
+  ['sodium', 'rubidium'],
+  ['embeddium', 'rubidium'],
+  ['phosphor', 'starlight']
+]
+
+// IDs that Fabric API, or one of the modules it bundles, provides.
+function isFabricApiId(id: string): boolean {
+  return id === 'fabric-api' || id === 'fabric' || id === 'fabric-api-base' || /^fabric-.+-v\d+$/.test(id)
+}
+
+// Every mod ID the given jars declare.
+export async function providedModIds(files: string[]): Promise<Set<string>> {
+  const declarations = await Promise.all(files.map((file) => readModDeclaration(file)))
+  return new Set(declarations.flatMap((declaration) => declaration?.ids ?? []))
+}
+
+// Returns the enabled jars that declare a Fabric API dependency nothing in the
+// folder provides.
+export async function modsNeedingFabricApi(files: Array<{ fileName: string; path: string }>): Promise<string[]> {
+  const declared = await Promise.all(files.map(async (file) => ({ ...file, declaration: await readModDeclaration(file.path) })))
+  const provided = new Set(declared.flatMap((file) => file.declaration?.ids ?? []))
+  if (provided.has('fabric-api') || provided.has('fabric')) return []
+  return declared
+    .filter((file) => file.declaration?.depends.some((id) => isFabricApiId(id) && !provided.has(id)))
+    .map((file) => file.fileName)
+}
+
+// Finds enabled jars that provide the same mod ID, or that a known or declared
+// incompatibility rules out, and returns the warnings for each file name.
+export async function findModConflicts(files: Array<{ fileName: string; path: string }>): Promise<Map<string, string[]>> {
+  const warnings = new Map<string, string[]>()
+  const warn = (fileName: string, message: string) => {
+    const list = warnings.get(fileName) ?? []
+    if (!list.includes(message)) warnings.set(fileName, [...list, message])
+  }
+  const declared = await Promise.all(files.map(async (file) => ({ ...file, declaration: await readModDeclaration(file.path) })))
+  const providers = new Map<string, string[]>()
+  for (const file of declared) {
+    for (const id of file.declaration?.ids ?? []) providers.set(id, [...providers.get(id) ?? [], file.fileName])
+  }
+
+  for (const [id, fileNames] of providers) {
+    if (fileNames.length < 2) continue
+    for (const fileName of fileNames) {
+      warn(fileName, `Provides the mod ID "${id}" like ${fileNames.filter((other) => other !== fileName).join(', ')}. Keep only one.`)
+    }
+  }
+  const incompatible = [...KNOWN_INCOMPATIBLE]
+  for (const file of declared) {
+    const own = file.declaration?.ids[0]
+    if (own) for (const other of file.declaration!.breaks) incompatible.push([own, other])
+  }
+  for (const [first, second] of incompatible) {
+    const firstFiles = providers.get(first) ?? []
+    const secondFiles = providers.get(second) ?? []
+    if (!firstFiles.length || !secondFiles.length) continue
+    for (const fileName of firstFiles) warn(fileName, `Does not work with ${second} (${secondFiles.join(', ')}).`)
+    for (const fileName of secondFiles) warn(fileName, `Does not work with ${first} (${firstFiles.join(', ')}).`)
+  }
+  return warnings
+}
@@ -1,8 +1,10 @@
+import { createHash } from 'node:crypto'
 import fs from 'node:fs/promises'
 import os from 'node:os'
 import { createRequire } from 'node:module'
 import path from 'node:path'
 import { Worker } from 'node:worker_threads'
+import { modIconCacheDirectory } from './paths'
 
 export interface ModSecurityFinding {
   category: 'mod'
@@ -10,15 +12,38 @@ export interface ModSecurityFinding {
   detail: string
 }
 
+// What a jar declares about itself for its loader: its mod IDs, the IDs it
+// needs, and the IDs it says it cannot run alongside.
+export interface ModDeclaration {
+  name?: string
+  version?: string
+  description?: string
+  loaders: Array<'fabric' | 'quilt' | 'forge' | 'neoforge'>
+  ids: string[]
+  depends: string[]
+  breaks: string[]
+}
+
+interface JarScan {
+  finding: ModSecurityFinding | null
+  declaration: ModDeclaration
+  // The declared icon, copied out of the jar into the icon cache.
+  icon?: string
+}
+
 interface WorkerReply {
   id: number
-  finding: ModSecurityFinding | null
+  scan?: JarScan | null
+  entries?: Record<string, Uint8Array> | null
 }
 
+// A jar scan, or with `entries` a read of just the named archive entries.
 interface QueuedScan {
   id: number
   file: string
-  resolve: (finding: ModSecurityFinding | null) => void
+  iconKey?: string
+  entries?: { names: string[]; maxBytes: number }
+  resolve: (reply: WorkerReply | null) => void
 }
 
 interface WorkerSlot {
@@ -35,6 +60,7 @@ const ADM_ZIP_MODULE = nodeRequire.resolve('adm-zip')
 // thread. Large mod folders can otherwise make the whole launcher appear frozen.
 const WORKER_SOURCE = String.raw`
 const { parentPort, workerData } = require('node:worker_threads')
+const fs = require('node:fs')
 const path = require('node:path')
 const AdmZip = require(workerData.admZipModule)
 
@@ -120,6 +146,88 @@ function identities(zip) {
   }
   return values
 }
+// Icons are a path, or a map of sizes to paths. The largest up to 256 pixels
+// is used, since the list shows them small.
+function iconPath(value) {
+  if (typeof value === 'string') return value
+  if (!value || typeof value !== 'object') return undefined
+  const sizes = Object.keys(value).map(Number).filter((size) => size > 0 && typeof value[size] === 'string').sort((a, b) => a - b)
+  const size = sizes.filter((item) => item <= 256).pop() || sizes[0]
+  return size ? value[size] : undefined
+}
+function writeIcon(zip, entryName, iconKey) {
+  if (!entryName || !/\.(png|jpe?g)$/i.test(entryName)) return undefined
+  const entry = zip.getEntry(entryName.replace(/^\/+/, ''))
+  if (!entry || entry.isDirectory || entry.header.size > 512 * 1024) return undefined
+  const target = path.join(workerData.iconDirectory, iconKey + path.extname(entryName).toLowerCase())
+  fs.mkdirSync(workerData.iconDirectory, { recursive: true })
+  fs.writeFileSync(target, entry.getData())
+  return target
+}
+function tomlModIds(text) {
+  // Only IDs inside [[mods]] tables; dependency tables repeat other mods' IDs.
+  const ids = []
+  let inMods = false
+  for (const line of text.split(/\r?\n/)) {
+    const header = line.match(/^\s*\[\[?\s*([^\]]+?)\s*\]\]?/)
+    if (header) { inMods = header[1] === 'mods'; continue }
+    const match = inMods && line.match(/^\s*modId\s*=\s*["']([^"']+)["']/)
+    if (match) ids.push(match[1])
+  }
+  return ids
+}
+function declaration(zip) {
+  const ids = new Set()
+  const depends = new Set()
+  const breaks = new Set()
+  const loaders = []
+  const about = {}
+  const describe = (name, version, description) => {
+    if (!about.name && typeof name === 'string') about.name = name
+    if (!about.version && typeof version === 'string' && !version.includes('$')) about.version = version
+    if (!about.description && typeof description === 'string') about.description = description.slice(0, 1000)
+  }
+  const fabric = json(zip, 'fabric.mod.json')
+  if (fabric) {
+    loaders.push('fabric')
+    describe(fabric.name, fabric.version, fabric.description)
+    about.iconPath = iconPath(fabric.icon)
+  }
+  if (fabric && typeof fabric.id === 'string') ids.add(fabric.id)
+  if (fabric && fabric.depends && typeof fabric.depends === 'object') for (const id of Object.keys(fabric.depends)) depends.add(id)
+  for (const id of Array.isArray(fabric && fabric.provides) ? fabric.provides : []) if (typeof id === 'string') ids.add(id)
+  for (const field of ['breaks', 'conflicts']) {
+    const value = fabric && fabric[field]
+    if (value && typeof value === 'object') for (const id of Object.keys(value)) breaks.add(id)
+  }
+  const quilt = json(zip, 'quilt.mod.json')
+  const loader = quilt && quilt.quilt_loader
+  if (loader) {
+    loaders.push('quilt')
+    describe(loader.metadata && loader.metadata.name, loader.version, loader.metadata && loader.metadata.description)
+    about.iconPath = about.iconPath || iconPath(loader.metadata && loader.metadata.icon)
+  }
+  if (loader && typeof loader.id === 'string') ids.add(loader.id)
+  for (const entry of Array.isArray(loader && loader.provides) ? loader.provides : []) {
+    const id = typeof entry === 'string' ? entry : entry && entry.id
+    if (typeof id === 'string') ids.add(id)
+  }
+  for (const entry of Array.isArray(loader && loader.depends) ? loader.depends : []) {
+    const id = typeof entry === 'string' ? entry : entry && entry.id
+    if (typeof id === 'string') depends.add(id.replace(/^[^:]+:/, ''))
+  }
+  for (const name of ['META-INF/mods.toml', 'META-INF/neoforge.mods.toml']) {
+    const entry = zip.getEntry(name)
+    if (!entry || entry.isDirectory) continue
+    const text = entry.getData().toString('utf8').slice(0, 500000)
+    loaders.push(name.includes('neoforge') ? 'neoforge' : 'forge')
+    for (const id of tomlModIds(text)) ids.add(id)
+    const field = (key) => { const match = text.match(new RegExp('^\\s*' + key + '\\s*=\\s*["\']([^"\']+)["\']', 'm')); return match ? match[1] : undefined }
+    describe(field('displayName'), field('version'), field('description'))
+    about.iconPath = about.iconPath || field('logoFile')
+  }
+  return { ...about, loaders, ids: [...ids], depends: [...depends], breaks: [...breaks] }
+}
 function filenameFinding(file) {
   const base = path.basename(file).toLowerCase().replace(/\.jar(?:\.disabled)?$/i, '')
   for (const [alias, label] of FILE_ALIASES) {
@@ -132,9 +240,8 @@ function filenameFinding(file) {
   }
   return null
 }
-function inspect(file) {
+function findingFor(file, zip) {
   try {
-    const zip = new AdmZip(file)
     let declared = null
     for (const identity of identities(zip)) {
       const label = BLOCKED.get(identity)
@@ -151,18 +258,46 @@ function inspect(file) {
       title: 'Blocked client modification detected',
       detail: path.basename(file) + ' was identified from high-confidence identity evidence: ' + evidence + '.'
     } : null
+  } catch {
+    return null
+  }
+}
+function inspect(file, iconKey) {
+  try {
+    const zip = new AdmZip(file)
+    if (!zip.getEntries().length) return null
+    const declared = declaration(zip)
+    let icon
+    try { icon = writeIcon(zip, declared.iconPath, iconKey) } catch { icon = undefined }
+    delete declared.iconPath
+    return { finding: findingFor(file, zip), declaration: declared, icon }
   } catch {
     // Broken archives are left to the loader's normal diagnostics. They are not
     // silently reclassified as cheats.
     return null
   }
 }
-parentPort.on('message', ({ id, file }) => {
-  parentPort.postMessage({ id, finding: inspect(file) })
+function readEntries(file, names, maxBytes) {
+  try {
+    const zip = new AdmZip(file)
+    const found = {}
+    for (const name of names) {
+      const entry = zip.getEntry(name)
+      if (entry && !entry.isDirectory && entry.header.size <= maxBytes) found[name] = entry.getData()
+    }
+    return found
+  } catch {
+    return null
+  }
+}
+parentPort.on('message', ({ id, file, iconKey, entries }) => {
+  parentPort.postMessage(entries
+    ? { id, entries: readEntries(file, entries.names, entries.maxBytes) }
+    : { id, scan: inspect(file, iconKey) })
 })
 `
 
-const scanCache = new Map<string, { stamp: string; finding: ModSecurityFinding | null }>()
+const scanCache = new Map<string, { stamp: string; scan: JarScan | null }>()
 const queue: QueuedScan[] = []
 const pending = new Map<number, QueuedScan>()
 const workers: WorkerSlot[] = []
@@ -192,7 +327,7 @@ function scheduleIdleShutdown(): void {
 function createWorkerSlot(): WorkerSlot {
   const worker = new Worker(WORKER_SOURCE, {
     eval: true,
-    workerData: { admZipModule: ADM_ZIP_MODULE }
+    workerData: { admZipModule: ADM_ZIP_MODULE, iconDirectory: modIconCacheDirectory() }
   })
   const slot: WorkerSlot = { worker, busy: false }
   worker.on('message', (reply: WorkerReply) => {
@@ -202,7 +337,7 @@ function createWorkerSlot(): WorkerSlot {
     slot.busy = false
     slot.taskId = undefined
     slot.worker.unref()
-    task?.resolve(reply.finding)
+    task?.resolve(reply)
     drainQueue()
   })
   worker.on('error', () => recoverWorker(slot))
@@ -242,7 +377,7 @@ function drainQueue(): void {
     slot.taskId = task.id
     slot.worker.ref()
     pending.set(task.id, task)
-    slot.worker.postMessage({ id: task.id, file: task.file })
+    slot.worker.postMessage({ id: task.id, file: task.file, iconKey: task.iconKey, entries: task.entries })
   }
   scheduleIdleShutdown()
 }
@@ -252,28 +387,74 @@ async function fileStamp(file: string): Promise<string> {
   return `${stat.size}:${Math.floor(stat.mtimeMs)}`
 }
 
-function scanInWorker(file: string): Promise<ModSecurityFinding | null> {
+function scanInWorker(file: string, iconKey: string): Promise<JarScan | null> {
   return new Promise((resolve) => {
-    queue.push({ id: nextTaskId++, file, resolve })
+    queue.push({ id: nextTaskId++, file, iconKey, resolve: (reply) => resolve(reply?.scan ?? null) })
     drainQueue()
   })
 }
 
-export async function inspectModJar(file: string): Promise<ModSecurityFinding | null> {
+// Reads only the named entries of an archive on the scan workers. Entries that
+// are missing or larger than maxBytes are left out; an unreadable archive
+// gives null.
+export function readArchiveEntries(file: string, names: string[], maxBytes: number): Promise<Map<string, Buffer> | null> {
+  return new Promise((resolve) => {
+    queue.push({
+      id: nextTaskId++,
+      file,
+      entries: { names, maxBytes },
+      resolve: (reply) => resolve(reply?.entries
+        ? new Map(Object.entries(reply.entries).map(([name, data]) => [name, Buffer.from(data.buffer, data.byteOffset, data.byteLength)]))
+        : null)
+    })
+    drainQueue()
+  })
+}
+
+async function scanJar(file: string): Promise<JarScan | null> {
   try {
     const stamp = await fileStamp(file)
     const cached = scanCache.get(file)
-    if (cached?.stamp === stamp) return cached.finding
-    const finding = await scanInWorker(file)
-    scanCache.set(file, { stamp, finding })
+    if (cached?.stamp === stamp) return cached.scan
+    const scan = await scanInWorker(file, createHash('sha1').update(`${file}:${stamp}`).digest('hex'))
+    scanCache.set(file, { stamp, scan })
     if (scanCache.size > 1_000) {
       for (const key of scanCache.keys()) {
         scanCache.delete(key)
         if (scanCache.size <= 700) break
       }
     }
-    return finding
+    return scan
   } catch {
     return null
   }
 }
+
+export async function inspectModJar(file: string): Promise<ModSecurityFinding | null> {
+  return (await scanJar(file))?.finding ?? null
+}
+
+// Returns the icon a jar declares as a data URL. If the icon cache was cleared
+// since the jar was scanned, it is scanned again to put the icon back.
+export async function readModIcon(file: string): Promise<string | null> {
+  for (let attempt = 0; attempt < 2; attempt++) {
+    const icon = (await scanJar(file))?.icon
+    if (!icon) return null
+    const data = await fs.readFile(icon).catch(() => null)
+    if (data) return `data:image/${path.extname(icon) === '.png' ? 'png' : 'jpeg'};base64,${data.toString('base64')}`
+    scanCache.delete(file)
+  }
+  return null
+}
+
+// Whether the jar or pack opens as a non-empty archive, from the same scan the
+// security check uses.
+export async function isReadableArchive(file: string): Promise<boolean> {
+  return await scanJar(file) !== null
+}
+
+// Reads the mod IDs from the same scan the security check uses, so listing
+// mods after a launch check does not open the jars again.
+export async function readModDeclaration(file: string): Promise<ModDeclaration | null> {
+  return (await scanJar(file))?.declaration ?? null
+}
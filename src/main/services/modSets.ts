@@ -0,0 +1,117 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import { randomUUID } from 'node:crypto'
+import type { LauncherInstance, ModSet, ModSetApplyResult, ModSetList } from '../types'
+import { getInstance } from './instances'
+import { FABRIC_API_PROJECT, listMods, setModEnabled } from './modrinth'
+import { metadataDirectory } from './paths'
+
+interface ModSetFile {
+  sets: ModSet[]
+  activeId?: string
+}
+
+function setsPath(instance: LauncherInstance): string {
+  return path.join(metadataDirectory(instance.slug), 'mod-sets.json')
+}
+
+async function readSets(instance: LauncherInstance): Promise<ModSetFile> {
+  try {
+    const value = JSON.parse(await fs.readFile(setsPath(instance), 'utf8')) as ModSetFile
+    return { sets: Array.isArray(value.sets) ? value.sets : [], activeId: value.activeId }
+  } catch {
+    return { sets: [] }
+  }
+}
+
+async function writeSets(instance: LauncherInstance, file: ModSetFile): Promise<void> {
+  await fs.mkdir(metadataDirectory(instance.slug), { recursive: true })
+  // Written beside the file and renamed over it, so a crash mid-write cannot
+  // leave it truncated.
+  const target = setsPath(instance)
+  const temp = `${target}.${process.pid}.${Date.now()}.tmp`
+  try {
+    await fs.writeFile(temp, JSON.stringify(file, null, 2), 'utf8')
+    await fs.rename(temp, target)
+  } finally {
+    await fs.rm(temp, { force: true }).catch(() => undefined)
+  }
+}
+
+function sameMods(a: string[], b: string[]): boolean {
+  const names = new Set(a)
+  return names.size === new Set(b).size && b.every((name) => names.has(name))
+}
+
+// The set last switched to only counts as active while the enabled mods still
+// match it; toggling a mod by hand leaves no set active.
+export async function listModSets(instanceId: string): Promise<ModSetList> {
+  const instance = getInstance(instanceId)
+  const file = await readSets(instance)
+  const enabled = (await listMods(instanceId)).filter((mod) => mod.enabled).map((mod) => mod.fileName)
+  const active = file.sets.find((set) => set.id === file.activeId)
+  return { sets: file.sets, activeId: active && sameMods(active.enabled, enabled) ? active.id : undefined }
+}
+
+// Saves the mods enabled right now under a name. Saving under the name of an
+// existing set replaces what that set enables.
+export async function saveModSet(instanceId: string, name: string): Promise<ModSet> {
+  const instance = getInstance(instanceId)
+  const trimmed = name.trim().slice(0, 48)
+  if (!trimmed) throw new Error('Give the mod set a name.')
+  const enabled = (await listMods(instanceId)).filter((mod) => mod.enabled).map((mod) => mod.fileName)
+  const file = await readSets(instance)
+  const now = new Date().toISOString()
+  const existing = file.sets.find((set) => set.name.toLowerCase() === trimmed.toLowerCase())
+  const saved: ModSet = existing
+    ? { ...existing, name: trimmed, enabled, updatedAt: now }
+    : { id: randomUUID(), name: trimmed, enabled, createdAt: now, updatedAt: now }
+  file.sets = existing ? file.sets.map((set) => set.id === saved.id ? saved : set) : [...file.sets, saved]
+  file.activeId = saved.id
+  await writeSets(instance, file)
+  return saved
+}
+
+export async function deleteModSet(instanceId: string, setId: string): Promise<void> {
+  const instance = getInstance(instanceId)
+  const file = await readSets(instance)
+  file.sets = file.sets.filter((set) => set.id !== setId)
+  if (file.activeId === setId) delete file.activeId
+  await writeSets(instance, file)
+}
+
+// Enables exactly the mods in the set and disables the rest by renaming them
+// to .jar.disabled. If a rename fails part way, the mods already switched are
+// put back so the instance is never left between two sets. MegaClient
+// instances keep Fabric API, which the client cannot start without.
+export async function applyModSet(instanceId: string, setId: string): Promise<ModSetApplyResult> {
+  const instance = getInstance(instanceId)
+  const file = await readSets(instance)
+  const set = file.sets.find((item) => item.id === setId)
+  if (!set) throw new Error('That mod set no longer exists.')
+  const mods = await listMods(instanceId)
+  const wanted = new Set(set.enabled)
+  const changes = mods
+    .filter((mod) => !(instance.customClient && mod.projectId === FABRIC_API_PROJECT))
+    .filter((mod) => mod.enabled !== wanted.has(mod.fileName))
+    .map((mod) => ({ fileName: mod.fileName, enabled: wanted.has(mod.fileName) }))
+  const applied: typeof changes = []
+  try {
+    for (const change of changes) {
+      await setModEnabled(instanceId, change.fileName, change.enabled)
+      applied.push(change)
+    }
+  } catch (error) {
+    for (const change of applied.reverse()) await setModEnabled(instanceId, change.fileName, !change.enabled).catch(() => undefined)
+    throw error
+  }
+  file.activeId = set.id
+  await writeSets(instance, file)
+  const present = new Set(mods.map((mod) => mod.fileName))
+  return {
+    setId: set.id,
+    enabled: changes.filter((change) => change.enabled).length,
+    disabled: changes.filter((change) => !change.enabled).length,
+    missing: set.enabled.filter((fileName) => !present.has(fileName))
+  }
+}
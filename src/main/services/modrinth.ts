@@ -1,8 +1,9 @@
+import { createHash } from 'node:crypto'
 import fs from 'node:fs/promises'
 import path from 'node:path'
 import AdmZip from 'adm-zip'
-import type { DiscoverContentType, LauncherInstance, TrackedMod, TrackedPack } from '../types'
-import { getInstance, updateInstance } from './instances'
+import type { CollectionInstallReport, DiscoverContentType, InstallPlan, InstallPlanItem, InstalledMod, InstanceUpdateSummary, LauncherInstance, ModListImportReport, ModpackUpdateReport, ModrinthProjectDetails, ModUpdateReport, ModUpdateResult, PackInstallResult, ShaderLoaderRequirement, TrackedMod, TrackedPack } from '../types'
+import { createInstance, deleteInstance, getInstance, updateInstance } from './instances'
 import {
   instanceDirectory,
   metadataDirectory,
@@ -10,7 +11,24 @@ import {
   resourcePacksDirectory,
   shaderPacksDirectory
 } from './paths'
-import { downloadFile, fetchJson, hashFile } from './net'
+import { hashFile } from './net'
+import { MODRINTH_API, modrinthCachedJson, modrinthJson } from './modrinthClient'
+import { download, downloadConcurrency, runWithConcurrency } from './downloads'
+import { downloadObject } from './objectStore'
+import { findModConflicts, modsNeedingFabricApi, providedModIds } from './modConflicts'
+import { readModIcon } from './modSecurity'
+import { enabledResourcePacks, setResourcePackEnabled } from './gameOptions'
+import { packFormatWarning, readResourcePackMeta } from './resourcePackMeta'
+
+export const FABRIC_API_PROJECT = 'P7dR8mSH'
+// Mod IDs of the mods that let the game load shader packs.
+const SHADER_LOADER_IDS = ['iris', 'oculus', 'optifine', 'optifabric']
+// The mods offered for shader support on each loader, most preferred first.
+const SHADER_LOADER_PROJECTS: Record<Exclude<LauncherInstance['loader'], 'vanilla'>, Array<{ projectId: string; title: string }>> = {
+  fabric: [{ projectId: 'iris', title: 'Iris Shaders' }],
+  neoforge: [{ projectId: 'iris', title: 'Iris Shaders' }, { projectId: 'oculus', title: 'Oculus' }],
+  forge: [{ projectId: 'oculus', title: 'Oculus' }]
+}
 
 interface SearchHit {
   project_id: string
@@ -65,6 +83,26 @@ interface ModrinthProject {
   description: string
   icon_url?: string
   project_type: DiscoverContentType
+  body?: string
+  categories?: string[]
+  downloads?: number
+  followers?: number
+  published?: string
+  updated?: string
+  license?: { id: string; name: string; url?: string | null }
+  gallery?: Array<{ url: string; featured: boolean; title?: string | null; description?: string | null; ordering?: number }>
+  source_url?: string | null
+  issues_url?: string | null
+  wiki_url?: string | null
+  discord_url?: string | null
+  donation_urls?: Array<{ platform: string; url: string }>
+}
+
+interface ModrinthCollection {
+  id: string
+  name: string
+  description?: string | null
+  projects: string[]
 }
 
 interface ModpackIndex {
@@ -83,13 +121,38 @@ interface ModpackIndex {
   dependencies: Record<string, string>
 }
 
+// A shareable list of an instance's mods. Mods published on Modrinth are
+// reinstalled from it; others are listed by hash so they can be recognised.
+interface ModListManifest {
+  format: 'megaclient-mod-list'
+  formatVersion: 1
+  exportedAt: string
+  instance: { name: string; minecraftVersion: string; loader: string; loaderVersion?: string }
+  mods: Array<{
+    title: string
+    fileName: string
+    enabled: boolean
+    projectId?: string
+    versionId?: string
+    versionNumber?: string
+    hashes: { sha1: string; sha512?: string }
+  }>
+}
+
 interface ModMetadataFile { mods: TrackedMod[] }
+// The mods an update replaced, as they were before. Their jars sit next to
+// this file until the next update replaces them or they are rolled back.
+interface ModRollbackFile { createdAt: string; mods: TrackedMod[] }
 interface PackMetadataFile { packs: TrackedPack[] }
 
 const searchCache = new Map<string, { value: SearchResponse; expires: number }>()
 const projectCache = new Map<string, { value: ModrinthProject; expires: number }>()
 const versionsCache = new Map<string, { value: ModrinthVersion[]; expires: number }>()
+const versionCache = new Map<string, { value: ModrinthVersion; expires: number }>()
 const installLocks = new Map<string, Promise<unknown>>()
+// SHA-1 of jars in mods folders, keyed by path, size and modification time so
+// unchanged files are not read again on every install.
+const jarHashCache = new Map<string, string>()
 
 function pruneTimedCache<T>(cache: Map<string, { value: T; expires: number }>, maximum: number): void {
   const now = Date.now()
@@ -121,6 +184,15 @@ async function writeState(instance: LauncherInstance, state: ModMetadataFile): P
   await fs.writeFile(stateFile(instance), JSON.stringify(state, null, 2), 'utf8')
 }
 
+function rollbackDirectory(instance: LauncherInstance): string {
+  return path.join(metadataDirectory(instance.slug), 'mod-rollback')
+}
+
+async function readRollback(instance: LauncherInstance): Promise<ModRollbackFile | null> {
+  try { return JSON.parse(await fs.readFile(path.join(rollbackDirectory(instance), 'rollback.json'), 'utf8')) as ModRollbackFile }
+  catch { return null }
+}
+
 async function readPackState(instance: LauncherInstance): Promise<PackMetadataFile> {
   try { return JSON.parse(await fs.readFile(packStateFile(instance), 'utf8')) as PackMetadataFile }
   catch { return { packs: [] } }
@@ -140,11 +212,15 @@ function packDirectory(instance: LauncherInstance, type: TrackedPack['contentTyp
   return type === 'resourcepack' ? resourcePacksDirectory(instance.slug) : shaderPacksDirectory(instance.slug)
 }
 
+export type SearchSort = 'relevance' | 'downloads' | 'follows' | 'newest' | 'updated'
+
 export async function searchContent(input: {
   query: string
   type: DiscoverContentType
   instanceId?: string
   offset?: number
+  limit?: number
+  sort?: SearchSort
 }): Promise<SearchResponse> {
   const facets: string[][] = [[`project_type:${input.type}`]]
   if (input.instanceId) {
@@ -153,17 +229,17 @@ export async function searchContent(input: {
     const loaders = compatibleLoaders(instance)
     if (input.type === 'mod' && loaders.length) facets.push(loaders.map((loader) => `categories:${loader}`))
   }
-  const url = new URL('https://api.modrinth.com/v2/search')
+  const url = new URL(`${MODRINTH_API}/search`)
   url.searchParams.set('query', input.query.trim())
-  url.searchParams.set('limit', '30')
+  url.searchParams.set('limit', String(Math.max(1, Math.min(100, input.limit ?? 30))))
   url.searchParams.set('offset', String(input.offset ?? 0))
-  url.searchParams.set('index', input.query.trim() ? 'relevance' : 'downloads')
+  url.searchParams.set('index', input.sort ?? (input.query.trim() ? 'relevance' : 'downloads'))
   url.searchParams.set('facets', JSON.stringify(facets))
 
   const cacheKey = url.toString()
   const cached = searchCache.get(cacheKey)
   if (cached && cached.expires > Date.now()) return cached.value
-  const value = await fetchJson<SearchResponse>(cacheKey)
+  const value = await modrinthJson<SearchResponse>(cacheKey)
   searchCache.set(cacheKey, { value, expires: Date.now() + 45_000 })
   if (searchCache.size > 80) {
     for (const [key, entry] of searchCache) {
@@ -173,10 +249,28 @@ export async function searchContent(input: {
   return value
 }
 
+// What the Discover page opens with: the most downloaded or most followed mods,
+// shaders and resource packs for the instance's game version and loader.
+export async function popularContent(input: {
+  instanceId?: string
+  sort?: Extract<SearchSort, 'downloads' | 'follows' | 'updated'>
+  limit?: number
+}): Promise<Record<'mod' | 'shader' | 'resourcepack', SearchHit[]>> {
+  const types = ['mod', 'shader', 'resourcepack'] as const
+  const results = await Promise.all(types.map((type) => searchContent({
+    query: '',
+    type,
+    instanceId: input.instanceId,
+    limit: input.limit ?? 8,
+    sort: input.sort ?? 'downloads'
+  })))
+  return { mod: results[0]!.hits, shader: results[1]!.hits, resourcepack: results[2]!.hits }
+}
+
 async function project(projectId: string): Promise<ModrinthProject> {
   const cached = projectCache.get(projectId)
   if (cached && cached.expires > Date.now()) return cached.value
-  const value = await fetchJson<ModrinthProject>(`https://api.modrinth.com/v2/project/${encodeURIComponent(projectId)}`)
+  const value = await modrinthCachedJson<ModrinthProject>(`/project/${encodeURIComponent(projectId)}`, 10 * 60_000)
   projectCache.set(projectId, { value, expires: Date.now() + 10 * 60_000 })
   pruneTimedCache(projectCache, 180)
   return value
@@ -194,7 +288,7 @@ async function versionsFor(projectId: string, instance: LauncherInstance, type:
   const cached = versionsCache.get(cacheKey)
   if (cached && cached.expires > Date.now()) return cached.value
 
-  const url = new URL(`https://api.modrinth.com/v2/project/${encodeURIComponent(projectId)}/version`)
+  const url = new URL(`${MODRINTH_API}/project/${encodeURIComponent(projectId)}/version`)
   url.searchParams.set('game_versions', JSON.stringify([instance.minecraftVersion]))
   if (type === 'mod') {
     const loaders = compatibleLoaders(instance)
@@ -202,14 +296,66 @@ async function versionsFor(projectId: string, instance: LauncherInstance, type:
   } else if (type === 'resourcepack') {
     url.searchParams.set('loaders', JSON.stringify(['minecraft']))
   }
-  const value = sortVersions(await fetchJson<ModrinthVersion[]>(url.toString()))
+  const value = sortVersions(await modrinthCachedJson<ModrinthVersion[]>(url.toString(), 5 * 60_000))
   versionsCache.set(cacheKey, { value, expires: Date.now() + 5 * 60_000 })
   pruneTimedCache(versionsCache, 260)
   return value
 }
 
+// A published version's files never change, so exact dependency lookups are
+// cached for longer than project listings.
 async function getVersion(versionId: string): Promise<ModrinthVersion> {
-  return fetchJson<ModrinthVersion>(`https://api.modrinth.com/v2/version/${encodeURIComponent(versionId)}`)
+  const cached = versionCache.get(versionId)
+  if (cached && cached.expires > Date.now()) return cached.value
+  const value = await modrinthCachedJson<ModrinthVersion>(`/version/${encodeURIComponent(versionId)}`, 60 * 60_000)
+  versionCache.set(versionId, { value, expires: Date.now() + 60 * 60_000 })
+  pruneTimedCache(versionCache, 400)
+  return value
+}
+
+// Everything a project page shows before installing. Versions are listed for
+// every game version and loader; with an instance, each one says whether it
+// would install there.
+export async function getProjectDetails(idOrSlug: string, instanceId?: string): Promise<ModrinthProjectDetails> {
+  const value = await project(idOrSlug)
+  const versions = sortVersions(await modrinthCachedJson<ModrinthVersion[]>(`/project/${encodeURIComponent(value.id)}/version`, 5 * 60_000))
+  const instance = instanceId ? getInstance(instanceId) : undefined
+  const links: ModrinthProjectDetails['links'] = []
+  if (value.source_url) links.push({ kind: 'source', label: 'Source code', url: value.source_url })
+  if (value.issues_url) links.push({ kind: 'issues', label: 'Issue tracker', url: value.issues_url })
+  if (value.wiki_url) links.push({ kind: 'wiki', label: 'Wiki', url: value.wiki_url })
+  if (value.discord_url) links.push({ kind: 'discord', label: 'Discord', url: value.discord_url })
+  for (const donation of value.donation_urls ?? []) links.push({ kind: 'donation', label: donation.platform || 'Donate', url: donation.url })
+  return {
+    id: value.id,
+    slug: value.slug,
+    title: value.title,
+    description: value.description,
+    body: value.body ?? '',
+    projectType: value.project_type,
+    iconUrl: value.icon_url,
+    categories: value.categories ?? [],
+    downloads: value.downloads ?? 0,
+    followers: value.followers ?? 0,
+    publishedAt: value.published,
+    updatedAt: value.updated,
+    license: value.license ? { id: value.license.id, name: value.license.name || value.license.id, url: value.license.url ?? undefined } : undefined,
+    gallery: [...value.gallery ?? []]
+      .sort((a, b) => Number(b.featured) - Number(a.featured) || (a.ordering ?? 0) - (b.ordering ?? 0))
+      .map((image) => ({ url: image.url, title: image.title ?? undefined, description: image.description ?? undefined, featured: image.featured })),
+    links,
+    versions: versions.map((version) => ({
+      id: version.id,
+      name: version.name,
+      versionNumber: version.version_number,
+      type: version.version_type,
+      gameVersions: version.game_versions,
+      loaders: version.loaders,
+      publishedAt: version.date_published,
+      downloads: version.downloads,
+      compatible: instance ? versionSupportsInstance(version, instance, value.project_type) : undefined
+    }))
+  }
 }
 
 function versionSupportsInstance(version: ModrinthVersion, instance: LauncherInstance, type: DiscoverContentType): boolean {
@@ -262,6 +408,9 @@ async function installDependencies(
       if (instance.loader === 'vanilla') {
         throw new Error(`${resolved.project.title} requires a mod loader. Use a Fabric, Forge or NeoForge instance.`)
       }
+      // A copy the launcher did not install, such as a local jar, already
+      // provides the dependency.
+      if ((await untrackedModsByProject(instance)).has(resolved.version.project_id)) continue
       await installModVersion(instance, resolved.version, resolved.project, visited, onProgress)
     } else if (resolved.project.project_type === 'resourcepack' || resolved.project.project_type === 'shader') {
       await installPackVersion(instance, resolved.version, resolved.project, visited, onProgress)
@@ -269,6 +418,96 @@ async function installDependencies(
   }
 }
 
+async function cachedSha1(file: string): Promise<string> {
+  const stat = await fs.stat(file)
+  const key = `${file}:${stat.size}:${stat.mtimeMs}`
+  const cached = jarHashCache.get(key)
+  if (cached) return cached
+  const hash = (await hashFile(file, 'sha1')).toLowerCase()
+  jarHashCache.set(key, hash)
+  if (jarHashCache.size > 2_000) jarHashCache.delete(jarHashCache.keys().next().value!)
+  return hash
+}
+
+// Jars in the mods folder that the launcher did not install, such as local
+// files or older copies, grouped by the Modrinth project their hash belongs
+// to. If Modrinth cannot be reached nothing is reported, so installs go ahead.
+async function untrackedModsByProject(instance: LauncherInstance): Promise<Map<string, Array<{ fileName: string; sha1: string }>>> {
+  const directory = modsDirectory(instance.slug)
+  const tracked = new Set((await readState(instance)).mods.map((mod) => mod.fileName))
+  const files: Array<{ fileName: string; sha1: string }> = []
+  for (const name of await fs.readdir(directory).catch(() => [] as string[])) {
+    const fileName = name.replace(/\.disabled$/i, '')
+    if (!/\.jar$/i.test(fileName) || tracked.has(fileName)) continue
+    const sha1 = await cachedSha1(path.join(directory, name)).catch(() => null)
+    if (sha1) files.push({ fileName, sha1 })
+  }
+  const byProject = new Map<string, Array<{ fileName: string; sha1: string }>>()
+  if (!files.length) return byProject
+  const versions = await versionsByHash(files.map((file) => file.sha1)).catch(() => ({} as Record<string, ModrinthVersion>))
+  for (const file of files) {
+    const projectId = versions[file.sha1]?.project_id
+    if (projectId) byProject.set(projectId, [...byProject.get(projectId) ?? [], file])
+  }
+  return byProject
+}
+
+// Walks the same dependencies an install would, without downloading, so the
+// files can be shown and confirmed first.
+export async function resolveInstallPlan(projectId: string, instanceId: string): Promise<InstallPlan> {
+  const instance = getInstance(instanceId)
+  const info = await project(projectId)
+  const plan: InstallPlan = { projectId, instanceId, items: [], downloadBytes: 0, problems: [] }
+  if (info.project_type === 'mod' && instance.loader === 'vanilla') {
+    plan.problems.push('Use a Fabric, Forge, or NeoForge instance to install mods.')
+    return plan
+  }
+  const version = info.project_type === 'modpack'
+    ? sortVersions(await modrinthCachedJson<ModrinthVersion[]>(`/project/${encodeURIComponent(projectId)}/version`, 60_000))[0]
+    : (await versionsFor(projectId, instance, info.project_type))[0]
+  if (!version) {
+    plan.problems.push(`${info.title} has no version for Minecraft ${instance.minecraftVersion}${info.project_type === 'mod' ? ` with ${instance.loader}` : ''}.`)
+    return plan
+  }
+  const [mods, packs] = await Promise.all([readState(instance), readPackState(instance)])
+  const installed = new Set([...mods.mods, ...packs.packs].map((item) => item.versionId).filter(Boolean))
+  const untracked = info.project_type === 'mod' ? await untrackedModsByProject(instance) : new Map<string, Array<{ fileName: string; sha1: string }>>()
+  const visited = new Set<string>()
+  const visit = async (current: ModrinthVersion, currentProject: ModrinthProject, requiredBy?: string): Promise<void> => {
+    if (visited.has(current.id)) return
+    visited.add(current.id)
+    const file = bestFile(current, currentProject.project_type === 'modpack' ? '.mrpack' : currentProject.project_type === 'mod' ? '.jar' : '.zip')
+    const item: InstallPlanItem = {
+      projectId: current.project_id, versionId: current.id, title: currentProject.title, versionNumber: current.version_number,
+      fileName: file.filename, size: file.size, contentType: currentProject.project_type, requiredBy, alreadyInstalled: installed.has(current.id)
+    }
+    const duplicates = untracked.get(current.project_id) ?? []
+    // A dependency already in the folder is used as it is; the requested mod
+    // replaces any other copy, unless that copy is the very same file.
+    if (duplicates.length && (requiredBy || duplicates.some((duplicate) => duplicate.sha1 === file.hashes.sha1?.toLowerCase()))) item.alreadyInstalled = true
+    else if (duplicates.length) item.replaces = duplicates.map((duplicate) => duplicate.fileName)
+    plan.items.push(item)
+    if (!item.alreadyInstalled) plan.downloadBytes += file.size
+    // A modpack's contents are listed in its archive, not as dependencies.
+    if (currentProject.project_type === 'modpack') return
+    for (const dependency of current.dependencies.filter((entry) => entry.dependency_type === 'required')) {
+      const resolved = await resolveDependencyVersion(dependency, instance)
+      if (!resolved) plan.problems.push(`A dependency of ${currentProject.title} has no compatible version for this instance.`)
+      else if (resolved.project.project_type === 'mod' && instance.loader === 'vanilla') plan.problems.push(`${resolved.project.title} requires a mod loader. Use a Fabric, Forge or NeoForge instance.`)
+      else await visit(resolved.version, resolved.project, currentProject.title)
+    }
+  }
+  await visit(version, info)
+  // Shader packs list no dependencies, but do nothing without a shader mod.
+  if (info.project_type === 'shader') {
+    const requirement = await shaderLoaderRequirement(instance)
+    const loaderVersion = requirement?.projectId ? (await versionsFor(requirement.projectId, instance, 'mod'))[0] : undefined
+    if (loaderVersion) await visit(loaderVersion, await project(requirement!.projectId!), info.title)
+    else if (requirement) plan.problems.push(requirement.reason)
+  }
+  return plan
+}
+
 async function installModVersion(
   instance: LauncherInstance,
   version: ModrinthVersion,
@@ -298,7 +537,7 @@ async function installModVersion(
   const target = path.join(directory, file.filename)
   await fs.mkdir(directory, { recursive: true })
   onProgress?.(`Downloading ${info.title}`)
-  await downloadFile(file.url, target, (downloaded, total) => onProgress?.(`Downloading ${info.title}`, total ? downloaded / total : undefined))
+  await downloadObject(file.url, target, file.hashes.sha1, (downloaded, total) => onProgress?.(`Downloading ${info.title}`, total ? downloaded / total : undefined))
   if (file.hashes.sha512 && await hashFile(target, 'sha512') !== file.hashes.sha512) {
     await fs.rm(target, { force: true })
     throw new Error(`The downloaded file for ${info.title} failed its integrity check.`)
@@ -347,7 +586,7 @@ async function installPackVersion(
   const target = path.join(directory, file.filename)
   await fs.mkdir(directory, { recursive: true })
   onProgress?.(`Downloading ${info.title}`)
-  await downloadFile(file.url, target, (downloaded, total) => onProgress?.(`Downloading ${info.title}`, total ? downloaded / total : undefined))
+  await downloadObject(file.url, target, file.hashes.sha1, (downloaded, total) => onProgress?.(`Downloading ${info.title}`, total ? downloaded / total : undefined))
   if (file.hashes.sha512 && await hashFile(target, 'sha512') !== file.hashes.sha512) {
     await fs.rm(target, { force: true })
     throw new Error(`The downloaded file for ${info.title} failed its integrity check.`)
@@ -369,9 +608,50 @@ async function installPackVersion(
   }
   state.packs = [...state.packs.filter((item) => item.projectId !== tracked.projectId), tracked]
   await writePackState(instance, state)
+  if (tracked.contentType === 'resourcepack') {
+    if (old?.fileName && old.fileName !== tracked.fileName && old.contentType === 'resourcepack') await setResourcePackEnabled(instance, old.fileName, false)
+    await setResourcePackEnabled(instance, tracked.fileName, true)
+  }
   return tracked
 }
 
+export async function reinstallTrackedContent(
+  instanceId: string,
+  item: Pick<TrackedMod, 'projectId' | 'versionId'>,
+  onProgress?: (message: string, progress?: number) => void
+): Promise<TrackedMod | TrackedPack> {
+  if (!item.projectId || !item.versionId) throw new Error('Only Modrinth content can be downloaded again.')
+  const instance = getInstance(instanceId)
+  const [version, info] = await Promise.all([getVersion(item.versionId), project(item.projectId)])
+  if (info.project_type === 'resourcepack' || info.project_type === 'shader') {
+    return installPackVersion(instance, version, info, new Set(), onProgress)
+  }
+  return installModVersion(instance, version, info, new Set(), onProgress)
+}
+
+export async function forgetTrackedContent(instanceId: string, fileNames: string[]): Promise<void> {
+  const instance = getInstance(instanceId)
+  const names = new Set(fileNames)
+  const [mods, packs] = await Promise.all([readState(instance), readPackState(instance)])
+  mods.mods = mods.mods.filter((mod) => !names.has(mod.fileName))
+  packs.packs = packs.packs.filter((item) => !names.has(item.fileName))
+  await Promise.all([writeState(instance, mods), writePackState(instance, packs)])
+}
+
+export async function trackedContent(instanceId: string): Promise<{ mods: TrackedMod[]; packs: TrackedPack[] }> {
+  const instance = getInstance(instanceId)
+  const [mods, packs] = await Promise.all([readState(instance), readPackState(instance)])
+  return { mods: mods.mods, packs: packs.packs }
+}
+
+export async function trackImportedMods(instanceId: string, mods: TrackedMod[]): Promise<void> {
+  const instance = getInstance(instanceId)
+  const state = await readState(instance)
+  const files = new Set(mods.map((mod) => mod.fileName))
+  state.mods = [...state.mods.filter((mod) => !files.has(mod.fileName)), ...mods]
+  await writeState(instance, state)
+}
+
 async function withInstallLock<T>(key: string, task: () => Promise<T>): Promise<T> {
   const existing = installLocks.get(key)
   if (existing) return existing as Promise<T>
@@ -380,14 +660,68 @@ async function withInstallLock<T>(key: string, task: () => Promise<T>): Promise<
   return promise
 }
 
-export async function installMod(instanceId: string, projectId: string, onProgress?: (message: string, progress?: number) => void): Promise<TrackedMod> {
+// Some mods need Fabric API without listing it as a Modrinth dependency, so
+// the jars' own metadata is checked and Fabric API installed when missing.
+export async function ensureFabricApiFor(
+  instance: LauncherInstance,
+  fileNames: string[],
+  onProgress?: (message: string, progress?: number) => void
+): Promise<boolean> {
+  if (instance.loader !== 'fabric' || !fileNames.length) return false
+  const directory = modsDirectory(instance.slug)
+  const names = await fs.readdir(directory).catch(() => [] as string[])
+  const enabled = names.filter((name) => name.endsWith('.jar')).map((fileName) => ({ fileName, path: path.join(directory, fileName) }))
+  const needing = await modsNeedingFabricApi(enabled)
+  if (!needing.some((fileName) => fileNames.includes(fileName))) return false
+  const version = (await versionsFor(FABRIC_API_PROJECT, instance, 'mod'))[0]
+  if (!version) throw new Error(`Fabric API is needed but has no version for Minecraft ${instance.minecraftVersion}.`)
+  await installModVersion(instance, version, await project(FABRIC_API_PROJECT), new Set(), onProgress)
+  return true
+}
+
+// An untracked copy of the same project in the mods folder is adopted when it
+// is the identical file. Any other copy is only swapped out with `replace`, so
+// two versions of a mod never end up side by side.
+export async function installMod(
+  instanceId: string,
+  projectId: string,
+  onProgress?: (message: string, progress?: number) => void,
+  replace = false
+): Promise<TrackedMod> {
   return withInstallLock(`mod:${instanceId}:${projectId}`, async () => {
     const instance = getInstance(instanceId)
     if (instance.loader === 'vanilla') throw new Error('Use a Fabric, Forge, or NeoForge instance to install mods.')
     const info = await project(projectId)
     const version = (await versionsFor(projectId, instance, 'mod'))[0]
     if (!version) throw new Error(`No compatible ${instance.loader} version is available for Minecraft ${instance.minecraftVersion}.`)
-    return installModVersion(instance, version, info, new Set(), onProgress)
+    const duplicates = (await untrackedModsByProject(instance)).get(version.project_id) ?? []
+    const file = bestFile(version, '.jar')
+    const identical = duplicates.find((duplicate) => duplicate.sha1 === file.hashes.sha1?.toLowerCase())
+    if (identical) {
+      const directory = modsDirectory(instance.slug)
+      const enabled = await fs.access(path.join(directory, identical.fileName)).then(() => true, () => false)
+      const tracked: TrackedMod = {
+        projectId: version.project_id, versionId: version.id, title: info.title, fileName: identical.fileName,
+        enabled, versionNumber: version.version_number, iconUrl: info.icon_url, installedAt: new Date().toISOString(), source: 'modrinth'
+      }
+      const state = await readState(instance)
+      state.mods = [...state.mods.filter((mod) => mod.projectId !== tracked.projectId), tracked]
+      await writeState(instance, state)
+      return tracked
+    }
+    if (duplicates.length && !replace) {
+      throw new Error(`${info.title} is already in the mods folder as ${duplicates.map((duplicate) => duplicate.fileName).join(', ')}. Replace it to install this version instead.`)
+    }
+    const installed = await installModVersion(instance, version, info, new Set(), onProgress)
+    const directory = modsDirectory(instance.slug)
+    for (const duplicate of duplicates.filter((item) => item.fileName !== installed.fileName)) {
+      await Promise.all([
+        fs.rm(path.join(directory, duplicate.fileName), { force: true }),
+        fs.rm(path.join(directory, `${duplicate.fileName}.disabled`), { force: true })
+      ])
+    }
+    await ensureFabricApiFor(instance, [installed.fileName], onProgress)
+    return installed
   })
 }
 
@@ -395,26 +729,64 @@ export async function installPack(
   instanceId: string,
   projectId: string,
   contentType: TrackedPack['contentType'],
-  onProgress?: (message: string, progress?: number) => void
-): Promise<TrackedPack> {
+  onProgress?: (message: string, progress?: number) => void,
+  installShaderLoader = false
+): Promise<PackInstallResult> {
   return withInstallLock(`${contentType}:${instanceId}:${projectId}`, async () => {
     const instance = getInstance(instanceId)
     const info = await project(projectId)
     if (info.project_type !== contentType) throw new Error(`This project is not a ${contentType === 'shader' ? 'shader pack' : 'resource pack'}.`)
     const version = (await versionsFor(projectId, instance, contentType))[0]
     if (!version) throw new Error(`No compatible version is available for Minecraft ${instance.minecraftVersion}.`)
-    return installPackVersion(instance, version, info, new Set(), onProgress)
+    let shaderLoader: TrackedMod | undefined
+    if (contentType === 'shader') {
+      const requirement = await shaderLoaderRequirement(instance)
+      if (requirement && !(installShaderLoader && requirement.projectId)) return { status: 'needs-shader-loader', requirement }
+      if (requirement?.projectId) {
+        shaderLoader = await installMod(instanceId, requirement.projectId, onProgress)
+        if (!shaderLoader.enabled) await setModEnabled(instanceId, shaderLoader.fileName, true)
+      }
+    }
+    return { status: 'installed', pack: await installPackVersion(instance, version, info, new Set(), onProgress), shaderLoader }
   })
 }
 
-export async function listMods(instanceId: string): Promise<TrackedMod[]> {
+// Finds what the instance needs before shader packs do anything: nothing when
+// a jar in the mods folder provides Iris, Oculus or OptiFine, otherwise the
+// mod to install or, when none fits the instance, only the reason.
+export async function shaderLoaderRequirement(instance: LauncherInstance): Promise<ShaderLoaderRequirement | null> {
+  if (instance.loader === 'vanilla') {
+    return { title: 'Iris Shaders', reason: 'Vanilla Minecraft cannot load shader packs. Use a Fabric or NeoForge instance with Iris, or a Forge instance with Oculus.' }
+  }
+  const directory = modsDirectory(instance.slug)
+  const jars = (await fs.readdir(directory).catch(() => [] as string[])).filter((name) => name.endsWith('.jar'))
+  // OptiFine jars do not always declare a mod ID.
+  if (jars.some((name) => /optifine/i.test(name))) return null
+  const ids = await providedModIds(jars.map((name) => path.join(directory, name)))
+  if (SHADER_LOADER_IDS.some((id) => ids.has(id))) return null
+  const candidates = SHADER_LOADER_PROJECTS[instance.loader]
+  for (const candidate of candidates) {
+    if ((await versionsFor(candidate.projectId, instance, 'mod'))[0]) {
+      return { ...candidate, reason: `Shader packs need ${candidate.title}, which is not installed in this instance.` }
+    }
+  }
+  return {
+    title: candidates[0].title,
+    reason: `Shader packs need ${candidates.map((candidate) => candidate.title).join(' or ')}, which has no version for Minecraft ${instance.minecraftVersion} with ${instance.loader}.`
+  }
+}
+
+// Lists every jar in the mods folder. Enabled jars are checked against each
+// other so two copies of a mod, or mods that cannot run together, are warned
+// about before the game crashes at startup.
+export async function listMods(instanceId: string): Promise<InstalledMod[]> {
   const instance = getInstance(instanceId)
   const directory = modsDirectory(instance.slug)
   await fs.mkdir(directory, { recursive: true })
   const files = await fs.readdir(directory)
   const state = await readState(instance)
   const trackedByFile = new Map(state.mods.map((mod) => [mod.fileName, mod]))
-  const mods: TrackedMod[] = []
+  const mods: InstalledMod[] = []
   for (const file of files.filter((name) => !name.startsWith('mc-runtime-') && (name.endsWith('.jar') || name.endsWith('.jar.disabled')))) {
     const enabled = file.endsWith('.jar')
     const base = enabled ? file : file.slice(0, -'.disabled'.length)
@@ -423,9 +795,28 @@ export async function listMods(instanceId: string): Promise<TrackedMod[]> {
       title: base.replace(/\.jar$/i, ''), fileName: base, enabled, source: 'local'
     })
   }
+  // Jars not installed from Modrinth show the icon they carry themselves.
+  await Promise.all(mods.filter((mod) => !mod.iconUrl).map(async (mod) => {
+    const icon = await readModIcon(path.join(directory, mod.enabled ? mod.fileName : `${mod.fileName}.disabled`))
+    if (icon) mod.iconUrl = icon
+  }))
+  const enabled = mods.filter((mod) => mod.enabled).map((mod) => ({ fileName: mod.fileName, path: path.join(directory, mod.fileName) }))
+  const conflicts = await findModConflicts(enabled)
+  const needFabricApi = new Set(instance.loader === 'fabric' ? await modsNeedingFabricApi(enabled) : [])
+  for (const mod of mods) {
+    const warnings = [...conflicts.get(mod.fileName) ?? []]
+    if (needFabricApi.has(mod.fileName)) {
+      mod.needsFabricApi = true
+      warnings.push('Needs Fabric API, which is not installed.')
+    }
+    if (warnings.length) mod.warnings = warnings
+  }
   return mods.sort((a, b) => a.title.localeCompare(b.title))
 }
 
+// Resource packs can be zips or unpacked folders, and are turned on through
+// the list in options.txt that the game reads. Shader packs are zips that are
+// hidden from the game by renaming them.
 export async function listPacks(instanceId: string, contentType?: TrackedPack['contentType']): Promise<TrackedPack[]> {
   const instance = getInstance(instanceId)
   const state = await readPackState(instance)
@@ -436,18 +827,54 @@ export async function listPacks(instanceId: string, contentType?: TrackedPack['c
     await fs.mkdir(directory, { recursive: true })
     const files = await fs.readdir(directory)
     const trackedByFile = new Map(state.packs.filter((item) => item.contentType === type).map((item) => [item.fileName, item]))
-    for (const file of files.filter((name) => name.endsWith('.zip') || name.endsWith('.zip.disabled'))) {
-      const enabled = file.endsWith('.zip')
-      const base = enabled ? file : file.slice(0, -'.disabled'.length)
+    const selected = type === 'resourcepack' ? new Set(await enabledResourcePacks(instance)) : null
+    for (const file of files) {
+      const folder = type === 'resourcepack' && !/\.zip(\.disabled)?$/i.test(file)
+      if (folder && !await fs.access(path.join(directory, file, 'pack.mcmeta')).then(() => true, () => false)) continue
+      if (!folder && !/\.zip(\.disabled)?$/i.test(file)) continue
+      const renamed = file.endsWith('.disabled')
+      const base = renamed ? file.slice(0, -'.disabled'.length) : file
+      const enabled = !renamed && (!selected || selected.has(`file/${base}`))
       const tracked = trackedByFile.get(base)
-      packs.push(tracked ? { ...tracked, enabled } : {
-        title: base.replace(/\.zip$/i, ''), fileName: base, enabled, contentType: type, source: 'local'
-      })
+      const pack: TrackedPack = tracked ? { ...tracked, enabled } : {
+        title: base.replace(/\.zip$/i, ''), fileName: base, enabled, contentType: type, source: 'local', folder: folder || undefined
+      }
+      if (type === 'resourcepack') {
+        const meta = await readResourcePackMeta(path.join(directory, file))
+        pack.description = meta.description
+        pack.packFormat = meta.packFormat
+        pack.iconUrl ??= meta.iconUrl
+        const warning = packFormatWarning(meta, instance.minecraftVersion)
+        if (warning) pack.warnings = [warning]
+      }
+      packs.push(pack)
     }
   }
   return packs.sort((a, b) => a.title.localeCompare(b.title))
 }
 
+// Copies a pack file into the instance after checking it is the kind of pack
+// it is being added as. Resource packs are turned on straight away.
+export async function importLocalPack(instanceId: string, source: string, type: TrackedPack['contentType']): Promise<TrackedPack> {
+  const instance = getInstance(instanceId)
+  const fileName = path.basename(source)
+  if (path.extname(fileName).toLowerCase() !== '.zip') throw new Error(`${fileName} is not a .zip pack.`)
+  let entries: string[]
+  try {
+    entries = new AdmZip(source).getEntries().map((entry) => entry.entryName)
+  } catch {
+    throw new Error(`${fileName} could not be read as a zip file.`)
+  }
+  if (type === 'resourcepack' && !entries.includes('pack.mcmeta')) throw new Error(`${fileName} is not a resource pack; it has no pack.mcmeta.`)
+  if (type === 'shader' && !entries.some((name) => name.startsWith('shaders/'))) throw new Error(`${fileName} is not a shader pack; it has no shaders folder.`)
+  const directory = packDirectory(instance, type)
+  await fs.mkdir(directory, { recursive: true })
+  await fs.copyFile(source, path.join(directory, fileName))
+  await fs.rm(path.join(directory, `${fileName}.disabled`), { force: true })
+  if (type === 'resourcepack') await setResourcePackEnabled(instance, fileName, true)
+  return { title: fileName.replace(/\.zip$/i, ''), fileName, enabled: true, contentType: type, source: 'local' }
+}
+
 export async function setModEnabled(instanceId: string, fileName: string, enabled: boolean): Promise<void> {
   const instance = getInstance(instanceId)
   const enabledPath = path.join(modsDirectory(instance.slug), fileName)
@@ -460,7 +887,13 @@ export async function setModEnabled(instanceId: string, fileName: string, enable
 export async function setPackEnabled(instanceId: string, fileName: string, type: TrackedPack['contentType'], enabled: boolean): Promise<void> {
   const instance = getInstance(instanceId)
   const enabledPath = path.join(packDirectory(instance, type), fileName)
-  await fs.rename(enabled ? `${enabledPath}.disabled` : enabledPath, enabled ? enabledPath : `${enabledPath}.disabled`)
+  if (type === 'resourcepack') {
+    // Packs hidden by renaming, as earlier versions did, are renamed back.
+    if (enabled) await fs.rename(`${enabledPath}.disabled`, enabledPath).catch(() => undefined)
+    await setResourcePackEnabled(instance, fileName, enabled)
+  } else {
+    await fs.rename(enabled ? `${enabledPath}.disabled` : enabledPath, enabled ? enabledPath : `${enabledPath}.disabled`)
+  }
   const state = await readPackState(instance)
   state.packs = state.packs.map((item) => item.fileName === fileName && item.contentType === type ? { ...item, enabled } : item)
   await writePackState(instance, state)
@@ -477,8 +910,11 @@ export async function removeMod(instanceId: string, fileName: string): Promise<v
 
 export async function removePack(instanceId: string, fileName: string, type: TrackedPack['contentType']): Promise<void> {
   const instance = getInstance(instanceId)
+  // Folder packs are removed recursively, so the name must stay inside the folder.
+  if (!fileName || fileName === '.' || fileName === '..' || path.basename(fileName) !== fileName) throw new Error('That pack name is not valid.')
   const base = path.join(packDirectory(instance, type), fileName)
-  await Promise.all([fs.rm(base, { force: true }), fs.rm(`${base}.disabled`, { force: true })])
+  await Promise.all([fs.rm(base, { recursive: true, force: true }), fs.rm(`${base}.disabled`, { force: true })])
+  if (type === 'resourcepack') await setResourcePackEnabled(instance, fileName, false)
   const state = await readPackState(instance)
   state.packs = state.packs.filter((item) => item.fileName !== fileName || item.contentType !== type)
   await writePackState(instance, state)
@@ -497,14 +933,153 @@ export async function updateMod(instanceId: string, projectId: string, onProgres
   return { ...installed, enabled: wasEnabled }
 }
 
-export async function updateAllMods(instanceId: string, onProgress?: (message: string, progress?: number) => void): Promise<number> {
+// Accepts a collection link such as https://modrinth.com/collection/AbCd1234
+// or the bare ID.
+function collectionId(input: string): string {
+  const value = input.trim()
+  const id = value.match(/modrinth\.com\/collection\/([A-Za-z0-9]+)/i)?.[1] ?? (/^[A-Za-z0-9]+$/.test(value) ? value : undefined)
+  if (!id) throw new Error('Paste a Modrinth collection link or ID.')
+  return id
+}
+
+// Installs every project in a collection that has a version for the instance,
+// with the dependencies each one needs. Projects that do not fit, such as
+// modpacks or mods on a vanilla instance, are skipped and reported.
+export async function installCollection(
+  instanceId: string,
+  input: string,
+  onProgress?: (message: string, progress?: number) => void
+): Promise<CollectionInstallReport> {
   const instance = getInstance(instanceId)
-  const state = await readState(instance)
-  let updated = 0
-  for (const mod of state.mods.filter((item) => item.projectId && item.source === 'modrinth')) {
-    if (await updateMod(instanceId, mod.projectId!, onProgress)) updated++
+  const id = collectionId(input)
+  // Collections are only served by version 3 of the API.
+  const collection = await modrinthJson<ModrinthCollection>(`${MODRINTH_API.replace(/\/v2$/, '/v3')}/collection/${encodeURIComponent(id)}`)
+  const projects = collection.projects.length
+    ? await modrinthCachedJson<ModrinthProject[]>(`/projects?ids=${encodeURIComponent(JSON.stringify(collection.projects))}`, 10 * 60_000)
+    : []
+  const report: CollectionInstallReport = { collectionId: id, name: collection.name, results: [] }
+  for (const [position, item] of projects.entries()) {
+    onProgress?.(`Installing ${item.title} from ${collection.name}`, position / projects.length)
+    const skip = (detail: string) => report.results.push({ projectId: item.id, title: item.title, status: 'skipped', detail })
+    if (item.project_type === 'modpack') { skip('Modpacks are installed as their own instance.'); continue }
+    if (item.project_type === 'mod' && instance.loader === 'vanilla') { skip('Vanilla instances do not load mods.'); continue }
+    try {
+      if (!(await versionsFor(item.id, instance, item.project_type))[0]) { skip(`No version for Minecraft ${instance.minecraftVersion}.`); continue }
+      if (item.project_type === 'mod') await installMod(instanceId, item.id, onProgress)
+      else {
+        const result = await installPack(instanceId, item.id, item.project_type, onProgress, true)
+        if (result.status === 'needs-shader-loader') { skip(result.requirement.reason); continue }
+      }
+      report.results.push({ projectId: item.id, title: item.title, status: 'installed' })
+    } catch (error) {
+      report.results.push({ projectId: item.id, title: item.title, status: 'failed', detail: error instanceof Error ? error.message : String(error) })
+    }
   }
-  return updated
+  onProgress?.(`Installed ${collection.name}`, 1)
+  return report
+}
+
+// Lists the Modrinth mods, and the modpack the instance came from, that have
+// a newer version than the one installed. Nothing is downloaded.
+export async function checkInstanceUpdates(instanceId: string): Promise<InstanceUpdateSummary> {
+  const instance = getInstance(instanceId)
+  const summary: InstanceUpdateSummary = { instanceId, name: instance.name, mods: [] }
+  for (const mod of (await readState(instance)).mods) {
+    if (!mod.projectId || mod.source !== 'modrinth') continue
+    const latest = (await versionsFor(mod.projectId, instance, 'mod'))[0]
+    if (latest && latest.id !== mod.versionId) {
+      summary.mods.push({ projectId: mod.projectId, title: mod.title, currentVersion: mod.versionNumber, latestVersion: latest.version_number })
+    }
+  }
+  if (instance.modpack) {
+    const { projectId, versionId, title } = instance.modpack
+    const latest = sortVersions(await modrinthCachedJson<ModrinthVersion[]>(`/project/${encodeURIComponent(projectId)}/version`, 60_000))[0]
+    if (latest && latest.id !== versionId) {
+      summary.modpack = { projectId, title, currentVersionId: versionId, latestVersionId: latest.id, latestVersion: latest.version_number }
+    }
+  }
+  return summary
+}
+
+// Updates the instance's Modrinth mods, or only the listed projects, one at a
+// time so a failure is reported for that mod and the rest carry on. The jars
+// being replaced are kept so the whole update can be rolled back.
+export async function updateInstanceMods(
+  instanceId: string,
+  projectIds?: string[],
+  onProgress?: (message: string, progress?: number) => void
+): Promise<ModUpdateReport> {
+  return withInstallLock(`mod-updates:${instanceId}`, async () => {
+    const instance = getInstance(instanceId)
+    const wanted = projectIds ? new Set(projectIds) : undefined
+    const candidates = (await readState(instance)).mods.filter((mod) => mod.projectId && mod.source === 'modrinth' && (!wanted || wanted.has(mod.projectId)))
+    const rollback = rollbackDirectory(instance)
+    const results: ModUpdateResult[] = []
+    const replaced: TrackedMod[] = []
+    let rollbackCleared = false
+    for (const [position, mod] of candidates.entries()) {
+      const result: ModUpdateResult = { projectId: mod.projectId!, title: mod.title, status: 'current', fromVersion: mod.versionNumber }
+      results.push(result)
+      onProgress?.(`Checking ${mod.title}`, position / candidates.length)
+      try {
+        const latest = (await versionsFor(mod.projectId!, instance, 'mod'))[0]
+        if (!latest || latest.id === mod.versionId) continue
+        if (!rollbackCleared) {
+          await fs.rm(rollback, { recursive: true, force: true })
+          await fs.mkdir(rollback, { recursive: true })
+          rollbackCleared = true
+        }
+        const base = path.join(modsDirectory(instance.slug), mod.fileName)
+        await fs.copyFile(mod.enabled ? base : `${base}.disabled`, path.join(rollback, mod.fileName)).catch(() => undefined)
+        const installed = await installModVersion(instance, latest, await project(mod.projectId!), new Set(), onProgress)
+        if (!mod.enabled) await setModEnabled(instanceId, installed.fileName, false)
+        replaced.push(mod)
+        result.status = 'updated'
+        result.toVersion = installed.versionNumber
+      } catch (error) {
+        result.status = 'failed'
+        result.error = error instanceof Error ? error.message : String(error)
+      }
+    }
+    if (rollbackCleared) {
+      const file: ModRollbackFile = { createdAt: new Date().toISOString(), mods: replaced }
+      await fs.writeFile(path.join(rollback, 'rollback.json'), JSON.stringify(file, null, 2), 'utf8')
+    }
+    onProgress?.('Mods checked', 1)
+    return { instanceId, results, rollbackAvailable: replaced.length > 0, finishedAt: new Date().toISOString() }
+  })
+}
+
+// Puts back the jars the last update replaced, removing the versions it
+// installed. Returns how many mods were restored.
+export async function rollbackModUpdates(instanceId: string): Promise<number> {
+  return withInstallLock(`mod-updates:${instanceId}`, async () => {
+    const instance = getInstance(instanceId)
+    const rollback = await readRollback(instance)
+    if (!rollback?.mods.length) throw new Error('There is no mod update to roll back for this instance.')
+    const directory = modsDirectory(instance.slug)
+    const state = await readState(instance)
+    let restored = 0
+    for (const previous of rollback.mods) {
+      const kept = path.join(rollbackDirectory(instance), previous.fileName)
+      if (!await fs.access(kept).then(() => true, () => false)) continue
+      const current = state.mods.find((mod) => mod.projectId === previous.projectId)
+      if (current && current.fileName !== previous.fileName) {
+        await Promise.all([
+          fs.rm(path.join(directory, current.fileName), { force: true }),
+          fs.rm(path.join(directory, `${current.fileName}.disabled`), { force: true })
+        ])
+      }
+      const target = path.join(directory, previous.fileName)
+      await fs.rm(previous.enabled ? `${target}.disabled` : target, { force: true })
+      await fs.copyFile(kept, previous.enabled ? target : `${target}.disabled`)
+      state.mods = [...state.mods.filter((mod) => mod.projectId !== previous.projectId), previous]
+      restored++
+    }
+    await writeState(instance, state)
+    await fs.rm(rollbackDirectory(instance), { recursive: true, force: true })
+    return restored
+  })
 }
 
 function safeDestination(root: string, relative: string): string {
@@ -535,49 +1110,493 @@ function loaderFromDependencies(dependencies: Record<string, string>): { loader:
   return { loader: 'vanilla' }
 }
 
+// Hosts the Modrinth pack format allows file downloads from.
+const MODPACK_DOWNLOAD_HOSTS = new Set(['cdn.modrinth.com', 'github.com', 'raw.githubusercontent.com', 'gitlab.com'])
+
+function readModpackIndex(zip: AdmZip): ModpackIndex {
+  const indexEntry = zip.getEntry('modrinth.index.json')
+  if (!indexEntry) throw new Error('This file is not a valid Modrinth modpack.')
+  let index: ModpackIndex
+  try {
+    index = JSON.parse(indexEntry.getData().toString('utf8')) as ModpackIndex
+  } catch {
+    throw new Error('The modpack index could not be read.')
+  }
+  if (index.game !== 'minecraft' || index.formatVersion !== 1 || !Array.isArray(index.files)) throw new Error('This modpack uses a format MegaClient does not support.')
+  if (!index.dependencies?.minecraft) throw new Error('The modpack does not declare a Minecraft version.')
+  return index
+}
+
+function modpackDownloadUrl(item: ModpackIndex['files'][number]): string | undefined {
+  return item.downloads.find((url) => {
+    try {
+      const parsed = new URL(url)
+      return parsed.protocol === 'https:' && MODPACK_DOWNLOAD_HOSTS.has(parsed.hostname)
+    } catch {
+      return false
+    }
+  })
+}
+
+// Installs a .mrpack into an instance: sets its Minecraft version and loader
+// from the index, downloads every client file with its published hashes
+// checked, then applies the overrides and the client-only overrides on top.
+export async function applyModpackArchive(
+  instanceId: string,
+  archive: string,
+  onProgress?: (message: string, progress?: number) => void,
+  patch: Partial<Pick<LauncherInstance, 'name' | 'modpack'>> = {}
+): Promise<LauncherInstance> {
+  const zip = new AdmZip(archive)
+  const index = readModpackIndex(zip)
+  const loader = loaderFromDependencies(index.dependencies)
+  const instance = await updateInstance(instanceId, {
+    ...patch, minecraftVersion: index.dependencies.minecraft, loader: loader.loader, loaderVersion: loader.loaderVersion
+  })
+  const root = instanceDirectory(instance.slug)
+  await fs.mkdir(root, { recursive: true })
+  const installable = index.files.filter((item) => item.env?.client !== 'unsupported')
+  for (const item of installable) {
+    // Every file must be verifiable and come from a host the format allows.
+    if (!item.hashes?.sha1 && !item.hashes?.sha512) throw new Error(`${item.path} has no published hash.`)
+    if (!modpackDownloadUrl(item)) throw new Error(`${item.path} is not downloaded from a host Modrinth modpacks may use.`)
+    safeDestination(root, item.path)
+  }
+  let completed = 0
+  await runWithConcurrency(installable, downloadConcurrency(), async (item) => {
+    const destination = safeDestination(root, item.path)
+    await downloadObject(modpackDownloadUrl(item)!, destination, item.hashes.sha1, undefined, { size: item.fileSize })
+    if (item.hashes.sha512 && (await hashFile(destination, 'sha512')).toLowerCase() !== item.hashes.sha512.toLowerCase()) {
+      await fs.rm(destination, { force: true })
+      throw new Error(`Integrity check failed for ${item.path}.`)
+    }
+    completed++
+    onProgress?.(`Installing ${path.basename(item.path)}`, completed / installable.length)
+  })
+  await extractOverrides(zip, 'overrides', root)
+  await extractOverrides(zip, 'client-overrides', root)
+  return instance
+}
+
+async function downloadModpackVersion(
+  instance: LauncherInstance,
+  version: ModrinthVersion,
+  title: string,
+  onProgress?: (message: string, progress?: number) => void
+): Promise<string> {
+  const file = bestFile(version, '.mrpack')
+  const temp = path.join(metadataDirectory(instance.slug), `${version.id}.mrpack`)
+  await fs.mkdir(metadataDirectory(instance.slug), { recursive: true })
+  await download(file.url, temp, (downloaded, total) => onProgress?.(`Downloading ${title}`, total ? downloaded / total : undefined))
+  return temp
+}
+
 export async function installModpack(instanceId: string, projectId: string, onProgress?: (message: string, progress?: number) => void): Promise<LauncherInstance> {
   return withInstallLock(`modpack:${instanceId}:${projectId}`, async () => {
-    let instance = getInstance(instanceId)
+    const instance = getInstance(instanceId)
     const info = await project(projectId)
-    const versions = sortVersions(await fetchJson<ModrinthVersion[]>(`https://api.modrinth.com/v2/project/${encodeURIComponent(projectId)}/version`))
+    const versions = sortVersions(await modrinthCachedJson<ModrinthVersion[]>(`/project/${encodeURIComponent(projectId)}/version`, 60_000))
     const version = versions[0]
     if (!version) throw new Error('No modpack version is available.')
-    const file = bestFile(version, '.mrpack')
-    const temp = path.join(metadataDirectory(instance.slug), `${version.id}.mrpack`)
-    await fs.mkdir(metadataDirectory(instance.slug), { recursive: true })
-    await downloadFile(file.url, temp, (downloaded, total) => onProgress?.(`Downloading ${info.title}`, total ? downloaded / total : undefined))
+    const temp = await downloadModpackVersion(instance, version, info.title, onProgress)
     try {
-      const zip = new AdmZip(temp)
-      const indexEntry = zip.getEntry('modrinth.index.json')
-      if (!indexEntry) throw new Error('This file is not a valid Modrinth modpack.')
-      const index = JSON.parse(indexEntry.getData().toString('utf8')) as ModpackIndex
-      if (!index.dependencies.minecraft) throw new Error('The modpack does not declare a Minecraft version.')
-      const loader = loaderFromDependencies(index.dependencies)
-      instance = await updateInstance(instanceId, {
-        name: info.title, minecraftVersion: index.dependencies.minecraft, loader: loader.loader,
-        loaderVersion: loader.loaderVersion, modpack: { projectId, versionId: version.id, title: info.title }
+      const installed = await applyModpackArchive(instanceId, temp, onProgress, {
+        name: info.title, modpack: { projectId, versionId: version.id, title: info.title }
       })
-      await fs.mkdir(instanceDirectory(instance.slug), { recursive: true })
+      onProgress?.(`Installed ${info.title}`, 1)
+      return installed
+    } finally {
+      await fs.rm(temp, { force: true }).catch(() => undefined)
+    }
+  })
+}
+
+// Creates a new instance from a .mrpack file on disk. If the install fails
+// the half-made instance is removed again.
+export async function importModpackFile(file: string, onProgress?: (message: string, progress?: number) => void): Promise<LauncherInstance> {
+  const index = readModpackIndex(new AdmZip(file))
+  const instance = await createInstance({
+    name: index.name?.trim() || path.basename(file, path.extname(file)),
+    minecraftVersion: index.dependencies.minecraft,
+    ...loaderFromDependencies(index.dependencies)
+  })
+  // A file downloaded from Modrinth is recognised by its hash, so the new
+  // instance can be upgraded to later versions of the pack.
+  const sha1 = (await hashFile(file, 'sha1')).toLowerCase()
+  const published = (await versionsByHash([sha1]).catch(() => ({} as Record<string, ModrinthVersion>)))[sha1]
+  const info = published ? await project(published.project_id).catch(() => null) : null
+  try {
+    const installed = await applyModpackArchive(instance.id, file, onProgress, info && published
+      ? { modpack: { projectId: info.id, versionId: published.id, title: info.title } }
+      : {})
+    onProgress?.(`Imported ${installed.name}`, 1)
+    return installed
+  } catch (error) {
+    await deleteInstance(instance.id).catch(() => undefined)
+    throw error
+  }
+}
+
+function sha1Of(data: Buffer): string {
+  return createHash('sha1').update(data).digest('hex')
+}
+
+// The files a pack version's overrides put into the instance, by path, with
+// client-only overrides taking precedence as they do when installing.
+function overrideHashes(zip: AdmZip | null): Map<string, { sha1: string; entry: AdmZip.IZipEntry }> {
+  const files = new Map<string, { sha1: string; entry: AdmZip.IZipEntry }>()
+  for (const prefix of ['overrides', 'client-overrides']) {
+    for (const entry of zip?.getEntries() ?? []) {
+      if (entry.isDirectory || !entry.entryName.startsWith(`${prefix}/`)) continue
+      const relative = entry.entryName.slice(prefix.length + 1)
+      if (relative) files.set(relative, { sha1: sha1Of(entry.getData()), entry })
+    }
+  }
+  return files
+}
+
+async function diskSha1(file: string): Promise<string | null> {
+  return (await hashFile(file, 'sha1').catch(() => null))?.toLowerCase() ?? null
+}
+
+// Moves an instance created from a Modrinth pack to the pack's newest version.
+// The old and new indexes are compared so only files the pack changed are
+// downloaded, and files it dropped are removed. Everything the pack never
+// shipped, such as mods the player added, is left alone, and a config the
+// player edited is kept rather than overwritten. New files are downloaded
+// before anything in the instance is touched.
+export async function updateModpack(instanceId: string, onProgress?: (message: string, progress?: number) => void): Promise<ModpackUpdateReport> {
+  const current = getInstance(instanceId)
+  if (!current.modpack) throw new Error(`${current.name} was not installed from a Modrinth modpack.`)
+  const pack = current.modpack
+  return withInstallLock(`modpack:${instanceId}:${pack.projectId}`, async () => {
+    const latest = sortVersions(await modrinthCachedJson<ModrinthVersion[]>(`/project/${encodeURIComponent(pack.projectId)}/version`, 60_000))[0]
+    if (!latest) throw new Error('No modpack version is available.')
+    const previous = await getVersion(pack.versionId).catch(() => null)
+    const report: ModpackUpdateReport = {
+      instanceId, title: pack.title, fromVersion: previous?.version_number, toVersion: latest.version_number,
+      added: 0, updated: 0, removed: 0, keptChanges: []
+    }
+    if (latest.id === pack.versionId) return report
+
+    const newArchive = await downloadModpackVersion(current, latest, pack.title, onProgress)
+    // The installed version's archive says which files came from the pack. If
+    // it is no longer published, nothing is removed.
+    const oldArchive = previous ? await downloadModpackVersion(current, previous, `${pack.title} ${previous.version_number}`, onProgress).catch(() => null) : null
+    const staging = path.join(metadataDirectory(current.slug), 'modpack-update')
+    try {
+      const newZip = new AdmZip(newArchive)
+      const oldZip = oldArchive ? new AdmZip(oldArchive) : null
+      const newIndex = readModpackIndex(newZip)
+      const oldIndex = oldZip ? readModpackIndex(oldZip) : null
+      const root = instanceDirectory(current.slug)
+      const client = (item: ModpackIndex['files'][number]) => item.env?.client !== 'unsupported'
+      const oldFiles = new Map((oldIndex?.files ?? []).filter(client).map((item) => [item.path, item]))
+      const newFiles = newIndex.files.filter(client)
+      for (const item of newFiles) {
+        if (!item.hashes?.sha1 && !item.hashes?.sha512) throw new Error(`${item.path} has no published hash.`)
+        if (!modpackDownloadUrl(item)) throw new Error(`${item.path} is not downloaded from a host Modrinth modpacks may use.`)
+        safeDestination(root, item.path)
+      }
+
+      const changed: typeof newFiles = []
+      for (const item of newFiles) {
+        const before = oldFiles.get(item.path)
+        const onDisk = await fs.access(safeDestination(root, item.path)).then(() => true, () => false)
+        if (!onDisk || !before || before.hashes.sha1 !== item.hashes.sha1 || before.hashes.sha512 !== item.hashes.sha512) changed.push(item)
+      }
+      await fs.rm(staging, { recursive: true, force: true })
+      await fs.mkdir(staging, { recursive: true })
       let completed = 0
-      const installable = index.files.filter((item) => item.env?.client !== 'unsupported')
-      for (const item of installable) {
-        const url = item.downloads[0]
-        if (!url) continue
-        const destination = safeDestination(instanceDirectory(instance.slug), item.path)
-        onProgress?.(`Installing ${path.basename(item.path)}`, installable.length ? completed / installable.length : undefined)
-        await downloadFile(url, destination)
-        if (item.hashes.sha512 && await hashFile(destination, 'sha512') !== item.hashes.sha512) {
-          await fs.rm(destination, { force: true })
+      await runWithConcurrency(changed, downloadConcurrency(), async (item) => {
+        const destination = path.join(staging, String(changed.indexOf(item)))
+        await downloadObject(modpackDownloadUrl(item)!, destination, item.hashes.sha1, undefined, { size: item.fileSize })
+        if (item.hashes.sha512 && (await hashFile(destination, 'sha512')).toLowerCase() !== item.hashes.sha512.toLowerCase()) {
           throw new Error(`Integrity check failed for ${item.path}.`)
         }
         completed++
+        onProgress?.(`Downloading ${path.basename(item.path)}`, completed / changed.length)
+      })
+
+      onProgress?.(`Updating ${pack.title}`)
+      const newPaths = new Set(newFiles.map((item) => item.path))
+      for (const [relative, item] of oldFiles) {
+        if (newPaths.has(relative)) continue
+        const destination = safeDestination(root, relative)
+        // A pack mod the player disabled is removed in its disabled form too.
+        for (const candidate of [destination, `${destination}.disabled`]) {
+          const sha1 = await diskSha1(candidate)
+          if (!sha1) continue
+          if (!item.hashes.sha1 || sha1 === item.hashes.sha1.toLowerCase()) {
+            await fs.rm(candidate, { force: true })
+            report.removed++
+          } else report.keptChanges.push(relative)
+        }
       }
-      await extractOverrides(zip, 'overrides', instanceDirectory(instance.slug))
-      await extractOverrides(zip, 'client-overrides', instanceDirectory(instance.slug))
-      onProgress?.(`Installed ${info.title}`, 1)
-      return instance
+      for (const [position, item] of changed.entries()) {
+        const destination = safeDestination(root, item.path)
+        const existed = await fs.access(destination).then(() => true, () => false)
+        await fs.mkdir(path.dirname(destination), { recursive: true })
+        await fs.rm(destination, { force: true })
+        await fs.rename(path.join(staging, String(position)), destination)
+        if (existed || oldFiles.has(item.path)) report.updated++
+        else report.added++
+      }
+
+      const oldOverrides = overrideHashes(oldZip)
+      const newOverrides = overrideHashes(newZip)
+      for (const [relative, item] of newOverrides) {
+        const destination = safeDestination(root, relative)
+        const sha1 = await diskSha1(destination)
+        if (sha1 === item.sha1) continue
+        // Only files still as the previous version shipped them are replaced.
+        if (sha1 && sha1 !== oldOverrides.get(relative)?.sha1) {
+          report.keptChanges.push(relative)
+          continue
+        }
+        await fs.mkdir(path.dirname(destination), { recursive: true })
+        await fs.writeFile(destination, item.entry.getData())
+        if (sha1) report.updated++
+        else report.added++
+      }
+      for (const [relative, item] of oldOverrides) {
+        if (newOverrides.has(relative)) continue
+        const destination = safeDestination(root, relative)
+        if (await diskSha1(destination) === item.sha1) {
+          await fs.rm(destination, { force: true })
+          report.removed++
+        }
+      }
+
+      const loader = loaderFromDependencies(newIndex.dependencies)
+      await updateInstance(instanceId, {
+        minecraftVersion: newIndex.dependencies.minecraft,
+        loader: loader.loader,
+        loaderVersion: loader.loaderVersion,
+        modpack: { ...pack, versionId: latest.id }
+      })
+      onProgress?.(`Updated ${pack.title} to ${latest.version_number}`, 1)
+      return report
     } finally {
-      await fs.rm(temp, { force: true }).catch(() => undefined)
+      await Promise.all([
+        fs.rm(newArchive, { force: true }),
+        oldArchive ? fs.rm(oldArchive, { force: true }) : undefined,
+        fs.rm(staging, { recursive: true, force: true })
+      ]).catch(() => undefined)
     }
   })
 }
+
+// Left out of exported modpacks: worlds, their backups, logs and screenshots
+// belong to this player, and game files are downloaded again by whoever
+// installs the pack.
+const EXPORT_EXCLUDED = new Set([
+  'saves', 'backups', 'logs', 'crash-reports', 'screenshots', 'debug', 'libraries', 'assets', 'versions', 'natives', 'resources',
+  '.megaclient', '.fabric', '.cache', 'usercache.json', 'usernamecache.json', 'realms_persistence.json', 'command_history.txt'
+])
+// Files in these folders are looked up on Modrinth and, when found, listed by
+// download link rather than copied into the pack.
+const EXPORT_LINKED_FOLDERS = new Set(['mods', 'resourcepacks', 'shaderpacks'])
+
+// Shared folders such as resource packs are links, and are followed so the
+// export holds what the instance actually uses.
+async function exportableFiles(root: string, relative = ''): Promise<string[]> {
+  const files: string[] = []
+  for (const name of await fs.readdir(path.join(root, relative)).catch(() => [] as string[])) {
+    if (!relative && (EXPORT_EXCLUDED.has(name) || /^hs_err_pid\d+\.log$/i.test(name))) continue
+    if (/\.part$|\.tmp$/i.test(name)) continue
+    const child = relative ? `${relative}/${name}` : name
+    const stat = await fs.stat(path.join(root, child)).catch(() => null)
+    if (stat?.isDirectory()) files.push(...await exportableFiles(root, child))
+    else if (stat?.isFile()) files.push(child)
+  }
+  return files
+}
+
+async function versionsByHash(hashes: string[]): Promise<Record<string, ModrinthVersion>> {
+  const found: Record<string, ModrinthVersion> = {}
+  for (let start = 0; start < hashes.length; start += 500) {
+    Object.assign(found, await modrinthJson<Record<string, ModrinthVersion>>('/version_files', {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ hashes: hashes.slice(start, start + 500), algorithm: 'sha1' })
+    }))
+  }
+  return found
+}
+
+function modpackDependencies(instance: LauncherInstance): Record<string, string> {
+  const dependencies: Record<string, string> = { minecraft: instance.minecraftVersion }
+  if (instance.loader === 'vanilla') return dependencies
+  if (!instance.loaderVersion) throw new Error(`Launch ${instance.name} once so its ${instance.loader} version is known, then export it again.`)
+  if (instance.loader === 'fabric') dependencies['fabric-loader'] = instance.loaderVersion
+  else if (instance.loader === 'neoforge') dependencies.neoforge = instance.loaderVersion
+  // Instances store Forge as <minecraft>-<forge>; the pack format wants the
+  // Forge version alone.
+  else dependencies.forge = instance.loaderVersion.startsWith(`${instance.minecraftVersion}-`)
+    ? instance.loaderVersion.slice(instance.minecraftVersion.length + 1)
+    : instance.loaderVersion
+  return dependencies
+}
+
+// Writes an instance as a .mrpack others can import. Mods and packs that are
+// published on Modrinth are matched by SHA-1 and listed with their download
+// link; everything else, such as configs and private jars, goes into the
+// overrides folder as it is.
+export async function exportModpack(
+  instanceId: string,
+  destination: string,
+  onProgress?: (message: string, progress?: number) => void
+): Promise<{ file: string; linked: number; overrides: number }> {
+  const instance = getInstance(instanceId)
+  if (instance.customClient) throw new Error('The MegaClient client instance cannot be exported as a modpack.')
+  const dependencies = modpackDependencies(instance)
+  const root = instance.linkedDirectory ?? instanceDirectory(instance.slug)
+  onProgress?.('Collecting instance files')
+  const files = await exportableFiles(root)
+  const candidates = files.filter((file) => {
+    const [folder, name, ...rest] = file.split('/')
+    return EXPORT_LINKED_FOLDERS.has(folder!) && name && !rest.length && /\.(jar|zip)$/i.test(name)
+  })
+  const hashes = new Map<string, string>()
+  for (const file of candidates) hashes.set(file, (await hashFile(path.join(root, file), 'sha1')).toLowerCase())
+  onProgress?.('Looking up mods on Modrinth')
+  const versions = hashes.size ? await versionsByHash([...new Set(hashes.values())]) : {}
+
+  const index: ModpackIndex = {
+    formatVersion: 1,
+    game: 'minecraft',
+    versionId: new Date().toISOString().slice(0, 10),
+    name: instance.name,
+    files: [],
+    dependencies
+  }
+  const zip = new AdmZip()
+  let overrides = 0
+  for (const [position, file] of files.entries()) {
+    onProgress?.(`Adding ${path.basename(file)}`, position / files.length)
+    const sha1 = hashes.get(file)
+    const remote = sha1 ? versions[sha1]?.files.find((item) => item.hashes.sha1?.toLowerCase() === sha1) : undefined
+    if (sha1 && remote) {
+      index.files.push({
+        path: file,
+        hashes: { sha1, sha512: remote.hashes.sha512 ?? await hashFile(path.join(root, file), 'sha512') },
+        downloads: [remote.url],
+        fileSize: remote.size
+      })
+      continue
+    }
+    zip.addLocalFile(path.join(root, file), path.posix.dirname(`overrides/${file}`))
+    overrides++
+  }
+  zip.addFile('modrinth.index.json', Buffer.from(JSON.stringify(index, null, 2), 'utf8'))
+  const temp = `${destination}.${process.pid}.${Date.now()}.tmp`
+  try {
+    await zip.writeZipPromise(temp)
+    await fs.rm(destination, { force: true })
+    await fs.rename(temp, destination)
+  } finally {
+    await fs.rm(temp, { force: true }).catch(() => undefined)
+  }
+  onProgress?.(`Exported ${instance.name}`, 1)
+  return { file: destination, linked: index.files.length, overrides }
+}
+
+// Writes the instance's mods as a JSON list. Mods the launcher did not install
+// are matched on Modrinth by hash so they can be reinstalled too.
+export async function exportModList(instanceId: string, destination: string): Promise<{ file: string; mods: number; linked: number }> {
+  const instance = getInstance(instanceId)
+  const directory = modsDirectory(instance.slug)
+  const mods = (await listMods(instanceId)).filter((mod) => mod.source !== 'client')
+  const hashes = new Map<string, string>()
+  for (const mod of mods) hashes.set(mod.fileName, await cachedSha1(path.join(directory, mod.enabled ? mod.fileName : `${mod.fileName}.disabled`)))
+  const unknown = mods.filter((mod) => !mod.projectId).map((mod) => hashes.get(mod.fileName)!)
+  const versions = unknown.length ? await versionsByHash(unknown).catch(() => ({} as Record<string, ModrinthVersion>)) : {}
+  const manifest: ModListManifest = {
+    format: 'megaclient-mod-list',
+    formatVersion: 1,
+    exportedAt: new Date().toISOString(),
+    instance: { name: instance.name, minecraftVersion: instance.minecraftVersion, loader: instance.loader, loaderVersion: instance.loaderVersion || undefined },
+    mods: mods.map((mod) => {
+      const sha1 = hashes.get(mod.fileName)!
+      const version = mod.projectId ? undefined : versions[sha1]
+      return {
+        title: mod.title,
+        fileName: mod.fileName,
+        enabled: mod.enabled,
+        projectId: mod.projectId ?? version?.project_id,
+        versionId: mod.versionId ?? version?.id,
+        versionNumber: mod.versionNumber ?? version?.version_number,
+        hashes: { sha1, sha512: version?.files.find((file) => file.hashes.sha1?.toLowerCase() === sha1)?.hashes.sha512 }
+      }
+    })
+  }
+  await fs.writeFile(destination, JSON.stringify(manifest, null, 2), 'utf8')
+  return { file: destination, mods: manifest.mods.length, linked: manifest.mods.filter((mod) => mod.projectId).length }
+}
+
+// Installs the mods in a list into an instance. The exact listed version is
+// used when it runs on the instance's game version and loader, otherwise the
+// newest one that does. Mods already present, by project or by identical
+// file, are left alone, and mods that were disabled are installed disabled.
+export async function importModList(
+  instanceId: string,
+  file: string,
+  onProgress?: (message: string, progress?: number) => void
+): Promise<ModListImportReport> {
+  const instance = getInstance(instanceId)
+  if (instance.loader === 'vanilla') throw new Error('Use a Fabric, Forge, or NeoForge instance to install mods.')
+  let manifest: ModListManifest
+  try {
+    manifest = JSON.parse(await fs.readFile(file, 'utf8')) as ModListManifest
+  } catch {
+    throw new Error('The file is not a MegaClient mod list.')
+  }
+  if (manifest?.format !== 'megaclient-mod-list' || !Array.isArray(manifest.mods)) throw new Error('The file is not a MegaClient mod list.')
+  if (manifest.formatVersion > 1) throw new Error('This mod list was made by a newer version of MegaClient.')
+
+  const directory = modsDirectory(instance.slug)
+  const present = new Set<string>()
+  for (const name of await fs.readdir(directory).catch(() => [] as string[])) {
+    const sha1 = /\.jar(\.disabled)?$/i.test(name) ? await cachedSha1(path.join(directory, name)).catch(() => null) : null
+    if (sha1) present.add(sha1)
+  }
+  const trackedProjects = new Set((await readState(instance)).mods.map((mod) => mod.projectId).filter(Boolean))
+  const loaders = compatibleLoaders(instance)
+  const report: ModListImportReport = { name: manifest.instance?.name ?? path.basename(file), results: [] }
+  const installed: string[] = []
+  for (const [position, entry] of manifest.mods.entries()) {
+    const title = entry.title || entry.fileName
+    onProgress?.(`Installing ${title}`, position / manifest.mods.length)
+    if ((entry.hashes?.sha1 && present.has(entry.hashes.sha1.toLowerCase())) || (entry.projectId && trackedProjects.has(entry.projectId))) {
+      report.results.push({ title, status: 'present' })
+      continue
+    }
+    if (!entry.projectId) {
+      report.results.push({ title, status: 'skipped', detail: 'Not published on Modrinth. Copy the file across by hand.' })
+      continue
+    }
+    try {
+      const exact = entry.versionId ? await getVersion(entry.versionId).catch(() => null) : null
+      let mod: TrackedMod
+      if (exact && exact.game_versions.includes(instance.minecraftVersion) && exact.loaders.some((loader) => loaders.includes(loader))) {
+        const info = await project(entry.projectId)
+        mod = await withInstallLock(`mod:${instanceId}:${entry.projectId}`, () => installModVersion(instance, exact, info, new Set(), onProgress))
+        installed.push(mod.fileName)
+      } else {
+        mod = await installMod(instanceId, entry.projectId, onProgress, true)
+      }
+      if (!entry.enabled && mod.enabled) await setModEnabled(instanceId, mod.fileName, false)
+      report.results.push({
+        title,
+        status: 'installed',
+        detail: entry.versionNumber && mod.versionNumber !== entry.versionNumber ? `Installed ${mod.versionNumber} instead of ${entry.versionNumber}.` : undefined
+      })
+    } catch (error) {
+      report.results.push({ title, status: 'failed', detail: error instanceof Error ? error.message : String(error) })
+    }
+  }
+  await ensureFabricApiFor(instance, installed, onProgress).catch(() => false)
+  onProgress?.(`Installed mods from ${report.name}`, 1)
+  return report
+}
@@ -0,0 +1,131 @@
+import type { DiscoverContentType, ModrinthAccount, ModrinthLibrary, ModrinthLibraryProject } from '../types'
+import { modrinthJson, setModrinthAuthorization } from './modrinthClient'
+import { logger } from './logger'
+import { store } from './store'
+
+interface ModrinthUser {
+  id: string
+  username: string
+  avatar_url?: string | null
+}
+
+interface ModrinthUserProject {
+  id: string
+  slug: string
+  title: string
+  description: string
+  project_type: DiscoverContentType
+  icon_url?: string | null
+  downloads: number
+  categories?: string[]
+  game_versions?: string[]
+}
+
+let user: ModrinthUser | null = null
+
+function publicAccount(value: ModrinthUser): ModrinthAccount {
+  return { id: value.id, username: value.username, avatarUrl: value.avatar_url ?? undefined }
+}
+
+function libraryProject(value: ModrinthUserProject): ModrinthLibraryProject {
+  return {
+    project_id: value.id,
+    project_type: value.project_type,
+    slug: value.slug,
+    title: value.title,
+    description: value.description,
+    author: '',
+    categories: value.categories ?? [],
+    versions: value.game_versions ?? [],
+    downloads: value.downloads,
+    icon_url: value.icon_url ?? undefined
+  }
+}
+
+function tokenHeaders(token: string): RequestInit {
+  return { headers: { Authorization: token } }
+}
+
+function rejectedToken(error: unknown): boolean {
+  return /\((?:401|403)\)/.test(error instanceof Error ? error.message : '')
+}
+
+// Loads a saved token so every Modrinth request made from then on is sent
+// under the player's own rate limit. The account is looked up in the
+// background so a revoked token stops being sent.
+export async function restoreModrinthAccount(): Promise<void> {
+  const token = await store.loadModrinthToken()
+  setModrinthAuthorization(token)
+  if (token) void modrinthAccount()
+}
+
+export async function modrinthAccount(): Promise<ModrinthAccount | null> {
+  if (user) return publicAccount(user)
+  const token = await store.loadModrinthToken()
+  if (!token) return null
+  try {
+    user = await modrinthJson<ModrinthUser>('/user', tokenHeaders(token))
+    return publicAccount(user)
+  } catch (error) {
+    // The token is kept when Modrinth cannot be reached. A token it refuses
+    // stops being sent, since it would make public requests fail too.
+    if (rejectedToken(error)) setModrinthAuthorization(null)
+    logger.warn('The linked Modrinth account could not be loaded.', { error })
+    return null
+  }
+}
+
+// Tokens are checked against Modrinth before they are saved, so a mistyped or
+// revoked token is reported here instead of failing every later request.
+export async function linkModrinthAccount(token: string): Promise<ModrinthAccount> {
+  const trimmed = token.trim()
+  if (!trimmed) throw new Error('Paste a Modrinth personal access token first.')
+  let value: ModrinthUser
+  try {
+    value = await modrinthJson<ModrinthUser>('/user', tokenHeaders(trimmed))
+  } catch (error) {
+    if (rejectedToken(error)) throw new Error('Modrinth did not accept that token. Check that it has not expired and includes the read user scope.')
+    throw error
+  }
+  await store.saveModrinthToken(trimmed)
+  setModrinthAuthorization(trimmed)
+  user = value
+  return publicAccount(value)
+}
+
+export async function unlinkModrinthAccount(): Promise<void> {
+  await store.clearModrinthToken()
+  setModrinthAuthorization(null)
+  user = null
+}
+
+async function requireUser(): Promise<ModrinthUser> {
+  await modrinthAccount()
+  if (!user) throw new Error('Link a Modrinth account in Settings first.')
+  return user
+}
+
+export async function modrinthLibrary(): Promise<ModrinthLibrary> {
+  const account = await requireUser()
+  const id = encodeURIComponent(account.id)
+  const [followed, owned] = await Promise.all([
+    modrinthJson<ModrinthUserProject[]>(`/user/${id}/follows`),
+    modrinthJson<ModrinthUserProject[]>(`/user/${id}/projects`)
+  ])
+  return {
+    followed: followed.map(libraryProject),
+    owned: owned.map((project) => ({ ...libraryProject(project), author: account.username }))
+  }
+}
+
+export async function setProjectFollowed(projectId: string, followed: boolean): Promise<void> {
+  await requireUser()
+  const url = `/project/${encodeURIComponent(projectId)}/follow`
+  // Modrinth answers with an empty body, which does not parse as JSON but is
+  // still a success.
+  try {
+    await modrinthJson<unknown>(url, { method: followed ? 'POST' : 'DELETE' })
+  } catch (error) {
+    if (!(error instanceof SyntaxError)) throw error
+  }
+}
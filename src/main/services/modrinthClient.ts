@@ -0,0 +1,122 @@
+import { sendRequest, type RequestLimiter } from './net'
+import { fetchCachedJson } from './httpCache'
+import { logger } from './logger'
+
+export const MODRINTH_API = 'https://api.modrinth.com/v2'
+
+// Modrinth allows 300 requests a minute from one address. A few are left
+// unused so the website and other launchers on the same network keep working.
+const MAX_CONCURRENT = 6
+const RESERVE = 5
+// A 429 is retried after the window resets rather than failing the install.
+const RETRIES = 4
+
+interface Waiter {
+  resolve: () => void
+  signal?: AbortSignal | null
+  cancel: () => void
+}
+
+const waiting: Waiter[] = []
+let active = 0
+let remaining = Number.POSITIVE_INFINITY
+let resetAt = 0
+let resetTimer: NodeJS.Timeout | null = null
+let authorization: string | null = null
+
+function pump(): void {
+  if (resetAt && Date.now() >= resetAt) {
+    remaining = Number.POSITIVE_INFINITY
+    resetAt = 0
+  }
+  while (waiting.length && active < MAX_CONCURRENT && remaining > RESERVE) {
+    const next = waiting.shift()!
+    next.signal?.removeEventListener('abort', next.cancel)
+    active++
+    remaining--
+    next.resolve()
+  }
+  if (waiting.length && resetAt && remaining <= RESERVE && !resetTimer) {
+    resetTimer = setTimeout(() => {
+      resetTimer = null
+      pump()
+    }, Math.max(0, resetAt - Date.now()) + 250)
+  }
+}
+
+function header(response: Response, name: string): number | null {
+  const value = response.headers.get(name)
+  return value !== null && Number.isFinite(Number(value)) ? Number(value) : null
+}
+
+// Modrinth reports the requests left in the window and the seconds until it
+// resets on every response.
+function noteLimits(response: Response): void {
+  const left = header(response, 'x-ratelimit-remaining')
+  const reset = header(response, 'x-ratelimit-reset')
+  if (left !== null && reset !== null) {
+    remaining = left
+    resetAt = Date.now() + reset * 1000
+  }
+  if (response.status === 429) {
+    const wait = header(response, 'retry-after') ?? reset ?? 10
+    remaining = 0
+    resetAt = Math.max(resetAt, Date.now() + wait * 1000)
+    logger.warn('Modrinth is rate limiting requests; waiting for the limit to reset.', { seconds: wait })
+  }
+}
+
+const limiter: RequestLimiter = {
+  acquire(signal) {
+    signal?.throwIfAborted()
+    return new Promise((resolve, reject) => {
+      const waiter: Waiter = {
+        resolve,
+        signal,
+        cancel: () => {
+          const index = waiting.indexOf(waiter)
+          if (index >= 0) waiting.splice(index, 1)
+          reject(signal!.reason)
+        }
+      }
+      signal?.addEventListener('abort', waiter.cancel, { once: true })
+      waiting.push(waiter)
+      pump()
+    })
+  },
+  release(response) {
+    active--
+    if (response) noteLimits(response)
+    pump()
+  }
+}
+
+// A personal access token, when the player has linked a Modrinth account. It
+// is sent with API calls but not with the cached public lookups.
+export function setModrinthAuthorization(token: string | null): void {
+  authorization = token
+}
+
+function apiUrl(pathOrUrl: string): string {
+  return pathOrUrl.startsWith('https://') ? pathOrUrl : `${MODRINTH_API}${pathOrUrl}`
+}
+
+// Every Modrinth API call goes through one queue that follows the limit
+// headers, so installing a modpack with hundreds of dependencies waits its
+// turn instead of failing with 429 halfway through.
+export async function modrinthJson<T>(pathOrUrl: string, init: RequestInit = {}): Promise<T> {
+  const headers = new Headers(init.headers)
+  if (authorization && !headers.has('Authorization')) headers.set('Authorization', authorization)
+  const response = await sendRequest(apiUrl(pathOrUrl), { ...init, headers }, { limiter, retries: RETRIES })
+  if (!response.ok) {
+    const text = await response.text().catch(() => '')
+    throw new Error(`Request failed (${response.status})${text ? `: ${text.slice(0, 240)}` : ''}`)
+  }
+  return response.json() as Promise<T>
+}
+
+// Project and version lookups are kept on disk and revalidated, and reused
+// without a request for maxAgeMs.
+export function modrinthCachedJson<T>(pathOrUrl: string, maxAgeMs: number): Promise<T> {
+  return fetchCachedJson<T>(apiUrl(pathOrUrl), { maxAgeMs, staleIfError: true, limiter, retries: RETRIES })
+}
@@ -0,0 +1,173 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import AdmZip from 'adm-zip'
+import { download } from './downloads'
+import { currentRuleEnvironment, type RuleEnvironment } from './rules'
+import { applicableLibraries, containedPath, resolveVersionJson, type LibraryArtifact, type VersionJson, type VersionLibrary } from './versionJson'
+
+type NativeArtifact = LibraryArtifact & { path: string }
+
+interface NativeJar {
+  artifact: NativeArtifact
+  exclude: string[]
+}
+
+interface NativesStamp {
+  key: string
+  jars: string[]
+}
+
+const STAMP_FILE = '.megaclient-natives.json'
+const NATIVE_EXTENSIONS = /\.(?:dll|so|dylib|jnilib)$/i
+// LWJGL 3.3 ships every architecture of an OS in one jar under folders such as
+// windows/x64 or macos/arm64, which collide by name once extracted.
+const ARCH_SEGMENTS = new Set(['x86', 'x64', 'arm32', 'arm64'])
+
+function nativesArch(environment: RuleEnvironment): string {
+  if (environment.arch === 'x86_64') return 'x64'
+  if (environment.arch === 'arm') return 'arm32'
+  return environment.arch
+}
+
+function fallbackClassifiers(environment: RuleEnvironment): string[] {
+  // LWJGL 3 publishes its macOS natives as natives-macos rather than natives-osx.
+  const names = environment.name === 'osx' ? ['natives-osx', 'natives-macos'] : [`natives-${environment.name}`]
+  return environment.arch === 'arm64' ? [...names.map((name) => `${name}-arm64`), ...names] : names
+}
+
+function nativeClassifier(library: VersionLibrary, environment: RuleEnvironment): string | undefined {
+  const classifiers = (library.downloads as { classifiers?: Record<string, unknown> } | undefined)?.classifiers
+  const mapped = library.natives?.[environment.name]?.replace('${arch}', environment.arch === 'x86' ? '32' : '64')
+  // Patched profiles add ARM64 classifiers next to the x86 ones the natives
+  // map still points at.
+  if (mapped && environment.arch === 'arm64' && classifiers?.[`${mapped}-arm64`]) return `${mapped}-arm64`
+  if (mapped) return mapped
+  if (!library.natives) return undefined
+  // Some modpack and loader profiles were written on Windows and only map the
+  // windows classifier, although the library publishes natives for every OS.
+  return fallbackClassifiers(environment).find((classifier) => classifiers?.[classifier])
+}
+
+function nativeJars(libraries: VersionLibrary[], environment: RuleEnvironment, missing: string[] = []): NativeJar[] {
+  const jars: NativeJar[] = []
+  for (const library of libraries) {
+    const downloads = library.downloads as { artifact?: NativeArtifact; classifiers?: Record<string, NativeArtifact> } | undefined
+    const classifier = nativeClassifier(library, environment)
+    if (classifier) {
+      const artifact = downloads?.classifiers?.[classifier]
+      if (artifact?.path) jars.push({ artifact, exclude: library.extract?.exclude ?? [] })
+      else missing.push(`${library.name}:${classifier}`)
+      continue
+    }
+    if (library.natives) {
+      missing.push(library.name)
+      continue
+    }
+    // Newer profiles list natives as ordinary libraries with a natives-*
+    // classifier and OS rules, which applicableLibraries has already applied.
+    const [, , , nameClassifier] = library.name.split(':')
+    if (nameClassifier?.startsWith('natives-') && downloads?.artifact?.path) {
+      jars.push({ artifact: downloads.artifact, exclude: library.extract?.exclude ?? ['META-INF/'] })
+    }
+  }
+  return jars
+}
+
+async function ensureNativeJar(librariesDirectory: string, artifact: NativeArtifact): Promise<string> {
+  const file = containedPath(librariesDirectory, artifact.path)
+  if (!file) throw new Error(`The native library path ${artifact.path} is invalid.`)
+  const stat = await fs.stat(file).catch(() => null)
+  if (stat?.isFile() && (!artifact.size || stat.size === artifact.size)) return file
+  if (!artifact.url) throw new Error(`The native library ${artifact.path} is missing.`)
+  await download(artifact.url, file, undefined, { sha1: artifact.sha1, size: artifact.size })
+  return file
+}
+
+async function readStamp(directory: string): Promise<NativesStamp | null> {
+  try {
+    return JSON.parse(await fs.readFile(path.join(directory, STAMP_FILE), 'utf8')) as NativesStamp
+  } catch {
+    return null
+  }
+}
+
+async function extractJar(jarFile: string, destination: string, exclude: string[], arch: string): Promise<number> {
+  let extracted = 0
+  for (const entry of new AdmZip(jarFile).getEntries()) {
+    if (entry.isDirectory || exclude.some((prefix) => entry.entryName.startsWith(prefix))) continue
+    if (!NATIVE_EXTENSIONS.test(entry.entryName)) continue
+    const segments = entry.entryName.split('/')
+    const entryArch = segments.find((segment) => ARCH_SEGMENTS.has(segment))
+    if (entryArch && entryArch !== arch) continue
+    // Native loaders look the library up by file name in a single folder.
+    const target = containedPath(destination, segments.at(-1)!)
+    if (!target) continue
+    await fs.writeFile(target, entry.getData())
+    extracted++
+  }
+  return extracted
+}
+
+// Extracts a version's native libraries once into natives/<version>-<os>-<arch>
+// and records the source jars in a stamp, so later launches reuse the folder
+// until the profile's natives change. Folders left behind by other versions of
+// this instance are removed.
+export async function prepareNatives(
+  gameDirectory: string,
+  versionId: string,
+  onMissing?: (library: string) => void,
+  arch?: string
+): Promise<string | null> {
+  // The natives must match the JVM, which on ARM64 may be an emulated x64 one.
+  const environment = { ...currentRuleEnvironment(), ...(arch ? { arch } : {}) }
+  const version = await resolveVersionJson(gameDirectory, versionId).catch(() => null)
+  if (!version) return null
+  const missing: string[] = []
+  const jars = nativeJars(applicableLibraries(version, environment), environment, missing)
+  for (const library of missing) onMissing?.(library)
+  if (!jars.length) return null
+
+  const arch = nativesArch(environment)
+  const key = `${versionId}-${environment.name}-${arch}`
+  const nativesRoot = path.join(gameDirectory, 'natives')
+  const directory = containedPath(nativesRoot, key)
+  if (!directory) return null
+  const stamp: NativesStamp = { key, jars: jars.map(({ artifact }) => `${artifact.path}@${artifact.sha1 ?? artifact.size ?? ''}`).sort() }
+
+  const existing = await readStamp(directory)
+  if (existing?.key !== key || existing.jars.join('\n') !== stamp.jars.join('\n')) {
+    const librariesDirectory = path.join(gameDirectory, 'libraries')
+    const staging = `${directory}.tmp`
+    await fs.rm(staging, { recursive: true, force: true })
+    await fs.mkdir(staging, { recursive: true })
+    for (const jar of jars) await extractJar(await ensureNativeJar(librariesDirectory, jar.artifact), staging, jar.exclude, arch)
+    await fs.writeFile(path.join(staging, STAMP_FILE), JSON.stringify(stamp, null, 2), 'utf8')
+    await fs.rm(directory, { recursive: true, force: true })
+    await fs.rename(staging, directory)
+  }
+
+  for (const entry of await fs.readdir(nativesRoot, { withFileTypes: true }).catch(() => [])) {
+    if (!entry.isDirectory() || entry.name === key) continue
+    const other = path.join(nativesRoot, entry.name)
+    if (entry.name.endsWith('.tmp') || await readStamp(other)) await fs.rm(other, { recursive: true, force: true }).catch(() => undefined)
+  }
+  return directory
+}
+
+export function nativesJvmArguments(directory: string): string[] {
+  // LWJGL 2 and 3 both search org.lwjgl.librarypath before java.library.path,
+  // which the loader profile already points at its own natives folder.
+  const value = directory.replaceAll('\\', '/')
+  return [`-Dorg.lwjgl.librarypath=${value}`, `-Dnet.java.games.input.librarypath=${value}`]
+}
+
+// Versions that declare natives only have ARM64 builds from LWJGL 3.3 on,
+// either as natives-*-arm64 classifiers or inside the combined natives jars.
+export function hasArm64Natives(version: VersionJson): boolean {
+  const libraries = version.libraries ?? []
+  const natives = libraries.filter((library) => library.natives || library.name.split(':')[3]?.startsWith('natives-'))
+  if (!natives.length) return true
+  return natives.some((library) => /(?:arm64|aarch64)/.test(library.name)
+    || Object.keys((library.downloads as { classifiers?: Record<string, unknown> } | undefined)?.classifiers ?? {}).some((name) => /(?:arm64|aarch64)/.test(name))
+    || /^org\.lwjgl:lwjgl[\w-]*:3\.(?:[3-9]|\d{2,})\./.test(library.name))
+}
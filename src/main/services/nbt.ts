@@ -0,0 +1,79 @@
+import { gunzip, inflate } from 'node:zlib'
+import { promisify } from 'node:util'
+
+const gunzipAsync = promisify(gunzip)
+const inflateAsync = promisify(inflate)
+
+export type NbtValue = number | string | NbtValue[] | NbtCompound
+export interface NbtCompound { [name: string]: NbtValue }
+
+// Nesting deeper than this is treated as a corrupt file rather than read.
+const MAX_DEPTH = 512
+
+class NbtReader {
+  private offset = 0
+
+  constructor(private readonly buffer: Buffer) {}
+
+  private take(bytes: number): number {
+    if (this.offset + bytes > this.buffer.length) throw new Error('The NBT data ends unexpectedly.')
+    const start = this.offset
+    this.offset += bytes
+    return start
+  }
+
+  private count(): number {
+    const value = this.buffer.readInt32BE(this.take(4))
+    // Every element takes at least one byte, so a longer count is corrupt.
+    if (value < 0 || value > this.buffer.length - this.offset) throw new Error('The NBT data has an invalid length.')
+    return value
+  }
+
+  string(): string {
+    const length = this.buffer.readUInt16BE(this.take(2))
+    const start = this.take(length)
+    return this.buffer.toString('utf8', start, start + length)
+  }
+
+  tagType(): number {
+    return this.buffer.readUInt8(this.take(1))
+  }
+
+  // Longs are returned as numbers; the values read here, such as timestamps,
+  // fit without losing precision.
+  value(type: number, depth = 0): NbtValue {
+    if (depth > MAX_DEPTH) throw new Error('The NBT data is nested too deeply.')
+    switch (type) {
+      case 1: return this.buffer.readInt8(this.take(1))
+      case 2: return this.buffer.readInt16BE(this.take(2))
+      case 3: return this.buffer.readInt32BE(this.take(4))
+      case 4: return Number(this.buffer.readBigInt64BE(this.take(8)))
+      case 5: return this.buffer.readFloatBE(this.take(4))
+      case 6: return this.buffer.readDoubleBE(this.take(8))
+      case 7: return Array.from({ length: this.count() }, () => this.value(1))
+      case 8: return this.string()
+      case 9: {
+        const itemType = this.tagType()
+        return Array.from({ length: this.count() }, () => this.value(itemType, depth + 1))
+      }
+      case 10: {
+        const compound: NbtCompound = {}
+        for (let tag = this.tagType(); tag !== 0; tag = this.tagType()) compound[this.string()] = this.value(tag, depth + 1)
+        return compound
+      }
+      case 11: return Array.from({ length: this.count() }, () => this.value(3))
+      case 12: return Array.from({ length: this.count() }, () => this.value(4))
+      default: throw new Error(`Unknown NBT tag type ${type}.`)
+    }
+  }
+}
+
+// Reads a named NBT file such as level.dat, which may be gzipped, zlib
+// compressed or stored as it is, and returns its root compound.
+export async function readNbt(data: Buffer): Promise<NbtCompound> {
+  const raw = data[0] === 0x1f && data[1] === 0x8b ? await gunzipAsync(data) : data[0] === 0x78 ? await inflateAsync(data) : data
+  const reader = new NbtReader(raw)
+  if (reader.tagType() !== 10) throw new Error('The NBT data does not start with a compound.')
+  reader.string()
+  return reader.value(10) as NbtCompound
+}
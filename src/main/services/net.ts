@@ -1,36 +1,143 @@
 import fs from 'node:fs/promises'
 import path from 'node:path'
 import { createHash } from 'node:crypto'
+import { assertOnline, isOffline, noteRequestResult } from './connectivity'
 
 const USER_AGENT = 'MegaClient/1.9.5 (MegaStudios Minecraft Launcher)'
 const jsonRequests = new Map<string, Promise<unknown>>()
 
-export async function fetchWithTimeout(url: string, init: RequestInit = {}, timeoutMs = 30000): Promise<Response> {
+// Idempotent requests are retried after network errors and these statuses.
+const RETRYABLE_STATUSES = new Set([408, 425, 429, 500, 502, 503, 504])
+// The longest Retry-After that is waited out. A server asking for longer fails
+// the request straight away with a message saying when to try again.
+const MAX_RETRY_AFTER_MS = 60_000
+// When each host that answered with Retry-After may be asked again. Every
+// request to that host waits, not just the one that was refused.
+const hostCooldowns = new Map<string, number>()
+
+// Holds each attempt until an API's rate limit allows it and sees every
+// response, so limit headers can be read as they arrive.
+export interface RequestLimiter {
+  acquire(signal?: AbortSignal | null): Promise<void>
+  release(response: Response | null): void
+}
+
+export interface RequestOptions {
+  timeoutMs?: number
+  retries?: number
+  limiter?: RequestLimiter
+}
+
+// Exponential from half a second, capped at eight, with full jitter on the
+// upper half so clients that failed together do not retry together.
+function backoffDelay(attempt: number): number {
+  const ceiling = Math.min(8_000, 500 * 2 ** attempt)
+  return ceiling / 2 + Math.random() * (ceiling / 2)
+}
+
+function sleep(ms: number, signal?: AbortSignal | null): Promise<void> {
+  return new Promise((resolve, reject) => {
+    const timer = setTimeout(() => {
+      signal?.removeEventListener('abort', cancel)
+      resolve()
+    }, ms)
+    const cancel = (): void => {
+      clearTimeout(timer)
+      reject(signal!.reason)
+    }
+    signal?.addEventListener('abort', cancel, { once: true })
+  })
+}
+
+function retryAfterMs(response: Response): number | null {
+  const value = response.headers.get('retry-after')?.trim()
+  if (!value) return null
+  const ms = /^\d+$/.test(value) ? Number(value) * 1000 : Date.parse(value) - Date.now()
+  return Number.isFinite(ms) ? Math.max(0, ms) : null
+}
+
+async function waitForHost(host: string, signal?: AbortSignal | null): Promise<void> {
+  const wait = (hostCooldowns.get(host) ?? 0) - Date.now()
+  if (wait <= 0) {
+    hostCooldowns.delete(host)
+    return
+  }
+  if (wait > MAX_RETRY_AFTER_MS) throw new Error(`${host} is limiting requests. Try again in about ${Math.ceil(wait / 1000)} seconds.`)
+  await sleep(wait, signal)
+}
+
+async function requestOnce(url: string, init: RequestInit, timeoutMs: number): Promise<Response> {
   const controller = new AbortController()
   const timer = setTimeout(() => controller.abort(), timeoutMs)
+  // A caller's signal cancels the request as well, with the caller's reason.
+  const cancel = (): void => controller.abort()
+  init.signal?.throwIfAborted()
+  init.signal?.addEventListener('abort', cancel, { once: true })
+  const headers = new Headers(init.headers)
+  if (!headers.has('User-Agent')) headers.set('User-Agent', USER_AGENT)
   try {
-    const response = await fetch(url, {
-      ...init,
-      signal: controller.signal,
-      redirect: 'follow',
-      headers: { 'User-Agent': USER_AGENT, ...(init.headers ?? {}) }
-    })
-    if (!response.ok) {
-      const text = await response.text().catch(() => '')
-      throw new Error(`Request failed (${response.status})${text ? `: ${text.slice(0, 240)}` : ''}`)
-    }
-    return response
+    return await fetch(url, { ...init, signal: controller.signal, redirect: 'follow', headers })
   } catch (error) {
+    init.signal?.throwIfAborted()
     if (error instanceof Error && error.name === 'AbortError') throw new Error('The request timed out. Please try again.')
     throw error
   } finally {
     clearTimeout(timer)
+    init.signal?.removeEventListener('abort', cancel)
   }
 }
 
+// The one HTTP entry point: every request carries MegaClient's user agent and
+// a timeout, and GET and HEAD requests are retried with backoff, or after the
+// delay a Retry-After header asks for. The response is returned whatever its
+// status; fetchWithTimeout turns failures into errors.
+// In offline mode nothing is sent, and while the internet is unreachable
+// requests are tried once so cached fallbacks take over quickly.
+export async function sendRequest(url: string, init: RequestInit = {}, options: RequestOptions = {}): Promise<Response> {
+  assertOnline()
+  const method = String(init.method ?? 'GET').toUpperCase()
+  const retries = isOffline() ? 0 : options.retries ?? (method === 'GET' || method === 'HEAD' ? 2 : 0)
+  const host = new URL(url).host
+  for (let attempt = 0; ; attempt++) {
+    await waitForHost(host, init.signal)
+    await options.limiter?.acquire(init.signal)
+    let response: Response | null = null
+    let retryAfter: number | null = null
+    try {
+      response = await requestOnce(url, init, options.timeoutMs ?? 30000)
+      noteRequestResult(true)
+      if (!RETRYABLE_STATUSES.has(response.status)) return response
+      retryAfter = retryAfterMs(response)
+      if (retryAfter !== null) hostCooldowns.set(host, Math.max(hostCooldowns.get(host) ?? 0, Date.now() + retryAfter))
+      if (attempt >= retries || (retryAfter ?? 0) > MAX_RETRY_AFTER_MS) return response
+      await response.body?.cancel().catch(() => undefined)
+    } catch (error) {
+      init.signal?.throwIfAborted()
+      if (attempt >= retries) {
+        noteRequestResult(false)
+        throw error
+      }
+    } finally {
+      options.limiter?.release(response)
+    }
+    // A Retry-After delay is waited out by waitForHost on the next attempt.
+    if (!retryAfter) await sleep(backoffDelay(attempt), init.signal)
+  }
+}
+
+export async function fetchWithTimeout(url: string, init: RequestInit = {}, timeoutMs = 30000): Promise<Response> {
+  const response = await sendRequest(url, init, { timeoutMs })
+  if (!response.ok) {
+    const text = await response.text().catch(() => '')
+    throw new Error(`Request failed (${response.status})${text ? `: ${text.slice(0, 240)}` : ''}`)
+  }
+  return response
+}
+
 export async function fetchJson<T>(url: string, init: RequestInit = {}): Promise<T> {
   const method = String(init.method ?? 'GET').toUpperCase()
-  if (method !== 'GET' || init.body) return (await fetchWithTimeout(url, init)).json() as Promise<T>
+  // Cancellable requests are not shared, so one caller cannot abort another's.
+  if (method !== 'GET' || init.body || init.signal) return (await fetchWithTimeout(url, init)).json() as Promise<T>
 
   const headers = [...new Headers(init.headers).entries()]
     .sort(([left], [right]) => left.localeCompare(right))
@@ -46,24 +153,130 @@ export async function fetchJson<T>(url: string, init: RequestInit = {}): Promise
   return request
 }
 
+// Reads a response body as text, counting the bytes as they arrive, so a
+// chunked response or one without Content-Length cannot exceed the limit.
+export async function readLimitedText(response: Response, maxBytes: number, message: string): Promise<string> {
+  if (Number(response.headers.get('content-length') ?? 0) > maxBytes) {
+    await response.body?.cancel().catch(() => undefined)
+    throw new Error(message)
+  }
+  const chunks: Uint8Array[] = []
+  let received = 0
+  const reader = response.body?.getReader()
+  while (reader) {
+    const { value, done } = await reader.read()
+    if (done) break
+    received += value.byteLength
+    if (received > maxBytes) {
+      await reader.cancel().catch(() => undefined)
+      throw new Error(message)
+    }
+    chunks.push(value)
+  }
+  return Buffer.concat(chunks).toString('utf8')
+}
+
+// Resumable downloads stream into <destination>.part and keep it when they
+// fail, so the next attempt asks the server for the rest with a Range request.
+// A server that ignores the range answers with the whole file, which then
+// replaces the partial one.
+async function openDownload(url: string, part: string | null, options: { timeoutMs?: number; signal?: AbortSignal }): Promise<{ response: Response; offset: number }> {
+  const offset = part ? (await fs.stat(part).catch(() => null))?.size ?? 0 : 0
+  if (!offset) return { response: await fetchWithTimeout(url, { signal: options.signal }, options.timeoutMs ?? 120000), offset: 0 }
+  try {
+    const response = await fetchWithTimeout(url, { signal: options.signal, headers: { Range: `bytes=${offset}-` } }, options.timeoutMs ?? 120000)
+    const start = Number(/^bytes (\d+)-/.exec(response.headers.get('content-range') ?? '')?.[1])
+    if (response.status === 206 && start === offset) return { response, offset }
+    if (response.status === 206) await response.body?.cancel().catch(() => undefined)
+    else return { response, offset: 0 }
+  } catch (error) {
+    options.signal?.throwIfAborted()
+    // 416 means the partial file is already as long as, or longer than, the file.
+    if (!(error instanceof Error && error.message.startsWith('Request failed (416)'))) throw error
+  }
+  await fs.rm(part!, { force: true })
+  return { response: await fetchWithTimeout(url, { signal: options.signal }, options.timeoutMs ?? 120000), offset: 0 }
+}
+
+async function hashInto(hash: ReturnType<typeof createHash>, file: string): Promise<void> {
+  const handle = await fs.open(file, 'r')
+  try {
+    for await (const chunk of handle.createReadStream({ autoClose: false })) hash.update(chunk as Buffer)
+  } finally {
+    await handle.close()
+  }
+}
+
+interface DownloadOptions {
+  maxBytes?: number
+  timeoutMs?: number
+  signal?: AbortSignal
+  resume?: boolean
+  throttle?: (bytes: number) => Promise<void>
+  sha1?: string
+  size?: number
+}
+
+const activeDownloads = new Map<string, { url: string; done: Promise<void> }>()
+
+// Instances share the libraries and assets folders, so two launches can ask
+// for the same file at once. Only one transfer writes a destination and its
+// .part file at a time: a request for the same URL shares the running one,
+// and anything else bound for that destination waits for it to finish.
 export async function downloadFile(
   url: string,
   destination: string,
   onProgress?: (downloaded: number, total: number) => void,
-  options: { maxBytes?: number; timeoutMs?: number } = {}
+  options: DownloadOptions = {}
+): Promise<void> {
+  const key = path.resolve(destination)
+  let active: { url: string; done: Promise<void> } | undefined
+  while ((active = activeDownloads.get(key))) {
+    const succeeded = await active.done.then(() => true, () => false)
+    options.signal?.throwIfAborted()
+    if (succeeded && active.url === url) return
+  }
+  const done = transferFile(url, destination, onProgress, options)
+  activeDownloads.set(key, { url, done })
+  try {
+    await done
+  } finally {
+    if (activeDownloads.get(key)?.done === done) activeDownloads.delete(key)
+  }
+}
+
+// Every finished download is checked before it replaces the destination: its
+// length against Content-Length and the expected size, and its SHA-1 when the
+// caller knows it. A resumable transfer that ended early keeps its partial
+// file; one that is complete but wrong is discarded.
+async function transferFile(
+  url: string,
+  destination: string,
+  onProgress: ((downloaded: number, total: number) => void) | undefined,
+  options: DownloadOptions
 ): Promise<void> {
-  const response = await fetchWithTimeout(url, {}, options.timeoutMs ?? 120000)
-  const total = Number(response.headers.get('content-length') ?? 0)
+  await fs.mkdir(path.dirname(destination), { recursive: true })
+  const part = options.resume ? `${destination}.part` : null
+  const { response, offset } = await openDownload(url, part, options)
+  // With a content encoding, Content-Length counts the compressed bytes.
+  const encoded = (response.headers.get('content-encoding') ?? 'identity') !== 'identity'
+  const length = encoded ? 0 : Number(response.headers.get('content-length') ?? 0)
+  const total = length ? offset + length : options.size ?? 0
   if (options.maxBytes && total > options.maxBytes) throw new Error('The download is larger than MegaClient can safely install.')
   const body = response.body
   if (!body) throw new Error('The download returned no data.')
-  await fs.mkdir(path.dirname(destination), { recursive: true })
-  const temp = `${destination}.${process.pid}.${Date.now()}.download`
-  const handle = await fs.open(temp, 'wx')
-  let downloaded = 0
+  const temp = part ?? `${destination}.${process.pid}.${Date.now()}.download`
+  const hash = options.sha1 ? createHash('sha1') : null
+  if (hash && offset) await hashInto(hash, temp)
+  const handle = await fs.open(temp, offset ? 'a' : part ? 'w' : 'wx')
+  let downloaded = offset
   try {
     const reader = body.getReader()
     while (true) {
+      if (options.signal?.aborted) {
+        await reader.cancel().catch(() => undefined)
+        options.signal.throwIfAborted()
+      }
       const { value, done } = await reader.read()
       if (done) break
       if (!value?.byteLength) continue
@@ -73,27 +286,35 @@ export async function downloadFile(
         throw new Error('The download exceeded MegaClient’s safe size limit.')
       }
       await handle.write(value)
+      hash?.update(value)
       onProgress?.(downloaded, total)
+      await options.throttle?.(value.byteLength)
     }
     await handle.sync()
+    if (total && downloaded < total) throw new Error(`The download ended early (${downloaded} of ${total} bytes). Please try again.`)
   } catch (error) {
     await handle.close().catch(() => undefined)
-    await fs.rm(temp, { force: true }).catch(() => undefined)
+    if (!part || (options.maxBytes && downloaded > options.maxBytes)) await fs.rm(temp, { force: true }).catch(() => undefined)
     throw error
   }
   await handle.close()
+  const problem = total && downloaded > total
+    ? `The download was longer than expected (${downloaded} of ${total} bytes).`
+    : options.size && downloaded !== options.size
+      ? `The download was ${downloaded} bytes instead of the published ${options.size}.`
+      : hash && hash.digest('hex') !== options.sha1!.toLowerCase()
+        ? 'The download did not match its published SHA-1.'
+        : null
+  if (problem) {
+    await fs.rm(temp, { force: true }).catch(() => undefined)
+    throw new Error(`${path.basename(destination)}: ${problem}`)
+  }
   await fs.rm(destination, { force: true }).catch(() => undefined)
   await fs.rename(temp, destination)
 }
 
-export async function hashFile(file: string, algorithm: 'sha1' | 'sha512'): Promise<string> {
+export async function hashFile(file: string, algorithm: 'sha1' | 'sha256' | 'sha512'): Promise<string> {
   const hash = createHash(algorithm)
-  const handle = await fs.open(file, 'r')
-  try {
-    const stream = handle.createReadStream({ autoClose: false })
-    for await (const chunk of stream) hash.update(chunk as Buffer)
-    return hash.digest('hex')
-  } finally {
-    await handle.close()
-  }
+  await hashInto(hash, file)
+  return hash.digest('hex')
 }
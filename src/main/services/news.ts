@@ -0,0 +1,57 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import { createHash } from 'node:crypto'
+import type { NewsItem } from '../types'
+import { fetchCachedJson } from './httpCache'
+import { download } from './downloads'
+import { newsCacheDirectory } from './paths'
+
+const CONTENT_BASE_URL = 'https://launchercontent.mojang.com'
+const NEWS_URL = `${CONTENT_BASE_URL}/v2/news.json`
+const NEWS_MAX_AGE_MS = 30 * 60_000
+
+interface NewsEntry {
+  id: string
+  title: string
+  tag?: string
+  category?: string
+  date?: string
+  text?: string
+  newsType?: string[]
+  newsPageImage?: { url?: string }
+  playPageImage?: { url?: string }
+}
+
+const IMAGE_TYPES: Record<string, string> = { '.png': 'image/png', '.jpg': 'image/jpeg', '.jpeg': 'image/jpeg', '.webp': 'image/webp' }
+
+// Thumbnails are kept next to the cached feed and handed to the renderer as
+// data URLs, so the home screen shows them offline as well.
+async function cachedThumbnail(url: string): Promise<string | undefined> {
+  const extension = path.extname(new URL(url).pathname).toLowerCase()
+  const type = IMAGE_TYPES[extension]
+  if (!type) return undefined
+  const file = path.join(newsCacheDirectory(), `${createHash('sha1').update(url).digest('hex')}${extension}`)
+  if (!await fs.stat(file).catch(() => null)) await download(url, file, undefined, { maxBytes: 4 * 1024 * 1024, label: 'News image' })
+  return `data:${type};base64,${(await fs.readFile(file)).toString('base64')}`
+}
+
+// Minecraft: Java Edition news from the feed the official launcher shows. The
+// feed is cached on disk for half an hour and served from there when offline.
+export async function getNewsItems(limit = 10): Promise<NewsItem[]> {
+  const feed = await fetchCachedJson<{ entries?: NewsEntry[] }>(NEWS_URL, { maxAgeMs: NEWS_MAX_AGE_MS, staleIfError: true })
+  const entries = (feed.entries ?? [])
+    .filter((entry) => entry.id && entry.title && (!entry.newsType || entry.newsType.includes('Java')))
+    .sort((left, right) => Date.parse(right.date ?? '') - Date.parse(left.date ?? ''))
+    .slice(0, Math.max(1, Math.min(30, limit)))
+  return Promise.all(entries.map(async (entry) => {
+    const image = entry.newsPageImage?.url ?? entry.playPageImage?.url
+    return {
+      id: entry.id,
+      title: entry.title,
+      tag: entry.tag ?? entry.category,
+      date: entry.date,
+      text: entry.text,
+      imageDataUrl: image ? await cachedThumbnail(new URL(image, CONTENT_BASE_URL).toString()).catch(() => undefined) : undefined
+    }
+  }))
+}
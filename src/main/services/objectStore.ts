@@ -0,0 +1,77 @@
+import fs from 'node:fs/promises'
+import { constants } from 'node:fs'
+import path from 'node:path'
+import { download } from './downloads'
+import { hashFile } from './net'
+import { sharedDirectory } from './paths'
+
+type DownloadOptions = NonNullable<Parameters<typeof download>[3]>
+
+export function objectStoreDirectory(): string {
+  return sharedDirectory('objects')
+}
+
+function objectPath(sha1: string): string {
+  return path.join(objectStoreDirectory(), sha1.slice(0, 2), sha1)
+}
+
+// A hard link costs no space. Across volumes, copy-on-write is tried where the
+// file system supports it and a plain copy otherwise.
+async function linkOrCopy(source: string, destination: string): Promise<void> {
+  await fs.mkdir(path.dirname(destination), { recursive: true })
+  const temp = `${destination}.${process.pid}.${Date.now()}.link`
+  try {
+    await fs.link(source, temp).catch(() => fs.copyFile(source, temp, constants.COPYFILE_FICLONE))
+    await fs.rename(temp, destination)
+  } finally {
+    await fs.rm(temp, { force: true }).catch(() => undefined)
+  }
+}
+
+// Objects being downloaded or linked, which cleanup must not treat as unused
+// before their first link exists.
+let pendingObjects = 0
+
+export function objectStoreBusy(): boolean {
+  return pendingObjects > 0
+}
+
+// Files with a published SHA-1, such as mods and packs, are downloaded once
+// into a store named by that hash and hard-linked into each instance, so the
+// same jar in ten instances takes the space of one. Files without a hash are
+// downloaded straight to their destination.
+export async function downloadObject(
+  url: string,
+  destination: string,
+  sha1: string | undefined,
+  onProgress?: (downloaded: number, total: number) => void,
+  options: DownloadOptions = {}
+): Promise<void> {
+  const hash = sha1?.toLowerCase()
+  if (!hash || !/^[0-9a-f]{40}$/.test(hash)) return download(url, destination, onProgress, options)
+  const stored = objectPath(hash)
+  // Stored objects are hashed again before reuse, so one damaged by an edit
+  // through a link is downloaded again rather than spread further.
+  const valid = async (): Promise<boolean> => (await hashFile(stored, 'sha1').catch(() => '')).toLowerCase() === hash
+  pendingObjects++
+  try {
+    if (!await valid()) await download(url, stored, onProgress, { ...options, sha1: hash })
+    await linkOrCopy(stored, destination)
+  } finally {
+    pendingObjects--
+  }
+}
+
+// Objects no instance links any more. Copies made across volumes are not
+// counted as links, so those objects only stay cached until the next cleanup.
+export async function unusedObjects(): Promise<string[]> {
+  const unused: string[] = []
+  for (const prefix of await fs.readdir(objectStoreDirectory()).catch(() => [] as string[])) {
+    for (const name of await fs.readdir(path.join(objectStoreDirectory(), prefix)).catch(() => [] as string[])) {
+      const file = path.join(objectStoreDirectory(), prefix, name)
+      const stat = await fs.stat(file).catch(() => null)
+      if (stat?.isFile() && stat.nlink <= 1) unused.push(file)
+    }
+  }
+  return unused
+}
@@ -17,6 +17,37 @@ export function emlRootDirectory(): string {
   return path.join(app.getPath('home'), `.${launcherRootName}`)
 }
 
+export function vanillaMinecraftDirectory(): string {
+  if (process.platform === 'darwin') return path.join(app.getPath('appData'), 'minecraft')
+  if (process.platform === 'win32') return path.join(app.getPath('appData'), '.minecraft')
+  return path.join(app.getPath('home'), '.minecraft')
+}
+
+export function sharedDirectory(kind: string): string {
+  // Instance slugs never start with a dot, so this cannot collide with one.
+  return path.join(emlRootDirectory(), '.shared', kind)
+}
+
+export function httpCacheDirectory(): string {
+  return path.join(dataDirectory(), 'cache', 'http')
+}
+
+export function newsCacheDirectory(): string {
+  return path.join(dataDirectory(), 'cache', 'news')
+}
+
+export function modIconCacheDirectory(): string {
+  return path.join(dataDirectory(), 'cache', 'mod-icons')
+}
+
+export function runtimesDirectory(): string {
+  return path.join(emlRootDirectory(), '.runtimes')
+}
+
+export function archiveDirectory(): string {
+  return path.join(emlRootDirectory(), '.archive')
+}
+
 export function instanceDirectory(slug: string): string {
   return path.join(emlRootDirectory(), slug)
 }
@@ -29,6 +60,10 @@ export function metadataDirectory(slug: string): string {
   return path.join(instanceDirectory(slug), '.megaclient')
 }
 
+export function launchLogsDirectory(slug: string): string {
+  return path.join(metadataDirectory(slug), 'launcher_logs')
+}
+
 export function resourcePacksDirectory(slug: string): string {
   return path.join(instanceDirectory(slug), 'resourcepacks')
 }
@@ -40,3 +75,14 @@ export function shaderPacksDirectory(slug: string): string {
 export function savesDirectory(slug: string): string {
   return path.join(instanceDirectory(slug), 'saves')
 }
+
+// World backups are kept outside the instance folder, so modpack exports leave
+// them out and deleting the instance does not take them with it. They are
+// keyed by instance ID, as a new instance can reuse a deleted one's slug.
+export function worldBackupsDirectory(instanceId: string): string {
+  return path.join(emlRootDirectory(), '.backups', instanceId)
+}
+
+export function screenshotsDirectory(slug: string): string {
+  return path.join(instanceDirectory(slug), 'screenshots')
+}
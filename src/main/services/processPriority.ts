@@ -0,0 +1,36 @@
+import { execFile } from 'node:child_process'
+import { promisify } from 'node:util'
+import type { ProcessPriority } from '../types'
+
+const execFileAsync = promisify(execFile)
+
+const WINDOWS_PRIORITY: Record<ProcessPriority, string> = {
+  'below-normal': 'BelowNormal',
+  normal: 'Normal',
+  'above-normal': 'AboveNormal'
+}
+
+const NICE_VALUE: Record<ProcessPriority, number> = {
+  'below-normal': 10,
+  normal: 0,
+  'above-normal': -5
+}
+
+// Applied to the running Java process, since the game is spawned by the
+// bundled launcher. Raising the priority on macOS and Linux needs elevated
+// rights, so that failure is reported rather than ignored.
+export async function applyProcessPriority(processIds: number[], priority: ProcessPriority, affinity?: number[]): Promise<void> {
+  const ids = [...new Set(processIds.filter((id) => Number.isInteger(id) && id > 0))]
+  if (!ids.length || (priority === 'normal' && !affinity?.length)) return
+  if (process.platform === 'win32') {
+    const mask = affinity?.length ? affinity.reduce((total, core) => total + 2 ** core, 0) : 0
+    const command = [
+      "$ErrorActionPreference='Stop'",
+      `foreach ($p in Get-Process -Id ${ids.join(',')}) { $p.PriorityClass='${WINDOWS_PRIORITY[priority]}'${mask ? `; $p.ProcessorAffinity=[IntPtr]${mask}` : ''} }`
+    ].join(';')
+    await execFileAsync('powershell.exe', ['-NoProfile', '-NonInteractive', '-ExecutionPolicy', 'Bypass', '-Command', command], { windowsHide: true, timeout: 10_000 })
+    return
+  }
+  if (priority === 'normal') return
+  await execFileAsync('renice', ['-n', String(NICE_VALUE[priority]), '-p', ...ids.map(String)], { timeout: 10_000 })
+}
@@ -0,0 +1,52 @@
+import { execFile } from 'node:child_process'
+import os from 'node:os'
+import { promisify } from 'node:util'
+
+const execFileAsync = promisify(execFile)
+
+export interface ProcessSample {
+  memoryBytes: number
+  cpuSeconds: number
+}
+
+// ps prints cumulative CPU time as [[dd-]hh:]mm:ss[.cc].
+function psTime(value: string): number {
+  const [days, clock] = value.includes('-') ? value.split('-') as [string, string] : ['0', value]
+  const seconds = clock.split(':').reduce((total, part) => total * 60 + Number(part), 0)
+  return Number(days) * 86_400 + seconds
+}
+
+// Sums resident memory and total CPU time over the given processes. CPU time
+// is cumulative, so callers turn two samples into a usage percentage.
+export async function sampleProcesses(processIds: number[]): Promise<ProcessSample | null> {
+  const ids = [...new Set(processIds.filter((id) => Number.isInteger(id) && id > 0))]
+  if (!ids.length) return null
+  if (process.platform === 'win32') {
+    const command = `Get-Process -Id ${ids.join(',')} -ErrorAction SilentlyContinue | Select-Object WorkingSet64,CPU | ConvertTo-Json -Compress`
+    const { stdout } = await execFileAsync('powershell.exe', ['-NoProfile', '-NonInteractive', '-ExecutionPolicy', 'Bypass', '-Command', command], {
+      windowsHide: true,
+      timeout: 10_000
+    })
+    if (!stdout.trim()) return null
+    const parsed = JSON.parse(stdout) as { WorkingSet64?: number; CPU?: number } | Array<{ WorkingSet64?: number; CPU?: number }>
+    const rows = Array.isArray(parsed) ? parsed : [parsed]
+    return rows.reduce<ProcessSample>((total, row) => ({
+      memoryBytes: total.memoryBytes + (row.WorkingSet64 ?? 0),
+      cpuSeconds: total.cpuSeconds + (row.CPU ?? 0)
+    }), { memoryBytes: 0, cpuSeconds: 0 })
+  }
+  const { stdout } = await execFileAsync('ps', ['-o', 'rss=,time=', '-p', ids.join(',')], { timeout: 10_000 }).catch(() => ({ stdout: '' }))
+  const rows = stdout.split('\n').map((line) => line.trim().split(/\s+/)).filter((row) => row.length === 2)
+  if (!rows.length) return null
+  return rows.reduce<ProcessSample>((total, [rss, time]) => ({
+    memoryBytes: total.memoryBytes + Number(rss) * 1024,
+    cpuSeconds: total.cpuSeconds + psTime(time!)
+  }), { memoryBytes: 0, cpuSeconds: 0 })
+}
+
+// Share of the whole machine, as Task Manager and Activity Monitor show it.
+export function cpuPercent(previous: ProcessSample, current: ProcessSample, elapsedMs: number): number {
+  if (elapsedMs <= 0) return 0
+  const used = Math.max(0, current.cpuSeconds - previous.cpuSeconds) / (elapsedMs / 1000)
+  return Math.min(100, Math.round((used / Math.max(1, os.cpus().length)) * 1000) / 10)
+}
@@ -4,6 +4,7 @@ import fs from 'node:fs/promises'
 import path from 'node:path'
 import type { MinecraftProfileCape, MinecraftProfileData, MinecraftProfileSkin } from '../types'
 import { getSavedAccount, isLikelyTransientMicrosoftError, refreshAccount } from './account'
+import { sendRequest } from './net'
 
 interface ApiProfile {
   id?: string
@@ -31,7 +32,7 @@ const STALE_CACHE_MS = 7 * 24 * 60 * 60_000
 let memoryCache: ProfileCacheRecord | null = null
 let profileInFlight: { uuid: string; promise: Promise<MinecraftProfileData> } | null = null
 
-function cacheFile(): string {
+export function profileCacheFile(): string {
   return path.join(app.getPath('userData'), 'minecraft-profile-cache.json')
 }
 
@@ -127,7 +128,7 @@ function parseProfileBody(body: string): ApiProfile | null {
 
 async function readDiskCache(uuid: string): Promise<ProfileCacheRecord | null> {
   try {
-    const record = JSON.parse(await fs.readFile(cacheFile(), 'utf8')) as ProfileCacheRecord
+    const record = JSON.parse(await fs.readFile(profileCacheFile(), 'utf8')) as ProfileCacheRecord
     if (record.uuid !== uuid || !record.data || !Number.isFinite(record.fetchedAt)) return null
     return record
   } catch {
@@ -142,11 +143,17 @@ async function getCachedRecord(uuid: string): Promise<ProfileCacheRecord | null>
   return disk
 }
 
+// Forgets the saved skins and capes; the next profile view loads them again.
+export async function clearProfileCache(): Promise<void> {
+  memoryCache = null
+  await fs.rm(profileCacheFile(), { force: true })
+}
+
 async function saveCache(uuid: string, data: MinecraftProfileData): Promise<MinecraftProfileData> {
   const record: ProfileCacheRecord = { uuid, fetchedAt: Date.now(), data: { ...data, revision: Date.now() } }
   memoryCache = record
-  await fs.mkdir(path.dirname(cacheFile()), { recursive: true })
-  await fs.writeFile(cacheFile(), JSON.stringify(record), 'utf8').catch(() => undefined)
+  await fs.mkdir(path.dirname(profileCacheFile()), { recursive: true })
+  await fs.writeFile(profileCacheFile(), JSON.stringify(record), 'utf8').catch(() => undefined)
   return record.data
 }
 
@@ -159,15 +166,14 @@ async function authorisedFetch(
 ): Promise<AuthorisedResponse> {
   const send = (account: Account): Promise<Response> => {
     const init = buildInit()
-    return fetch(url, {
+    return sendRequest(url, {
       ...init,
       headers: {
         Accept: 'application/json',
         ...init.headers,
         Authorization: `Bearer ${account.accessToken}`
-      },
-      signal: AbortSignal.timeout(timeoutMs)
-    })
+      }
+    }, { timeoutMs })
   }
 
   let account = initialAccount
@@ -0,0 +1,96 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import type { InstanceVerificationIssue, InstanceVerificationReport, TrackedMod, TrackedPack } from '../types'
+import { verifyAssets, verifyLibraries, verifyVersionFiles } from './gameFiles'
+import { getInstance } from './instances'
+import { isReadableArchive } from './modSecurity'
+import { forgetTrackedContent, reinstallTrackedContent, trackedContent } from './modrinth'
+import { instanceDirectory, metadataDirectory, modsDirectory, resourcePacksDirectory, shaderPacksDirectory } from './paths'
+import { logger } from './logger'
+
+async function existingFile(base: string): Promise<string | null> {
+  for (const candidate of [base, `${base}.disabled`]) {
+    const stat = await fs.stat(candidate).catch(() => null)
+    if (stat?.isFile()) return candidate
+  }
+  return null
+}
+
+function contentPath(slug: string, item: TrackedMod | TrackedPack): string {
+  if ('contentType' in item) {
+    const directory = item.contentType === 'resourcepack' ? resourcePacksDirectory(slug) : shaderPacksDirectory(slug)
+    return path.join(directory, item.fileName)
+  }
+  return path.join(modsDirectory(slug), item.fileName)
+}
+
+export async function verifyInstance(
+  instanceId: string,
+  repair = false,
+  onProgress?: (message: string, progress?: number) => void
+): Promise<InstanceVerificationReport> {
+  const instance = getInstance(instanceId)
+  if (instance.archivedAt) throw new Error(`${instance.name} is archived. Restore it before verifying.`)
+  const issues: InstanceVerificationIssue[] = []
+
+  for (const directory of [instanceDirectory(instance.slug), modsDirectory(instance.slug), metadataDirectory(instance.slug)]) {
+    if (await fs.stat(directory).catch(() => null)) continue
+    if (repair) await fs.mkdir(directory, { recursive: true })
+    issues.push({ kind: 'missing-directory', file: directory, detail: 'The folder was missing.', repaired: repair })
+  }
+
+  const { mods, packs } = await trackedContent(instanceId)
+  const items: Array<TrackedMod | TrackedPack> = [...mods, ...packs].filter((item) => item.fileName)
+  const forgotten: string[] = []
+  let checked = 0
+  for (const item of items) {
+    onProgress?.(`Checking ${item.title}`, items.length ? checked / items.length : undefined)
+    checked++
+    const file = await existingFile(contentPath(instance.slug, item))
+    const kind: InstanceVerificationIssue['kind'] | null = !file ? 'missing-file' : await isReadableArchive(file) ? null : 'corrupt-file'
+    if (!kind) continue
+
+    let repaired = false
+    if (repair && item.projectId && item.versionId && item.source === 'modrinth') {
+      if (file) await fs.rm(file, { force: true })
+      try {
+        await reinstallTrackedContent(instanceId, item, onProgress)
+        repaired = true
+      } catch (error) {
+        logger.warn(`${item.title} could not be repaired.`, { error })
+      }
+    } else if (repair && !file) {
+      // Files removed outside MegaClient cannot be restored, so the stale
+      // tracking entry is dropped instead of reporting it on every check.
+      forgotten.push(item.fileName)
+      repaired = true
+    }
+    issues.push({
+      kind,
+      file: item.fileName,
+      detail: kind === 'missing-file' ? `${item.title} is tracked but its file is missing.` : `${item.title} is not a readable archive.`,
+      repaired
+    })
+  }
+  if (forgotten.length) await forgetTrackedContent(instanceId, forgotten)
+
+  const versionFiles = await verifyVersionFiles(instanceDirectory(instance.slug), instance, { repair, onProgress })
+  checked += versionFiles.checked
+  for (const file of versionFiles.damaged) {
+    issues.push({ kind: 'version', file, detail: 'The file is missing or does not match its published SHA-1.', repaired: versionFiles.repaired.includes(file) })
+  }
+  const libraries = await verifyLibraries(instanceDirectory(instance.slug), instance.customVersionId ?? instance.minecraftVersion, { repair, onProgress })
+  checked += libraries.checked
+  for (const file of libraries.damaged) {
+    const repaired = libraries.repaired.includes(file)
+    issues.push({ kind: 'library', file, detail: 'The library is missing or does not match its published SHA-1.', repaired })
+  }
+  const assets = await verifyAssets(instanceDirectory(instance.slug), instance.customVersionId ?? instance.minecraftVersion, { thorough: true, repair, onProgress })
+  checked += assets.checked
+  for (const file of assets.damaged) {
+    issues.push({ kind: 'asset', file, detail: 'The asset object is missing or does not match its hash.', repaired: assets.repaired.includes(file) })
+  }
+
+  onProgress?.(issues.length ? `Found ${issues.length} problem${issues.length === 1 ? '' : 's'}` : 'No problems found', 1)
+  return { instanceId, checkedFiles: checked, issues, checkedAt: new Date().toISOString() }
+}
@@ -0,0 +1,138 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import { readArchiveEntries } from './modSecurity'
+
+export interface ResourcePackMeta {
+  description?: string
+  packFormat?: number
+  // The range of formats the pack declares it supports, if any.
+  supported?: [number, number]
+  iconUrl?: string
+}
+
+// The first release using each resource pack format. Newer releases, and
+// snapshots, are not listed, so packs for them are not judged.
+const PACK_FORMATS: Array<[string, number]> = [
+  ['1.6.1', 1], ['1.9', 2], ['1.11', 3], ['1.13', 4], ['1.15', 5], ['1.16.2', 6], ['1.17', 7], ['1.18', 8],
+  ['1.19', 9], ['1.19.3', 12], ['1.19.4', 13], ['1.20', 15], ['1.20.2', 18], ['1.20.3', 22], ['1.20.5', 32],
+  ['1.21', 34], ['1.21.2', 42], ['1.21.4', 46], ['1.21.5', 55], ['1.21.6', 63], ['1.21.7', 64]
+]
+const LAST_KNOWN_RELEASE = '1.21.8'
+// pack.png files larger than this are not shown.
+const MAX_ICON_BYTES = 512 * 1024
+const MAX_ENTRY_BYTES = 1024 * 1024
+
+const metaCache = new Map<string, ResourcePackMeta>()
+
+function releaseParts(version: string): number[] | null {
+  return /^\d+(\.\d+){1,2}$/.test(version) ? version.split('.').map(Number) : null
+}
+
+function compareReleases(a: number[], b: number[]): number {
+  for (let index = 0; index < 3; index++) {
+    const difference = (a[index] ?? 0) - (b[index] ?? 0)
+    if (difference) return difference
+  }
+  return 0
+}
+
+export function resourcePackFormat(minecraftVersion: string): number | undefined {
+  const version = releaseParts(minecraftVersion)
+  if (!version || compareReleases(version, releaseParts(LAST_KNOWN_RELEASE)!) > 0) return undefined
+  let format: number | undefined
+  for (const [release, value] of PACK_FORMATS) {
+    if (compareReleases(version, releaseParts(release)!) >= 0) format = value
+  }
+  return format
+}
+
+// Descriptions may be plain text or a chat component; only the text is kept,
+// without formatting codes.
+function componentText(value: unknown): string {
+  if (typeof value === 'string') return value
+  if (typeof value === 'number' || typeof value === 'boolean') return String(value)
+  if (Array.isArray(value)) return value.map(componentText).join('')
+  if (value && typeof value === 'object') {
+    const record = value as { text?: unknown; translate?: unknown; extra?: unknown }
+    return componentText(record.text ?? record.translate ?? '') + componentText(record.extra ?? '')
+  }
+  return ''
+}
+
+function formatRange(value: unknown): [number, number] | undefined {
+  if (typeof value === 'number') return [value, value]
+  if (Array.isArray(value) && typeof value[0] === 'number' && typeof value[1] === 'number') return [value[0], value[1]]
+  if (value && typeof value === 'object') {
+    const record = value as { min_inclusive?: unknown; max_inclusive?: unknown }
+    if (typeof record.min_inclusive === 'number' && typeof record.max_inclusive === 'number') return [record.min_inclusive, record.max_inclusive]
+  }
+  return undefined
+}
+
+// min_format and max_format may be a number or a [major, minor] pair.
+function majorFormat(value: unknown): number | undefined {
+  if (typeof value === 'number') return value
+  return Array.isArray(value) && typeof value[0] === 'number' ? value[0] : undefined
+}
+
+function parseMeta(text: string | null, icon: Buffer | null): ResourcePackMeta {
+  const meta: ResourcePackMeta = {}
+  if (icon && icon.length <= MAX_ICON_BYTES) meta.iconUrl = `data:image/png;base64,${icon.toString('base64')}`
+  if (!text) return meta
+  try {
+    const pack = (JSON.parse(text.replace(/^\uFEFF/, '')) as { pack?: Record<string, unknown> }).pack
+    if (!pack) return meta
+    const description = componentText(pack.description).replace(/§./g, '').trim()
+    if (description) meta.description = description
+    if (typeof pack.pack_format === 'number') meta.packFormat = pack.pack_format
+    const minimum = majorFormat(pack.min_format)
+    const maximum = majorFormat(pack.max_format)
+    meta.supported = minimum !== undefined && maximum !== undefined ? [minimum, maximum] : formatRange(pack.supported_formats)
+    meta.packFormat ??= meta.supported?.[1]
+  } catch {
+    // An unreadable pack.mcmeta leaves the pack without a description.
+  }
+  return meta
+}
+
+async function readMeta(target: string): Promise<ResourcePackMeta> {
+  const stat = await fs.stat(target)
+  if (stat.isDirectory()) {
+    const [text, icon] = await Promise.all([
+      fs.readFile(path.join(target, 'pack.mcmeta'), 'utf8').catch(() => null),
+      fs.readFile(path.join(target, 'pack.png')).catch(() => null)
+    ])
+    return parseMeta(text, icon)
+  }
+  const entries = await readArchiveEntries(target, ['pack.mcmeta', 'pack.png'], MAX_ENTRY_BYTES)
+  return parseMeta(entries?.get('pack.mcmeta')?.toString('utf8') ?? null, entries?.get('pack.png') ?? null)
+}
+
+// Reads a resource pack's pack.mcmeta and pack.png. Zips are cached by size
+// and modification time; folders are read again each time, since editing a
+// file inside one does not change the folder's own time.
+export async function readResourcePackMeta(target: string): Promise<ResourcePackMeta> {
+  const stat = await fs.stat(target).catch(() => null)
+  if (!stat) return {}
+  const key = stat.isDirectory() ? '' : `${target}:${stat.size}:${stat.mtimeMs}`
+  const cached = key ? metaCache.get(key) : undefined
+  if (cached) return cached
+  const meta = await readMeta(target).catch(() => ({}))
+  if (key) {
+    metaCache.set(key, meta)
+    if (metaCache.size > 300) metaCache.delete(metaCache.keys().next().value!)
+  }
+  return meta
+}
+
+// A warning when the pack is made for a different game version than the
+// instance's, or nothing when it fits or either format is unknown.
+export function packFormatWarning(meta: ResourcePackMeta, minecraftVersion: string): string | undefined {
+  const expected = resourcePackFormat(minecraftVersion)
+  if (expected === undefined || meta.packFormat === undefined) return undefined
+  const [minimum, maximum] = meta.supported ?? [meta.packFormat, meta.packFormat]
+  if (expected >= minimum && expected <= maximum) return undefined
+  return maximum < expected
+    ? `Made for an older version of Minecraft (pack format ${meta.packFormat}; ${minecraftVersion} uses ${expected}).`
+    : `Made for a newer version of Minecraft (pack format ${meta.packFormat}; ${minecraftVersion} uses ${expected}).`
+}
@@ -0,0 +1,72 @@
+import os from 'node:os'
+
+export interface MojangRule {
+  action: 'allow' | 'disallow'
+  os?: { name?: string; version?: string; arch?: string }
+  features?: Record<string, boolean>
+}
+
+export interface RuleEnvironment {
+  name: 'windows' | 'osx' | 'linux'
+  version: string
+  arch: string
+  features: Record<string, boolean>
+}
+
+export function mojangOsName(platform: NodeJS.Platform = process.platform): RuleEnvironment['name'] {
+  if (platform === 'win32') return 'windows'
+  if (platform === 'darwin') return 'osx'
+  return 'linux'
+}
+
+export function mojangArch(arch: string = process.arch): string {
+  // Mojang uses "x86" for 32-bit Intel, and its newer manifests use "arm64".
+  if (arch === 'ia32') return 'x86'
+  if (arch === 'x64') return 'x86_64'
+  return arch
+}
+
+export function currentRuleEnvironment(features: Record<string, boolean> = {}): RuleEnvironment {
+  return { name: mojangOsName(), version: os.release(), arch: mojangArch(), features }
+}
+
+function archMatches(expected: string, actual: string): boolean {
+  if (expected === actual) return true
+  // Older manifests only distinguish "x86" from everything else.
+  if (expected === 'x86_64' || expected === 'amd64') return actual === 'x86_64'
+  if (expected === 'aarch64') return actual === 'arm64'
+  return false
+}
+
+function versionMatches(pattern: string, version: string): boolean {
+  try {
+    return new RegExp(pattern).test(version)
+  } catch {
+    return false
+  }
+}
+
+function ruleMatches(rule: MojangRule, environment: RuleEnvironment): boolean {
+  if (rule.os) {
+    if (rule.os.name && rule.os.name !== environment.name) return false
+    if (rule.os.arch && !archMatches(rule.os.arch, environment.arch)) return false
+    if (rule.os.version && !versionMatches(rule.os.version, environment.version)) return false
+  }
+  if (rule.features) {
+    for (const [feature, expected] of Object.entries(rule.features)) {
+      if (Boolean(environment.features[feature]) !== expected) return false
+    }
+  }
+  return true
+}
+
+// Follows the vanilla launcher: with no rules everything is allowed, otherwise
+// the last matching rule decides and nothing is allowed when no rule matches.
+export function rulesAllow(rules: unknown[] | undefined, environment: RuleEnvironment = currentRuleEnvironment()): boolean {
+  if (!rules?.length) return true
+  let allowed = false
+  for (const rule of rules as MojangRule[]) {
+    if (ruleMatches(rule, environment)) allowed = rule.action === 'allow'
+  }
+  return allowed
+}
@@ -0,0 +1,52 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import type { LauncherInstance } from '../types'
+import { FABRIC_API_PROJECT, listMods } from './modrinth'
+import { metadataDirectory, modsDirectory } from './paths'
+
+function journalPath(instance: LauncherInstance): string {
+  return path.join(metadataDirectory(instance.slug), 'safe-mode.json')
+}
+
+// Renames every enabled mod to .jar.disabled for one launch. The renamed files
+// are journalled first, so mods are still restored if the launcher closes
+// while the game is running. MegaClient instances keep the runtime and Fabric
+// API they cannot start without.
+export async function disableModsForSafeMode(instance: LauncherInstance, keep: string[] = []): Promise<string[]> {
+  await restoreSafeModeMods(instance)
+  const directory = modsDirectory(instance.slug)
+  const required = new Set(keep)
+  if (instance.customClient) {
+    for (const mod of await listMods(instance.id)) if (mod.projectId === FABRIC_API_PROJECT) required.add(mod.fileName)
+  }
+  const names = (await fs.readdir(directory).catch(() => [] as string[]))
+    .filter((name) => name.endsWith('.jar') && !name.startsWith('mc-runtime-') && !required.has(name))
+  const disabled: string[] = []
+  for (const name of names) {
+    if (await fs.stat(path.join(directory, `${name}.disabled`)).catch(() => null)) continue
+    disabled.push(name)
+  }
+  await fs.mkdir(metadataDirectory(instance.slug), { recursive: true })
+  await fs.writeFile(journalPath(instance), JSON.stringify({ disabled }, null, 2), 'utf8')
+  for (const name of disabled) await fs.rename(path.join(directory, name), path.join(directory, `${name}.disabled`))
+  return disabled
+}
+
+export async function restoreSafeModeMods(instance: LauncherInstance): Promise<number> {
+  let journal: { disabled?: string[] }
+  try {
+    journal = JSON.parse(await fs.readFile(journalPath(instance), 'utf8')) as { disabled?: string[] }
+  } catch {
+    return 0
+  }
+  const directory = modsDirectory(instance.slug)
+  let restored = 0
+  for (const name of journal.disabled ?? []) {
+    if (path.basename(name) !== name || !name.endsWith('.jar')) continue
+    if (await fs.stat(path.join(directory, name)).catch(() => null)) continue
+    const moved = await fs.rename(path.join(directory, `${name}.disabled`), path.join(directory, name)).then(() => true, () => false)
+    if (moved) restored++
+  }
+  await fs.rm(journalPath(instance), { force: true })
+  return restored
+}
@@ -0,0 +1,111 @@
+import { clipboard, nativeImage, type NativeImage } from 'electron'
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import type { ScreenshotSummary } from '../types'
+import { getInstance } from './instances'
+import { fetchWithTimeout } from './net'
+import { screenshotsDirectory } from './paths'
+import { store } from './store'
+
+const IMAGE_TYPES: Record<string, string> = { '.png': 'image/png', '.jpg': 'image/jpeg', '.jpeg': 'image/jpeg' }
+// Only the newest are listed; older screenshots stay in the folder.
+const MAX_LISTED = 200
+const THUMBNAIL_WIDTH = 320
+// Larger files are refused rather than sent to the image host.
+const MAX_UPLOAD_BYTES = 32 * 1024 * 1024
+
+const thumbnailCache = new Map<string, string>()
+
+// Windows and macOS make thumbnails without decoding the whole image; Linux
+// has no such call, so the image is read and scaled down.
+async function readThumbnail(file: string): Promise<NativeImage> {
+  if (process.platform === 'win32' || process.platform === 'darwin') {
+    return nativeImage.createThumbnailFromPath(file, { width: THUMBNAIL_WIDTH, height: THUMBNAIL_WIDTH })
+  }
+  const image = nativeImage.createFromPath(file)
+  return image.isEmpty() ? image : image.resize({ width: THUMBNAIL_WIDTH, quality: 'good' })
+}
+
+async function thumbnail(file: string, size: number, modifiedAt: number): Promise<string | undefined> {
+  const key = `${file}:${size}:${modifiedAt}`
+  const cached = thumbnailCache.get(key)
+  if (cached) return cached
+  const image = await readThumbnail(file).catch(() => null)
+  if (!image || image.isEmpty()) return undefined
+  const url = image.toDataURL()
+  thumbnailCache.set(key, url)
+  if (thumbnailCache.size > MAX_LISTED * 2) thumbnailCache.delete(thumbnailCache.keys().next().value!)
+  return url
+}
+
+// A screenshot directly inside the instance's screenshots folder.
+export async function screenshotFile(instanceId: string, fileName: string): Promise<string> {
+  const instance = getInstance(instanceId)
+  if (!fileName || path.basename(fileName) !== fileName || !IMAGE_TYPES[path.extname(fileName).toLowerCase()]) {
+    throw new Error('That screenshot could not be found.')
+  }
+  const file = path.join(screenshotsDirectory(instance.slug), fileName)
+  if (!(await fs.stat(file).catch(() => null))?.isFile()) throw new Error('That screenshot could not be found.')
+  return file
+}
+
+export async function screenshotsFolder(instanceId: string): Promise<string> {
+  const directory = screenshotsDirectory(getInstance(instanceId).slug)
+  await fs.mkdir(directory, { recursive: true })
+  return directory
+}
+
+// Lists the instance's screenshots, newest first, with a small preview of each.
+export async function listScreenshots(instanceId: string): Promise<ScreenshotSummary[]> {
+  const directory = screenshotsDirectory(getInstance(instanceId).slug)
+  const names = (await fs.readdir(directory).catch(() => [] as string[])).filter((name) => IMAGE_TYPES[path.extname(name).toLowerCase()])
+  const files = await Promise.all(names.map(async (fileName) => ({ fileName, stat: await fs.stat(path.join(directory, fileName)).catch(() => null) })))
+  const newest = files
+    .filter((file) => file.stat?.isFile())
+    .sort((a, b) => b.stat!.mtimeMs - a.stat!.mtimeMs)
+    .slice(0, MAX_LISTED)
+  return Promise.all(newest.map(async ({ fileName, stat }) => ({
+    fileName,
+    takenAt: stat!.mtime.toISOString(),
+    sizeBytes: stat!.size,
+    thumbnailUrl: await thumbnail(path.join(directory, fileName), stat!.size, stat!.mtimeMs)
+  })))
+}
+
+export async function copyScreenshot(instanceId: string, fileName: string): Promise<void> {
+  const image = nativeImage.createFromPath(await screenshotFile(instanceId, fileName))
+  if (image.isEmpty()) throw new Error('The screenshot could not be read.')
+  clipboard.writeImage(image)
+}
+
+function valueAt(value: unknown, keyPath: string): unknown {
+  return keyPath.split('.').reduce<unknown>((current, key) => current && typeof current === 'object' ? (current as Record<string, unknown>)[key] : undefined, value)
+}
+
+// Posts the screenshot to the image host set in Settings and returns the link
+// it answers with. The link is also copied to the clipboard.
+export async function uploadScreenshot(instanceId: string, fileName: string): Promise<string> {
+  const { screenshotUploadUrl, screenshotUploadField, screenshotUploadUrlPath } = store.getData().settings
+  if (!screenshotUploadUrl) throw new Error('Set an image host in Settings to upload screenshots.')
+  const file = await screenshotFile(instanceId, fileName)
+  const stat = await fs.stat(file)
+  if (stat.size > MAX_UPLOAD_BYTES) throw new Error('This screenshot is too large to upload.')
+  const form = new FormData()
+  form.append(screenshotUploadField, new Blob([await fs.readFile(file)], { type: IMAGE_TYPES[path.extname(file).toLowerCase()] }), fileName)
+  const key = await store.loadScreenshotUploadKey()
+  const response = await fetchWithTimeout(screenshotUploadUrl, { method: 'POST', body: form, headers: key ? { Authorization: key } : undefined }, 120_000)
+  const text = (await response.text()).trim()
+  let link: unknown = text
+  if (screenshotUploadUrlPath) {
+    try {
+      link = valueAt(JSON.parse(text), screenshotUploadUrlPath)
+    } catch {
+      throw new Error('The image host did not answer with JSON. Check the response link path in Settings.')
+    }
+  }
+  if (typeof link !== 'string' || !/^https?:\/\/\S+$/i.test(link.trim())) {
+    throw new Error('The image host did not return a link. Check the response link path in Settings.')
+  }
+  clipboard.writeText(link.trim())
+  return link.trim()
+}
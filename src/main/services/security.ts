@@ -86,6 +86,15 @@ function nativeModuleIdentity(module: ModuleInfo): { label: string; name: string
   return null
 }
 
+async function listUnixProcesses(): Promise<ProcessInfo[]> {
+  const { stdout } = await execFileAsync('ps', ['-axww', '-o', 'pid=,ucomm=,args='], { maxBuffer: 8 * 1024 * 1024, timeout: 12_000 })
+  return stdout.split('\n').flatMap((line) => {
+    const match = /^\s*(\d+)\s+(\S+)\s+(.*)$/.exec(line)
+    return match ? [{ ProcessId: Number(match[1]), Name: match[2], CommandLine: match[3] }] : []
+  })
+}
+
+// Launch protection only runs on Windows, so elsewhere there is nothing to scan.
 async function listProcesses(force = false): Promise<ProcessInfo[]> {
   if (process.platform !== 'win32') return []
   if (!force && processCache && processCache.expiresAt > Date.now()) return processCache.values
@@ -139,14 +148,18 @@ export async function scanRunningTools(): Promise<SecurityFinding[]> {
   return findings
 }
 
+// Finding the game's own processes is needed everywhere, to stop it and show
+// its resource use, so this has its own listing on macOS and Linux.
 export async function findGameJavaProcesses(instance: LauncherInstance): Promise<ProcessInfo[]> {
-  const processes = await listProcesses(true).catch(() => [])
+  const processes = await (process.platform === 'win32' ? listProcesses(true) : listUnixProcesses()).catch(() => [])
   const root = normalise(emlRootDirectory())
-  const slug = normalise(instance.slug)
+  // The instance folder must end where the slug does, so my-pack does not also
+  // match a running my-pack-2.
+  const escaped = `${root}/${normalise(instance.slug)}`.replace(/[.*+?^${}()|[\]\\]/g, '\\$&')
+  const folder = new RegExp(`${escaped}(?![\\w.-])`)
   return processes.filter((item) => {
     const name = normalise(item.Name)
-    const command = normalise(item.CommandLine)
-    return (name === 'java.exe' || name === 'javaw.exe') && command.includes(root) && command.includes(slug)
+    return (name === 'java' || name === 'java.exe' || name === 'javaw.exe') && folder.test(normalise(item.CommandLine))
   })
 }
 
@@ -186,6 +199,16 @@ async function listModules(processIds: number[]): Promise<ModuleInfo[]> {
   return Array.isArray(parsed) ? parsed : [parsed]
 }
 
+export function userJvmArguments(raw: string | undefined): string[] {
+  const values = (raw ?? '').match(/(?:[^\s"]+|"[^"]*")+/g) ?? []
+  const args = values.map((value) => value.replace(/"/g, '')).filter(Boolean)
+  for (const arg of args) {
+    const marker = firstMarker(normalise(arg), FORBIDDEN_JVM_ARGUMENTS)
+    if (marker) throw new Error(`The JVM argument ${marker} is not allowed by launch protection.`)
+  }
+  return args
+}
+
 export async function scanLoadedModules(processIds: number[]): Promise<SecurityFinding[]> {
   const modules = await listModules(processIds)
   const findings: SecurityFinding[] = []
@@ -202,10 +225,45 @@ export async function scanLoadedModules(processIds: number[]): Promise<SecurityF
   return findings
 }
 
+function isAlive(processId: number): boolean {
+  try {
+    process.kill(processId, 0)
+    return true
+  } catch (error) {
+    return (error as NodeJS.ErrnoException).code === 'EPERM'
+  }
+}
+
+// How long Minecraft gets to save and exit after SIGTERM before it is killed.
+const TERMINATE_GRACE_MS = 10_000
+
 export async function terminateProcesses(processIds: number[]): Promise<void> {
-  if (process.platform !== 'win32') return
-  for (const processId of [...new Set(processIds.filter((id) => Number.isInteger(id) && id > 0))]) {
-    await execFileAsync('taskkill.exe', ['/PID', String(processId), '/T', '/F'], { windowsHide: true }).catch(() => undefined)
+  const ids = [...new Set(processIds.filter((id) => Number.isInteger(id) && id > 0))]
+  if (process.platform === 'win32') {
+    for (const processId of ids) {
+      await execFileAsync('taskkill.exe', ['/PID', String(processId), '/T', '/F'], { windowsHide: true }).catch(() => undefined)
+    }
+    return
+  }
+  for (const processId of ids) {
+    try {
+      process.kill(processId, 'SIGTERM')
+    } catch {
+      // The process has already exited.
+    }
+  }
+  const deadline = Date.now() + TERMINATE_GRACE_MS
+  let remaining = ids.filter(isAlive)
+  while (remaining.length && Date.now() < deadline) {
+    await new Promise((resolve) => setTimeout(resolve, 250))
+    remaining = remaining.filter(isAlive)
+  }
+  for (const processId of remaining) {
+    try {
+      process.kill(processId, 'SIGKILL')
+    } catch {
+      // The process exited during the grace period.
+    }
   }
 }
 
@@ -215,16 +273,24 @@ export async function runPreflightSecurity(instance: LauncherInstance): Promise<
   if (finding) throw new Error(`${finding.title}: ${finding.detail}`)
 }
 
+const JAVA_OPTION_VARIABLES = ['JAVA_TOOL_OPTIONS', '_JAVA_OPTIONS', 'JDK_JAVA_OPTIONS'] as const
+let secureEnvironmentUsers = 0
+let savedJavaOptions = new Map<string, string | undefined>()
+
+// Concurrent launches share process.env, so the variables are only restored
+// once the last launch that cleared them has finished.
 export function secureChildEnvironment(): () => void {
-  const names = ['JAVA_TOOL_OPTIONS', '_JAVA_OPTIONS', 'JDK_JAVA_OPTIONS'] as const
-  const previous = new Map<string, string | undefined>()
-  for (const name of names) {
-    previous.set(name, process.env[name])
-    delete process.env[name]
+  if (secureEnvironmentUsers++ === 0) {
+    savedJavaOptions = new Map(JAVA_OPTION_VARIABLES.map((name) => [name, process.env[name]]))
+    for (const name of JAVA_OPTION_VARIABLES) delete process.env[name]
   }
+  let restored = false
   return () => {
-    for (const name of names) {
-      const value = previous.get(name)
+    if (restored) return
+    restored = true
+    if (--secureEnvironmentUsers > 0) return
+    for (const name of JAVA_OPTION_VARIABLES) {
+      const value = savedJavaOptions.get(name)
       if (value === undefined) delete process.env[name]
       else process.env[name] = value
     }
@@ -177,6 +177,16 @@ async function queryStatus(address: string): Promise<PartnerServerStatus> {
   }
 }
 
+// Recent status results carry each server's icon as a data URL.
+export function serverStatusCacheSize(): { bytes: number; files: number } {
+  const icons = [...statusCache.values()].map((entry) => entry.value.icon).filter((icon): icon is string => Boolean(icon))
+  return { bytes: icons.reduce((total, icon) => total + Buffer.byteLength(icon), 0), files: icons.length }
+}
+
+export function clearServerStatusCache(): void {
+  statusCache.clear()
+}
+
 export async function getPartnerServerStatus(address: string, force = false): Promise<PartnerServerStatus> {
   const key = address.trim().toLowerCase()
   const cached = statusCache.get(key)
@@ -0,0 +1,93 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import type { DiskUsageCategory, InstanceDiskUsage } from '../types'
+import { getInstance } from './instances'
+import { instanceDirectory, sharedDirectory, worldBackupsDirectory } from './paths'
+import { store } from './store'
+
+const CATEGORY_BY_ENTRY: Record<string, DiskUsageCategory> = {
+  saves: 'worlds',
+  backups: 'worlds',
+  mods: 'mods',
+  resourcepacks: 'packs',
+  shaderpacks: 'packs',
+  texturepacks: 'packs',
+  assets: 'assets',
+  resources: 'assets',
+  libraries: 'libraries',
+  versions: 'versions',
+  natives: 'versions',
+  logs: 'logs',
+  'crash-reports': 'logs',
+  debug: 'logs'
+}
+
+function emptyCategories(): Record<DiskUsageCategory, number> {
+  return { worlds: 0, mods: 0, packs: 0, assets: 0, libraries: 0, versions: 0, logs: 0, other: 0 }
+}
+
+// Symbolic links are not followed, so shared folders are measured once at
+// their source rather than again inside every instance that links them.
+export async function directorySize(target: string): Promise<number> {
+  const stat = await fs.lstat(target).catch(() => null)
+  if (!stat || stat.isSymbolicLink()) return 0
+  if (!stat.isDirectory()) return stat.size
+  let total = 0
+  for (const entry of await fs.readdir(target).catch(() => [] as string[])) total += await directorySize(path.join(target, entry))
+  return total
+}
+
+function categoryOf(entry: string): DiskUsageCategory {
+  return CATEGORY_BY_ENTRY[entry.toLowerCase()] ?? (/^hs_err_pid\d+\.log$/i.test(entry) ? 'logs' : 'other')
+}
+
+async function linksToShared(link: string, kind: string): Promise<boolean> {
+  const stat = await fs.lstat(link).catch(() => null)
+  if (!stat?.isSymbolicLink()) return false
+  const [target, shared] = await Promise.all([fs.realpath(link).catch(() => ''), fs.realpath(sharedDirectory(kind)).catch(() => null)])
+  return target === shared
+}
+
+// Shared saves, resource packs, libraries and assets are split evenly between
+// the instances that link them.
+async function sharedShare(kind: string): Promise<number> {
+  let users = 0
+  for (const instance of store.getData().instances) {
+    if (!instance.archivedAt && !instance.linkedDirectory && await linksToShared(path.join(instanceDirectory(instance.slug), kind), kind)) users++
+  }
+  return users ? Math.round(await directorySize(sharedDirectory(kind)) / users) : 0
+}
+
+// Breaks an instance's footprint down for the storage view.
+export async function getInstanceDiskUsage(id: string): Promise<InstanceDiskUsage> {
+  const instance = getInstance(id)
+  const root = instance.linkedDirectory ?? instanceDirectory(instance.slug)
+  const categories = emptyCategories()
+  let sharedBytes = 0
+  for (const entry of await fs.readdir(root).catch(() => [] as string[])) {
+    if (await linksToShared(path.join(root, entry), entry)) {
+      const share = await sharedShare(entry)
+      categories[categoryOf(entry)] += share
+      sharedBytes += share
+      continue
+    }
+    const size = await directorySize(path.join(root, entry))
+    if (entry === '.megaclient') {
+      // MegaClient's own metadata, apart from the launch logs it keeps there.
+      const launchLogs = await directorySize(path.join(root, entry, 'launcher_logs'))
+      categories.logs += launchLogs
+      categories.other += size - launchLogs
+      continue
+    }
+    categories[categoryOf(entry)] += size
+  }
+  // World backups are kept outside the instance folder but still count as its worlds.
+  categories.worlds += await directorySize(worldBackupsDirectory(instance.id))
+  return {
+    instanceId: instance.id,
+    totalBytes: Object.values(categories).reduce((sum, value) => sum + value, 0),
+    sharedBytes,
+    categories,
+    measuredAt: new Date().toISOString()
+  }
+}
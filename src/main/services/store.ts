@@ -11,13 +11,26 @@ const defaultSettings: LauncherSettings = {
   height: 720,
   fullscreen: false,
   showConsole: true,
-  minimizeToTrayOnLaunch: true,
+  launcherVisibility: 'tray',
   showSnapshots: false,
+  showLegacyVersions: false,
   javaMode: 'auto',
   javaPath: '',
   checkUpdates: true,
   discordActivity: true,
-  reducedMotion: false
+  reducedMotion: false,
+  defaultJvmArgs: '',
+  defaultLoader: 'vanilla',
+  logLevel: 'info',
+  downloadSpeedLimit: 0,
+  downloadConcurrency: 6,
+  offlineMode: false,
+  backgroundPrepare: false,
+  meteredConnection: 'auto',
+  contentUpdateCheckHours: 0,
+  screenshotUploadUrl: '',
+  screenshotUploadField: 'file',
+  screenshotUploadUrlPath: ''
 }
 
 const defaultData: StoredData = { settings: defaultSettings, instances: [] }
@@ -60,9 +73,44 @@ function normaliseSettings(input: unknown): LauncherSettings {
   settings.height = Math.max(360, Math.min(4320, Number(settings.height) || defaultSettings.height))
   settings.javaPath = typeof settings.javaPath === 'string' ? settings.javaPath.trim() : ''
   if (settings.javaMode !== 'manual') settings.javaMode = 'auto'
+  settings.defaultJvmArgs = typeof settings.defaultJvmArgs === 'string' ? settings.defaultJvmArgs.trim().slice(0, 4_000) : ''
+  if (!['vanilla', 'forge', 'neoforge', 'fabric'].includes(settings.defaultLoader)) settings.defaultLoader = 'vanilla'
+  if (!['error', 'warn', 'info', 'debug'].includes(settings.logLevel)) settings.logLevel = 'info'
+  // KB/s, where 0 means unlimited.
+  const speedLimit = Math.floor(Number(settings.downloadSpeedLimit) || 0)
+  settings.downloadSpeedLimit = speedLimit > 0 ? Math.max(64, Math.min(1_000_000, speedLimit)) : 0
+  settings.downloadConcurrency = Math.max(1, Math.min(16, Math.floor(Number(settings.downloadConcurrency)) || defaultSettings.downloadConcurrency))
+  if (!['keep-open', 'minimize', 'tray', 'close'].includes(settings.launcherVisibility)) {
+    // Settings saved before the visibility choice only had the tray toggle.
+    settings.launcherVisibility = candidate.minimizeToTrayOnLaunch === false ? 'keep-open' : 'tray'
+  }
+  if (!['auto', 'metered', 'unmetered'].includes(settings.meteredConnection)) settings.meteredConnection = 'auto'
+  if (![0, 6, 24].includes(settings.contentUpdateCheckHours)) settings.contentUpdateCheckHours = 0
+  settings.screenshotUploadUrl = typeof settings.screenshotUploadUrl === 'string' && /^https:\/\/\S+$/i.test(settings.screenshotUploadUrl.trim())
+    ? settings.screenshotUploadUrl.trim().slice(0, 2_000)
+    : ''
+  settings.screenshotUploadField = typeof settings.screenshotUploadField === 'string' && /^[\w.-]{1,64}$/.test(settings.screenshotUploadField.trim())
+    ? settings.screenshotUploadField.trim()
+    : 'file'
+  settings.screenshotUploadUrlPath = typeof settings.screenshotUploadUrlPath === 'string' && /^[\w-]+(?:\.[\w-]+)*$/.test(settings.screenshotUploadUrlPath.trim())
+    ? settings.screenshotUploadUrlPath.trim().slice(0, 200)
+    : ''
   return settings
 }
 
+// Secrets are encrypted with the operating system's key store where it is
+// available, and only base64-encoded where it is not.
+function seal(text: string): AccountEnvelope {
+  return safeStorage.isEncryptionAvailable()
+    ? { encrypted: true, value: safeStorage.encryptString(text).toString('base64') }
+    : { encrypted: false, value: Buffer.from(text, 'utf8').toString('base64') }
+}
+
+function unseal(envelope: AccountEnvelope): string {
+  const bytes = Buffer.from(envelope.value, 'base64')
+  return envelope.encrypted && safeStorage.isEncryptionAvailable() ? safeStorage.decryptString(bytes) : bytes.toString('utf8')
+}
+
 class Store {
   private data: StoredData = structuredClone(defaultData)
   private saveQueue: Promise<void> = Promise.resolve()
@@ -75,12 +123,21 @@ class Store {
     return path.join(dataDirectory(), 'account.json')
   }
 
+  private get modrinthTokenFile(): string {
+    return path.join(dataDirectory(), 'modrinth.json')
+  }
+
+  private get screenshotUploadKeyFile(): string {
+    return path.join(dataDirectory(), 'screenshot-upload.json')
+  }
+
   async initialize(): Promise<void> {
     const loaded = await readJson<Partial<StoredData>>(this.dataFile, {})
     this.data = {
       settings: normaliseSettings(loaded.settings),
       instances: Array.isArray(loaded.instances) ? loaded.instances : [],
-      selectedInstanceId: loaded.selectedInstanceId
+      selectedInstanceId: loaded.selectedInstanceId,
+      firstRunImportOffered: loaded.firstRunImportOffered === true || undefined
     }
     await this.save()
   }
@@ -113,6 +170,11 @@ class Store {
     await this.save()
   }
 
+  async markFirstRunImportOffered(): Promise<void> {
+    this.data.firstRunImportOffered = true
+    await this.save()
+  }
+
   async save(): Promise<void> {
     const snapshot = structuredClone(this.data)
     this.saveQueue = this.saveQueue.catch(() => undefined).then(() => atomicWrite(this.dataFile, snapshot))
@@ -120,25 +182,14 @@ class Store {
   }
 
   async saveAccount(account: AuthenticatedAccount): Promise<void> {
-    const raw = Buffer.from(JSON.stringify(account), 'utf8')
-    let envelope: AccountEnvelope
-    if (safeStorage.isEncryptionAvailable()) {
-      envelope = { encrypted: true, value: safeStorage.encryptString(raw.toString('utf8')).toString('base64') }
-    } else {
-      envelope = { encrypted: false, value: raw.toString('base64') }
-    }
-    await atomicWrite(this.accountFile, envelope)
+    await atomicWrite(this.accountFile, seal(JSON.stringify(account)))
   }
 
   async loadAccount(): Promise<AuthenticatedAccount | null> {
     const envelope = await readJson<AccountEnvelope | null>(this.accountFile, null)
     if (!envelope) return null
     try {
-      const bytes = Buffer.from(envelope.value, 'base64')
-      const json = envelope.encrypted && safeStorage.isEncryptionAvailable()
-        ? safeStorage.decryptString(bytes)
-        : bytes.toString('utf8')
-      return JSON.parse(json) as AuthenticatedAccount
+      return JSON.parse(unseal(envelope)) as AuthenticatedAccount
     } catch {
       return null
     }
@@ -147,6 +198,43 @@ class Store {
   async clearAccount(): Promise<void> {
     await fs.rm(this.accountFile, { force: true })
   }
+
+  async saveModrinthToken(token: string): Promise<void> {
+    await atomicWrite(this.modrinthTokenFile, seal(token))
+  }
+
+  async loadModrinthToken(): Promise<string | null> {
+    const envelope = await readJson<AccountEnvelope | null>(this.modrinthTokenFile, null)
+    if (!envelope) return null
+    try {
+      return unseal(envelope) || null
+    } catch {
+      return null
+    }
+  }
+
+  async clearModrinthToken(): Promise<void> {
+    await fs.rm(this.modrinthTokenFile, { force: true })
+  }
+
+  // The Authorization header sent with screenshot uploads.
+  async saveScreenshotUploadKey(value: string): Promise<void> {
+    await atomicWrite(this.screenshotUploadKeyFile, seal(value))
+  }
+
+  async loadScreenshotUploadKey(): Promise<string | null> {
+    const envelope = await readJson<AccountEnvelope | null>(this.screenshotUploadKeyFile, null)
+    if (!envelope) return null
+    try {
+      return unseal(envelope) || null
+    } catch {
+      return null
+    }
+  }
+
+  async clearScreenshotUploadKey(): Promise<void> {
+    await fs.rm(this.screenshotUploadKeyFile, { force: true })
+  }
 }
 
 export const store = new Store()
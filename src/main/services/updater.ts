@@ -1,5 +1,7 @@
 import { app, net, powerMonitor } from 'electron'
 import electronUpdater, { type AppUpdater } from 'electron-updater'
+import { logger } from './logger'
+import { isOfflineMode } from './connectivity'
 
 const { autoUpdater } = electronUpdater as { autoUpdater: AppUpdater }
 
@@ -111,7 +113,7 @@ function installListeners(): void {
     publish({ state: 'ready', version: info.version, percent: 100, transferred: state.total, checkedAt: new Date().toISOString() })
   })
   autoUpdater.on('error', (error) => {
-    console.warn('[MegaClient] Automatic update error:', error)
+    logger.warn('Automatic update error.', { error })
     publish({ state: 'error', message: friendlyUpdateError(error), checkedAt: new Date().toISOString() })
     schedule(RETRY_INTERVAL)
   })
@@ -147,6 +149,11 @@ export async function checkForUpdates(reason: 'manual' | 'startup' | 'scheduled'
     return state
   }
 
+  if (isOfflineMode()) {
+    publish({ state: 'offline', message: 'Offline mode is on', checkedAt: new Date().toISOString() })
+    return state
+  }
+
   if (!net.isOnline()) {
     publish({ state: 'offline', message: 'Waiting for an internet connection', checkedAt: new Date().toISOString() })
     schedule(RETRY_INTERVAL)
@@ -157,7 +164,7 @@ export async function checkForUpdates(reason: 'manual' | 'startup' | 'scheduled'
   publish({ state: 'checking', checkedAt: new Date(now).toISOString() })
   checkInFlight = autoUpdater.checkForUpdates()
     .catch((error: unknown) => {
-      console.warn('[MegaClient] Update check failed:', error)
+      logger.warn('Update check failed.', { error })
       publish({ state: 'error', message: friendlyUpdateError(error), checkedAt: new Date().toISOString() })
       schedule(RETRY_INTERVAL)
       return state
@@ -0,0 +1,181 @@
+import fs from 'node:fs/promises'
+import path from 'node:path'
+import { currentRuleEnvironment, rulesAllow, type RuleEnvironment } from './rules'
+
+export type VersionArgument = string | { rules?: unknown[]; value: string | string[] }
+
+export interface VersionLibrary {
+  name: string
+  downloads?: unknown
+  rules?: unknown[]
+  natives?: Record<string, string>
+  extract?: { exclude?: string[] }
+  // A Maven repository, for loader libraries without a downloads entry.
+  url?: string
+  sha1?: string
+  size?: number
+}
+
+// A library's downloadable jar, as its downloads entry lists it.
+export interface LibraryArtifact {
+  path?: string
+  sha1?: string
+  size?: number
+  url?: string
+}
+
+export interface VersionJson {
+  id: string
+  inheritsFrom?: string
+  type?: string
+  mainClass?: string
+  minecraftArguments?: string
+  arguments?: { game?: VersionArgument[]; jvm?: VersionArgument[] }
+  assets?: string
+  assetIndex?: { id: string; sha1?: string; size?: number; totalSize?: number; url?: string }
+  downloads?: Record<string, { sha1?: string; size?: number; url: string }>
+  javaVersion?: { component?: string; majorVersion?: number }
+  libraries?: VersionLibrary[]
+  releaseTime?: string
+  time?: string
+  [key: string]: unknown
+}
+
+const MAXIMUM_CHAIN_LENGTH = 16
+
+// Paths in profiles and asset indexes come from downloaded JSON, so they are
+// resolved inside root and refused when they would leave it.
+export function containedPath(root: string, relative: string): string | null {
+  const destination = path.resolve(root, relative)
+  return destination.startsWith(`${path.resolve(root)}${path.sep}`) ? destination : null
+}
+
+export async function readJsonFile<T>(file: string): Promise<T | null> {
+  try {
+    return JSON.parse(await fs.readFile(file, 'utf8')) as T
+  } catch {
+    return null
+  }
+}
+
+export function versionJsonPath(gameDirectory: string, id: string): string {
+  return path.join(gameDirectory, 'versions', id, `${id}.json`)
+}
+
+export async function readVersionJson(gameDirectory: string, id: string): Promise<VersionJson | null> {
+  return readJsonFile<VersionJson>(versionJsonPath(gameDirectory, id))
+}
+
+function libraryKey(library: VersionLibrary): string {
+  // group:artifact plus classifier, without the version, so a child can
+  // replace a parent's library with a newer build of the same artifact.
+  const [group, artifact, , classifier] = library.name.split(':')
+  return [group, artifact, classifier ?? ''].join(':')
+}
+
+export function mergeVersionJson(parent: VersionJson, child: VersionJson): VersionJson {
+  const libraries: VersionLibrary[] = []
+  const seen = new Set<string>()
+  for (const library of [...(child.libraries ?? []), ...(parent.libraries ?? [])]) {
+    const key = libraryKey(library)
+    if (seen.has(key)) continue
+    seen.add(key)
+    libraries.push(library)
+  }
+
+  const merged: VersionJson = { ...parent, ...child, libraries }
+  if (parent.arguments || child.arguments) {
+    merged.arguments = {
+      game: [...(parent.arguments?.game ?? []), ...(child.arguments?.game ?? [])],
+      jvm: [...(parent.arguments?.jvm ?? []), ...(child.arguments?.jvm ?? [])]
+    }
+  }
+  if (child.downloads || parent.downloads) merged.downloads = { ...parent.downloads, ...child.downloads }
+  merged.inheritsFrom = parent.inheritsFrom
+  if (!merged.inheritsFrom) delete merged.inheritsFrom
+  return merged
+}
+
+// Loader profiles inherit from the vanilla version, and some modpack or custom
+// profiles inherit from another loader profile. Each level is merged in turn so
+// the result is the flat profile the vanilla launcher would run.
+export async function resolveVersionJson(
+  gameDirectory: string,
+  id: string,
+  load: (id: string) => Promise<VersionJson | null> = (versionId) => readVersionJson(gameDirectory, versionId)
+): Promise<VersionJson> {
+  const chain: VersionJson[] = []
+  const visited = new Set<string>()
+  let next: string | undefined = id
+  while (next) {
+    if (visited.has(next)) throw new Error(`The version profile ${id} has a circular inheritsFrom chain through ${next}.`)
+    if (chain.length >= MAXIMUM_CHAIN_LENGTH) throw new Error(`The version profile ${id} inherits from too many other profiles.`)
+    visited.add(next)
+    const current = await load(next)
+    if (!current) throw new Error(`The version profile ${next} required by ${id} is not installed.`)
+    chain.push(current)
+    next = current.inheritsFrom
+  }
+
+  let resolved = chain.pop()!
+  while (chain.length) resolved = mergeVersionJson(resolved, chain.pop()!)
+  return { ...resolved, id }
+}
+
+// Where a library lives under libraries/ by its Maven coordinates, for
+// loader profiles that list a repository URL instead of a download path.
+export function mavenLibraryPath(name: string): string | null {
+  const [coordinates, extension = 'jar'] = name.split('@')
+  const [group, artifact, version, classifier] = coordinates!.split(':')
+  if (!group || !artifact || !version) return null
+  return [...group.split('.'), artifact, version, `${artifact}-${version}${classifier ? `-${classifier}` : ''}.${extension}`].join('/')
+}
+
+export function applicableLibraries(version: VersionJson, environment: RuleEnvironment = currentRuleEnvironment()): VersionLibrary[] {
+  return (version.libraries ?? []).filter((library) => rulesAllow(library.rules, environment))
+}
+
+export function applicableArguments(
+  version: VersionJson,
+  kind: 'game' | 'jvm',
+  environment: RuleEnvironment = currentRuleEnvironment()
+): string[] {
+  if (!version.arguments) return kind === 'game' ? (version.minecraftArguments ?? '').split(' ').filter(Boolean) : []
+  const values: string[] = []
+  for (const argument of version.arguments[kind] ?? []) {
+    if (typeof argument === 'string') values.push(argument)
+    else if (rulesAllow(argument.rules, environment)) values.push(...(Array.isArray(argument.value) ? argument.value : [argument.value]))
+  }
+  return values
+}
+
+export function validateVersionJson(value: unknown): VersionJson {
+  const version = value as VersionJson
+  if (!version || typeof version !== 'object' || typeof version.id !== 'string') throw new Error('The version profile has no id.')
+  if (!/^[\w.+-]{1,80}$/.test(version.id)) throw new Error('The version profile id contains unsupported characters.')
+  if (!version.inheritsFrom && !version.mainClass) throw new Error('The version profile must declare a mainClass or inherit from another version.')
+  if (version.libraries !== undefined && !Array.isArray(version.libraries)) throw new Error('The version profile libraries are invalid.')
+  return version
+}
+
+export async function installVersionJson(gameDirectory: string, value: unknown): Promise<VersionJson> {
+  const version = validateVersionJson(value)
+  const destination = versionJsonPath(gameDirectory, version.id)
+  await fs.mkdir(path.dirname(destination), { recursive: true })
+  await fs.writeFile(destination, JSON.stringify(version, null, 2), 'utf8')
+  return version
+}
+
+// The last profile in the chain is the vanilla version everything builds on.
+export async function baseMinecraftVersion(gameDirectory: string, id: string, fallback?: (id: string) => Promise<VersionJson | null>): Promise<string> {
+  let current = await readVersionJson(gameDirectory, id)
+  const visited = new Set<string>()
+  while (current?.inheritsFrom) {
+    if (visited.has(current.id)) break
+    visited.add(current.id)
+    const parent = current.inheritsFrom
+    current = await readVersionJson(gameDirectory, parent) ?? await fallback?.(parent) ?? null
+    if (!current) return parent
+  }
+  return current?.id ?? id
+}
@@ -1,41 +1,129 @@
 import { XMLParser } from 'fast-xml-parser'
-import { fetchJson, fetchWithTimeout } from './net'
-import type { LoaderType } from '../types'
+import { fetchJson } from './net'
+import { fetchCachedJson, fetchCachedText } from './httpCache'
+import type { LoaderType, VersionPatchNotes, VersionTrack } from '../types'
+import type { VersionJson } from './versionJson'
 
 interface MojangManifest {
   latest: { release: string; snapshot: string }
-  versions: Array<{ id: string; type: string; url: string; releaseTime: string }>
+  versions: Array<{ id: string; type: string; url: string; sha1?: string; releaseTime: string }>
 }
 
+interface PatchNotesEntry {
+  title: string
+  version: string
+  type: string
+  date?: string
+  shortText?: string
+  contentPath?: string
+  image?: { url?: string }
+}
+
+const PATCH_NOTES_URL = 'https://launchercontent.mojang.com/v2'
+
 interface FabricLoaderEntry { loader: { version: string; stable: boolean } }
 
 let cachedManifest: MojangManifest | null = null
 let cachedAt = 0
 
-export async function getMinecraftVersions(includeSnapshots: boolean): Promise<Array<{ id: string; type: string }>> {
+async function minecraftManifest(): Promise<MojangManifest> {
   if (!cachedManifest || Date.now() - cachedAt > 10 * 60_000) {
-    cachedManifest = await fetchJson<MojangManifest>('https://piston-meta.mojang.com/mc/game/version_manifest_v2.json')
+    // The last manifest that downloaded stays usable offline, so the version
+    // list and launches of installed versions keep working.
+    cachedManifest = await fetchCachedJson<MojangManifest>('https://piston-meta.mojang.com/mc/game/version_manifest_v2.json', { staleIfError: true })
     cachedAt = Date.now()
   }
-  const cutoff = cachedManifest.versions.find((version) => version.id === '1.8.9')?.releaseTime
-  const minimumTime = cutoff ? Date.parse(cutoff) : Date.parse('2015-12-09T00:00:00Z')
-  return cachedManifest.versions
+  return cachedManifest
+}
+
+export async function minecraftVersionType(id: string): Promise<string | undefined> {
+  return (await minecraftManifest()).versions.find((version) => version.id === id)?.type
+}
+
+export async function minecraftReleaseTime(id: string): Promise<number | undefined> {
+  const releaseTime = (await minecraftManifest()).versions.find((version) => version.id === id)?.releaseTime
+  return releaseTime ? Date.parse(releaseTime) : undefined
+}
+
+const VERSION_ALIASES: Record<string, VersionTrack> = {
+  latest: 'release',
+  'latest-release': 'release',
+  'latest-snapshot': 'snapshot'
+}
+
+export function versionAliasTrack(id: string): VersionTrack | undefined {
+  return VERSION_ALIASES[id.trim().toLowerCase()]
+}
+
+// Resolves the aliases the official launcher understands to a concrete id.
+// Anything that is not an alias is returned unchanged.
+export async function resolveMinecraftVersionId(id: string): Promise<string> {
+  const track = versionAliasTrack(id)
+  if (!track) return id
+  const manifest = await minecraftManifest()
+  return track === 'snapshot' ? manifest.latest.snapshot : manifest.latest.release
+}
+
+// The body is the HTML Mojang publishes for the launcher's patch notes page.
+export async function getVersionPatchNotes(versionId: string): Promise<VersionPatchNotes | null> {
+  const id = await resolveMinecraftVersionId(versionId)
+  const index = await fetchCachedJson<{ entries?: PatchNotesEntry[] }>(`${PATCH_NOTES_URL}/javaPatchNotes.json`, { maxAgeMs: 30 * 60_000, staleIfError: true })
+  const entry = index.entries?.find((item) => item.version === id)
+  if (!entry) return null
+  const content = entry.contentPath && /^[\w./-]+\.json$/.test(entry.contentPath)
+    ? await fetchCachedJson<{ body?: string }>(`${PATCH_NOTES_URL}/${entry.contentPath}`, { maxAgeMs: 24 * 60 * 60_000, staleIfError: true }).catch(() => null)
+    : null
+  return {
+    version: entry.version,
+    title: entry.title,
+    type: entry.type,
+    date: entry.date,
+    imageUrl: entry.image?.url ? new URL(entry.image.url, 'https://launchercontent.mojang.com').toString() : undefined,
+    summary: entry.shortText,
+    body: content?.body ?? ''
+  }
+}
+
+// Where a version's JSON is published, with the SHA-1 of its exact bytes.
+export async function minecraftVersionEntry(id: string): Promise<{ url: string; sha1?: string } | undefined> {
+  const entry = (await minecraftManifest()).versions.find((version) => version.id === id)
+  return entry ? { url: entry.url, sha1: entry.sha1 } : undefined
+}
+
+export async function minecraftVersionJson(id: string): Promise<VersionJson | null> {
+  const entry = (await minecraftManifest()).versions.find((version) => version.id === id)
+  return entry ? fetchJson<VersionJson>(entry.url) : null
+}
+
+export async function getMinecraftVersions(includeSnapshots: boolean, includeLegacy = false): Promise<Array<{ id: string; type: string }>> {
+  const manifest = await minecraftManifest()
+  const cutoff = manifest.versions.find((version) => version.id === '1.8.9')?.releaseTime
+  const minimumTime = includeLegacy ? 0 : cutoff ? Date.parse(cutoff) : Date.parse('2015-12-09T00:00:00Z')
+  const types = new Set(['release', ...(includeSnapshots ? ['snapshot'] : []), ...(includeLegacy ? ['old_beta', 'old_alpha'] : [])])
+  return manifest.versions
     .filter((version) => Date.parse(version.releaseTime) >= minimumTime)
-    .filter((version) => includeSnapshots || version.type === 'release')
+    .filter((version) => types.has(version.type))
     .map(({ id, type }) => ({ id, type }))
 }
 
 export async function getLoaderVersions(loader: LoaderType, minecraftVersion: string): Promise<string[]> {
   if (loader === 'vanilla') return []
   if (loader === 'fabric') {
-    const entries = await fetchJson<FabricLoaderEntry[]>(`https://meta.fabricmc.net/v2/versions/loader/${encodeURIComponent(minecraftVersion)}`)
-    return entries.map((entry) => entry.loader.version)
+    const entries = await fetchCachedJson<FabricLoaderEntry[]>(`https://meta.fabricmc.net/v2/versions/loader/${encodeURIComponent(minecraftVersion)}`, { maxAgeMs: 5 * 60_000, staleIfError: true })
+    const snapshot = await minecraftVersionType(minecraftVersion).catch(() => undefined) === 'snapshot'
+    if (snapshot && !entries.length) {
+      throw new Error(`Fabric does not support the ${minecraftVersion} snapshot yet. New snapshots are usually supported within a few days.`)
+    }
+    // Releases recommend the newest stable loader. Snapshots often need a
+    // loader build that is not marked stable yet, so they keep the newest.
+    const ordered = snapshot ? entries : [...entries.filter((entry) => entry.loader.stable), ...entries.filter((entry) => !entry.loader.stable)]
+    return ordered.map((entry) => entry.loader.version)
   }
 
   const url = loader === 'forge'
     ? 'https://maven.minecraftforge.net/net/minecraftforge/forge/maven-metadata.xml'
     : 'https://maven.neoforged.net/releases/net/neoforged/neoforge/maven-metadata.xml'
-  const xml = await (await fetchWithTimeout(url)).text()
+  const xml = await fetchCachedText(url, { maxAgeMs: 30 * 60_000, staleIfError: true })
   const parser = new XMLParser({ ignoreAttributes: false })
   const parsed = parser.parse(xml) as { metadata?: { versioning?: { versions?: { version?: string | string[] } } } }
   const raw = parsed.metadata?.versioning?.versions?.version ?? []
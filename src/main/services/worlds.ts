@@ -2,10 +2,13 @@ import fs from 'node:fs/promises'
 import path from 'node:path'
 import { randomBytes } from 'node:crypto'
 import AdmZip from 'adm-zip'
-import type { WorldSummary } from '../types'
+import type { LauncherInstance, WorldBackup, WorldBackupPolicy, WorldGameMode, WorldSummary } from '../types'
 import { getInstance } from './instances'
-import { metadataDirectory, savesDirectory } from './paths'
-import { downloadFile } from './net'
+import { instanceDirectory, metadataDirectory, savesDirectory, worldBackupsDirectory } from './paths'
+import { download } from './downloads'
+import { readNbt } from './nbt'
+import { directorySize } from './storage'
+import { zipFolder } from './zipFolder'
 
 function safeName(value: string): string {
   const cleaned = value.replace(/[^a-z0-9 _.-]/gi, '').trim().replace(/[. ]+$/g, '')
@@ -28,6 +31,50 @@ async function uniqueDirectory(parent: string, requested: string): Promise<strin
   throw new Error('Could not create a unique folder for this world.')
 }
 
+const GAME_MODES: WorldGameMode[] = ['survival', 'creative', 'adventure', 'spectator']
+// icon.png files larger than this are not shown.
+const MAX_ICON_BYTES = 256 * 1024
+
+interface LevelData {
+  LevelName?: string
+  GameType?: number
+  hardcore?: number
+  LastPlayed?: number
+  Version?: { Name?: string }
+}
+
+async function readLevel(directory: string): Promise<Partial<WorldSummary>> {
+  try {
+    const data = (await readNbt(await fs.readFile(path.join(directory, 'level.dat')))).Data as LevelData | undefined
+    if (!data || typeof data !== 'object') return {}
+    const details: Partial<WorldSummary> = {}
+    if (typeof data.LevelName === 'string' && data.LevelName.trim()) details.name = data.LevelName.trim()
+    if (data.hardcore === 1) details.gameMode = 'hardcore'
+    else if (typeof data.GameType === 'number') details.gameMode = GAME_MODES[data.GameType]
+    if (typeof data.Version?.Name === 'string') details.version = data.Version.Name
+    if (typeof data.LastPlayed === 'number' && data.LastPlayed > 0) details.lastPlayed = new Date(data.LastPlayed).toISOString()
+    return details
+  } catch {
+    // An unreadable level.dat still lists the world under its folder name.
+    return {}
+  }
+}
+
+async function readIcon(directory: string): Promise<string | undefined> {
+  const icon = path.join(directory, 'icon.png')
+  const stat = await fs.stat(icon).catch(() => null)
+  if (!stat?.isFile() || stat.size > MAX_ICON_BYTES) return undefined
+  return `data:image/png;base64,${(await fs.readFile(icon)).toString('base64')}`
+}
+
+async function worldSummary(directory: string): Promise<WorldSummary> {
+  const folderName = path.basename(directory)
+  const [stat, level, iconUrl, sizeBytes] = await Promise.all([fs.stat(directory), readLevel(directory), readIcon(directory), directorySize(directory)])
+  return { id: folderName, name: folderName, folderName, modifiedAt: stat.mtime.toISOString(), ...level, sizeBytes, iconUrl }
+}
+
+// Lists the saves with the name, game mode, version and last played time
+// from each level.dat, most recently played first.
 export async function listWorlds(instanceId: string): Promise<WorldSummary[]> {
   const instance = getInstance(instanceId)
   const saves = savesDirectory(instance.slug)
@@ -37,15 +84,33 @@ export async function listWorlds(instanceId: string): Promise<WorldSummary[]> {
     const directory = path.join(saves, entry.name)
     try {
       await fs.access(path.join(directory, 'level.dat'))
-      const stat = await fs.stat(directory)
-      return { id: entry.name, name: entry.name, folderName: entry.name, modifiedAt: stat.mtime.toISOString() } satisfies WorldSummary
+      return await worldSummary(directory)
     } catch {
       return null
     }
   }))
-  return worlds.filter((world): world is WorldSummary => Boolean(world)).sort((a, b) => Date.parse(b.modifiedAt) - Date.parse(a.modifiedAt))
+  const playedAt = (world: WorldSummary) => Date.parse(world.lastPlayed ?? world.modifiedAt)
+  return worlds.filter((world): world is WorldSummary => Boolean(world)).sort((a, b) => playedAt(b) - playedAt(a))
+}
+
+// Archive entries that are never part of a world: Finder's resource forks
+// and the lock file the game holds while a world is open.
+function ignoredEntry(normal: string): boolean {
+  return normal.startsWith('__MACOSX/') || /(^|\/)(?:\._[^/]*|\.DS_Store|session\.lock)$/.test(normal)
+}
+
+// The world's level.dat closest to the top of the archive, so a world that
+// keeps its own backups inside still imports from its outer folder.
+function worldLevelEntry(entries: AdmZip.IZipEntry[]): AdmZip.IZipEntry | undefined {
+  const levels = entries
+    .map((entry) => ({ entry, normal: entry.entryName.replaceAll('\\', '/') }))
+    .filter(({ entry, normal }) => !entry.isDirectory && /(^|\/)level\.dat$/i.test(normal) && !ignoredEntry(normal))
+  return levels.sort((a, b) => a.normal.split('/').length - b.normal.split('/').length)[0]?.entry
 }
 
+// Imports a world ZIP whether the world's files sit at the top of the archive
+// or inside one or more folders. The level.dat is read before anything is
+// extracted, so a damaged world is refused instead of added.
 export async function importWorldZip(instanceId: string, archivePath: string): Promise<WorldSummary> {
   const instance = getInstance(instanceId)
   if (path.extname(archivePath).toLowerCase() !== '.zip') throw new Error('Choose a ZIP archive containing a Minecraft world.')
@@ -55,12 +120,23 @@ export async function importWorldZip(instanceId: string, archivePath: string): P
   const archive = new AdmZip(archivePath)
   const entries = archive.getEntries()
   if (!entries.length || entries.length > 100_000) throw new Error('This world archive is empty or contains too many files.')
-  const levelEntry = entries.find((entry) => !entry.isDirectory && /(^|\/)level\.dat$/i.test(entry.entryName.replaceAll('\\', '/')))
+  const levelEntry = worldLevelEntry(entries)
   if (!levelEntry) throw new Error('This ZIP does not contain a Minecraft level.dat file.')
+  let level: { LevelName?: unknown } | undefined
+  try {
+    level = (await readNbt(levelEntry.getData())).Data as { LevelName?: unknown } | undefined
+  } catch {
+    throw new Error('The level.dat in this ZIP is damaged, so the world cannot be imported.')
+  }
+  if (!level || typeof level !== 'object') throw new Error('The level.dat in this ZIP is damaged, so the world cannot be imported.')
 
   const normalLevelPath = levelEntry.entryName.replaceAll('\\', '/')
   const prefix = normalLevelPath.slice(0, -'level.dat'.length).replace(/\/$/, '')
-  const suggested = prefix ? path.basename(prefix) : path.basename(archivePath, path.extname(archivePath))
+  // Without a folder to name it after, the world's own name is used, since
+  // downloaded archives only have a temporary file name.
+  const suggested = prefix
+    ? path.basename(prefix)
+    : typeof level.LevelName === 'string' && level.LevelName.trim() ? level.LevelName : path.basename(archivePath, path.extname(archivePath))
   const saves = savesDirectory(instance.slug)
   await fs.mkdir(saves, { recursive: true })
   const destinationRoot = await uniqueDirectory(saves, suggested)
@@ -71,6 +147,7 @@ export async function importWorldZip(instanceId: string, archivePath: string): P
     for (const entry of entries) {
       const normal = entry.entryName.replaceAll('\\', '/')
       if (prefix && normal !== prefix && !normal.startsWith(`${prefix}/`)) continue
+      if (ignoredEntry(normal)) continue
       const relative = prefix ? normal.slice(prefix.length).replace(/^\//, '') : normal
       if (!relative) continue
       const destination = safeDestination(destinationRoot, relative)
@@ -89,13 +166,7 @@ export async function importWorldZip(instanceId: string, archivePath: string): P
     throw error
   }
 
-  const stat = await fs.stat(destinationRoot)
-  return {
-    id: path.basename(destinationRoot),
-    name: path.basename(destinationRoot),
-    folderName: path.basename(destinationRoot),
-    modifiedAt: stat.mtime.toISOString()
-  }
+  return worldSummary(destinationRoot)
 }
 
 export async function downloadWorldZip(
@@ -114,7 +185,7 @@ export async function downloadWorldZip(
   const temp = path.join(tempDirectory, `world-${randomBytes(12).toString('hex')}.zip`)
   try {
     onProgress?.('Downloading world', 0)
-    await downloadFile(parsed.toString(), temp, (downloaded, total) => {
+    await download(parsed.toString(), temp, (downloaded, total) => {
       onProgress?.('Downloading world', total ? downloaded / total : undefined)
     }, { maxBytes: 2 * 1024 * 1024 * 1024, timeoutMs: 10 * 60_000 })
     onProgress?.('Installing world', 0.96)
@@ -139,3 +210,144 @@ export async function worldFolder(instanceId: string, worldId?: string): Promise
   await fs.mkdir(saves, { recursive: true })
   return worldId ? safeDestination(saves, worldId) : saves
 }
+
+// A world folder directly inside saves; nested paths are refused so a world
+// ID always names a single folder in both saves and backups.
+async function existingWorld(saves: string, worldId: string): Promise<string> {
+  if (!worldId || path.basename(worldId) !== worldId) throw new Error('That world could not be found.')
+  const directory = safeDestination(saves, worldId)
+  if (!await fs.stat(path.join(directory, 'level.dat')).catch(() => null)) throw new Error('That world could not be found.')
+  return directory
+}
+
+function backupStamp(date: Date): string {
+  const pad = (value: number) => String(value).padStart(2, '0')
+  return `${date.getFullYear()}-${pad(date.getMonth() + 1)}-${pad(date.getDate())}_${pad(date.getHours())}-${pad(date.getMinutes())}-${pad(date.getSeconds())}`
+}
+
+// Automatic backups end in "-auto", before any number added to keep the
+// name unique.
+const AUTOMATIC_BACKUP = /-auto(?: \(\d+\))?\.zip$/
+
+function backupPath(instanceId: string, backupId: string): string {
+  const [worldFolder, fileName, ...rest] = backupId.split('/')
+  if (!worldFolder || !fileName || rest.length || !fileName.endsWith('.zip')) throw new Error('That backup could not be found.')
+  return safeDestination(worldBackupsDirectory(instanceId), path.join(worldFolder, fileName))
+}
+
+// Backups used to be kept in the instance's own backups folder, and are moved
+// out the first time the instance's backups are looked at.
+async function backupsRoot(instance: LauncherInstance): Promise<string> {
+  const root = worldBackupsDirectory(instance.id)
+  if (instance.linkedDirectory) return root
+  const legacy = path.join(instanceDirectory(instance.slug), 'backups')
+  if ((await fs.stat(legacy).catch(() => null))?.isDirectory() && !await fs.stat(root).catch(() => null)) {
+    await fs.mkdir(path.dirname(root), { recursive: true })
+    await fs.rename(legacy, root).catch(async () => {
+      await fs.cp(legacy, root, { recursive: true })
+      await fs.rm(legacy, { recursive: true, force: true })
+    })
+  }
+  return root
+}
+
+// Zips a world into <world folder>/<date and time>.zip in the instance's
+// backups folder. The world folder is kept at the top of the archive, so a
+// backup can also be imported like any world ZIP. session.lock is left out, as
+// the game keeps it locked and recreates it.
+export async function backupWorld(
+  instanceId: string,
+  worldId: string,
+  onProgress?: (message: string, progress?: number) => void,
+  automatic = false
+): Promise<WorldBackup> {
+  const instance = getInstance(instanceId)
+  const directory = await existingWorld(savesDirectory(instance.slug), worldId)
+  const backups = path.join(await backupsRoot(instance), worldId)
+  await fs.mkdir(backups, { recursive: true })
+  const created = new Date()
+  const base = `${backupStamp(created)}${automatic ? '-auto' : ''}`
+  let fileName = `${base}.zip`
+  for (let index = 2; await fs.stat(path.join(backups, fileName)).catch(() => null); index++) fileName = `${base} (${index}).zip`
+  const destination = path.join(backups, fileName)
+  const temp = `${destination}.${process.pid}.tmp`
+  onProgress?.(`Backing up ${worldId}`, 0)
+  try {
+    await zipFolder(directory, temp, { prefix: worldId, skipNames: ['session.lock'] })
+    await fs.rename(temp, destination)
+  } finally {
+    await fs.rm(temp, { force: true }).catch(() => undefined)
+  }
+  onProgress?.(`Backed up ${worldId}`, 1)
+  const stat = await fs.stat(destination)
+  return { id: `${worldId}/${fileName}`, worldFolder: worldId, fileName, createdAt: created.toISOString(), sizeBytes: stat.size, automatic }
+}
+
+// Lists backups newest first, optionally only those of one world. Backups
+// stay listed after their world is deleted.
+export async function listWorldBackups(instanceId: string, worldId?: string): Promise<WorldBackup[]> {
+  const instance = getInstance(instanceId)
+  const root = await backupsRoot(instance)
+  const folders = worldId ? [worldId] : await fs.readdir(root).catch(() => [] as string[])
+  const backups: WorldBackup[] = []
+  for (const worldFolder of folders) {
+    if (path.basename(worldFolder) !== worldFolder) continue
+    for (const fileName of await fs.readdir(path.join(root, worldFolder)).catch(() => [] as string[])) {
+      if (!fileName.endsWith('.zip')) continue
+      const stat = await fs.stat(path.join(root, worldFolder, fileName)).catch(() => null)
+      if (!stat?.isFile()) continue
+      backups.push({ id: `${worldFolder}/${fileName}`, worldFolder, fileName, createdAt: stat.mtime.toISOString(), sizeBytes: stat.size, automatic: AUTOMATIC_BACKUP.test(fileName) })
+    }
+  }
+  return backups.sort((a, b) => Date.parse(b.createdAt) - Date.parse(a.createdAt))
+}
+
+// Restores a backup as a new world. An existing world is never overwritten:
+// when the folder is taken, the backup is restored next to it under a
+// numbered name.
+export async function restoreWorldBackup(instanceId: string, backupId: string): Promise<WorldSummary> {
+  const instance = getInstance(instanceId)
+  const archive = backupPath(instance.id, backupId)
+  if (!await fs.stat(archive).catch(() => null)) throw new Error('That backup could not be found.')
+  return importWorldZip(instanceId, archive)
+}
+
+export async function deleteWorldBackup(instanceId: string, backupId: string): Promise<void> {
+  const instance = getInstance(instanceId)
+  await fs.rm(backupPath(instance.id, backupId), { force: true })
+}
+
+// Removes the automatic backups of a world that the policy no longer keeps.
+// The newest automatic backup always stays, however old it is.
+async function pruneAutomaticBackups(instanceId: string, worldId: string, policy: WorldBackupPolicy): Promise<number> {
+  const automatic = (await listWorldBackups(instanceId, worldId)).filter((backup) => backup.automatic)
+  const cutoff = policy.keepDays ? Date.now() - policy.keepDays * 24 * 60 * 60 * 1000 : 0
+  const expired = automatic.filter((backup, index) => index > 0 && (index >= policy.keepLast || Date.parse(backup.createdAt) < cutoff))
+  for (const backup of expired) await deleteWorldBackup(instanceId, backup.id)
+  return expired.length
+}
+
+// Backs up every world whose level.dat was saved since the session started,
+// which the game does whenever a world is played, then prunes each world's
+// automatic backups. A world that cannot be backed up does not stop the rest.
+export async function backupPlayedWorlds(
+  instanceId: string,
+  since: number,
+  policy: WorldBackupPolicy
+): Promise<{ backups: WorldBackup[]; removed: number; failed: Array<{ worldId: string; error: string }> }> {
+  const instance = getInstance(instanceId)
+  const saves = savesDirectory(instance.slug)
+  const entries = await fs.readdir(saves, { withFileTypes: true }).catch(() => [])
+  const result = { backups: [] as WorldBackup[], removed: 0, failed: [] as Array<{ worldId: string; error: string }> }
+  for (const entry of entries.filter((item) => item.isDirectory())) {
+    const level = await fs.stat(path.join(saves, entry.name, 'level.dat')).catch(() => null)
+    if (!level || level.mtimeMs < since) continue
+    try {
+      result.backups.push(await backupWorld(instanceId, entry.name, undefined, true))
+      result.removed += await pruneAutomaticBackups(instanceId, entry.name, policy)
+    } catch (error) {
+      result.failed.push({ worldId: entry.name, error: error instanceof Error ? error.message : String(error) })
+    }
+  }
+  return result
+}
@@ -0,0 +1,60 @@
+import { createRequire } from 'node:module'
+import { Worker } from 'node:worker_threads'
+
+export interface ZipFolderOptions {
+  // Path prefix for every entry in the archive.
+  prefix?: string
+  // File or folder names left out wherever they appear.
+  skipNames?: string[]
+  // Top-level entries of the source folder left out.
+  skipTopLevel?: string[]
+}
+
+const ADM_ZIP_MODULE = createRequire(import.meta.url).resolve('adm-zip')
+
+// Compressing a folder can take a while and holds the whole archive in memory,
+// so it runs on a worker thread rather than Electron's main thread.
+const ZIP_WORKER_SOURCE = String.raw`
+const { parentPort, workerData } = require('node:worker_threads')
+const AdmZip = require(workerData.admZipModule)
+const skipNames = new Set(workerData.skipNames)
+const skipTopLevel = new Set(workerData.skipTopLevel)
+const zip = new AdmZip()
+zip.addLocalFolder(workerData.source, workerData.prefix, (entry) => {
+  const parts = entry.replaceAll('\\', '/').split('/').filter(Boolean)
+  return !skipTopLevel.has(parts[0]) && !parts.some((part) => skipNames.has(part))
+})
+zip.writeZipPromise(workerData.destination).then(
+  () => parentPort.postMessage({ ok: true }),
+  (error) => parentPort.postMessage({ ok: false, error: String((error && error.message) || error) })
+)
+`
+
+export function zipFolder(source: string, destination: string, options: ZipFolderOptions = {}): Promise<void> {
+  return new Promise((resolve, reject) => {
+    const worker = new Worker(ZIP_WORKER_SOURCE, {
+      eval: true,
+      workerData: {
+        admZipModule: ADM_ZIP_MODULE,
+        source,
+        destination,
+        prefix: options.prefix ?? '',
+        skipNames: options.skipNames ?? [],
+        skipTopLevel: options.skipTopLevel ?? []
+      }
+    })
+    let settled = false
+    worker.once('message', (reply: { ok: boolean; error?: string }) => {
+      settled = true
+      if (reply.ok) resolve()
+      else reject(new Error(reply.error || 'The folder could not be compressed.'))
+    })
+    worker.once('error', (error) => {
+      settled = true
+      reject(error)
+    })
+    worker.once('exit', (code) => {
+      if (!settled) reject(new Error(`The folder could not be compressed (the worker exited with code ${code}).`))
+    })
+  })
+}
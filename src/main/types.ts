@@ -2,21 +2,98 @@ import type { Account } from 'eml-lib'
 
 export type LoaderType = 'vanilla' | 'forge' | 'neoforge' | 'fabric'
 
+export type SharedFolderKind = 'saves' | 'resourcepacks'
+
+export type VersionTrack = 'release' | 'snapshot'
+
+export interface RestartOnCrash {
+  maxRetries: number
+  withinSeconds: number
+}
+
+// Set when an instance keeps crashing soon after launch. Automatic restarts
+// stay off until a run ends cleanly or the flag is cleared.
+export interface UnstableFlag {
+  since: string
+  crashes: number
+  summary?: string
+}
+
+export type ProcessPriority = 'below-normal' | 'normal' | 'above-normal'
+
+// Backs up the worlds played in a session when the game exits. Older
+// automatic backups of each world are removed past the newest keepLast, or
+// once older than keepDays when that is set; backups made by hand are kept.
+export interface WorldBackupPolicy {
+  keepLast: number
+  keepDays: number
+}
+
 export interface LauncherInstance {
   id: string
   name: string
   slug: string
   minecraftVersion: string
+  versionType?: string
+  customVersionId?: string
+  trackLatest?: VersionTrack
   loader: LoaderType
   loaderVersion?: string
   icon?: string
   createdAt: string
   updatedAt: string
   lastPlayedAt?: string
+  lastExitedAt?: string
+  totalPlaytimeMs?: number
+  launchCount?: number
+  memoryMin?: number
+  memoryMax?: number
+  jvmArgs?: string
+  processPriority?: ProcessPriority
+  cpuAffinity?: number[]
+  restartOnCrash?: RestartOnCrash
+  worldBackups?: WorldBackupPolicy
+  unstable?: UnstableFlag
+  linkedDirectory?: string
+  sharedFolders?: SharedFolderKind[]
+  archivedAt?: string
   customClient: boolean
   modpack?: { projectId: string; versionId: string; title: string }
+  notes?: string
+}
+
+export interface InstanceStats {
+  lastPlayedAt?: string
+  lastExitedAt?: string
+  totalPlaytimeMs: number
+  launchCount: number
+}
+
+export type DiskUsageCategory = 'worlds' | 'mods' | 'packs' | 'assets' | 'libraries' | 'versions' | 'logs' | 'other'
+
+export interface InstanceDiskUsage {
+  instanceId: string
+  totalBytes: number
+  // The part of totalBytes that is this instance's share of shared folders.
+  sharedBytes: number
+  categories: Record<DiskUsageCategory, number>
+  measuredAt: string
+}
+
+export interface CleanupReport {
+  removed: Array<{ kind: 'version' | 'library' | 'natives' | 'runtime' | 'object'; path: string; bytes: number }>
+  reclaimedBytes: number
+  // Instances left alone because they were running or being prepared.
+  skipped: string[]
+  dryRun: boolean
 }
 
+export type LogLevel = 'error' | 'warn' | 'info' | 'debug'
+
+export type LauncherVisibility = 'keep-open' | 'minimize' | 'tray' | 'close'
+
+export type MeteredConnectionSetting = 'auto' | 'metered' | 'unmetered'
+
 export interface LauncherSettings {
   memoryMin: number
   memoryMax: number
@@ -24,19 +101,39 @@ export interface LauncherSettings {
   height: number
   fullscreen: boolean
   showConsole: boolean
-  minimizeToTrayOnLaunch: boolean
+  launcherVisibility: LauncherVisibility
   showSnapshots: boolean
+  showLegacyVersions: boolean
   javaMode: 'auto' | 'manual'
   javaPath: string
   checkUpdates: boolean
   discordActivity: boolean
   reducedMotion: boolean
+  defaultJvmArgs: string
+  defaultLoader: LoaderType
+  logLevel: LogLevel
+  downloadSpeedLimit: number
+  downloadConcurrency: number
+  offlineMode: boolean
+  backgroundPrepare: boolean
+  meteredConnection: MeteredConnectionSetting
+  // Hours between background mod and modpack update checks, 0 when off.
+  contentUpdateCheckHours: number
+  // An HTTPS endpoint screenshots are posted to as multipart form data, or
+  // empty when uploading is off. The link is read from the JSON response at
+  // screenshotUploadUrlPath, such as "data.link", or is the whole response
+  // body when that is empty.
+  screenshotUploadUrl: string
+  screenshotUploadField: string
+  screenshotUploadUrlPath: string
 }
 
 export interface StoredData {
   settings: LauncherSettings
   instances: LauncherInstance[]
   selectedInstanceId?: string
+  // Set once the official launcher import has been offered on the first start.
+  firstRunImportOffered?: boolean
 }
 
 export interface PublicAccount {
@@ -46,8 +143,38 @@ export interface PublicAccount {
   xboxGamertag?: string
 }
 
+export type LaunchStage = 'client' | 'security' | 'prepare' | 'download' | 'assets' | 'java' | 'natives' | 'loader' | 'launch' | 'prepared'
+
+export type LaunchStateStage = 'resolving-version' | 'downloading-assets' | 'ensuring-java' | 'spawning' | 'running' | 'exited' | 'failed' | 'cancelled'
+
+export interface LaunchStageEntry {
+  stage: LaunchStateStage
+  at: string
+}
+
+export interface ProgressCounter {
+  current: number
+  total: number
+}
+
+export interface ProgressDetail {
+  bytes?: ProgressCounter
+  items?: ProgressCounter
+  speed?: number
+}
+
+// Sent on mc:progress. Unlike launch:progress it never needs its message
+// parsed: percent is 0-100 and bytes and items are raw counts.
+export interface LaunchProgressEvent extends ProgressDetail {
+  instanceId: string
+  stage: LaunchStage
+  message: string
+  percent?: number
+}
+
 export interface LaunchProgress {
-  phase: string
+  instanceId?: string
+  phase: LaunchStage
   message: string
   progress?: number
   downloaded?: number
@@ -55,6 +182,156 @@ export interface LaunchProgress {
   speed?: number
 }
 
+export type CacheKind = 'server-icons' | 'avatars' | 'metadata' | 'partial-downloads'
+
+export interface CacheSizeReport {
+  kinds: Record<CacheKind, number>
+  totalBytes: number
+  measuredAt: string
+}
+
+export interface CacheClearResult {
+  clearedBytes: number
+  // Kinds left alone because something was using them.
+  skipped: CacheKind[]
+  size: CacheSizeReport
+}
+
+// A large download waiting for the user's go-ahead on a metered connection.
+// A prompt is sent again with closed set once its launch is cancelled.
+export interface MeteredPrompt {
+  id: string
+  instanceId?: string
+  name: string
+  what: string
+  bytes: number
+  closed?: boolean
+}
+
+export interface ConnectivityStatus {
+  state: 'online' | 'unreachable' | 'offline-mode'
+  checkedAt?: string
+}
+
+export interface DownloadItem {
+  id: string
+  url: string
+  fileName: string
+  label?: string
+  status: 'queued' | 'downloading' | 'paused' | 'completed' | 'failed' | 'cancelled'
+  downloaded: number
+  total: number
+  error?: string
+  createdAt: string
+  updatedAt: string
+}
+
+export interface RunningGame {
+  instanceId: string
+  name: string
+  pid?: number
+  processIds: number[]
+  startedAt: string
+  uptimeSeconds: number
+  minecraftVersion: string
+  loader: LoaderType
+  loaderVersion?: string
+}
+
+export interface ExitExplanation {
+  id: string
+  title: string
+  detail: string
+}
+
+export interface BisectRound {
+  testing: string[]
+  crashed: boolean
+  at: string
+}
+
+export interface ModSet {
+  id: string
+  name: string
+  // File names of the mods the set enables, without .disabled.
+  enabled: string[]
+  createdAt: string
+  updatedAt: string
+}
+
+export interface ModSetList {
+  sets: ModSet[]
+  activeId?: string
+}
+
+export interface ModSetApplyResult {
+  setId: string
+  enabled: number
+  disabled: number
+  // Mods the set enables that are no longer installed.
+  missing: string[]
+}
+
+export interface BisectState {
+  candidates: string[]
+  testing: string[]
+  rounds: BisectRound[]
+  startedAt: string
+  culprit?: string
+  // The confirming run did not crash, so no single mod could be blamed.
+  inconclusive?: boolean
+}
+
+export interface CrashReport {
+  file: string
+  description?: string
+  exception?: string
+  stackHead: string[]
+  suspectedMods: string[]
+}
+
+export interface JvmErrorLog {
+  file: string
+  summary: string[]
+  problematicFrame?: string
+  library?: string
+  gpuDriver: boolean
+}
+
+export interface LaunchLogSummary {
+  name: string
+  startedAt: string
+  size: number
+  modifiedAt: string
+}
+
+export type LogSearchLevel = 'ERROR' | 'WARN' | 'INFO' | 'DEBUG'
+
+export interface LogSearchOptions {
+  source: 'launch' | 'game'
+  name: string
+  query?: string
+  regex?: boolean
+  caseSensitive?: boolean
+  levels?: LogSearchLevel[]
+  context?: number
+  limit?: number
+}
+
+export interface LogSearchMatch {
+  line: number
+  text: string
+  level?: LogSearchLevel
+  before: string[]
+  after: string[]
+}
+
+export interface LogSearchResult {
+  matches: LogSearchMatch[]
+  total: number
+  truncated: boolean
+}
+
 export interface TrackedMod {
   projectId?: string
   versionId?: string
@@ -64,7 +341,115 @@ export interface TrackedMod {
   versionNumber?: string
   iconUrl?: string
   installedAt?: string
-  source?: 'modrinth' | 'local' | 'client'
+  source?: 'modrinth' | 'curseforge' | 'local' | 'client'
+  curseforge?: { projectId: number; fileId: number }
+}
+
+// A mod as listed for an instance, with what was found by reading its jar.
+export interface InstalledMod extends TrackedMod {
+  warnings?: string[]
+  // Set when the jar depends on Fabric API and no jar in the folder provides it.
+  needsFabricApi?: boolean
+}
+
+// A profile in an instance's versions folder that can be its custom version.
+export interface VersionProfileInfo {
+  id: string
+  inheritsFrom?: string
+  // Whether the profile brings its own game jar rather than its parent's.
+  hasJar: boolean
+}
+
+export interface LocalModInfo {
+  fileName: string
+  title: string
+  version?: string
+  description?: string
+  modIds: string[]
+  loaders: Array<'fabric' | 'quilt' | 'forge' | 'neoforge'>
+  // For example when the jar is made for a different loader than the instance.
+  warnings: string[]
+}
+
+export interface CollectionInstallReport {
+  collectionId: string
+  name: string
+  results: Array<{ projectId: string; title: string; status: 'installed' | 'skipped' | 'failed'; detail?: string }>
+}
+
+export interface ModpackUpdateReport {
+  instanceId: string
+  title: string
+  fromVersion?: string
+  toVersion: string
+  added: number
+  updated: number
+  removed: number
+  // Files the player changed, left as they are instead of being replaced.
+  keptChanges: string[]
+}
+
+export interface ModListImportReport {
+  // The name of the instance the list was exported from.
+  name: string
+  results: Array<{ title: string; status: 'installed' | 'present' | 'skipped' | 'failed'; detail?: string }>
+}
+
+export interface ModUpdateResult {
+  projectId: string
+  title: string
+  status: 'updated' | 'current' | 'failed'
+  fromVersion?: string
+  toVersion?: string
+  error?: string
+}
+
+export interface ModUpdateReport {
+  instanceId: string
+  results: ModUpdateResult[]
+  // True when replaced jars were kept and the update can be rolled back.
+  rollbackAvailable: boolean
+  finishedAt: string
+}
+
+export interface InstallPlanItem {
+  projectId: string
+  versionId: string
+  title: string
+  versionNumber: string
+  fileName: string
+  size: number
+  contentType: DiscoverContentType
+  // The title of the project that needs this one, for dependencies.
+  requiredBy?: string
+  alreadyInstalled: boolean
+  // Other copies of the same mod in the mods folder that installing removes.
+  replaces?: string[]
+}
+
+export interface InstallPlan {
+  projectId: string
+  instanceId: string
+  items: InstallPlanItem[]
+  // Bytes still to download, leaving out what is already installed.
+  downloadBytes: number
+  // Reasons the install would fail, such as a dependency with no version for
+  // this instance.
+  problems: string[]
+}
+
+export interface InstanceUpdateSummary {
+  instanceId: string
+  name: string
+  mods: Array<{ projectId: string; title: string; currentVersion?: string; latestVersion: string }>
+  modpack?: { projectId: string; title: string; currentVersionId: string; latestVersionId: string; latestVersion: string }
+}
+
+export interface ContentUpdateSummary {
+  checkedAt: string
+  // Mods plus modpacks with a newer version, across all instances.
+  total: number
+  instances: InstanceUpdateSummary[]
 }
 
 export interface AccountEnvelope {
@@ -96,6 +481,63 @@ export type AuthenticatedAccount = Account
 
 export type DiscoverContentType = 'mod' | 'modpack' | 'resourcepack' | 'shader'
 
+export interface ProjectVersionSummary {
+  id: string
+  name: string
+  versionNumber: string
+  type: 'release' | 'beta' | 'alpha'
+  gameVersions: string[]
+  loaders: string[]
+  publishedAt: string
+  downloads: number
+  // Only set when details were requested for an instance.
+  compatible?: boolean
+}
+
+export interface ModrinthProjectDetails {
+  id: string
+  slug: string
+  title: string
+  description: string
+  body: string
+  projectType: DiscoverContentType
+  iconUrl?: string
+  categories: string[]
+  downloads: number
+  followers: number
+  publishedAt?: string
+  updatedAt?: string
+  license?: { id: string; name: string; url?: string }
+  gallery: Array<{ url: string; title?: string; description?: string; featured: boolean }>
+  links: Array<{ kind: 'source' | 'issues' | 'wiki' | 'discord' | 'donation'; label: string; url: string }>
+  versions: ProjectVersionSummary[]
+}
+
+export interface ModrinthAccount {
+  id: string
+  username: string
+  avatarUrl?: string
+}
+
+// Shaped like a search hit so the Discover page can list it the same way.
+export interface ModrinthLibraryProject {
+  project_id: string
+  project_type: DiscoverContentType
+  slug: string
+  title: string
+  description: string
+  author: string
+  categories: string[]
+  versions: string[]
+  downloads: number
+  icon_url?: string
+}
+
+export interface ModrinthLibrary {
+  followed: ModrinthLibraryProject[]
+  owned: ModrinthLibraryProject[]
+}
+
 export interface TrackedPack {
   projectId?: string
   versionId?: string
@@ -107,11 +549,94 @@ export interface TrackedPack {
   installedAt?: string
   contentType: 'resourcepack' | 'shader'
   source: 'modrinth' | 'local'
+  // An unpacked resource pack folder rather than a zip.
+  folder?: boolean
+  // Read from a resource pack's pack.mcmeta when listing.
+  description?: string
+  packFormat?: number
+  warnings?: string[]
 }
 
+// What an instance is missing before the game can load shader packs.
+export interface ShaderLoaderRequirement {
+  // The Modrinth project that adds shader support to the instance's loader.
+  // Left out when none can be installed, such as on vanilla.
+  projectId?: string
+  title: string
+  reason: string
+}
+
+// Shader packs are not installed into an instance that cannot load them;
+// the mod it needs is returned instead, unless installing it was allowed.
+export type PackInstallResult =
+  | { status: 'installed'; pack: TrackedPack; shaderLoader?: TrackedMod }
+  | { status: 'needs-shader-loader'; requirement: ShaderLoaderRequirement }
+
+export type WorldGameMode = 'survival' | 'creative' | 'adventure' | 'spectator' | 'hardcore'
+
 export interface WorldSummary {
   id: string
   name: string
   folderName: string
   modifiedAt: string
+  // Read from level.dat; missing when it cannot be read.
+  gameMode?: WorldGameMode
+  // The game version the world was last opened in. Worlds from before 1.9 do
+  // not record one.
+  version?: string
+  lastPlayed?: string
+  sizeBytes?: number
+  iconUrl?: string
+}
+
+export interface ScreenshotSummary {
+  fileName: string
+  takenAt: string
+  sizeBytes: number
+  // A small preview as a data URL.
+  thumbnailUrl?: string
+}
+
+export interface WorldBackup {
+  // The backup's path inside the backups folder, "<world folder>/<file>".
+  id: string
+  worldFolder: string
+  fileName: string
+  createdAt: string
+  sizeBytes: number
+  // Made when the game exited rather than by hand.
+  automatic: boolean
+}
+
+export interface InstanceVerificationIssue {
+  kind: 'missing-directory' | 'missing-file' | 'corrupt-file' | 'version' | 'library' | 'asset'
+  file: string
+  detail: string
+  repaired: boolean
+}
+
+export interface InstanceVerificationReport {
+  instanceId: string
+  checkedFiles: number
+  issues: InstanceVerificationIssue[]
+  checkedAt: string
+}
+
+export interface NewsItem {
+  id: string
+  title: string
+  tag?: string
+  date?: string
+  text?: string
+  imageDataUrl?: string
+}
+
+export interface VersionPatchNotes {
+  version: string
+  title: string
+  type: string
+  date?: string
+  imageUrl?: string
+  summary?: string
+  body: string
 }
@@ -1,4 +1,4 @@
-import { contextBridge, ipcRenderer } from 'electron'
+import { contextBridge, ipcRenderer, webUtils } from 'electron'
 
 const api = {
   window: {
@@ -16,8 +16,13 @@ const api = {
     rendererReady: () => ipcRenderer.invoke('app:renderer-ready'),
     reload: () => ipcRenderer.invoke('app:reload'),
     quit: () => ipcRenderer.invoke('app:quit'),
-    getVersions: (includeSnapshots: boolean) => ipcRenderer.invoke('versions:minecraft', includeSnapshots),
+    getVersions: (includeSnapshots: boolean, includeLegacy = false) => ipcRenderer.invoke('versions:minecraft', includeSnapshots, includeLegacy),
     getLoaderVersions: (loader: string, version: string) => ipcRenderer.invoke('versions:loader', loader, version),
+    getPatchNotes: (version: string) => ipcRenderer.invoke('versions:patch-notes', version),
+    getNews: () => ipcRenderer.invoke('news:list'),
+    cleanupGameFiles: (dryRun: boolean) => ipcRenderer.invoke('storage:cleanup', dryRun),
+    cacheSize: () => ipcRenderer.invoke('cache:size'),
+    clearCache: (kinds: string[]) => ipcRenderer.invoke('cache:clear', kinds),
     checkUpdates: () => ipcRenderer.invoke('updates:check'),
     installUpdate: () => ipcRenderer.invoke('updates:install'),
     onBootStatus: (callback: (event: { value: number; message: string; detail?: string }) => void) => {
@@ -51,24 +56,82 @@ const api = {
   },
   instances: {
     create: (input: unknown) => ipcRenderer.invoke('instances:create', input),
+    chooseLinkedDirectory: () => ipcRenderer.invoke('instances:choose-linked-directory'),
     update: (id: string, patch: unknown) => ipcRenderer.invoke('instances:update', id, patch),
     delete: (id: string) => ipcRenderer.invoke('instances:delete', id),
     select: (id: string) => ipcRenderer.invoke('instances:select', id),
+    getNotes: (id: string) => ipcRenderer.invoke('instances:get-notes', id),
+    setNotes: (id: string, notes: string) => ipcRenderer.invoke('instances:set-notes', id, notes),
+    stats: (id: string) => ipcRenderer.invoke('instances:stats', id),
+    diskUsage: (id: string) => ipcRenderer.invoke('instances:disk-usage', id),
+    copyConfiguration: (sourceId: string, targetId: string, items?: string[]) => ipcRenderer.invoke('instances:copy-configuration', sourceId, targetId, items),
+    importMultiMc: () => ipcRenderer.invoke('instances:import-multimc'),
+    importMrpack: () => ipcRenderer.invoke('instances:import-mrpack'),
+    exportMrpack: (id: string) => ipcRenderer.invoke('instances:export-mrpack', id),
+    detectVanilla: () => ipcRenderer.invoke('instances:detect-vanilla'),
+    importVanilla: (profileId?: string) => ipcRenderer.invoke('instances:import-vanilla', profileId),
+    firstRunImport: () => ipcRenderer.invoke('instances:first-run-import'),
+    detectCurseForge: () => ipcRenderer.invoke('instances:detect-curseforge'),
+    importCurseForge: (folder?: string) => ipcRenderer.invoke('instances:import-curseforge', folder),
+    setSharedFolder: (id: string, kind: 'saves' | 'resourcepacks', shared: boolean) => ipcRenderer.invoke('instances:set-shared-folder', id, kind, shared),
+    archive: (id: string) => ipcRenderer.invoke('instances:archive', id),
+    unarchive: (id: string) => ipcRenderer.invoke('instances:unarchive', id),
+    verify: (id: string, repair = false) => ipcRenderer.invoke('instances:verify', id, repair),
+    setCustomVersion: (id: string) => ipcRenderer.invoke('instances:set-custom-version', id),
+    installVersionFromUrl: (id: string, url: string) => ipcRenderer.invoke('instances:install-version-url', id, url),
+    clearCustomVersion: (id: string) => ipcRenderer.invoke('instances:clear-custom-version', id),
+    versionProfiles: (id: string) => ipcRenderer.invoke('instances:version-profiles', id),
+    selectVersionProfile: (id: string, versionId: string) => ipcRenderer.invoke('instances:select-version-profile', id, versionId),
+    setVersionTracking: (id: string, track: 'release' | 'snapshot' | null) => ipcRenderer.invoke('instances:set-version-tracking', id, track),
+    importVanillaProfiles: (ids: string[], link = false) => ipcRenderer.invoke('instances:import-vanilla-profiles', ids, link),
+    exportVanilla: (ids?: string[]) => ipcRenderer.invoke('instances:export-vanilla', ids),
     openFolder: (id: string) => ipcRenderer.invoke('instances:open-folder', id),
     addLocalMod: (id: string) => ipcRenderer.invoke('instances:add-local-mod', id),
+    // Dropped files carry no path in the page, so it is looked up here.
+    importLocalMod: (id: string, file: File) => ipcRenderer.invoke('instances:import-local-mod', id, webUtils.getPathForFile(file)),
     launch: (id: string) => ipcRenderer.invoke('instances:launch', id),
     launchServer: (id: string, address: string) => ipcRenderer.invoke('instances:launch-server', id, address),
-    openConsole: () => ipcRenderer.invoke('instances:open-console')
+    launchWorld: (id: string, worldId: string) => ipcRenderer.invoke('instances:launch-world', id, worldId),
+    launchSafeMode: (id: string) => ipcRenderer.invoke('instances:launch-safe-mode', id),
+    startBisect: (id: string) => ipcRenderer.invoke('instances:bisect-start', id),
+    bisectStatus: (id: string) => ipcRenderer.invoke('instances:bisect-status', id),
+    cancelBisect: (id: string) => ipcRenderer.invoke('instances:bisect-cancel', id),
+    prepare: (id: string) => ipcRenderer.invoke('instances:prepare', id),
+    cancelLaunch: (id: string) => ipcRenderer.invoke('instances:cancel-launch', id),
+    stop: (id: string) => ipcRenderer.invoke('instances:stop', id),
+    running: () => ipcRenderer.invoke('instances:running'),
+    setRestartOnCrash: (id: string, options: { maxRetries: number; withinSeconds: number } | null) => ipcRenderer.invoke('instances:set-restart-on-crash', id, options),
+    setWorldBackups: (id: string, policy: { keepLast: number; keepDays: number } | null) => ipcRenderer.invoke('instances:set-world-backups', id, policy),
+    clearUnstable: (id: string) => ipcRenderer.invoke('instances:clear-unstable', id),
+    raiseMemory: (id: string) => ipcRenderer.invoke('instances:raise-memory', id),
+    setPriority: (id: string, priority: 'below-normal' | 'normal' | 'above-normal', affinity?: number[]) => ipcRenderer.invoke('instances:set-priority', id, priority, affinity),
+    consoleLines: (id: string, since?: number) => ipcRenderer.invoke('instances:console-lines', id, since),
+    launchLogs: (id: string) => ipcRenderer.invoke('instances:launch-logs', id),
+    readLaunchLog: (id: string, name: string) => ipcRenderer.invoke('instances:read-launch-log', id, name),
+    searchLog: (id: string, options: unknown) => ipcRenderer.invoke('instances:search-log', id, options),
+    openConsole: (id?: string) => ipcRenderer.invoke('instances:open-console', id)
   },
   mods: {
     search: (input: unknown) => ipcRenderer.invoke('mods:search', input),
-    install: (instanceId: string, projectId: string) => ipcRenderer.invoke('mods:install', instanceId, projectId),
+    popular: (input: unknown) => ipcRenderer.invoke('mods:popular', input),
+    installCollection: (instanceId: string, input: string) => ipcRenderer.invoke('mods:install-collection', instanceId, input),
+    installPlan: (projectId: string, instanceId: string) => ipcRenderer.invoke('mods:install-plan', projectId, instanceId),
+    project: (idOrSlug: string, instanceId?: string) => ipcRenderer.invoke('mods:project', idOrSlug, instanceId),
+    install: (instanceId: string, projectId: string, replace?: boolean) => ipcRenderer.invoke('mods:install', instanceId, projectId, replace),
     installModpack: (instanceId: string, projectId: string) => ipcRenderer.invoke('mods:install-modpack', instanceId, projectId),
     list: (instanceId: string) => ipcRenderer.invoke('mods:list', instanceId),
     setEnabled: (instanceId: string, fileName: string, enabled: boolean) => ipcRenderer.invoke('mods:set-enabled', instanceId, fileName, enabled),
     remove: (instanceId: string, fileName: string) => ipcRenderer.invoke('mods:remove', instanceId, fileName),
     update: (instanceId: string, projectId: string) => ipcRenderer.invoke('mods:update', instanceId, projectId),
-    updateAll: (instanceId: string) => ipcRenderer.invoke('mods:update-all', instanceId),
+    updateAll: (instanceId: string, projectIds?: string[]) => ipcRenderer.invoke('mods:update-all', instanceId, projectIds),
+    rollbackUpdate: (instanceId: string) => ipcRenderer.invoke('mods:rollback-update', instanceId),
+    updateModpack: (instanceId: string) => ipcRenderer.invoke('mods:update-modpack', instanceId),
+    exportList: (instanceId: string) => ipcRenderer.invoke('mods:export-list', instanceId),
+    importList: (instanceId: string) => ipcRenderer.invoke('mods:import-list', instanceId),
+    sets: (instanceId: string) => ipcRenderer.invoke('mods:sets', instanceId),
+    saveSet: (instanceId: string, name: string) => ipcRenderer.invoke('mods:save-set', instanceId, name),
+    deleteSet: (instanceId: string, setId: string) => ipcRenderer.invoke('mods:delete-set', instanceId, setId),
+    applySet: (instanceId: string, setId: string) => ipcRenderer.invoke('mods:apply-set', instanceId, setId),
     onProgress: (callback: (event: unknown) => void) => {
       const listener = (_: unknown, payload: unknown) => callback(payload)
       ipcRenderer.on('mods:progress', listener)
@@ -76,18 +139,74 @@ const api = {
     }
   },
   packs: {
-    install: (instanceId: string, projectId: string, type: 'resourcepack' | 'shader') => ipcRenderer.invoke('packs:install', instanceId, projectId, type),
+    install: (instanceId: string, projectId: string, type: 'resourcepack' | 'shader', installShaderLoader?: boolean) => ipcRenderer.invoke('packs:install', instanceId, projectId, type, installShaderLoader),
+    shaderLoader: (instanceId: string) => ipcRenderer.invoke('packs:shader-loader', instanceId),
     list: (instanceId: string, type?: 'resourcepack' | 'shader') => ipcRenderer.invoke('packs:list', instanceId, type),
     setEnabled: (instanceId: string, fileName: string, type: 'resourcepack' | 'shader', enabled: boolean) => ipcRenderer.invoke('packs:set-enabled', instanceId, fileName, type, enabled),
     remove: (instanceId: string, fileName: string, type: 'resourcepack' | 'shader') => ipcRenderer.invoke('packs:remove', instanceId, fileName, type),
+    addLocal: (instanceId: string, type: 'resourcepack' | 'shader') => ipcRenderer.invoke('packs:add-local', instanceId, type),
     openFolder: (instanceId: string, type: 'resourcepack' | 'shader') => ipcRenderer.invoke('packs:open-folder', instanceId, type)
   },
+  downloads: {
+    list: () => ipcRenderer.invoke('downloads:list'),
+    pause: () => ipcRenderer.invoke('downloads:pause'),
+    resume: () => ipcRenderer.invoke('downloads:resume'),
+    onEvent: (callback: (event: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('downloads:event', listener)
+      return () => ipcRenderer.removeListener('downloads:event', listener)
+    }
+  },
+  network: {
+    status: () => ipcRenderer.invoke('network:status'),
+    probe: () => ipcRenderer.invoke('network:probe'),
+    onStatus: (callback: (status: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('network:status', listener)
+      return () => ipcRenderer.removeListener('network:status', listener)
+    },
+    answerMetered: (id: string, allowed: boolean) => ipcRenderer.invoke('network:metered-answer', id, allowed),
+    onMeteredPrompt: (callback: (prompt: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('network:metered-prompt', listener)
+      return () => ipcRenderer.removeListener('network:metered-prompt', listener)
+    }
+  },
+  updates: {
+    contentSummary: () => ipcRenderer.invoke('updates:content-summary'),
+    checkContent: () => ipcRenderer.invoke('updates:check-content'),
+    onContent: (callback: (summary: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('updates:content', listener)
+      return () => ipcRenderer.removeListener('updates:content', listener)
+    }
+  },
+  modrinth: {
+    account: () => ipcRenderer.invoke('modrinth:account'),
+    link: (token: string) => ipcRenderer.invoke('modrinth:link', token),
+    unlink: () => ipcRenderer.invoke('modrinth:unlink'),
+    library: () => ipcRenderer.invoke('modrinth:library'),
+    follow: (projectId: string, followed: boolean) => ipcRenderer.invoke('modrinth:follow', projectId, followed)
+  },
   worlds: {
     list: (instanceId: string) => ipcRenderer.invoke('worlds:list', instanceId),
     importZip: (instanceId: string) => ipcRenderer.invoke('worlds:import', instanceId),
     download: (instanceId: string, url: string) => ipcRenderer.invoke('worlds:download', instanceId, url),
     delete: (instanceId: string, worldId: string) => ipcRenderer.invoke('worlds:delete', instanceId, worldId),
-    openFolder: (instanceId: string, worldId?: string) => ipcRenderer.invoke('worlds:open-folder', instanceId, worldId)
+    openFolder: (instanceId: string, worldId?: string) => ipcRenderer.invoke('worlds:open-folder', instanceId, worldId),
+    backups: (instanceId: string, worldId?: string) => ipcRenderer.invoke('worlds:backups', instanceId, worldId),
+    backup: (instanceId: string, worldId: string) => ipcRenderer.invoke('worlds:backup', instanceId, worldId),
+    restoreBackup: (instanceId: string, backupId: string) => ipcRenderer.invoke('worlds:restore-backup', instanceId, backupId),
+    deleteBackup: (instanceId: string, backupId: string) => ipcRenderer.invoke('worlds:delete-backup', instanceId, backupId)
+  },
+  screenshots: {
+    list: (instanceId: string) => ipcRenderer.invoke('screenshots:list', instanceId),
+    copy: (instanceId: string, fileName: string) => ipcRenderer.invoke('screenshots:copy', instanceId, fileName),
+    upload: (instanceId: string, fileName: string) => ipcRenderer.invoke('screenshots:upload', instanceId, fileName),
+    show: (instanceId: string, fileName: string) => ipcRenderer.invoke('screenshots:show', instanceId, fileName),
+    openFolder: (instanceId: string) => ipcRenderer.invoke('screenshots:open-folder', instanceId),
+    hasUploadKey: () => ipcRenderer.invoke('screenshots:upload-key'),
+    setUploadKey: (value: string | null) => ipcRenderer.invoke('screenshots:set-upload-key', value)
   },
   servers: {
     copyAddress: (address: string) => ipcRenderer.invoke('servers:copy-address', address),
@@ -96,12 +215,26 @@ const api = {
   settings: {
     update: (patch: unknown) => ipcRenderer.invoke('settings:update', patch)
   },
+  logging: {
+    setLevel: (level: 'error' | 'warn' | 'info' | 'debug') => ipcRenderer.invoke('logging:set-level', level),
+    openFile: () => ipcRenderer.invoke('logging:open-file')
+  },
   launchEvents: {
     onProgress: (callback: (event: unknown) => void) => {
       const listener = (_: unknown, payload: unknown) => callback(payload)
       ipcRenderer.on('launch:progress', listener)
       return () => ipcRenderer.removeListener('launch:progress', listener)
     },
+    onProgressEvent: (callback: (event: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('mc:progress', listener)
+      return () => ipcRenderer.removeListener('mc:progress', listener)
+    },
+    onStage: (callback: (event: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('mc:stage', listener)
+      return () => ipcRenderer.removeListener('mc:stage', listener)
+    },
     onError: (callback: (event: unknown) => void) => {
       const listener = (_: unknown, payload: unknown) => callback(payload)
       ipcRenderer.on('launch:error', listener)
@@ -112,6 +245,61 @@ const api = {
       ipcRenderer.on('launch:warning', listener)
       return () => ipcRenderer.removeListener('launch:warning', listener)
     },
+    onVersion: (callback: (event: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('launch:version', listener)
+      return () => ipcRenderer.removeListener('launch:version', listener)
+    },
+    onCancelled: (callback: (event: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('launch:cancelled', listener)
+      return () => ipcRenderer.removeListener('launch:cancelled', listener)
+    },
+    onCrashed: (callback: (event: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('mc:crashed', listener)
+      return () => ipcRenderer.removeListener('mc:crashed', listener)
+    },
+    onNativeCrash: (callback: (event: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('mc:native-crash', listener)
+      return () => ipcRenderer.removeListener('mc:native-crash', listener)
+    },
+    onBisect: (callback: (event: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('mc:bisect', listener)
+      return () => ipcRenderer.removeListener('mc:bisect', listener)
+    },
+    onBisectOffer: (callback: (event: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('mc:bisect-offer', listener)
+      return () => ipcRenderer.removeListener('mc:bisect-offer', listener)
+    },
+    onOutOfMemory: (callback: (event: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('mc:out-of-memory', listener)
+      return () => ipcRenderer.removeListener('mc:out-of-memory', listener)
+    },
+    onCrashLoop: (callback: (event: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('mc:crash-loop', listener)
+      return () => ipcRenderer.removeListener('mc:crash-loop', listener)
+    },
+    onStats: (callback: (event: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('mc:stats', listener)
+      return () => ipcRenderer.removeListener('mc:stats', listener)
+    },
+    onRestarting: (callback: (event: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('launch:restarting', listener)
+      return () => ipcRenderer.removeListener('launch:restarting', listener)
+    },
+    onExited: (callback: (event: unknown) => void) => {
+      const listener = (_: unknown, payload: unknown) => callback(payload)
+      ipcRenderer.on('mc:exited', listener)
+      return () => ipcRenderer.removeListener('mc:exited', listener)
+    },
     onClosed: (callback: (event: unknown) => void) => {
       const listener = (_: unknown, payload: unknown) => callback(payload)
       ipcRenderer.on('launch:closed', listener)
@@ -1,21 +1,31 @@
 import { useCallback, useDeferredValue, useEffect, useMemo, useRef, useState, type KeyboardEvent as ReactKeyboardEvent } from 'react'
 import {
   AlertTriangle,
+  Archive,
   Box,
+  Camera,
   Check,
   ChevronDown,
   ChevronLeft,
   ChevronRight,
   Cpu,
   Download,
+  FileArchive,
+  FileDown,
+  FileUp,
   FolderOpen,
   Gamepad2,
+  Heart,
   HardDrive,
+  History,
   Home,
   Clock3,
   Image,
   Info,
   Library,
+  Link2,
+  Newspaper,
+  WifiOff,
   Layers3,
   Lock,
   LogOut,
@@ -86,10 +96,17 @@ interface Instance {
   loader: Loader
   loaderVersion?: string
   customClient: boolean
+  customVersionId?: string
   createdAt: string
   updatedAt: string
   lastPlayedAt?: string
   modpack?: { title: string; projectId: string; versionId: string }
+  worldBackups?: WorldBackupPolicy
+}
+
+interface WorldBackupPolicy {
+  keepLast: number
+  keepDays: number
 }
 
 interface SettingsData {
@@ -99,13 +116,32 @@ interface SettingsData {
   height: number
   fullscreen: boolean
   showConsole: boolean
-  minimizeToTrayOnLaunch: boolean
+  launcherVisibility: 'keep-open' | 'minimize' | 'tray' | 'close'
   showSnapshots: boolean
+  showLegacyVersions: boolean
   javaMode: 'auto' | 'manual'
   javaPath: string
   checkUpdates: boolean
   discordActivity: boolean
   reducedMotion: boolean
+  defaultJvmArgs: string
+  defaultLoader: Loader
+  downloadSpeedLimit: number
+  downloadConcurrency: number
+  offlineMode: boolean
+  backgroundPrepare: boolean
+  meteredConnection: 'auto' | 'metered' | 'unmetered'
+  contentUpdateCheckHours: number
+  screenshotUploadUrl: string
+  screenshotUploadField: string
+  screenshotUploadUrlPath: string
+}
+
+interface ScreenshotItem {
+  fileName: string
+  takenAt: string
+  sizeBytes: number
+  thumbnailUrl?: string
 }
 
 interface Account {
@@ -123,7 +159,9 @@ interface ModItem {
   enabled: boolean
   versionNumber?: string
   iconUrl?: string
-  source?: 'modrinth' | 'local' | 'client'
+  source?: 'modrinth' | 'curseforge' | 'local' | 'client'
+  warnings?: string[]
+  needsFabricApi?: boolean
 }
 
 interface SearchHit {
@@ -138,6 +176,28 @@ interface SearchHit {
   versions: string[]
 }
 
+interface ProjectDetails {
+  id: string
+  title: string
+  description: string
+  body: string
+  iconUrl?: string
+  categories: string[]
+  downloads: number
+  followers: number
+  updatedAt?: string
+  license?: { id: string; name: string; url?: string }
+  gallery: Array<{ url: string; title?: string; description?: string; featured: boolean }>
+  links: Array<{ kind: string; label: string; url: string }>
+  versions: Array<{ id: string; name: string; versionNumber: string; type: 'release' | 'beta' | 'alpha'; gameVersions: string[]; loaders: string[]; publishedAt: string; downloads: number; compatible?: boolean }>
+}
+
+interface InstallPlan {
+  items: Array<{ projectId: string; versionId: string; title: string; versionNumber: string; fileName: string; size: number; requiredBy?: string; alreadyInstalled: boolean; replaces?: string[] }>
+  downloadBytes: number
+  problems: string[]
+}
+
 interface PackItem {
   projectId?: string
   versionId?: string
@@ -148,6 +208,31 @@ interface PackItem {
   iconUrl?: string
   contentType: 'resourcepack' | 'shader'
   source: 'modrinth' | 'local'
+  folder?: boolean
+  description?: string
+  packFormat?: number
+  warnings?: string[]
+}
+
+interface CurseForgeInstanceItem {
+  folder: string
+  name: string
+  minecraftVersion: string
+  loader: Loader
+  loaderVersion?: string
+  modCount: number
+}
+
+interface VanillaInstallationItem {
+  directory: string
+  profiles: Array<{ id: string; name: string; minecraftVersion?: string; loader: Loader; loaderVersion?: string; lastUsed?: string; track?: 'release' | 'snapshot' }>
+}
+
+interface CurseForgeSkippedFile {
+  name: string
+  fileName: string
+  reason: string
+  pageUrl?: string
 }
 
 interface WorldItem {
@@ -155,6 +240,11 @@ interface WorldItem {
   name: string
   folderName: string
   modifiedAt: string
+  gameMode?: 'survival' | 'creative' | 'adventure' | 'spectator' | 'hardcore'
+  version?: string
+  lastPlayed?: string
+  sizeBytes?: number
+  iconUrl?: string
 }
 
 interface ProfileSkin {
@@ -177,6 +267,26 @@ interface ProfileData {
   revision: number
 }
 
+interface ConnectivityStatus {
+  state: 'online' | 'unreachable' | 'offline-mode'
+  checkedAt?: string
+}
+
+interface ContentUpdateSummary {
+  checkedAt: string
+  total: number
+  instances: Array<{ instanceId: string; name: string; mods: Array<{ projectId: string; title: string; currentVersion?: string; latestVersion: string }>; modpack?: { title: string; latestVersion: string } }>
+}
+
+interface MeteredPrompt {
+  id: string
+  instanceId?: string
+  name: string
+  what: string
+  bytes: number
+  closed?: boolean
+}
+
 interface PartnerServerStatus {
   online: boolean
   address: string
@@ -192,6 +302,8 @@ interface PartnerServerStatus {
   error?: string
 }
 
+const FABRIC_API_PROJECT = 'P7dR8mSH'
+
 const navItems: Array<{ id: Tab; label: string; icon: typeof Home }> = [
   { id: 'home', label: 'Home', icon: Home },
   { id: 'instances', label: 'Instances', icon: Library },
@@ -272,7 +384,13 @@ function App() {
     speed?: number
   }>({ message: '' })
   const [update, setUpdate] = useState<any>(null)
+  const [connectivity, setConnectivity] = useState<ConnectivityStatus>()
+  const [meteredPrompts, setMeteredPrompts] = useState<MeteredPrompt[]>([])
+  const [contentUpdates, setContentUpdates] = useState<ContentUpdateSummary | null>(null)
+  const contentUpdateTotal = useRef(0)
   const [discordConfigured, setDiscordConfigured] = useState(false)
+  const [firstRunImport, setFirstRunImport] = useState(false)
+  const firstRunChecked = useRef(false)
   const [authenticating, setAuthenticating] = useState(false)
   const [accountMenuOpen, setAccountMenuOpen] = useState(false)
   const toastTimer = useRef<number | null>(null)
@@ -368,12 +486,42 @@ function App() {
     void bootstrap()
   }), [bootstrap])
   useEffect(() => window.mega.app.onUpdate(setUpdate), [])
+  useEffect(() => {
+    void window.mega.network.status().then(setConnectivity)
+    return window.mega.network.onStatus(setConnectivity)
+  }, [])
+  useEffect(() => window.mega.network.onMeteredPrompt((prompt) => {
+    setMeteredPrompts((current) => prompt.closed ? current.filter((item) => item.id !== prompt.id) : [...current, prompt])
+  }), [])
+  useEffect(() => {
+    void window.mega.updates.contentSummary().then((summary) => {
+      contentUpdateTotal.current = summary?.total ?? 0
+      setContentUpdates(summary)
+    })
+    return window.mega.updates.onContent((summary) => {
+      if (summary.total > contentUpdateTotal.current) notify(`${summary.total} mod and modpack update${summary.total === 1 ? ' is' : 's are'} available.`, 'success')
+      contentUpdateTotal.current = summary.total
+      setContentUpdates(summary)
+    })
+  }, [notify])
+  const answerMetered = async (prompt: MeteredPrompt, allowed: boolean) => {
+    setMeteredPrompts((current) => current.filter((item) => item.id !== prompt.id))
+    await window.mega.network.answerMetered(prompt.id, allowed)
+  }
   useEffect(() => {
     const retryUpdatesWhenOnline = () => void window.mega.app.checkUpdates()
     window.addEventListener('online', retryUpdatesWhenOnline)
     return () => window.removeEventListener('online', retryUpdatesWhenOnline)
   }, [])
 
+  // Offered once, on the first start after signing in, when the official
+  // launcher is installed and MegaClient has no instances yet.
+  useEffect(() => {
+    if (!account || booting || firstRunChecked.current) return
+    firstRunChecked.current = true
+    void window.mega.instances.firstRunImport().then((installation) => setFirstRunImport(Boolean(installation)), () => undefined)
+  }, [account, booting])
+
   useEffect(() => {
     document.documentElement.dataset.reducedMotion = settings?.reducedMotion ? 'true' : 'false'
   }, [settings?.reducedMotion])
@@ -432,6 +580,22 @@ function App() {
     }
   }
 
+  // Downloads the selected instance's game files now so it can be played
+  // later without a connection.
+  const prepare = async () => {
+    if (!selected) return
+    setLaunching(true)
+    setLaunchProgress({ message: 'Preparing game files', progress: 0 })
+    try {
+      if (await window.mega.instances.prepare(selected.id)) notify(`${selected.name} is ready to play offline.`, 'success')
+    } catch (error) {
+      notify(errorMessage(error), 'error')
+    } finally {
+      setLaunching(false)
+      setLaunchProgress({ message: '' })
+    }
+  }
+
   if (booting || bootError) {
     return (
       <StartupView
@@ -466,6 +630,7 @@ function App() {
             <button key={item.id} className={tab === item.id ? 'active' : ''} onClick={() => { setAccountMenuOpen(false); setTab(item.id) }}>
               <item.icon size={18} />
               <span>{item.label}</span>
+              {item.id === 'manage' && Boolean(contentUpdates?.total) && <em className="nav-badge" title={`${contentUpdates!.total} updates available`}>{contentUpdates!.total > 99 ? '99+' : contentUpdates!.total}</em>}
             </button>
           ))}
         </nav>
@@ -506,7 +671,15 @@ function App() {
             <div className="banner-progress"><i style={{ width: `${Math.max(2, update.percent ?? 0)}%` }} /></div>
           </div>
         )}
+        {connectivity && connectivity.state !== 'online' && (
+          <div className="update-banner quiet">
+            <WifiOff size={16} />
+            <span>{connectivity.state === 'offline-mode' ? 'Offline mode is on. Installed instances launch without checking for updates, and saved version lists are used.' : 'MegaClient cannot reach the internet. Installed instances still launch, using saved game files.'}</span>
+            {connectivity.state === 'unreachable' && <button onClick={() => void window.mega.network.probe()}>Try again</button>}
+          </div>
+        )}
         {launching && <ActivityBanner progress={launchProgress} onConsole={() => window.mega.instances.openConsole()} />}
+        {meteredPrompts[0] && <ConfirmDialog title={`Download ${formatBytes(meteredPrompts[0].bytes)} on a metered connection?`} message={`${meteredPrompts[0].name} needs ${meteredPrompts[0].what} before it can start. Choose Later to cancel the launch and download when you are on another connection.`} confirmLabel="Download now" cancelLabel="Later" icon={<WifiOff />} onCancel={() => void answerMetered(meteredPrompts[0]!, false)} onConfirm={() => answerMetered(meteredPrompts[0]!, true)} />}
         {tab === 'home' && (
           <HomeView
             selected={selected}
@@ -514,6 +687,7 @@ function App() {
             progress={launchProgress}
             clientVersion={clientVersion}
             onLaunch={launch}
+            onPrepare={prepare}
             onInstances={() => setTab('instances')}
             onBrowse={() => setTab('browse')}
             onManage={() => setTab('manage')}
@@ -532,13 +706,21 @@ function App() {
           />
         )}
         {tab === 'browse' && <BrowseView selected={selected} onChanged={bootstrap} notify={notify} />}
-        {tab === 'manage' && <ManageView selected={selected} notify={notify} />}
+        {tab === 'manage' && <ManageView selected={selected} onChanged={bootstrap} notify={notify} />}
         {tab === 'servers' && <ServersView selected={selected} launching={launching} notify={notify} />}
         {tab === 'cosmetics' && <CosmeticsView account={account} notify={notify} />}
         {tab === 'settings' && settings && (
-          <SettingsView settings={settings} setSettings={setSettings} update={update} version={version} clientVersion={clientVersion} discordConfigured={discordConfigured} notify={notify} />
+          <SettingsView settings={settings} setSettings={setSettings} update={update} version={version} clientVersion={clientVersion} discordConfigured={discordConfigured} contentUpdates={contentUpdates} notify={notify} />
         )}
       </main>
+      {firstRunImport && (
+        <ImportInstancesModal
+          firstRun
+          onClose={() => setFirstRunImport(false)}
+          onImported={async (instance) => { await bootstrap(); await selectInstance(instance.id); setTab('instances') }}
+          notify={notify}
+        />
+      )}
       {toast && <Toast toast={toast} />}
     </div>
   )
@@ -615,10 +797,13 @@ function Toast({ toast }: { toast: { message: string; kind: ToastKind } }) {
   return <div className={`toast ${toast.kind}`}>{icon}<span>{toast.message}</span></div>
 }
 
-function ConfirmDialog({ title, message, confirmLabel = 'Delete', onCancel, onConfirm }: {
+function ConfirmDialog({ title, message, confirmLabel = 'Delete', cancelLabel = 'Cancel', icon, onCancel, onConfirm }: {
   title: string
   message: string
   confirmLabel?: string
+  cancelLabel?: string
+  // Dialogs that do not delete anything show their own icon.
+  icon?: React.ReactNode
   onCancel: () => void
   onConfirm: () => Promise<void>
 }) {
@@ -631,9 +816,9 @@ function ConfirmDialog({ title, message, confirmLabel = 'Delete', onCancel, onCo
   return (
     <div className="modal-backdrop confirm-backdrop" onPointerDown={() => !busy && onCancel()}>
       <section className="confirm-dialog" role="alertdialog" aria-modal="true" aria-labelledby="confirm-title" onPointerDown={(event) => event.stopPropagation()}>
-        <div className="confirm-icon"><Trash2 size={20} /></div>
+        <div className="confirm-icon">{icon ?? <Trash2 size={20} />}</div>
         <div><h2 id="confirm-title">{title}</h2><p>{message}</p></div>
-        <div className="confirm-actions"><button className="secondary" disabled={busy} onClick={onCancel}>Cancel</button><button className="danger-button" disabled={busy} onClick={() => void confirm()}>{busy ? <RefreshCw className="spin" /> : <Trash2 />}{busy ? 'Removing…' : confirmLabel}</button></div>
+        <div className="confirm-actions"><button className="secondary" disabled={busy} onClick={onCancel}>{cancelLabel}</button><button className="danger-button" disabled={busy} onClick={() => void confirm()}>{busy ? <RefreshCw className="spin" /> : icon ?? <Trash2 />}{busy ? (icon ? 'Working…' : 'Removing…') : confirmLabel}</button></div>
       </section>
     </div>
   )
@@ -774,7 +959,7 @@ function PageHeading({ eyebrow, title, description, actions }: {
   )
 }
 
-function HomeView({ selected, launching, progress, clientVersion, onLaunch, onInstances, onBrowse, onManage, onServers, onConsole }: any) {
+function HomeView({ selected, launching, progress, clientVersion, onLaunch, onPrepare, onInstances, onBrowse, onManage, onServers, onConsole }: any) {
   return (
     <div className="page home-page">
       <section className="hero-card">
@@ -812,6 +997,7 @@ function HomeView({ selected, launching, progress, clientVersion, onLaunch, onIn
               ) : (
                 <div className="instance-meta">
                   <span><HardDrive size={14} />{selected.loaderVersion ? `Loader ${selected.loaderVersion}` : 'No loader required'}</span>
+                  <button onClick={onPrepare} title="Download everything this instance needs so it can be played offline"><Download size={15} /> Download files</button>
                   <button onClick={onConsole}><TerminalSquare size={15} /> Console</button>
                 </div>
               )}
@@ -830,14 +1016,81 @@ function HomeView({ selected, launching, progress, clientVersion, onLaunch, onIn
           </div>
         </section>
 
+        <NewsPanel />
       </div>
     </div>
   )
 }
 
+function NewsPanel() {
+  const [items, setItems] = useState<Array<{ id: string; title: string; tag?: string; date?: string; text?: string; imageDataUrl?: string }>>()
+  useEffect(() => {
+    let cancelled = false
+    window.mega.app.getNews().then((next) => { if (!cancelled) setItems(next) }, () => { if (!cancelled) setItems([]) })
+    return () => { cancelled = true }
+  }, [])
+  if (items && !items.length) return null
+  return (
+    <section className="panel news-panel">
+      <div className="panel-title"><span>Minecraft news</span></div>
+      {!items ? <div className="empty-compact"><RefreshCw className="spin" /><p>Loading news…</p></div> : (
+        <div className="news-list">
+          {items.slice(0, 4).map((item) => (
+            <article key={item.id}>
+              {item.imageDataUrl ? <img src={item.imageDataUrl} alt="" /> : <div className="news-placeholder"><Newspaper /></div>}
+              <span>
+                <small>{[item.tag, item.date ? new Date(item.date).toLocaleDateString('en-GB') : ''].filter(Boolean).join(' · ')}</small>
+                <strong>{item.title}</strong>
+                {item.text && <p>{item.text}</p>}
+              </span>
+            </article>
+          ))}
+        </div>
+      )}
+    </section>
+  )
+}
+
 function InstancesView({ instances, selectedId, settings, onSelect, onChanged, notify }: any) {
   const [showCreate, setShowCreate] = useState(false)
   const [pendingDelete, setPendingDelete] = useState<Instance>()
+  const [importing, setImporting] = useState(false)
+  const [exportingId, setExportingId] = useState<string>()
+  const [showImport, setShowImport] = useState(false)
+  const [versionProfileFor, setVersionProfileFor] = useState<Instance>()
+  const [progress, setProgress] = useState<any>()
+  useEffect(() => window.mega.mods.onProgress(setProgress), [])
+
+  const importModpack = async () => {
+    setImporting(true)
+    setProgress(undefined)
+    try {
+      const instance = await window.mega.instances.importMrpack()
+      if (!instance) return
+      await onChanged()
+      onSelect(instance.id)
+      notify(`Imported ${instance.name}.`, 'success')
+    } catch (error) {
+      notify(errorMessage(error), 'error')
+    } finally {
+      setImporting(false)
+    }
+  }
+
+  const exportModpack = async (instance: Instance) => {
+    setExportingId(instance.id)
+    setProgress(undefined)
+    try {
+      const result = await window.mega.instances.exportMrpack(instance.id)
+      if (!result) return
+      notify(`Exported ${instance.name} with ${result.linked} Modrinth ${result.linked === 1 ? 'file' : 'files'} and ${result.overrides} bundled.`, 'success')
+    } catch (error) {
+      notify(errorMessage(error), 'error')
+    } finally {
+      setExportingId(undefined)
+    }
+  }
+
   const remove = async (instance: Instance) => {
     try {
       await window.mega.instances.delete(instance.id)
@@ -855,8 +1108,9 @@ function InstancesView({ instances, selectedId, settings, onSelect, onChanged, n
         eyebrow="Library"
         title="Instances"
         description="Separate Minecraft setups with their own content and saves."
-        actions={<button className="primary" onClick={() => setShowCreate(true)}><Plus size={17} /> New instance</button>}
+        actions={<><button className="secondary" onClick={() => setShowImport(true)}><FolderOpen size={16} /> Import from…</button><button className="secondary" disabled={importing} onClick={() => void importModpack()}>{importing ? <RefreshCw className="spin" size={16} /> : <Upload size={16} />} Import .mrpack</button><button className="primary" onClick={() => setShowCreate(true)}><Plus size={17} /> New instance</button></>}
       />
+      {(importing || exportingId) && progress?.message && <ProgressStrip progress={progress} />}
       <div className="instance-grid">
         {instances.map((instance: Instance) => (
           <article key={instance.id} className={`instance-card ${selectedId === instance.id ? 'selected' : ''}`} onClick={() => onSelect(instance.id)}>
@@ -864,12 +1118,14 @@ function InstancesView({ instances, selectedId, settings, onSelect, onChanged, n
               <div className="instance-icon big">{instance.customClient ? <img src="./logo.png" alt="" /> : <Gamepad2 size={25} />}</div>
               <div className="instance-actions">
                 <button title="Open folder" onClick={(event) => { event.stopPropagation(); void window.mega.instances.openFolder(instance.id) }}><FolderOpen size={16} /></button>
+                {!instance.customClient && <button title="Version profile" onClick={(event) => { event.stopPropagation(); setVersionProfileFor(instance) }}><Layers3 size={16} /></button>}
+                {!instance.customClient && <button title="Export as .mrpack" disabled={!!exportingId} onClick={(event) => { event.stopPropagation(); void exportModpack(instance) }}>{exportingId === instance.id ? <RefreshCw className="spin" size={16} /> : <FileArchive size={16} />}</button>}
                 <button title="Delete" className="danger-icon" onClick={(event) => { event.stopPropagation(); setPendingDelete(instance) }}><Trash2 size={16} /></button>
               </div>
             </div>
             <h3>{instance.name}</h3>
             <p>Minecraft {instance.minecraftVersion}</p>
-            <div className="badges"><span>{loaderLabel(instance.loader)}</span>{instance.customClient && <span className="gradient-badge">MegaClient</span>}</div>
+            <div className="badges"><span>{instance.customVersionId ?? loaderLabel(instance.loader)}</span>{instance.customClient && <span className="gradient-badge">MegaClient</span>}</div>
             <div className="card-footer">
               <small>{instance.lastPlayedAt ? `Played ${new Date(instance.lastPlayedAt).toLocaleDateString('en-GB')}` : 'Not played yet'}</small>
               {selectedId === instance.id && <span><Check size={13} /> Selected</span>}
@@ -892,6 +1148,21 @@ function InstancesView({ instances, selectedId, settings, onSelect, onChanged, n
           notify={notify}
         />
       )}
+      {showImport && (
+        <ImportInstancesModal
+          onClose={() => setShowImport(false)}
+          onImported={async (instance) => { await onChanged(); onSelect(instance.id) }}
+          notify={notify}
+        />
+      )}
+      {versionProfileFor && (
+        <VersionProfileModal
+          instance={versionProfileFor}
+          onClose={() => setVersionProfileFor(undefined)}
+          onChanged={onChanged}
+          notify={notify}
+        />
+      )}
       {pendingDelete && <ConfirmDialog title={`Delete ${pendingDelete.name}?`} message="This removes the instance, its mods and local game files." onCancel={() => setPendingDelete(undefined)} onConfirm={() => remove(pendingDelete)} />}
     </div>
   )
@@ -904,6 +1175,180 @@ interface SelectMenuOption {
   disabled?: boolean
 }
 
+// Instances kept by other launchers, imported with their settings and mods.
+function ImportInstancesModal({ firstRun = false, onClose, onImported, notify }: {
+  // Opened by itself on the first start when the official launcher is found.
+  firstRun?: boolean
+  onClose: () => void
+  onImported: (instance: Instance) => Promise<void>
+  notify: (message: string, kind?: ToastKind) => void
+}) {
+  const [vanilla, setVanilla] = useState<VanillaInstallationItem | null>()
+  const [chosenProfiles, setChosenProfiles] = useState<string[]>([])
+  const [curseForge, setCurseForge] = useState<CurseForgeInstanceItem[]>()
+  const [busy, setBusy] = useState<string>()
+  const [progress, setProgress] = useState<any>()
+  const [skipped, setSkipped] = useState<{ name: string; files: CurseForgeSkippedFile[] }>()
+  useEffect(() => window.mega.mods.onProgress(setProgress), [])
+  useEffect(() => {
+    window.mega.instances.detectVanilla().then((installation) => {
+      setVanilla(installation)
+      setChosenProfiles(installation?.profiles.slice(0, 1).map((profile) => profile.id) ?? [])
+    }, () => setVanilla(null))
+    window.mega.instances.detectCurseForge().then(setCurseForge, () => setCurseForge([]))
+  }, [])
+
+  // Without launcher profiles, the folder's saves and settings are imported
+  // into one instance on the newest release.
+  const importVanilla = async () => {
+    setBusy('vanilla')
+    try {
+      const imported: Instance[] = chosenProfiles.length
+        ? await window.mega.instances.importVanillaProfiles(chosenProfiles)
+        : [await window.mega.instances.importVanilla()]
+      if (!imported[0]) return
+      await onImported(imported[0])
+      notify(imported.length === 1 ? `Imported ${imported[0].name}.` : `Imported ${imported.length} instances.`, 'success')
+      onClose()
+    } catch (error) {
+      notify(errorMessage(error), 'error')
+    } finally {
+      setBusy(undefined)
+    }
+  }
+
+  const toggleProfile = (id: string) => setChosenProfiles((ids) => ids.includes(id) ? ids.filter((item) => item !== id) : [...ids, id])
+
+  const importCurseForge = async (folder?: string) => {
+    setBusy(folder ?? 'curseforge')
+    setProgress(undefined)
+    try {
+      const result = await window.mega.instances.importCurseForge(folder)
+      if (!result) return
+      await onImported(result.instance)
+      if (result.skipped.length) setSkipped({ name: result.instance.name, files: result.skipped })
+      else notify(`Imported ${result.instance.name}.`, 'success')
+    } catch (error) {
+      notify(errorMessage(error), 'error')
+    } finally {
+      setBusy(undefined)
+    }
+  }
+
+  return (
+    <div className="modal-backdrop" onPointerDown={() => { if (!busy) onClose() }}>
+      <div className="modal import-modal" onPointerDown={(event) => event.stopPropagation()} role="dialog" aria-modal="true" aria-labelledby="import-instances-title">
+        <div className="modal-header"><div><small>{firstRun ? 'WELCOME' : 'IMPORT'}</small><h2 id="import-instances-title">{firstRun ? 'Bring your Minecraft over' : 'Import from another launcher'}</h2></div><button aria-label="Close" disabled={Boolean(busy)} onClick={onClose}><X /></button></div>
+        {firstRun && <p className="modal-description">MegaClient found the official Minecraft Launcher on this computer. Import its profiles to keep your worlds, resource packs and settings, or close this and start fresh.</p>}
+        {busy && progress?.message && <ProgressStrip progress={progress} />}
+        {skipped ? (
+          <>
+            <p className="modal-description">{skipped.name} was imported, but {skipped.files.length === 1 ? 'one mod' : `${skipped.files.length} mods`} could not be downloaded. Download {skipped.files.length === 1 ? 'it' : 'them'} from CurseForge and add {skipped.files.length === 1 ? 'it' : 'them'} to the instance's mods folder.</p>
+            <div className="import-list">
+              {skipped.files.map((file) => (
+                <div key={file.fileName}>
+                  <div><strong>{file.name}</strong><small>{file.reason}</small>{file.pageUrl && <small className="import-link" title={file.pageUrl}>{file.pageUrl}</small>}</div>
+                </div>
+              ))}
+            </div>
+          </>
+        ) : (
+          <>
+            <section className="import-section">
+              <div className="import-section-title"><strong>Official Minecraft Launcher</strong><button className="secondary" disabled={Boolean(busy) || !vanilla || (vanilla.profiles.length > 0 && !chosenProfiles.length)} onClick={() => void importVanilla()}>{busy === 'vanilla' ? <RefreshCw className="spin" size={15} /> : <Download size={15} />} {vanilla && !vanilla.profiles.length ? 'Import saves and settings' : 'Import selected'}</button></div>
+              {vanilla === undefined && <LoadingRows count={2} compact />}
+              {vanilla === null && <p className="modal-description">No official launcher installation was found.</p>}
+              {vanilla && <p className="modal-description">Saves, resource packs, options and servers are copied from {vanilla.directory} into a new instance for each profile you choose.</p>}
+              <div className="import-list">
+                {vanilla?.profiles.map((profile) => (
+                  <label key={profile.id}>
+                    <input type="checkbox" checked={chosenProfiles.includes(profile.id)} disabled={Boolean(busy)} onChange={() => toggleProfile(profile.id)} />
+                    <div><strong>{profile.name}</strong><small>{profile.track ? `Latest ${profile.track}` : `Minecraft ${profile.minecraftVersion}`} · {loaderLabel(profile.loader)}</small></div>
+                  </label>
+                ))}
+              </div>
+            </section>
+            <section className="import-section">
+              <div className="import-section-title"><strong>CurseForge</strong><button className="secondary" disabled={Boolean(busy)} onClick={() => void importCurseForge()}>{busy === 'curseforge' ? <RefreshCw className="spin" size={15} /> : <FolderOpen size={15} />} Choose folder</button></div>
+              {!curseForge && <LoadingRows count={2} compact />}
+              {curseForge && !curseForge.length && <p className="modal-description">No CurseForge instances were found in the usual folders.</p>}
+              <div className="import-list">
+                {curseForge?.map((item) => (
+                  <div key={item.folder}>
+                    <div><strong>{item.name}</strong><small>Minecraft {item.minecraftVersion} · {loaderLabel(item.loader)} · {item.modCount} {item.modCount === 1 ? 'mod' : 'mods'}</small></div>
+                    <button className="secondary" disabled={Boolean(busy)} onClick={() => void importCurseForge(item.folder)}>{busy === item.folder ? <RefreshCw className="spin" size={15} /> : <Download size={15} />} Import</button>
+                  </div>
+                ))}
+              </div>
+            </section>
+          </>
+        )}
+        <div className="modal-footer"><button className="secondary" disabled={Boolean(busy)} onClick={onClose}>{skipped ? 'Done' : firstRun ? 'Not now' : 'Close'}</button></div>
+      </div>
+    </div>
+  )
+}
+
+// Profiles copied into the instance's versions folder by hand, or chosen from
+// disk, run in place of the standard Minecraft version.
+function VersionProfileModal({ instance, onClose, onChanged, notify }: {
+  instance: Instance
+  onClose: () => void
+  onChanged: () => Promise<void>
+  notify: (message: string, kind?: ToastKind) => void
+}) {
+  const [profiles, setProfiles] = useState<Array<{ id: string; inheritsFrom?: string; hasJar: boolean }>>()
+  const [current, setCurrent] = useState(instance.customVersionId)
+  const [busy, setBusy] = useState<string>()
+  useEffect(() => {
+    window.mega.instances.versionProfiles(instance.id).then(setProfiles, () => setProfiles([]))
+  }, [instance.id])
+
+  const apply = async (key: string, action: () => Promise<Instance | null>) => {
+    setBusy(key)
+    try {
+      const updated = await action()
+      if (!updated) return
+      setCurrent(updated.customVersionId)
+      await onChanged()
+      notify(updated.customVersionId ? `${instance.name} now runs ${updated.customVersionId}.` : `${instance.name} runs Minecraft ${updated.minecraftVersion} again.`, 'success')
+      setProfiles(await window.mega.instances.versionProfiles(instance.id))
+    } catch (error) {
+      notify(errorMessage(error), 'error')
+    } finally {
+      setBusy(undefined)
+    }
+  }
+
+  return (
+    <div className="modal-backdrop" onPointerDown={() => { if (!busy) onClose() }}>
+      <div className="modal import-modal" onPointerDown={(event) => event.stopPropagation()} role="dialog" aria-modal="true" aria-labelledby="version-profile-title">
+        <div className="modal-header"><div><small>VERSION PROFILE</small><h2 id="version-profile-title">{instance.name}</h2></div><button aria-label="Close" disabled={Boolean(busy)} onClick={onClose}><X /></button></div>
+        <p className="modal-description">Copy a profile's folder into this instance's versions folder, or choose its JSON. A jar with the same name next to the JSON is installed with it.</p>
+        <section className="import-section">
+          <div className="import-section-title"><strong>Profiles in this instance</strong><button className="secondary" disabled={Boolean(busy)} onClick={() => void apply('file', () => window.mega.instances.setCustomVersion(instance.id))}>{busy === 'file' ? <RefreshCw className="spin" size={15} /> : <FolderOpen size={15} />} Choose file</button></div>
+          {!profiles && <LoadingRows count={2} compact />}
+          {profiles && !profiles.length && <p className="modal-description">No version profiles were found in this instance.</p>}
+          <div className="import-list">
+            {profiles?.map((profile) => (
+              <div key={profile.id}>
+                <div><strong>{profile.id}</strong><small>{profile.inheritsFrom ? `Based on ${profile.inheritsFrom}` : 'Standalone'}{profile.hasJar ? ' · Own game jar' : ''}</small></div>
+                {current === profile.id
+                  ? <span className="badges"><span><Check size={13} /> In use</span></span>
+                  : <button className="secondary" disabled={Boolean(busy)} onClick={() => void apply(profile.id, () => window.mega.instances.selectVersionProfile(instance.id, profile.id))}>{busy === profile.id ? <RefreshCw className="spin" size={15} /> : <Play size={15} />} Use</button>}
+              </div>
+            ))}
+          </div>
+        </section>
+        <div className="modal-footer">
+          {current && <button className="secondary" disabled={Boolean(busy)} onClick={() => void apply('clear', () => window.mega.instances.clearCustomVersion(instance.id))}>Use the standard version</button>}
+          <button className="secondary" disabled={Boolean(busy)} onClick={onClose}>Close</button>
+        </div>
+      </div>
+    </div>
+  )
+}
+
 function SelectMenu({
   value,
   options,
@@ -1062,7 +1507,7 @@ function SelectMenu({
 function CreateInstanceModal({ settings, onClose, onCreated, notify }: any) {
   const [custom, setCustom] = useState(false)
   const [name, setName] = useState('New instance')
-  const [loader, setLoader] = useState<Loader>('vanilla')
+  const [loader, setLoader] = useState<Loader>(settings.defaultLoader ?? 'vanilla')
   const [versions, setVersions] = useState<Array<{ id: string; type: string }>>([])
   const [mcVersion, setMcVersion] = useState('')
   const [loaderVersions, setLoaderVersions] = useState<string[]>([])
@@ -1072,12 +1517,13 @@ function CreateInstanceModal({ settings, onClose, onCreated, notify }: any) {
   const [loadingLoaderVersions, setLoadingLoaderVersions] = useState(false)
   const [versionsError, setVersionsError] = useState('')
   const [loaderError, setLoaderError] = useState('')
+  const [showPatchNotes, setShowPatchNotes] = useState(false)
 
   const loadMinecraftVersions = useCallback(async () => {
     setLoadingVersions(true)
     setVersionsError('')
     try {
-      const items = await window.mega.app.getVersions(settings.showSnapshots)
+      const items = await window.mega.app.getVersions(settings.showSnapshots, settings.showLegacyVersions)
       setVersions(items)
       setMcVersion((current) => current && items.some((item) => item.id === current) ? current : (items[0]?.id ?? ''))
       if (!items.length) setVersionsError('No Minecraft versions were returned. Check your connection and try again.')
@@ -1088,7 +1534,7 @@ function CreateInstanceModal({ settings, onClose, onCreated, notify }: any) {
     } finally {
       setLoadingVersions(false)
     }
-  }, [settings.showSnapshots])
+  }, [settings.showSnapshots, settings.showLegacyVersions])
 
   useEffect(() => { void loadMinecraftVersions() }, [loadMinecraftVersions])
 
@@ -1099,7 +1545,7 @@ function CreateInstanceModal({ settings, onClose, onCreated, notify }: any) {
       setMcVersion('26.2')
     } else if (name === 'MegaClient 26.2') {
       setName('New instance')
-      setLoader('vanilla')
+      setLoader(settings.defaultLoader ?? 'vanilla')
       setMcVersion(versions[0]?.id ?? '')
     }
   }, [custom, versions])
@@ -1166,70 +1612,115 @@ function CreateInstanceModal({ settings, onClose, onCreated, notify }: any) {
   const createDisabled = busy || (!custom && (loadingVersions || !mcVersion)) || (loader !== 'vanilla' && (loadingLoaderVersions || !loaderVersion))
 
   return (
-    <div className="modal-backdrop" onPointerDown={onClose}>
-      <div className="modal create-instance-modal" onPointerDown={(event) => event.stopPropagation()} role="dialog" aria-modal="true" aria-labelledby="create-instance-title">
-        <div className="modal-header">
-          <div><small>NEW INSTANCE</small><h2 id="create-instance-title">Create an instance</h2></div>
-          <button type="button" aria-label="Close create instance" onClick={onClose}><X /></button>
-        </div>
-        <div className="choice-row" aria-label="Instance type">
-          <button type="button" className={!custom ? 'chosen' : ''} onClick={() => setCustom(false)}><Gamepad2 /><span><strong>Standard</strong><small>Vanilla or a mod loader</small></span></button>
-          <button type="button" className={custom ? 'chosen client-choice' : ''} onClick={() => setCustom(true)}><img src="./logo.png" alt="" /><span><strong>MegaClient</strong><small>Fabric · Minecraft 26.2</small></span></button>
+    <>
+      <div className="modal-backdrop" onPointerDown={onClose}>
+        <div className="modal create-instance-modal" onPointerDown={(event) => event.stopPropagation()} role="dialog" aria-modal="true" aria-labelledby="create-instance-title">
+          <div className="modal-header">
+            <div><small>NEW INSTANCE</small><h2 id="create-instance-title">Create an instance</h2></div>
+            <button type="button" aria-label="Close create instance" onClick={onClose}><X /></button>
+          </div>
+          <div className="choice-row" aria-label="Instance type">
+            <button type="button" className={!custom ? 'chosen' : ''} onClick={() => setCustom(false)}><Gamepad2 /><span><strong>Standard</strong><small>Vanilla or a mod loader</small></span></button>
+            <button type="button" className={custom ? 'chosen client-choice' : ''} onClick={() => setCustom(true)}><img src="./logo.png" alt="" /><span><strong>MegaClient</strong><small>Fabric · Minecraft 26.2</small></span></button>
+          </div>
+          <label className="field-label">Instance name<input value={name} onChange={(event) => setName(event.target.value)} maxLength={48} autoFocus /></label>
+          {!custom && (
+            <>
+              <div className="form-grid">
+                <label className="field-label">Minecraft version
+                  <SelectMenu
+                    value={mcVersion}
+                    options={minecraftOptions}
+                    onChange={setMcVersion}
+                    disabled={loadingVersions || !minecraftOptions.length}
+                    placeholder={loadingVersions ? 'Loading versions…' : 'Select a version'}
+                    ariaLabel="Minecraft version"
+                    emptyLabel="No Minecraft versions match your search"
+                  />
+                </label>
+                <label className="field-label">Loader
+                  <SelectMenu value={loader} options={loaderOptions} onChange={(value) => setLoader(value as Loader)} ariaLabel="Mod loader" />
+                </label>
+              </div>
+              {versionsError && (
+                <div className="field-message error"><AlertTriangle size={15} /><span>{versionsError}</span><button type="button" onClick={() => void loadMinecraftVersions()}>Retry</button></div>
+              )}
+              {loader !== 'vanilla' && (
+                <label className="field-label">Loader version
+                  <SelectMenu
+                    value={loaderVersion}
+                    options={loaderVersionOptions}
+                    onChange={setLoaderVersion}
+                    disabled={loadingLoaderVersions || !loaderVersionOptions.length}
+                    placeholder={loadingLoaderVersions ? 'Checking compatibility…' : 'Select a compatible build'}
+                    ariaLabel={`${loaderLabel(loader)} version`}
+                  />
+                </label>
+              )}
+              {loaderError && <div className="field-message error"><AlertTriangle size={15} /><span>{loaderError}</span></div>}
+            </>
+          )}
+          {custom && (
+            <div className="client-note">
+              <img src="./logo.png" alt="" />
+              <div><strong>MegaClient {CLIENT_FALLBACK_VERSION}</strong><p>Minecraft 26.2 and everything it needs are prepared automatically.</p></div>
+              <span className="locked-chip"><Lock size={12} /> Protected</span>
+            </div>
+          )}
+          {custom && loadingLoaderVersions && <div className="field-message"><RefreshCw className="spin" size={15} /><span>Checking the required Fabric Loader build…</span></div>}
+          {custom && loaderError && <div className="field-message error"><AlertTriangle size={15} /><span>{loaderError}</span></div>}
+          <div className="modal-footer">
+            {mcVersion && <button type="button" className="secondary" onClick={() => setShowPatchNotes(true)}>Patch notes</button>}
+            <button type="button" className="secondary" onClick={onClose}>Cancel</button>
+            <button type="button" className="primary" onClick={create} disabled={createDisabled}>{busy ? <RefreshCw className="spin" /> : <Plus />}{busy ? 'Creating…' : 'Create instance'}</button>
+          </div>
         </div>
-        <label className="field-label">Instance name<input value={name} onChange={(event) => setName(event.target.value)} maxLength={48} autoFocus /></label>
-        {!custom && (
+      </div>
+      {showPatchNotes && <PatchNotesModal version={mcVersion} onClose={() => setShowPatchNotes(false)} />}
+    </>
+  )
+}
+
+// Mojang publishes the notes as HTML; only their text is shown.
+function patchNotesText(html: string): string {
+  const parsed = new DOMParser().parseFromString(html.replace(/<\/(?:p|li|h\d)>|<br\s*\/?>/gi, '$&\n'), 'text/html')
+  return (parsed.body.textContent ?? '').replace(/\n{3,}/g, '\n\n').trim()
+}
+
+function PatchNotesModal({ version, onClose }: { version: string; onClose: () => void }) {
+  const [notes, setNotes] = useState<Awaited<ReturnType<typeof window.mega.app.getPatchNotes>>>()
+  const [error, setError] = useState('')
+  useEffect(() => {
+    let cancelled = false
+    window.mega.app.getPatchNotes(version)
+      .then((value) => { if (!cancelled) setNotes(value) })
+      .catch((reason) => { if (!cancelled) setError(errorMessage(reason)) })
+    return () => { cancelled = true }
+  }, [version])
+  const body = useMemo(() => notes?.body ? patchNotesText(notes.body) : '', [notes])
+
+  return (
+    <div className="modal-backdrop" onPointerDown={onClose}>
+      <div className="modal project-modal" onPointerDown={(event) => event.stopPropagation()} role="dialog" aria-modal="true" aria-labelledby="patch-notes-title">
+        <div className="modal-header"><div><small>PATCH NOTES{notes?.date ? ` · ${new Date(notes.date).toLocaleDateString('en-GB')}` : ''}</small><h2 id="patch-notes-title">{notes?.title ?? `Minecraft ${version}`}</h2></div><button aria-label="Close" onClick={onClose}><X /></button></div>
+        {notes === undefined && !error && <LoadingRows count={3} compact />}
+        {error && <div className="field-message error"><AlertTriangle size={15} /><span>{error}</span></div>}
+        {notes === null && <p className="modal-description">Mojang has not published patch notes for Minecraft {version}.</p>}
+        {notes && (
           <>
-            <div className="form-grid">
-              <label className="field-label">Minecraft version
-                <SelectMenu
-                  value={mcVersion}
-                  options={minecraftOptions}
-                  onChange={setMcVersion}
-                  disabled={loadingVersions || !minecraftOptions.length}
-                  placeholder={loadingVersions ? 'Loading versions…' : 'Select a version'}
-                  ariaLabel="Minecraft version"
-                  emptyLabel="No Minecraft versions match your search"
-                />
-              </label>
-              <label className="field-label">Loader
-                <SelectMenu value={loader} options={loaderOptions} onChange={(value) => setLoader(value as Loader)} ariaLabel="Mod loader" />
-              </label>
-            </div>
-            {versionsError && (
-              <div className="field-message error"><AlertTriangle size={15} /><span>{versionsError}</span><button type="button" onClick={() => void loadMinecraftVersions()}>Retry</button></div>
-            )}
-            {loader !== 'vanilla' && (
-              <label className="field-label">Loader version
-                <SelectMenu
-                  value={loaderVersion}
-                  options={loaderVersionOptions}
-                  onChange={setLoaderVersion}
-                  disabled={loadingLoaderVersions || !loaderVersionOptions.length}
-                  placeholder={loadingLoaderVersions ? 'Checking compatibility…' : 'Select a compatible build'}
-                  ariaLabel={`${loaderLabel(loader)} version`}
-                />
-              </label>
-            )}
-            {loaderError && <div className="field-message error"><AlertTriangle size={15} /><span>{loaderError}</span></div>}
+            {notes.imageUrl && <div className="project-gallery"><img src={notes.imageUrl} alt="" loading="lazy" decoding="async" /></div>}
+            {notes.summary && <p className="modal-description">{notes.summary}</p>}
+            {body && <div className="project-body">{body}</div>}
           </>
         )}
-        {custom && (
-          <div className="client-note">
-            <img src="./logo.png" alt="" />
-            <div><strong>MegaClient {CLIENT_FALLBACK_VERSION}</strong><p>Minecraft 26.2 and everything it needs are prepared automatically.</p></div>
-            <span className="locked-chip"><Lock size={12} /> Protected</span>
-          </div>
-        )}
-        {custom && loadingLoaderVersions && <div className="field-message"><RefreshCw className="spin" size={15} /><span>Checking the required Fabric Loader build…</span></div>}
-        {custom && loaderError && <div className="field-message error"><AlertTriangle size={15} /><span>{loaderError}</span></div>}
         <div className="modal-footer">
-          <button type="button" className="secondary" onClick={onClose}>Cancel</button>
-          <button type="button" className="primary" onClick={create} disabled={createDisabled}>{busy ? <RefreshCw className="spin" /> : <Plus />}{busy ? 'Creating…' : 'Create instance'}</button>
+          <button className="secondary" onClick={onClose}>Close</button>
         </div>
       </div>
     </div>
   )
 }
+
 function BrowseView({ selected, onChanged, notify }: { selected?: Instance; onChanged: () => Promise<void>; notify: (message: string, kind?: ToastKind) => void }) {
   const [type, setType] = useState<'mod' | 'modpack' | 'resourcepack' | 'shader'>('mod')
   const [query, setQuery] = useState('')
@@ -1240,6 +1731,15 @@ function BrowseView({ selected, onChanged, notify }: { selected?: Instance; onCh
   const [progress, setProgress] = useState<any>(null)
   const [page, setPage] = useState(1)
   const [totalHits, setTotalHits] = useState(0)
+  const [sort, setSort] = useState<'downloads' | 'follows' | 'updated'>('downloads')
+  const [popular, setPopular] = useState<Record<'mod' | 'shader' | 'resourcepack', SearchHit[]>>()
+  const [viewing, setViewing] = useState<SearchHit>()
+  const [planning, setPlanning] = useState<string>()
+  const [pendingPlan, setPendingPlan] = useState<{ hit: SearchHit; plan: InstallPlan }>()
+  const [showCollection, setShowCollection] = useState(false)
+  const [library, setLibrary] = useState<ModrinthLibrary | null>(null)
+  const [following, setFollowing] = useState<string>()
+  const closeDetails = useCallback(() => setViewing(undefined), [])
   const requestId = useRef(0)
   const listRef = useRef<HTMLDivElement>(null)
   const pageSize = 30
@@ -1252,7 +1752,7 @@ function BrowseView({ selected, onChanged, notify }: { selected?: Instance; onCh
     const current = ++requestId.current
     const timer = window.setTimeout(() => {
       setLoading(true)
-      void window.mega.mods.search({ query: deferredQuery, type, instanceId: selected?.id, offset: (page - 1) * pageSize })
+      void window.mega.mods.search({ query: deferredQuery, type, instanceId: selected?.id, offset: (page - 1) * pageSize, sort: deferredQuery.trim() ? undefined : sort })
         .then((data) => {
           if (current !== requestId.current) return
           setResults(data.hits)
@@ -1262,7 +1762,40 @@ function BrowseView({ selected, onChanged, notify }: { selected?: Instance; onCh
         .finally(() => { if (current === requestId.current) setLoading(false) })
     }, 180)
     return () => window.clearTimeout(timer)
-  }, [deferredQuery, type, selected?.id, page, notify])
+  }, [deferredQuery, type, selected?.id, page, sort, notify])
+
+  useEffect(() => {
+    let cancelled = false
+    setPopular(undefined)
+    window.mega.mods.popular({ instanceId: selected?.id, sort, limit: 5 })
+      .then((value) => { if (!cancelled) setPopular(value) })
+      .catch(() => undefined)
+    return () => { cancelled = true }
+  }, [selected?.id, sort])
+
+  // Followed and owned projects are only listed with a linked Modrinth account.
+  useEffect(() => {
+    let cancelled = false
+    window.mega.modrinth.account()
+      .then((account) => account ? window.mega.modrinth.library() : null)
+      .then((value) => { if (!cancelled) setLibrary(value) })
+      .catch(() => undefined)
+    return () => { cancelled = true }
+  }, [])
+
+  const toggleFollow = async (hit: SearchHit) => {
+    if (!library) return
+    const followed = library.followed.some((item) => item.project_id === hit.project_id)
+    setFollowing(hit.project_id)
+    try {
+      await window.mega.modrinth.follow(hit.project_id, !followed)
+      setLibrary({ ...library, followed: followed ? library.followed.filter((item) => item.project_id !== hit.project_id) : [hit, ...library.followed] })
+    } catch (error) {
+      notify(errorMessage(error), 'error')
+    } finally {
+      setFollowing(undefined)
+    }
+  }
 
   const changePage = (next: number) => {
     const safe = Math.max(1, Math.min(pageCount, next))
@@ -1271,14 +1804,57 @@ function BrowseView({ selected, onChanged, notify }: { selected?: Instance; onCh
     listRef.current?.scrollIntoView({ behavior: 'smooth', block: 'start' })
   }
 
-  const install = async (hit: SearchHit) => {
+  // Dependencies and problems are shown before anything is downloaded; a
+  // single file with nothing else to pull in installs straight away.
+  const requestInstall = async (hit: SearchHit) => {
+    if (!selected) return notify('Create and select an instance first.', 'error')
+    setPlanning(hit.project_id)
+    try {
+      const plan = await window.mega.mods.installPlan(hit.project_id, selected.id)
+      if (plan.problems.length || plan.items.some((item) => item.replaces?.length) || plan.items.filter((item) => !item.alreadyInstalled).length > 1) setPendingPlan({ hit, plan })
+      else await install(hit)
+    } catch (error) {
+      notify(errorMessage(error), 'error')
+    } finally {
+      setPlanning(undefined)
+    }
+  }
+
+  const install = async (hit: SearchHit, replace = false) => {
     if (!selected) return notify('Create and select an instance first.', 'error')
+    setPendingPlan(undefined)
     setInstalling(hit.project_id)
     try {
-      if (type === 'mod') await window.mega.mods.install(selected.id, hit.project_id)
-      else if (type === 'modpack') await window.mega.mods.installModpack(selected.id, hit.project_id)
-      else await window.mega.packs.install(selected.id, hit.project_id, type)
-      notify(`${hit.title} installed.`, 'success')
+      let shaderLoader: string | undefined
+      if (hit.project_type === 'mod') await window.mega.mods.install(selected.id, hit.project_id, replace)
+      else if (hit.project_type === 'modpack') await window.mega.mods.installModpack(selected.id, hit.project_id)
+      else {
+        // The install plan already listed the shader mod, so it is installed
+        // along with the pack.
+        const result = await window.mega.packs.install(selected.id, hit.project_id, hit.project_type, true)
+        if (result.status === 'needs-shader-loader') return notify(result.requirement.reason, 'warning')
+        shaderLoader = result.shaderLoader?.title
+      }
+      notify(shaderLoader ? `${hit.title} installed with ${shaderLoader}.` : `${hit.title} installed.`, 'success')
+      await onChanged()
+    } catch (error) {
+      notify(errorMessage(error), 'error')
+    } finally {
+      setInstalling(undefined)
+      setProgress(null)
+    }
+  }
+
+  const installCollection = async (input: string) => {
+    if (!selected) return
+    setInstalling('collection')
+    try {
+      const report = await window.mega.mods.installCollection(selected.id, input)
+      setShowCollection(false)
+      const installed = report.results.filter((result) => result.status === 'installed').length
+      const failed = report.results.filter((result) => result.status === 'failed')
+      const skipped = report.results.length - installed - failed.length
+      notify(`Installed ${installed} of ${report.results.length} from ${report.name}${skipped ? `; ${skipped} not compatible` : ''}${failed.length ? `; ${failed.map((result) => result.title).join(', ')} failed` : ''}.`, failed.length ? 'warning' : 'success')
       await onChanged()
     } catch (error) {
       notify(errorMessage(error), 'error')
@@ -1297,6 +1873,7 @@ function BrowseView({ selected, onChanged, notify }: { selected?: Instance; onCh
         eyebrow="Discover"
         title="Find new content"
         description={selected ? `${selected.name} · Minecraft ${selected.minecraftVersion}` : 'Select an instance before installing.'}
+        actions={<button className="secondary" disabled={!selected || Boolean(installing)} onClick={() => setShowCollection(true)}><Library size={16} /> Install collection</button>}
       />
       <div className="browse-toolbar stacked-toolbar" ref={listRef}>
         <div className="segmented discover-types">
@@ -1307,43 +1884,195 @@ function BrowseView({ selected, onChanged, notify }: { selected?: Instance; onCh
         </div>
         <div className="search-box"><Search size={17} /><input placeholder={`Search ${labels[type]}…`} value={query} onChange={(event) => { setQuery(event.target.value); setPage(1) }} />{loading && <RefreshCw className="spin" size={16} />}</div>
       </div>
+      {!query.trim() && (
+        <div className="discover-sort">
+          <div className="segmented">
+            <button className={sort === 'downloads' ? 'active' : ''} onClick={() => { setSort('downloads'); setPage(1) }}>Most downloaded</button>
+            <button className={sort === 'follows' ? 'active' : ''} onClick={() => { setSort('follows'); setPage(1) }}>Trending</button>
+            <button className={sort === 'updated' ? 'active' : ''} onClick={() => { setSort('updated'); setPage(1) }}>Recently updated</button>
+          </div>
+        </div>
+      )}
+      {!query.trim() && page === 1 && popular && (
+        <div className="popular-grid">
+          {([['mod', 'Mods'], ['shader', 'Shaders'], ['resourcepack', 'Resource packs']] as const).map(([key, label]) => (
+            <section key={key}>
+              <h3>{label}{selected && <small> for {selected.minecraftVersion}</small>}</h3>
+              {popular[key].map((hit) => (
+                <button key={hit.project_id} onClick={() => setViewing(hit)}>
+                  <span className="content-icon">{hit.icon_url ? <img src={hit.icon_url} alt="" loading="lazy" decoding="async" /> : key === 'mod' ? <Box /> : key === 'shader' ? <Sparkles /> : <Image />}</span>
+                  <span><strong>{hit.title}</strong><small>{formatDownloads(hit.downloads)} downloads</small></span>
+                </button>
+              ))}
+              {!popular[key].length && <p>Nothing found for this instance.</p>}
+            </section>
+          ))}
+        </div>
+      )}
+      {!query.trim() && page === 1 && library && (library.followed.length > 0 || library.owned.length > 0) && (
+        <div className="popular-grid modrinth-library">
+          {([['followed', 'Followed on Modrinth'], ['owned', 'Your projects']] as const).filter(([key]) => library[key].length).map(([key, label]) => (
+            <section key={key}>
+              <h3>{label}</h3>
+              {library[key].slice(0, 6).map((hit) => (
+                <button key={hit.project_id} onClick={() => setViewing(hit)}>
+                  <span className="content-icon">{hit.icon_url ? <img src={hit.icon_url} alt="" loading="lazy" decoding="async" /> : <Box />}</span>
+                  <span><strong>{hit.title}</strong><small>{formatDownloads(hit.downloads)} downloads</small></span>
+                </button>
+              ))}
+            </section>
+          ))}
+        </div>
+      )}
       {installing && progress && <ProgressStrip progress={progress} />}
       {loading && !results.length && <LoadingRows count={5} />}
       <div className={`content-list ${loading && results.length ? 'is-loading' : ''}`} aria-busy={loading}>
         {results.map((hit) => (
           <article key={hit.project_id} className="content-card">
             <div className="content-icon">{hit.icon_url ? <img src={hit.icon_url} alt="" loading="lazy" decoding="async" /> : fallbackIcon}</div>
-            <div className="content-copy"><h3>{hit.title}<span>by {hit.author}</span></h3><p>{hit.description}</p><div className="content-meta"><span><Download size={13} />{formatDownloads(hit.downloads)}</span>{hit.categories.slice(0, 2).map((category) => <span key={category}>{category}</span>)}</div></div>
-            <button className="secondary install-button" disabled={!selected || Boolean(installing)} onClick={() => install(hit)}>{installing === hit.project_id ? <RefreshCw className="spin" /> : <Download />} {installing === hit.project_id ? 'Installing…' : 'Install'}</button>
+            <div className="content-copy"><h3><button className="content-title" onClick={() => setViewing(hit)}>{hit.title}</button><span>by {hit.author}</span></h3><p>{hit.description}</p><div className="content-meta"><span><Download size={13} />{formatDownloads(hit.downloads)}</span>{hit.categories.slice(0, 2).map((category) => <span key={category}>{category}</span>)}</div></div>
+            <button className="secondary install-button" disabled={!selected || Boolean(installing) || Boolean(planning)} onClick={() => void requestInstall(hit)}>{installing === hit.project_id || planning === hit.project_id ? <RefreshCw className="spin" /> : <Download />} {installing === hit.project_id ? 'Installing…' : 'Install'}</button>
           </article>
         ))}
       </div>
       {!loading && !results.length && <div className="empty-state small"><Search /><h2>No matching content</h2><p>Try a different search or instance.</p></div>}
       {totalHits > pageSize && <Pagination page={page} pageCount={pageCount} onChange={changePage} />}
+      {viewing && (
+        <ProjectDetailsModal
+          hit={viewing}
+          instance={selected}
+          installing={installing === viewing.project_id}
+          canInstall={Boolean(selected) && !installing && !planning}
+          onInstall={() => void requestInstall(viewing)}
+          followed={library ? library.followed.some((item) => item.project_id === viewing.project_id) : undefined}
+          followBusy={following === viewing.project_id}
+          onFollow={() => void toggleFollow(viewing)}
+          onClose={closeDetails}
+          notify={notify}
+        />
+      )}
+      {showCollection && <CollectionModal busy={installing === 'collection'} onClose={() => setShowCollection(false)} onInstall={installCollection} />}
+      {pendingPlan && <InstallPlanDialog title={pendingPlan.hit.title} plan={pendingPlan.plan} onCancel={() => setPendingPlan(undefined)} onConfirm={() => install(pendingPlan.hit, true)} />}
     </div>
   )
 }
 
-function LoadingRows({ count = 4, compact = false }: { count?: number; compact?: boolean }) {
+function InstallPlanDialog({ title, plan, onCancel, onConfirm }: { title: string; plan: InstallPlan; onCancel: () => void; onConfirm: () => Promise<void> }) {
+  const pending = plan.items.filter((item) => !item.alreadyInstalled)
   return (
-    <div className={`loading-rows ${compact ? 'compact' : ''}`} aria-label="Loading content" aria-busy="true">
-      {Array.from({ length: count }, (_, index) => (
-        <div className="loading-row" key={index}><i /><span><b /><small /></span><em /></div>
-      ))}
+    <div className="modal-backdrop nested-modal" onPointerDown={onCancel}>
+      <div className="modal install-plan-modal" onPointerDown={(event) => event.stopPropagation()} role="dialog" aria-modal="true" aria-labelledby="install-plan-title">
+        <div className="modal-header"><div><small>INSTALL</small><h2 id="install-plan-title">{title}</h2></div><button aria-label="Close" onClick={onCancel}><X /></button></div>
+        <p className="modal-description">{plan.problems.length
+          ? 'This cannot be installed in the selected instance.'
+          : `${pending.length} file${pending.length === 1 ? '' : 's'} will be downloaded, ${formatBytes(plan.downloadBytes)} in total.`}</p>
+        {plan.problems.map((problem) => <div key={problem} className="field-message error"><AlertTriangle size={15} /><span>{problem}</span></div>)}
+        <div className="project-versions">
+          {plan.items.map((item) => (
+            <div key={item.versionId}><strong>{item.title}</strong><small>{item.alreadyInstalled ? 'Already installed' : item.replaces?.length ? `Replaces ${item.replaces.join(', ')}` : item.requiredBy ? `Required by ${item.requiredBy}` : item.versionNumber}</small><small>{formatBytes(item.size)}</small></div>
+          ))}
+        </div>
+        <div className="modal-footer"><button className="secondary" onClick={onCancel}>Cancel</button><button className="primary" disabled={plan.problems.length > 0} onClick={() => void onConfirm()}><Download />Install {pending.length > 1 ? `${pending.length} files` : ''}</button></div>
+      </div>
     </div>
   )
 }
 
-function Pagination({ page, pageCount, onChange }: { page: number; pageCount: number; onChange: (page: number) => void }) {
-  const pages = useMemo(() => {
-    const values = new Set<number>([1, pageCount, page - 1, page, page + 1])
-    return [...values].filter((value) => value >= 1 && value <= pageCount).sort((a, b) => a - b)
-  }, [page, pageCount])
+function ProjectDetailsModal({ hit, instance, installing, canInstall, onInstall, followed, followBusy, onFollow, onClose, notify }: {
+  hit: SearchHit
+  instance?: Instance
+  installing: boolean
+  canInstall: boolean
+  onInstall: () => void
+  // Left undefined when no Modrinth account is linked.
+  followed?: boolean
+  followBusy: boolean
+  onFollow: () => void
+  onClose: () => void
+  notify: (message: string, kind?: ToastKind) => void
+}) {
+  const [details, setDetails] = useState<ProjectDetails>()
+  useEffect(() => {
+    let cancelled = false
+    window.mega.mods.project(hit.project_id, instance?.id)
+      .then((value) => { if (!cancelled) setDetails(value) })
+      .catch((error) => { if (!cancelled) { notify(errorMessage(error), 'error'); onClose() } })
+    return () => { cancelled = true }
+  }, [hit.project_id, instance?.id, notify, onClose])
+  const compatible = details?.versions.filter((version) => version.compatible !== false) ?? []
+  const shownVersions = (instance ? compatible : details?.versions ?? []).slice(0, 8)
+
   return (
-    <nav className="pagination" aria-label="Discover pages">
-      <button disabled={page === 1} onClick={() => onChange(page - 1)}><ChevronLeft size={16} /> Previous</button>
-      <div>
-        {pages.map((value, index) => (
+    <div className="modal-backdrop" onPointerDown={onClose}>
+      <div className="modal project-modal" onPointerDown={(event) => event.stopPropagation()} role="dialog" aria-modal="true" aria-labelledby="project-title">
+        <div className="modal-header">
+          <div className="project-heading">
+            <div className="content-icon">{hit.icon_url ? <img src={hit.icon_url} alt="" /> : <Box />}</div>
+            <div>{hit.author && <small>{hit.author.toUpperCase()}</small>}<h2 id="project-title">{hit.title}</h2></div>
+          </div>
+          <button aria-label="Close" onClick={onClose}><X /></button>
+        </div>
+        <p className="modal-description">{hit.description}</p>
+        {!details && <LoadingRows count={3} compact />}
+        {details && (
+          <>
+            <div className="content-meta project-meta">
+              <span><Download size={13} />{formatDownloads(details.downloads)}</span>
+              <span>{formatDownloads(details.followers)} followers</span>
+              {details.license && <span>{details.license.name}</span>}
+              {details.updatedAt && <span>Updated {new Date(details.updatedAt).toLocaleDateString('en-GB')}</span>}
+              {details.categories.slice(0, 4).map((category) => <span key={category}>{category}</span>)}
+            </div>
+            {details.gallery.length > 0 && (
+              <div className="project-gallery">
+                {details.gallery.slice(0, 6).map((image) => <img key={image.url} src={image.url} alt={image.title ?? ''} title={image.title} loading="lazy" decoding="async" />)}
+              </div>
+            )}
+            {details.body && <div className="project-body">{details.body}</div>}
+            <h3 className="project-section">{instance ? `Versions for ${instance.name}` : 'Versions'}</h3>
+            {instance && !compatible.length && <p className="modal-description">No version supports Minecraft {instance.minecraftVersion} with {loaderLabel(instance.loader)}.</p>}
+            <div className="project-versions">
+              {shownVersions.map((version) => (
+                <div key={version.id}><strong>{version.versionNumber}</strong><small>{version.type} · {version.loaders.join(', ')} · {version.gameVersions.slice(-3).join(', ')}</small><small>{new Date(version.publishedAt).toLocaleDateString('en-GB')}</small></div>
+              ))}
+            </div>
+            {details.links.length > 0 && (
+              <div className="project-links">
+                {details.links.map((link) => <span key={`${link.kind}:${link.url}`} title={link.url}><strong>{link.label}</strong>{link.url}</span>)}
+              </div>
+            )}
+          </>
+        )}
+        <div className="modal-footer">
+          <button className="secondary" onClick={onClose}>Close</button>
+          {followed !== undefined && <button className={`secondary follow-button ${followed ? 'following' : ''}`} disabled={followBusy} onClick={onFollow}><Heart />{followed ? 'Following' : 'Follow'}</button>}
+          <button className="primary" disabled={!canInstall || (Boolean(instance) && Boolean(details) && !compatible.length)} onClick={onInstall}>{installing ? <RefreshCw className="spin" /> : <Download />}{installing ? 'Installing…' : 'Install'}</button>
+        </div>
+      </div>
+    </div>
+  )
+}
+
+function LoadingRows({ count = 4, compact = false }: { count?: number; compact?: boolean }) {
+  return (
+    <div className={`loading-rows ${compact ? 'compact' : ''}`} aria-label="Loading content" aria-busy="true">
+      {Array.from({ length: count }, (_, index) => (
+        <div className="loading-row" key={index}><i /><span><b /><small /></span><em /></div>
+      ))}
+    </div>
+  )
+}
+
+function Pagination({ page, pageCount, onChange }: { page: number; pageCount: number; onChange: (page: number) => void }) {
+  const pages = useMemo(() => {
+    const values = new Set<number>([1, pageCount, page - 1, page, page + 1])
+    return [...values].filter((value) => value >= 1 && value <= pageCount).sort((a, b) => a - b)
+  }, [page, pageCount])
+  return (
+    <nav className="pagination" aria-label="Discover pages">
+      <button disabled={page === 1} onClick={() => onChange(page - 1)}><ChevronLeft size={16} /> Previous</button>
+      <div>
+        {pages.map((value, index) => (
           <span key={value}>
             {index > 0 && pages[index - 1] !== value - 1 && <i>…</i>}
             <button className={value === page ? 'active' : ''} aria-current={value === page ? 'page' : undefined} onClick={() => onChange(value)}>{value}</button>
@@ -1366,9 +2095,9 @@ function ProgressStrip({ progress }: { progress: any }) {
   )
 }
 
-type ManageSection = 'mods' | 'resourcepacks' | 'shaders' | 'worlds'
+type ManageSection = 'mods' | 'resourcepacks' | 'shaders' | 'worlds' | 'screenshots' | 'storage'
 
-function ManageView({ selected, notify }: { selected?: Instance; notify: (message: string, kind?: ToastKind) => void }) {
+function ManageView({ selected, onChanged, notify }: { selected?: Instance; onChanged: () => Promise<void>; notify: (message: string, kind?: ToastKind) => void }) {
   const [section, setSection] = useState<ManageSection>('mods')
   return (
     <div className="page manage-page">
@@ -1382,12 +2111,16 @@ function ManageView({ selected, notify }: { selected?: Instance; notify: (messag
         <button className={section === 'resourcepacks' ? 'active' : ''} onClick={() => setSection('resourcepacks')}><Image size={16} /> Resource packs</button>
         <button className={section === 'shaders' ? 'active' : ''} onClick={() => setSection('shaders')}><Sparkles size={16} /> Shaders</button>
         <button className={section === 'worlds' ? 'active' : ''} onClick={() => setSection('worlds')}><Map size={16} /> Worlds</button>
+        <button className={section === 'screenshots' ? 'active' : ''} onClick={() => setSection('screenshots')}><Camera size={16} /> Screenshots</button>
+        <button className={section === 'storage' ? 'active' : ''} onClick={() => setSection('storage')}><HardDrive size={16} /> Storage</button>
       </div>
       <div className="manage-surface">
-        {section === 'mods' && <ModsManager selected={selected} notify={notify} />}
+        {section === 'mods' && <ModsManager selected={selected} onChanged={onChanged} notify={notify} />}
         {section === 'resourcepacks' && <PacksManager selected={selected} type="resourcepack" notify={notify} />}
         {section === 'shaders' && <PacksManager selected={selected} type="shader" notify={notify} />}
-        {section === 'worlds' && <WorldsManager selected={selected} notify={notify} />}
+        {section === 'worlds' && <WorldsManager selected={selected} onChanged={onChanged} notify={notify} />}
+        {section === 'screenshots' && <ScreenshotsManager selected={selected} notify={notify} />}
+        {section === 'storage' && <StorageManager selected={selected} notify={notify} />}
       </div>
     </div>
   )
@@ -1402,16 +2135,27 @@ function ManagerHeading({ title, description, actions }: { title: string; descri
   )
 }
 
-function ModsManager({ selected, notify }: { selected?: Instance; notify: (message: string, kind?: ToastKind) => void }) {
+function ModsManager({ selected, onChanged, notify }: { selected?: Instance; onChanged: () => Promise<void>; notify: (message: string, kind?: ToastKind) => void }) {
   const [mods, setMods] = useState<ModItem[]>([])
   const [loading, setLoading] = useState(false)
   const [progress, setProgress] = useState<any>(null)
   const [pendingRemove, setPendingRemove] = useState<ModItem>()
+  const [updateFailures, setUpdateFailures] = useState<Array<{ projectId: string; title: string; error?: string }>>([])
+  const [canRollback, setCanRollback] = useState(false)
+  const [modSets, setModSets] = useState<{ sets: ModSet[]; activeId?: string }>({ sets: [] })
+  const [savingSet, setSavingSet] = useState(false)
+  const [pendingSetDelete, setPendingSetDelete] = useState<ModSet>()
+
+  useEffect(() => { setUpdateFailures([]); setCanRollback(false) }, [selected?.id])
 
   const load = useCallback(async () => {
-    if (!selected) return setMods([])
+    if (!selected) { setModSets({ sets: [] }); return setMods([]) }
     setLoading(true)
-    try { setMods(await window.mega.mods.list(selected.id)) }
+    try {
+      const [items, sets] = await Promise.all([window.mega.mods.list(selected.id), window.mega.mods.sets(selected.id)])
+      setMods(items)
+      setModSets(sets)
+    }
     catch (error) { notify(errorMessage(error), 'error') }
     finally { setLoading(false) }
   }, [selected?.id, notify])
@@ -1427,12 +2171,64 @@ function ModsManager({ selected, notify }: { selected?: Instance; notify: (messa
     } catch (error) { notify(errorMessage(error), 'error') }
   }
 
+  const [dragging, setDragging] = useState(false)
+  const dropMods = async (event: React.DragEvent) => {
+    event.preventDefault()
+    setDragging(false)
+    if (!selected) return
+    const files = [...event.dataTransfer.files].filter((file) => file.name.toLowerCase().endsWith('.jar'))
+    if (!files.length) return notify('Drop .jar mod files to add them.', 'error')
+    for (const file of files) {
+      try {
+        const mod = await window.mega.instances.importLocalMod(selected.id, file)
+        notify(mod.warnings.length ? `${mod.title} added. ${mod.warnings.join(' ')}` : `${mod.title}${mod.version ? ` ${mod.version}` : ''} added.`, mod.warnings.length ? 'warning' : 'success')
+      } catch (error) { notify(`${file.name}: ${errorMessage(error)}`, 'error') }
+    }
+    await load()
+  }
+
   const toggle = async (mod: ModItem) => {
     if (!selected) return
     const previous = mods
     setMods((items) => items.map((item) => item.fileName === mod.fileName ? { ...item, enabled: !item.enabled } : item))
-    try { await window.mega.mods.setEnabled(selected.id, mod.fileName, !mod.enabled) }
-    catch (error) { setMods(previous); notify(errorMessage(error), 'error') }
+    try {
+      await window.mega.mods.setEnabled(selected.id, mod.fileName, !mod.enabled)
+      // Conflicts are only checked between enabled jars, and a set stops
+      // being active once its mods are changed by hand.
+      await load()
+    } catch (error) { setMods(previous); notify(errorMessage(error), 'error') }
+  }
+
+  const applySet = async (set: ModSet) => {
+    if (!selected || set.id === modSets.activeId) return
+    setProgress({ message: `Switching to ${set.name}` })
+    try {
+      const result = await window.mega.mods.applySet(selected.id, set.id)
+      await load()
+      notify(result.missing.length
+        ? `Switched to ${set.name}. ${result.missing.length} mod${result.missing.length === 1 ? ' is' : 's are'} no longer installed: ${result.missing.join(', ')}.`
+        : `Switched to ${set.name}.`, result.missing.length ? 'warning' : 'success')
+    } catch (error) { notify(errorMessage(error), 'error') }
+    finally { setProgress(null) }
+  }
+
+  const saveSet = async (name: string) => {
+    if (!selected) return
+    try {
+      const set = await window.mega.mods.saveSet(selected.id, name)
+      setSavingSet(false)
+      setModSets(await window.mega.mods.sets(selected.id))
+      notify(`Saved ${set.enabled.length} enabled mod${set.enabled.length === 1 ? '' : 's'} as ${set.name}.`, 'success')
+    } catch (error) { notify(errorMessage(error), 'error') }
+  }
+
+  const deleteSet = async (set: ModSet) => {
+    if (!selected) return
+    try {
+      await window.mega.mods.deleteSet(selected.id, set.id)
+      setPendingSetDelete(undefined)
+      setModSets(await window.mega.mods.sets(selected.id))
+    } catch (error) { notify(errorMessage(error), 'error') }
   }
 
   const remove = async (mod: ModItem) => {
@@ -1449,6 +2245,17 @@ function ModsManager({ selected, notify }: { selected?: Instance; notify: (messa
     }
   }
 
+  const installFabricApi = async () => {
+    if (!selected) return
+    setProgress({ message: 'Installing Fabric API' })
+    try {
+      await window.mega.mods.install(selected.id, FABRIC_API_PROJECT)
+      await load()
+      notify('Fabric API installed.', 'success')
+    } catch (error) { notify(errorMessage(error), 'error') }
+    finally { setProgress(null) }
+  }
+
   const updateOne = async (mod: ModItem) => {
     if (!selected || !mod.projectId) return
     setProgress({ message: `Checking ${mod.title}` })
@@ -1463,31 +2270,105 @@ function ModsManager({ selected, notify }: { selected?: Instance; notify: (messa
   const updateAll = async () => {
     if (!selected) return
     setProgress({ message: 'Checking installed mods' })
+    setUpdateFailures([])
     try {
-      const count = await window.mega.mods.updateAll(selected.id)
+      const report = await window.mega.mods.updateAll(selected.id)
       await load()
-      notify(count ? `Updated ${count} mod${count === 1 ? '' : 's'}.` : 'All Modrinth mods are current.', 'success')
+      const updated = report.results.filter((result) => result.status === 'updated').length
+      const failed = report.results.filter((result) => result.status === 'failed')
+      setUpdateFailures(failed)
+      setCanRollback(report.rollbackAvailable)
+      if (failed.length) notify(`Updated ${updated} mod${updated === 1 ? '' : 's'}; ${failed.length} could not be updated.`, 'warning')
+      else notify(updated ? `Updated ${updated} mod${updated === 1 ? '' : 's'}.` : 'All Modrinth mods are current.', 'success')
+    } catch (error) { notify(errorMessage(error), 'error') }
+    finally { setProgress(null) }
+  }
+
+  const updateModpack = async () => {
+    if (!selected?.modpack) return
+    setProgress({ message: `Checking ${selected.modpack.title}` })
+    try {
+      const report = await window.mega.mods.updateModpack(selected.id)
+      if (report.fromVersion === report.toVersion) return notify(`${report.title} is already current.`, 'success')
+      await Promise.all([load(), onChanged()])
+      const changes = `${report.added} added, ${report.updated} updated, ${report.removed} removed`
+      notify(report.keptChanges.length
+        ? `Updated ${report.title} to ${report.toVersion} (${changes}). Kept your changes to ${report.keptChanges.join(', ')}.`
+        : `Updated ${report.title} to ${report.toVersion} (${changes}).`, report.keptChanges.length ? 'warning' : 'success')
+    } catch (error) { notify(errorMessage(error), 'error') }
+    finally { setProgress(null) }
+  }
+
+  const exportList = async () => {
+    if (!selected) return
+    try {
+      const result = await window.mega.mods.exportList(selected.id)
+      if (result) notify(`Exported ${result.mods} mod${result.mods === 1 ? '' : 's'}${result.linked < result.mods ? `; ${result.mods - result.linked} not on Modrinth must be shared by hand` : ''}.`, 'success')
+    } catch (error) { notify(errorMessage(error), 'error') }
+  }
+
+  const importList = async () => {
+    if (!selected) return
+    setProgress({ message: 'Reading mod list' })
+    setUpdateFailures([])
+    try {
+      const report = await window.mega.mods.importList(selected.id)
+      if (!report) return
+      await load()
+      const installed = report.results.filter((result) => result.status === 'installed').length
+      const problems = report.results.filter((result) => result.status === 'failed' || result.status === 'skipped')
+      setUpdateFailures(problems.map((result) => ({ projectId: result.title, title: result.title, error: result.detail })))
+      notify(`Installed ${installed} mod${installed === 1 ? '' : 's'} from ${report.name}${problems.length ? `; ${problems.length} could not be installed` : ''}.`, problems.length ? 'warning' : 'success')
+    } catch (error) { notify(errorMessage(error), 'error') }
+    finally { setProgress(null) }
+  }
+
+  const rollback = async () => {
+    if (!selected) return
+    setProgress({ message: 'Restoring previous mod versions' })
+    try {
+      const restored = await window.mega.mods.rollbackUpdate(selected.id)
+      setCanRollback(false)
+      setUpdateFailures([])
+      await load()
+      notify(`Restored ${restored} mod${restored === 1 ? '' : 's'} to the previous version.`, 'success')
     } catch (error) { notify(errorMessage(error), 'error') }
     finally { setProgress(null) }
   }
 
   return (
-    <section className="manager-section">
+    <section className={`manager-section ${dragging ? 'drop-target' : ''}`} onDragOver={(event) => { if (selected && event.dataTransfer.types.includes('Files')) { event.preventDefault(); setDragging(true) } }} onDragLeave={(event) => { if (!event.currentTarget.contains(event.relatedTarget as Node)) setDragging(false) }} onDrop={(event) => void dropMods(event)}>
       <ManagerHeading
         title="Mods"
-        description="Enable, disable, update or remove mods for this instance."
-        actions={<><button className="secondary" disabled={!selected} onClick={addLocal}><Upload size={16} /> Add JAR</button><button className="primary" disabled={!selected} onClick={updateAll}><RefreshCw size={16} /> Update all</button></>}
+        description="Enable, disable, update or remove mods for this instance. Drop .jar files here to add them."
+        actions={<><button className="secondary" disabled={!selected} onClick={addLocal}><Upload size={16} /> Add JAR</button><button className="secondary" disabled={!selected || Boolean(progress)} title="Install mods from a shared list" onClick={importList}><FileDown size={16} /> Import list</button><button className="secondary" disabled={!selected || !mods.length} title="Save the mod list to share" onClick={exportList}><FileUp size={16} /> Export list</button>{canRollback && <button className="secondary" disabled={Boolean(progress)} onClick={rollback}><ChevronLeft size={16} /> Roll back update</button>}<button className="primary" disabled={!selected || Boolean(progress)} onClick={updateAll}><RefreshCw size={16} /> Update all</button></>}
       />
       {progress && <ProgressStrip progress={progress} />}
+      {updateFailures.length > 0 && <div className="notice compact-notice"><AlertTriangle /><div><strong>Some mods could not be installed or updated</strong>{updateFailures.map((failure) => <p key={failure.projectId}>{failure.title}: {failure.error ?? 'Unknown error'}</p>)}</div></div>}
+      {selected && selected.loader !== 'vanilla' && (mods.length > 0 || modSets.sets.length > 0) && (
+        <div className="mod-sets" aria-label="Mod sets">
+          <span><Layers3 size={14} /> Mod sets</span>
+          {modSets.sets.map((set) => (
+            <span key={set.id} className={`mod-set-chip ${set.id === modSets.activeId ? 'active' : ''}`}>
+              <button disabled={Boolean(progress)} title={`${set.enabled.length} mods enabled`} onClick={() => void applySet(set)}>{set.name}</button>
+              <button aria-label={`Delete ${set.name}`} onClick={() => setPendingSetDelete(set)}><X size={12} /></button>
+            </span>
+          ))}
+          <button className="secondary" disabled={!mods.length} onClick={() => setSavingSet(true)}><Plus size={14} /> Save current</button>
+        </div>
+      )}
+      {selected?.modpack && <div className="notice compact-notice"><PackagePlus /><div><strong>Installed from {selected.modpack.title}</strong><p>Updating the modpack replaces the files it ships. Mods you added and configs you changed are kept.</p></div><button className="secondary" disabled={Boolean(progress)} onClick={() => void updateModpack()}><RefreshCw size={16} /> Update modpack</button></div>}
       {selected?.loader === 'vanilla' && <div className="notice compact-notice"><Info /><div><strong>Vanilla does not load mods</strong><p>Use Fabric, Forge or NeoForge for mod JARs.</p></div></div>}
+      {mods.some((mod) => mod.needsFabricApi) && <div className="notice compact-notice"><AlertTriangle /><div><strong>Fabric API is missing</strong><p>{mods.filter((mod) => mod.needsFabricApi).map((mod) => mod.title).join(', ')} will not load without it.</p></div><button className="secondary" disabled={Boolean(progress)} onClick={() => void installFabricApi()}><Download size={16} /> Install Fabric API</button></div>}
+      {mods.some((mod) => (mod.warnings?.length ?? 0) > (mod.needsFabricApi ? 1 : 0)) && <div className="notice compact-notice"><AlertTriangle /><div><strong>Some mods conflict</strong><p>Minecraft may crash at startup. Disable or remove one mod of each marked pair.</p></div></div>}
       <div className="mods-table">
         <div className="mods-head"><span>Mod</span><span>Version</span><span>Source</span><span>Status</span><span /></div>
         {loading && !mods.length && <LoadingRows count={4} compact />}
         {mods.map((mod) => (
           <div className={`mod-row ${!mod.enabled ? 'disabled' : ''}`} key={mod.fileName}>
-            <div className="mod-name"><div>{mod.iconUrl ? <img src={mod.iconUrl} alt="" loading="lazy" /> : <Box />}</div><span><strong>{mod.title}</strong><small>{mod.fileName}</small></span></div>
+            <div className="mod-name"><div>{mod.iconUrl ? <img src={mod.iconUrl} alt="" loading="lazy" /> : <Box />}</div><span><strong>{mod.title}{mod.warnings?.length ? <AlertTriangle className="mod-warning" size={13} aria-label="Conflict" /> : null}</strong><small>{mod.warnings?.length ? mod.warnings.join(' ') : mod.fileName}</small></span></div>
             <span>{mod.versionNumber ?? 'Local file'}</span>
-            <span className="source-tag">{mod.source === 'modrinth' ? 'Modrinth' : 'Local'}</span>
+            <span className="source-tag">{mod.source === 'modrinth' ? 'Modrinth' : mod.source === 'curseforge' ? 'CurseForge' : 'Local'}</span>
             <button className={`toggle ${mod.enabled ? 'on' : ''}`} aria-label={mod.enabled ? `Disable ${mod.title}` : `Enable ${mod.title}`} onClick={() => toggle(mod)}><i /></button>
             <div className="row-actions">{mod.projectId && <button title="Check for update" onClick={() => updateOne(mod)}><RefreshCw /></button>}<button title="Remove" onClick={() => setPendingRemove(mod)}><Trash2 /></button></div>
           </div>
@@ -1495,6 +2376,8 @@ function ModsManager({ selected, notify }: { selected?: Instance; notify: (messa
       </div>
       {!loading && !mods.length && <div className="empty-state"><Box /><h2>No mods installed</h2><p>Install compatible mods from Discover or add a local JAR.</p></div>}
       {pendingRemove && <ConfirmDialog title={`Remove ${pendingRemove.title}?`} message="The mod file will be removed from this instance." confirmLabel="Remove" onCancel={() => setPendingRemove(undefined)} onConfirm={() => remove(pendingRemove)} />}
+      {pendingSetDelete && <ConfirmDialog title={`Delete ${pendingSetDelete.name}?`} message="Only the saved set is deleted. No mods are enabled, disabled or removed." onCancel={() => setPendingSetDelete(undefined)} onConfirm={() => deleteSet(pendingSetDelete)} />}
+      {savingSet && <ModSetModal sets={modSets.sets} enabledCount={mods.filter((mod) => mod.enabled).length} onClose={() => setSavingSet(false)} onSave={saveSet} />}
     </section>
   )
 }
@@ -1503,11 +2386,20 @@ function PacksManager({ selected, type, notify }: { selected?: Instance; type: '
   const [packs, setPacks] = useState<PackItem[]>([])
   const [loading, setLoading] = useState(false)
   const [pendingRemove, setPendingRemove] = useState<PackItem>()
+  const [shaderLoader, setShaderLoader] = useState<ShaderLoaderRequirement | null>(null)
+  const [installingLoader, setInstallingLoader] = useState(false)
 
   const load = useCallback(async () => {
     if (!selected) return setPacks([])
     setLoading(true)
-    try { setPacks(await window.mega.packs.list(selected.id, type)) }
+    try {
+      const [items, requirement] = await Promise.all([
+        window.mega.packs.list(selected.id, type),
+        type === 'shader' ? window.mega.packs.shaderLoader(selected.id) : Promise.resolve(null)
+      ])
+      setPacks(items)
+      setShaderLoader(requirement)
+    }
     catch (error) { notify(errorMessage(error), 'error') }
     finally { setLoading(false) }
   }, [selected?.id, type, notify])
@@ -1536,37 +2428,69 @@ function PacksManager({ selected, type, notify }: { selected?: Instance; type: '
     }
   }
 
+  const addLocal = async () => {
+    if (!selected) return
+    try {
+      const count = await window.mega.packs.addLocal(selected.id, type)
+      if (count) { await load(); notify(`${count} pack${count === 1 ? '' : 's'} added.`, 'success') }
+    } catch (error) { notify(errorMessage(error), 'error') }
+  }
+
+  const installShaderLoader = async () => {
+    if (!selected || !shaderLoader?.projectId) return
+    setInstallingLoader(true)
+    try {
+      const mod = await window.mega.mods.install(selected.id, shaderLoader.projectId)
+      if (!mod.enabled) await window.mega.mods.setEnabled(selected.id, mod.fileName, true)
+      notify(`${shaderLoader.title} installed.`, 'success')
+      await load()
+    } catch (error) {
+      notify(errorMessage(error), 'error')
+    } finally {
+      setInstallingLoader(false)
+    }
+  }
+
   const label = type === 'resourcepack' ? 'Resource packs' : 'Shaders'
   return (
     <section className="manager-section">
       <ManagerHeading
         title={label}
-        description={`Manage ${label.toLowerCase()} installed for this instance.`}
-        actions={<button className="secondary" disabled={!selected} onClick={() => selected && window.mega.packs.openFolder(selected.id, type)}><FolderOpen size={16} /> Open folder</button>}
+        description={type === 'resourcepack'
+          ? 'Manage resource packs installed for this instance. Packs turned on here are the ones the game loads, newest on top.'
+          : `Manage ${label.toLowerCase()} installed for this instance.`}
+        actions={<><button className="secondary" disabled={!selected} onClick={addLocal}><Upload size={16} /> Add pack</button><button className="secondary" disabled={!selected} onClick={() => selected && window.mega.packs.openFolder(selected.id, type)}><FolderOpen size={16} /> Open folder</button></>}
       />
       {loading && !packs.length && <LoadingRows count={4} compact />}
+      {packs.length > 0 && shaderLoader && <div className="notice compact-notice"><AlertTriangle /><div><strong>Shaders will not load</strong><p>{shaderLoader.reason}</p></div>{shaderLoader.projectId && <button className="secondary" disabled={installingLoader} onClick={() => void installShaderLoader()}><Download size={16} /> {installingLoader ? 'Installing…' : `Install ${shaderLoader.title}`}</button>}</div>}
       <div className="pack-grid">
         {packs.map((pack) => (
           <article className={`pack-card ${pack.enabled ? '' : 'disabled'}`} key={`${pack.contentType}:${pack.fileName}`}>
             <div className="pack-icon">{pack.iconUrl ? <img src={pack.iconUrl} alt="" loading="lazy" /> : type === 'resourcepack' ? <Image /> : <Sparkles />}</div>
-            <div className="pack-copy"><h3>{pack.title}</h3><p>{pack.versionNumber ?? 'Local file'}</p></div>
+            <div className="pack-copy" title={pack.description}>
+              <h3>{pack.title}{pack.warnings?.length ? <AlertTriangle className="mod-warning" size={13} aria-label="Incompatible" /> : null}</h3>
+              <p>{pack.versionNumber ?? (pack.folder ? 'Local folder' : 'Local file')}{pack.packFormat !== undefined && ` · Format ${pack.packFormat}`}</p>
+              {pack.warnings?.length ? <p className="pack-warning">{pack.warnings.join(' ')}</p> : pack.description && <p className="pack-description">{pack.description}</p>}
+            </div>
             <button className={`toggle ${pack.enabled ? 'on' : ''}`} aria-label={pack.enabled ? 'Disable' : 'Enable'} onClick={() => toggle(pack)}><i /></button>
             <button className="icon-button danger-icon" title="Remove" onClick={() => setPendingRemove(pack)}><Trash2 size={16} /></button>
           </article>
         ))}
       </div>
       {!loading && !packs.length && <div className="empty-state"><Layers3 /><h2>No {label.toLowerCase()} installed</h2><p>Install compatible content from Discover.</p></div>}
-      {pendingRemove && <ConfirmDialog title={`Remove ${pendingRemove.title}?`} message={`The ${type === 'resourcepack' ? 'resource pack' : 'shader'} file will be removed from this instance.`} confirmLabel="Remove" onCancel={() => setPendingRemove(undefined)} onConfirm={() => remove(pendingRemove)} />}
+      {pendingRemove && <ConfirmDialog title={`Remove ${pendingRemove.title}?`} message={`The ${type === 'resourcepack' ? 'resource pack' : 'shader'} ${pendingRemove.folder ? 'folder' : 'file'} will be removed from this instance.`} confirmLabel="Remove" onCancel={() => setPendingRemove(undefined)} onConfirm={() => remove(pendingRemove)} />}
     </section>
   )
 }
 
-function WorldsManager({ selected, notify }: { selected?: Instance; notify: (message: string, kind?: ToastKind) => void }) {
+function WorldsManager({ selected, onChanged, notify }: { selected?: Instance; onChanged: () => Promise<void>; notify: (message: string, kind?: ToastKind) => void }) {
   const [worlds, setWorlds] = useState<WorldItem[]>([])
   const [loading, setLoading] = useState(false)
   const [downloadOpen, setDownloadOpen] = useState(false)
   const [progress, setProgress] = useState<any>(null)
   const [pendingDelete, setPendingDelete] = useState<WorldItem>()
+  const [backingUp, setBackingUp] = useState<string>()
+  const [backupsOpen, setBackupsOpen] = useState(false)
 
   const load = useCallback(async () => {
     if (!selected) return setWorlds([])
@@ -1583,7 +2507,7 @@ function WorldsManager({ selected, notify }: { selected?: Instance; notify: (mes
     if (!selected) return notify('Select an instance first.', 'error')
     try {
       const result = await window.mega.worlds.importZip(selected.id)
-      if (result) { await load(); notify('World imported.', 'success') }
+      if (result) { await load(); notify(`${result.name} imported.`, 'success') }
     } catch (error) { notify(errorMessage(error), 'error') }
   }
 
@@ -1599,6 +2523,24 @@ function WorldsManager({ selected, notify }: { selected?: Instance; notify: (mes
     finally { setProgress(null) }
   }
 
+  const backup = async (world: WorldItem) => {
+    if (!selected) return
+    setBackingUp(world.id)
+    try {
+      const result = await window.mega.worlds.backup(selected.id, world.id)
+      notify(`${world.name} backed up (${formatBytes(result.sizeBytes)}).`, 'success')
+    } catch (error) { notify(errorMessage(error), 'error') }
+    finally { setBackingUp(undefined); setProgress(null) }
+  }
+
+  const setBackupPolicy = async (policy: WorldBackupPolicy | null) => {
+    if (!selected) return
+    try {
+      await window.mega.instances.setWorldBackups(selected.id, policy)
+      await onChanged()
+    } catch (error) { notify(errorMessage(error), 'error') }
+  }
+
   const remove = async (world: WorldItem) => {
     if (!selected) return
     const previous = worlds
@@ -1618,26 +2560,242 @@ function WorldsManager({ selected, notify }: { selected?: Instance; notify: (mes
       <ManagerHeading
         title="Worlds"
         description="Import, download and manage worlds for this instance."
-        actions={<><button className="secondary" disabled={!selected} onClick={() => selected && window.mega.worlds.openFolder(selected.id)}><FolderOpen size={16} /> Saves folder</button><button className="secondary" disabled={!selected} onClick={importWorld}><Upload size={16} /> Import ZIP</button><button className="primary" disabled={!selected} onClick={() => setDownloadOpen(true)}><Download size={16} /> Download world</button></>}
+        actions={<><button className="secondary" disabled={!selected} onClick={() => selected && window.mega.worlds.openFolder(selected.id)}><FolderOpen size={16} /> Saves folder</button><button className="secondary" disabled={!selected} onClick={() => setBackupsOpen(true)}><History size={16} /> Backups</button><button className="secondary" disabled={!selected} onClick={importWorld}><Upload size={16} /> Import ZIP</button><button className="primary" disabled={!selected} onClick={() => setDownloadOpen(true)}><Download size={16} /> Download world</button></>}
       />
       {progress && <ProgressStrip progress={progress} />}
       {loading && !worlds.length && <LoadingRows count={3} compact />}
       <div className="world-grid">
         {worlds.map((world) => (
           <article className="world-card" key={world.id}>
-            <div className="world-icon"><Map /></div>
-            <div><h3>{world.name}</h3><p>Updated {new Date(world.modifiedAt).toLocaleDateString('en-GB', { day: 'numeric', month: 'short', year: 'numeric' })}</p></div>
-            <div className="row-actions"><button title="Open folder" onClick={() => selected && window.mega.worlds.openFolder(selected.id, world.id)}><FolderOpen /></button><button title="Delete" onClick={() => setPendingDelete(world)}><Trash2 /></button></div>
+            <div className="world-icon">{world.iconUrl ? <img src={world.iconUrl} alt="" loading="lazy" /> : <Map />}</div>
+            <div title={world.name === world.folderName ? undefined : world.folderName}>
+              <h3>{world.name}</h3>
+              <p>{[world.gameMode && world.gameMode[0].toUpperCase() + world.gameMode.slice(1), world.version, world.sizeBytes !== undefined && formatBytes(world.sizeBytes)].filter(Boolean).join(' · ')}</p>
+              <p>{world.lastPlayed ? 'Played' : 'Updated'} {new Date(world.lastPlayed ?? world.modifiedAt).toLocaleDateString('en-GB', { day: 'numeric', month: 'short', year: 'numeric' })}</p>
+            </div>
+            <div className="row-actions"><button title="Back up" disabled={Boolean(backingUp)} onClick={() => void backup(world)}>{backingUp === world.id ? <RefreshCw className="spin" /> : <Archive />}</button><button title="Open folder" onClick={() => selected && window.mega.worlds.openFolder(selected.id, world.id)}><FolderOpen /></button><button title="Delete" onClick={() => setPendingDelete(world)}><Trash2 /></button></div>
           </article>
         ))}
       </div>
       {!loading && !worlds.length && <div className="empty-state"><Map /><h2>No worlds found</h2><p>Import a ZIP, paste a direct download link or create a world in-game.</p></div>}
       {downloadOpen && <WorldDownloadModal onClose={() => setDownloadOpen(false)} onDownload={downloadWorld} />}
-      {pendingDelete && <ConfirmDialog title={`Delete ${pendingDelete.name}?`} message="This permanently removes the world from this instance." onCancel={() => setPendingDelete(undefined)} onConfirm={() => remove(pendingDelete)} />}
+      {backupsOpen && selected && <WorldBackupsModal instanceId={selected.id} worlds={worlds} policy={selected.worldBackups} onPolicyChange={setBackupPolicy} onRestored={load} onClose={() => setBackupsOpen(false)} notify={notify} />}
+      {pendingDelete && <ConfirmDialog title={`Delete ${pendingDelete.name}?`} message="This permanently removes the world from this instance. Its backups are kept." onCancel={() => setPendingDelete(undefined)} onConfirm={() => remove(pendingDelete)} />}
     </section>
   )
 }
 
+const STORAGE_CATEGORIES = [
+  ['worlds', 'Worlds'],
+  ['mods', 'Mods'],
+  ['packs', 'Resource packs and shaders'],
+  ['assets', 'Assets'],
+  ['libraries', 'Libraries'],
+  ['versions', 'Game versions'],
+  ['logs', 'Logs and crash reports'],
+  ['other', 'Settings and other files']
+] as const
+
+function StorageManager({ selected, notify }: { selected?: Instance; notify: (message: string, kind?: ToastKind) => void }) {
+  const [usage, setUsage] = useState<Awaited<ReturnType<typeof window.mega.instances.diskUsage>>>()
+  const [loading, setLoading] = useState(false)
+
+  const load = useCallback(async () => {
+    if (!selected) return setUsage(undefined)
+    setLoading(true)
+    try { setUsage(await window.mega.instances.diskUsage(selected.id)) }
+    catch (error) { notify(errorMessage(error), 'error') }
+    finally { setLoading(false) }
+  }, [selected?.id, notify])
+
+  useEffect(() => { void load() }, [load])
+
+  return (
+    <section className="manager-section">
+      <ManagerHeading
+        title="Storage"
+        description={usage ? `${formatBytes(usage.totalBytes)} used by this instance${usage.sharedBytes ? `, including ${formatBytes(usage.sharedBytes)} of shared folders` : ''}.` : 'How much disk space this instance uses.'}
+        actions={<><button className="secondary" disabled={!selected} onClick={() => selected && window.mega.instances.openFolder(selected.id)}><FolderOpen size={16} /> Instance folder</button><button className="secondary" disabled={!selected || loading} onClick={() => void load()}><RefreshCw size={16} className={loading ? 'spin' : ''} /> Measure again</button></>}
+      />
+      {loading && !usage && <LoadingRows count={4} compact />}
+      {usage && (
+        <div className="storage-breakdown">
+          <div className="storage-bar">{STORAGE_CATEGORIES.map(([key]) => usage.categories[key] > 0 && <i key={key} className={`storage-${key}`} style={{ width: `${(usage.categories[key] / Math.max(1, usage.totalBytes)) * 100}%` }} />)}</div>
+          {STORAGE_CATEGORIES.map(([key, label]) => (
+            <div className="storage-row" key={key}><span className={`storage-swatch storage-${key}`} /><strong>{label}</strong><small>{formatBytes(usage.categories[key])}</small></div>
+          ))}
+        </div>
+      )}
+      {!selected && <div className="empty-state"><HardDrive /><h2>No instance selected</h2><p>Select an instance to see how its disk space is used.</p></div>}
+    </section>
+  )
+}
+
+function ModSetModal({ sets, enabledCount, onClose, onSave }: { sets: ModSet[]; enabledCount: number; onClose: () => void; onSave: (name: string) => Promise<void> }) {
+  const [name, setName] = useState('')
+  const [busy, setBusy] = useState(false)
+  const replacing = sets.find((set) => set.name.toLowerCase() === name.trim().toLowerCase())
+  const submit = async () => {
+    if (!name.trim() || busy) return
+    setBusy(true)
+    try { await onSave(name.trim()) } finally { setBusy(false) }
+  }
+  return (
+    <div className="modal-backdrop nested-modal" onPointerDown={onClose}>
+      <div className="modal world-download-modal" onPointerDown={(event) => event.stopPropagation()} role="dialog" aria-modal="true" aria-labelledby="mod-set-title">
+        <div className="modal-header"><div><small>MOD SET</small><h2 id="mod-set-title">Save the enabled mods</h2></div><button aria-label="Close" onClick={onClose}><X /></button></div>
+        <p className="modal-description">The {enabledCount} mod{enabledCount === 1 ? '' : 's'} enabled now are saved under this name. Switching to the set later enables them and disables everything else.</p>
+        <label className="field-label">Set name<input autoFocus maxLength={48} value={name} onChange={(event) => setName(event.target.value)} onKeyDown={(event) => { if (event.key === 'Enter') void submit() }} placeholder="Performance only" /></label>
+        {replacing && <div className="field-message"><Info size={15} /><span>This replaces the mods saved in {replacing.name}.</span></div>}
+        <div className="modal-footer"><button className="secondary" onClick={onClose}>Cancel</button><button className="primary" disabled={!name.trim() || busy} onClick={() => void submit()}>{busy ? <RefreshCw className="spin" /> : <Check />}{replacing ? 'Replace set' : 'Save set'}</button></div>
+      </div>
+    </div>
+  )
+}
+
+function CollectionModal({ busy, onClose, onInstall }: { busy: boolean; onClose: () => void; onInstall: (input: string) => Promise<void> }) {
+  const [value, setValue] = useState('')
+  const submit = () => { if (value.trim() && !busy) void onInstall(value.trim()) }
+  return (
+    <div className="modal-backdrop nested-modal" onPointerDown={() => !busy && onClose()}>
+      <div className="modal world-download-modal" onPointerDown={(event) => event.stopPropagation()} role="dialog" aria-modal="true" aria-labelledby="collection-title">
+        <div className="modal-header"><div><small>MODRINTH COLLECTION</small><h2 id="collection-title">Install a collection</h2></div><button aria-label="Close" disabled={busy} onClick={onClose}><X /></button></div>
+        <p className="modal-description">Everything in the collection with a version for this instance is installed, along with its dependencies.</p>
+        <label className="field-label">Collection link or ID<input autoFocus value={value} onChange={(event) => setValue(event.target.value)} onKeyDown={(event) => { if (event.key === 'Enter') submit() }} placeholder="https://modrinth.com/collection/…" /></label>
+        <div className="modal-footer"><button className="secondary" disabled={busy} onClick={onClose}>Cancel</button><button className="primary" disabled={!value.trim() || busy} onClick={submit}>{busy ? <RefreshCw className="spin" /> : <Download />}{busy ? 'Installing…' : 'Install collection'}</button></div>
+      </div>
+    </div>
+  )
+}
+
+function ScreenshotsManager({ selected, notify }: { selected?: Instance; notify: (message: string, kind?: ToastKind) => void }) {
+  const [screenshots, setScreenshots] = useState<ScreenshotItem[]>([])
+  const [loading, setLoading] = useState(false)
+  const [uploading, setUploading] = useState<string>()
+
+  const load = useCallback(async () => {
+    if (!selected) return setScreenshots([])
+    setLoading(true)
+    try { setScreenshots(await window.mega.screenshots.list(selected.id)) }
+    catch (error) { notify(errorMessage(error), 'error') }
+    finally { setLoading(false) }
+  }, [selected?.id, notify])
+
+  useEffect(() => { void load() }, [load])
+
+  const copy = async (shot: ScreenshotItem) => {
+    if (!selected) return
+    try {
+      await window.mega.screenshots.copy(selected.id, shot.fileName)
+      notify('Screenshot copied to the clipboard.', 'success')
+    } catch (error) { notify(errorMessage(error), 'error') }
+  }
+
+  const upload = async (shot: ScreenshotItem) => {
+    if (!selected) return
+    setUploading(shot.fileName)
+    try {
+      const url = await window.mega.screenshots.upload(selected.id, shot.fileName)
+      notify(`Uploaded. Link copied: ${url}`, 'success')
+    } catch (error) { notify(errorMessage(error), 'error') }
+    finally { setUploading(undefined) }
+  }
+
+  return (
+    <section className="manager-section">
+      <ManagerHeading
+        title="Screenshots"
+        description="Screenshots taken in this instance with F2, newest first. Copy one to paste it anywhere, or upload it to the image host set in Settings."
+        actions={<><button className="secondary" disabled={!selected || loading} onClick={() => void load()}><RefreshCw size={16} className={loading ? 'spin' : ''} /> Refresh</button><button className="secondary" disabled={!selected} onClick={() => selected && window.mega.screenshots.openFolder(selected.id)}><FolderOpen size={16} /> Open folder</button></>}
+      />
+      {loading && !screenshots.length && <LoadingRows count={3} compact />}
+      <div className="screenshot-grid">
+        {screenshots.map((shot) => (
+          <article className="screenshot-card" key={shot.fileName}>
+            <button className="screenshot-preview" title="Show in folder" onClick={() => selected && window.mega.screenshots.show(selected.id, shot.fileName)}>{shot.thumbnailUrl ? <img src={shot.thumbnailUrl} alt={shot.fileName} loading="lazy" /> : <Camera />}</button>
+            <div className="screenshot-meta">
+              <span><strong>{new Date(shot.takenAt).toLocaleString('en-GB', { day: 'numeric', month: 'short', year: 'numeric', hour: '2-digit', minute: '2-digit' })}</strong><small>{formatBytes(shot.sizeBytes)}</small></span>
+              <div className="row-actions"><button title="Copy image" onClick={() => void copy(shot)}><Copy /></button><button title="Upload and copy link" disabled={Boolean(uploading)} onClick={() => void upload(shot)}>{uploading === shot.fileName ? <RefreshCw className="spin" /> : <Upload />}</button></div>
+            </div>
+          </article>
+        ))}
+      </div>
+      {!loading && !screenshots.length && <div className="empty-state"><Camera /><h2>No screenshots yet</h2><p>Press F2 in game to take one.</p></div>}
+    </section>
+  )
+}
+
+function WorldBackupsModal({ instanceId, worlds, policy, onPolicyChange, onRestored, onClose, notify }: {
+  instanceId: string
+  worlds: WorldItem[]
+  // Set when worlds are backed up automatically as the game exits.
+  policy?: WorldBackupPolicy
+  onPolicyChange: (policy: WorldBackupPolicy | null) => Promise<void>
+  onRestored: () => Promise<void>
+  onClose: () => void
+  notify: (message: string, kind?: ToastKind) => void
+}) {
+  const [backups, setBackups] = useState<WorldBackup[]>()
+  const [busy, setBusy] = useState<string>()
+  const [pendingDelete, setPendingDelete] = useState<WorldBackup>()
+  const [draft, setDraft] = useState<WorldBackupPolicy>(policy ?? { keepLast: 5, keepDays: 0 })
+
+  useEffect(() => { if (policy) setDraft(policy) }, [policy?.keepLast, policy?.keepDays])
+
+  useEffect(() => {
+    window.mega.worlds.backups(instanceId).then(setBackups).catch((error) => { notify(errorMessage(error), 'error'); setBackups([]) })
+  }, [instanceId, notify])
+
+  const worldName = (backup: WorldBackup) => worlds.find((world) => world.folderName === backup.worldFolder)?.name ?? backup.worldFolder
+
+  const restore = async (backup: WorldBackup) => {
+    setBusy(backup.id)
+    try {
+      const world = await window.mega.worlds.restoreBackup(instanceId, backup.id)
+      await onRestored()
+      notify(world.folderName === backup.worldFolder ? `${worldName(backup)} restored.` : `Restored as ${world.folderName}, next to the current world.`, 'success')
+    } catch (error) { notify(errorMessage(error), 'error') }
+    finally { setBusy(undefined) }
+  }
+
+  const remove = async (backup: WorldBackup) => {
+    try {
+      await window.mega.worlds.deleteBackup(instanceId, backup.id)
+      setBackups((items) => items?.filter((item) => item.id !== backup.id))
+      setPendingDelete(undefined)
+    } catch (error) { notify(errorMessage(error), 'error') }
+  }
+
+  return (
+    <>
+      <div className="modal-backdrop nested-modal" onPointerDown={onClose}>
+        <div className="modal world-backups-modal" onPointerDown={(event) => event.stopPropagation()} role="dialog" aria-modal="true" aria-labelledby="world-backups-title">
+          <div className="modal-header"><div><small>WORLD BACKUPS</small><h2 id="world-backups-title">Backups</h2></div><button aria-label="Close" onClick={onClose}><X /></button></div>
+          <p className="modal-description">Restoring never overwrites a world. If the world still exists, the backup is added next to it.</p>
+          <div className="setting-row"><div><strong>Back up when the game exits</strong><small>Worlds played in the session are backed up. Older automatic backups are removed; backups made by hand are kept.</small></div><button className={`toggle ${policy ? 'on' : ''}`} aria-label={policy ? 'Turn off automatic backups' : 'Turn on automatic backups'} onClick={() => void onPolicyChange(policy ? null : draft)}><i /></button></div>
+          {policy && <div className="world-backup-policy">
+            <label>Keep the newest<input type="number" min="1" max="100" value={draft.keepLast} onChange={(event) => setDraft({ ...draft, keepLast: Number(event.target.value) })} onBlur={() => void onPolicyChange(draft)} /></label>
+            <label>Remove after days (0 never)<input type="number" min="0" max="3650" value={draft.keepDays} onChange={(event) => setDraft({ ...draft, keepDays: Number(event.target.value) })} onBlur={() => void onPolicyChange(draft)} /></label>
+          </div>}
+          {!backups && <LoadingRows count={3} compact />}
+          {backups && !backups.length && <p className="modal-description">No backups yet. Back up a world from its card.</p>}
+          <div className="world-backup-list">
+            {backups?.map((backup) => (
+              <div key={backup.id}>
+                <div><strong>{worldName(backup)}{backup.automatic && <span className="world-backup-tag">Auto</span>}</strong><small>{new Date(backup.createdAt).toLocaleString('en-GB', { day: 'numeric', month: 'short', year: 'numeric', hour: '2-digit', minute: '2-digit' })} · {formatBytes(backup.sizeBytes)}</small></div>
+                <button className="secondary" disabled={Boolean(busy)} onClick={() => void restore(backup)}>{busy === backup.id ? <RefreshCw className="spin" size={15} /> : <History size={15} />} Restore</button>
+                <button className="icon-button danger-icon" title="Delete backup" disabled={Boolean(busy)} onClick={() => setPendingDelete(backup)}><Trash2 size={15} /></button>
+              </div>
+            ))}
+          </div>
+          <div className="modal-footer"><button className="secondary" onClick={onClose}>Close</button></div>
+        </div>
+      </div>
+      {pendingDelete && <ConfirmDialog title="Delete this backup?" message={`The backup of ${worldName(pendingDelete)} will be removed permanently.`} confirmLabel="Delete" onCancel={() => setPendingDelete(undefined)} onConfirm={() => remove(pendingDelete)} />}
+    </>
+  )
+}
+
 function WorldDownloadModal({ onClose, onDownload }: { onClose: () => void; onDownload: (url: string) => Promise<void> }) {
   const [url, setUrl] = useState('')
   const [busy, setBusy] = useState(false)
@@ -1859,17 +3017,61 @@ function CosmeticsView({ account, notify }: {
   )
 }
 
-function SettingsView({ settings, setSettings, update, version, clientVersion, discordConfigured, notify }: {
+function SettingsView({ settings, setSettings, update, version, clientVersion, discordConfigured, contentUpdates, notify }: {
   settings: SettingsData
   setSettings: (settings: SettingsData) => void
   update: any
   version: string
   clientVersion: string
   discordConfigured: boolean
+  contentUpdates: ContentUpdateSummary | null
   notify: (message: string, kind?: ToastKind) => void
 }) {
   const [draft, setDraft] = useState(settings)
+  const [checkingContent, setCheckingContent] = useState(false)
+  const [modrinth, setModrinth] = useState<ModrinthAccount | null>(null)
+  const [modrinthToken, setModrinthToken] = useState('')
+  const [linkingModrinth, setLinkingModrinth] = useState(false)
+  const [uploadKey, setUploadKey] = useState('')
+  const [hasUploadKey, setHasUploadKey] = useState(false)
   useEffect(() => setDraft(settings), [settings])
+  useEffect(() => { void window.mega.modrinth.account().then(setModrinth).catch(() => undefined) }, [])
+  useEffect(() => { void window.mega.screenshots.hasUploadKey().then(setHasUploadKey).catch(() => undefined) }, [])
+
+  const saveUploadKey = async (value: string | null) => {
+    try {
+      await window.mega.screenshots.setUploadKey(value)
+      setHasUploadKey(Boolean(value?.trim()))
+      setUploadKey('')
+    } catch (error) { notify(errorMessage(error), 'error') }
+  }
+
+  const linkModrinth = async () => {
+    setLinkingModrinth(true)
+    try {
+      const account = await window.mega.modrinth.link(modrinthToken)
+      setModrinth(account)
+      setModrinthToken('')
+      notify(`Linked Modrinth account ${account.username}.`, 'success')
+    } catch (error) { notify(errorMessage(error), 'error') }
+    finally { setLinkingModrinth(false) }
+  }
+
+  const unlinkModrinth = async () => {
+    try {
+      await window.mega.modrinth.unlink()
+      setModrinth(null)
+    } catch (error) { notify(errorMessage(error), 'error') }
+  }
+
+  const checkContent = async () => {
+    setCheckingContent(true)
+    try {
+      const summary = await window.mega.updates.checkContent()
+      if (!summary.total) notify('All mods and modpacks are current.', 'success')
+    } catch (error) { notify(errorMessage(error), 'error') }
+    finally { setCheckingContent(false) }
+  }
 
   const patch = async (values: Partial<SettingsData>) => {
     const optimistic = { ...draft, ...values }
@@ -1884,6 +3086,49 @@ function SettingsView({ settings, setSettings, update, version, clientVersion, d
     }
   }
 
+  const [cleanupPreview, setCleanupPreview] = useState<Awaited<ReturnType<typeof window.mega.app.cleanupGameFiles>>>()
+  const [cleaning, setCleaning] = useState(false)
+  const [confirmCleanup, setConfirmCleanup] = useState(false)
+
+  const scanCleanup = async () => {
+    setCleaning(true)
+    try { setCleanupPreview(await window.mega.app.cleanupGameFiles(true)) }
+    catch (error) { notify(errorMessage(error), 'error') }
+    finally { setCleaning(false) }
+  }
+
+  const runCleanup = async () => {
+    setCleaning(true)
+    try {
+      const result = await window.mega.app.cleanupGameFiles(false)
+      setCleanupPreview(undefined)
+      notify(`Freed ${formatBytes(result.reclaimedBytes)} of unused game files.`, 'success')
+    } catch (error) { notify(errorMessage(error), 'error') }
+    finally {
+      setCleaning(false)
+      setConfirmCleanup(false)
+    }
+  }
+
+  const [cacheSize, setCacheSize] = useState<Awaited<ReturnType<typeof window.mega.app.cacheSize>>>()
+  const [clearingCache, setClearingCache] = useState<string>()
+
+  useEffect(() => {
+    void window.mega.app.cacheSize().then(setCacheSize).catch(() => undefined)
+  }, [])
+
+  const clearCache = async (kinds: Array<keyof NonNullable<typeof cacheSize>['kinds']>) => {
+    setClearingCache(kinds.length === 1 ? kinds[0] : 'all')
+    try {
+      const result = await window.mega.app.clearCache(kinds)
+      setCacheSize(result.size)
+      notify(result.skipped.length
+        ? `Freed ${formatBytes(result.clearedBytes)}. Partial downloads were kept because downloads are still running.`
+        : `Freed ${formatBytes(result.clearedBytes)} of cached data.`, result.skipped.length ? 'warning' : 'success')
+    } catch (error) { notify(errorMessage(error), 'error') }
+    finally { setClearingCache(undefined) }
+  }
+
   const commitMemory = () => void patch({ memoryMin: draft.memoryMin, memoryMax: draft.memoryMax })
   const commitWindow = () => void patch({ width: draft.width, height: draft.height })
 
@@ -1895,6 +3140,12 @@ function SettingsView({ settings, setSettings, update, version, clientVersion, d
           <div className="settings-title"><SlidersHorizontal /><div><h3>Performance</h3><p>Memory allocated to Minecraft</p></div></div>
           <div className="setting-row"><div><strong>Maximum memory</strong><small>{(draft.memoryMax / 1024).toFixed(1)} GB</small></div><input type="range" min="2048" max="32768" step="512" value={draft.memoryMax} onChange={(event) => setDraft({ ...draft, memoryMax: Number(event.target.value) })} onPointerUp={commitMemory} onKeyUp={commitMemory} /></div>
           <div className="setting-row"><div><strong>Minimum memory</strong><small>{(draft.memoryMin / 1024).toFixed(1)} GB</small></div><input type="range" min="512" max={Math.max(1024, draft.memoryMax - 512)} step="512" value={draft.memoryMin} onChange={(event) => setDraft({ ...draft, memoryMin: Number(event.target.value) })} onPointerUp={commitMemory} onKeyUp={commitMemory} /></div>
+          <div className="setting-row"><div><strong>Default mod loader</strong><small>Chosen first when you create a new instance.</small></div><div className="segmented">{(['vanilla', 'fabric', 'forge', 'neoforge'] as const).map((value) => <button key={value} className={draft.defaultLoader === value ? 'active' : ''} onClick={() => patch({ defaultLoader: value })}>{loaderLabel(value)}</button>)}</div></div>
+          <label className="wide-label">Default JVM arguments<input value={draft.defaultJvmArgs} onChange={(event) => setDraft({ ...draft, defaultJvmArgs: event.target.value })} onBlur={() => patch({ defaultJvmArgs: draft.defaultJvmArgs })} placeholder="-XX:+UseG1GC" spellCheck={false} /></label>
+          <label className="wide-label">Download speed limit (KB/s, 0 for unlimited)<input type="number" min="0" max="1000000" step="64" value={draft.downloadSpeedLimit} onChange={(event) => setDraft({ ...draft, downloadSpeedLimit: Number(event.target.value) })} onBlur={() => patch({ downloadSpeedLimit: draft.downloadSpeedLimit })} /></label>
+          <div className="setting-row"><div><strong>Parallel downloads</strong><small>{draft.downloadConcurrency} at once. Lower this on unreliable Wi-Fi.</small></div><input type="range" min="1" max="16" step="1" value={draft.downloadConcurrency} onChange={(event) => setDraft({ ...draft, downloadConcurrency: Number(event.target.value) })} onPointerUp={() => patch({ downloadConcurrency: draft.downloadConcurrency })} onKeyUp={() => patch({ downloadConcurrency: draft.downloadConcurrency })} /></div>
+          <div className="setting-row"><div><strong>Metered connection</strong><small>On a metered connection, MegaClient asks before downloading game assets or Java. Detect reads the connection type Windows reports.</small></div><div className="segmented">{([['auto', 'Detect'], ['metered', 'Metered'], ['unmetered', 'Unmetered']] as const).map(([value, label]) => <button key={value} className={draft.meteredConnection === value ? 'active' : ''} onClick={() => patch({ meteredConnection: value })}>{label}</button>)}</div></div>
+          <SettingToggle title="Prepare while idle" description="When MegaClient has had nothing to do for a few minutes, download the selected instance's missing game files and Java at low priority so Play starts sooner. Skipped on battery power." checked={draft.backgroundPrepare} onChange={(value) => patch({ backgroundPrepare: value })} />
           <div className="settings-hint"><Cpu size={14} /><span>4–8 GB is suitable for most modded instances. Excessive allocation can make Java pauses worse.</span></div>
         </section>
 
@@ -1910,7 +3161,7 @@ function SettingsView({ settings, setSettings, update, version, clientVersion, d
         <section className="settings-section">
           <div className="settings-title"><TerminalSquare /><div><h3>Launch behaviour</h3><p>Console and launcher visibility</p></div></div>
           <SettingToggle title="Open launch console" description="Show a separate live log window while Minecraft starts and runs." checked={draft.showConsole} onChange={(value) => patch({ showConsole: value })} />
-          <SettingToggle title="Move launcher to tray while playing" description="Hide MegaClient when Minecraft starts and restore it when the game closes." checked={draft.minimizeToTrayOnLaunch} onChange={(value) => patch({ minimizeToTrayOnLaunch: value })} />
+          <div className="setting-row"><div><strong>Launcher while playing</strong><small>What MegaClient does once Minecraft starts. It returns when the game closes; Close quits it once the game and its world backups have finished.</small></div><div className="segmented">{([['keep-open', 'Keep open'], ['minimize', 'Minimise'], ['tray', 'Tray'], ['close', 'Close']] as const).map(([value, label]) => <button key={value} className={draft.launcherVisibility === value ? 'active' : ''} onClick={() => patch({ launcherVisibility: value })}>{label}</button>)}</div></div>
           <SettingToggle title="Discord activity status" description={discordConfigured ? 'Show whether you are browsing, launching or playing through MegaClient.' : 'Requires a MegaClient Discord application ID in the release configuration.'} checked={draft.discordActivity} onChange={(value) => patch({ discordActivity: value })} />
           {!discordConfigured && <div className="settings-hint warning"><Info size={14} /><span>Discord activity is ready but not configured in this build. Add the application ID before publishing.</span></div>}
           <SettingToggle title="Reduce interface motion" description="Disable non-essential movement while keeping transitions responsive." checked={draft.reducedMotion} onChange={(value) => patch({ reducedMotion: value })} />
@@ -1920,7 +3171,12 @@ function SettingsView({ settings, setSettings, update, version, clientVersion, d
           <div className="settings-title"><RefreshCw /><div><h3>Updates & versions</h3><p>Keep MegaClient current</p></div></div>
           <div className="version-summary"><span><small>Launcher</small><strong>v{version}</strong></span><span><small>Built-in client</small><strong>v{clientVersion}</strong></span><span><small>Minecraft</small><strong>26.2</strong></span></div>
           <SettingToggle title="Automatic update checks" description="Scan on startup, every 20 minutes, after sleep and when you return to MegaClient. Updates download automatically." checked={draft.checkUpdates} onChange={(value) => patch({ checkUpdates: value })} />
+          <div className="setting-row"><div><strong>Mod and modpack updates</strong><small>{contentUpdates
+            ? `${contentUpdates.total ? `${contentUpdates.total} available` : 'Everything is current'} · checked ${new Date(contentUpdates.checkedAt).toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' })}`
+            : 'Check Modrinth content in every instance in the background. Nothing is installed until you choose to update.'}</small></div><div className="segmented">{([[0, 'Off'], [6, 'Every 6 hours'], [24, 'Daily']] as const).map(([value, label]) => <button key={value} className={draft.contentUpdateCheckHours === value ? 'active' : ''} onClick={() => patch({ contentUpdateCheckHours: value })}>{label}</button>)}<button disabled={checkingContent} onClick={() => void checkContent()}>{checkingContent ? 'Checking…' : 'Check now'}</button></div></div>
+          <SettingToggle title="Offline mode" description="Make no network requests. Installed instances launch as they are and lists use their saved copies." checked={draft.offlineMode} onChange={(value) => patch({ offlineMode: value })} />
           <SettingToggle title="Show snapshots" description="Include Minecraft snapshots in the instance version list." checked={draft.showSnapshots} onChange={(value) => patch({ showSnapshots: value })} />
+          <SettingToggle title="Show legacy versions" description="Include releases older than 1.8.9, plus classic alpha and beta builds." checked={draft.showLegacyVersions} onChange={(value) => patch({ showLegacyVersions: value })} />
           <div className="update-row update-status-row">
             <span>
               <strong>{update?.state === 'checking' ? 'Checking automatically…' : update?.state === 'downloading' ? `Downloading update · ${Math.round(update.percent ?? 0)}%${updateTransferLabel(update) ? ` · ${updateTransferLabel(update)}` : ''}` : update?.state === 'ready' ? `Version ${update.version} is ready` : update?.state === 'current' ? 'MegaClient is up to date' : update?.state === 'offline' ? 'Waiting for an internet connection' : update?.state === 'error' ? (update.message ?? 'The last update scan could not finish') : 'Automatic update scanning is ready'}</strong>
@@ -1932,6 +3188,48 @@ function SettingsView({ settings, setSettings, update, version, clientVersion, d
           </div>
         </section>
 
+        <section className="settings-section full">
+          <div className="settings-title"><Link2 /><div><h3>Modrinth account</h3><p>Optional. Lets you follow projects, see followed and your own projects in Discover, and raises Modrinth's request limit.</p></div></div>
+          {modrinth ? (
+            <div className="update-row update-status-row modrinth-account">
+              {modrinth.avatarUrl && <img src={modrinth.avatarUrl} alt="" />}
+              <span><strong>Linked as {modrinth.username}</strong><small>The token is stored encrypted on this computer.</small></span>
+              <button className="secondary" onClick={() => void unlinkModrinth()}><LogOut /> Unlink</button>
+            </div>
+          ) : (
+            <>
+              <label className="wide-label">Personal access token<input type="password" value={modrinthToken} onChange={(event) => setModrinthToken(event.target.value)} placeholder="mrp_…" spellCheck={false} autoComplete="off" /></label>
+              <div className="update-row update-status-row">
+                <span><strong>No account linked</strong><small>Create a token under Settings › PATs on modrinth.com with the read user, read projects and write user scopes.</small></span>
+                <button className="primary" disabled={linkingModrinth || !modrinthToken.trim()} onClick={() => void linkModrinth()}>{linkingModrinth ? <RefreshCw className="spin" /> : <Link2 />} Link account</button>
+              </div>
+            </>
+          )}
+        </section>
+
+        <section className="settings-section full">
+          <div className="settings-title"><Upload /><div><h3>Screenshot sharing</h3><p>Optional. Screenshots are posted to this image host from Manage › Screenshots, and the link it returns is copied.</p></div></div>
+          <label className="wide-label">Upload URL<input value={draft.screenshotUploadUrl} onChange={(event) => setDraft({ ...draft, screenshotUploadUrl: event.target.value })} onBlur={() => patch({ screenshotUploadUrl: draft.screenshotUploadUrl })} placeholder="https://example.com/api/upload" spellCheck={false} /></label>
+          <div className="screenshot-host-fields">
+            <label className="wide-label">File field<input value={draft.screenshotUploadField} onChange={(event) => setDraft({ ...draft, screenshotUploadField: event.target.value })} onBlur={() => patch({ screenshotUploadField: draft.screenshotUploadField })} placeholder="file" spellCheck={false} /></label>
+            <label className="wide-label">Link in response<input value={draft.screenshotUploadUrlPath} onChange={(event) => setDraft({ ...draft, screenshotUploadUrlPath: event.target.value })} onBlur={() => patch({ screenshotUploadUrlPath: draft.screenshotUploadUrlPath })} placeholder="data.link, or empty for a plain-text link" spellCheck={false} /></label>
+          </div>
+          {hasUploadKey ? (
+            <div className="update-row update-status-row">
+              <span><strong>Authorization header saved</strong><small>It is stored encrypted on this computer and sent with every upload.</small></span>
+              <button className="secondary" onClick={() => void saveUploadKey(null)}><X /> Remove</button>
+            </div>
+          ) : (
+            <>
+              <label className="wide-label">Authorization header<input type="password" value={uploadKey} onChange={(event) => setUploadKey(event.target.value)} placeholder="Client-ID …" spellCheck={false} autoComplete="off" /></label>
+              <div className="update-row update-status-row">
+                <span><strong>No authorization header</strong><small>Only needed by hosts that ask for a key or token with each upload.</small></span>
+                <button className="secondary" disabled={!uploadKey.trim()} onClick={() => void saveUploadKey(uploadKey)}><Lock /> Save</button>
+              </div>
+            </>
+          )}
+        </section>
+
         <section className="settings-section full security-locked compact-security">
           <div className="settings-title"><ShieldCheck /><div><h3>Launch protection</h3><p>High-confidence checks run automatically without blocking normal mods for compatibility references or addon filenames.</p></div><span className="always-on"><Lock size={12} /> Always on</span></div>
         </section>
@@ -1941,7 +3239,40 @@ function SettingsView({ settings, setSettings, update, version, clientVersion, d
           <div className="segmented java-mode"><button className={draft.javaMode === 'auto' ? 'active' : ''} onClick={() => patch({ javaMode: 'auto' })}>Automatic</button><button className={draft.javaMode === 'manual' ? 'active' : ''} onClick={() => patch({ javaMode: 'manual' })}>Custom path</button></div>
           {draft.javaMode === 'manual' && <label className="wide-label">Java executable path<input value={draft.javaPath} onChange={(event) => setDraft({ ...draft, javaPath: event.target.value })} onBlur={() => patch({ javaPath: draft.javaPath })} placeholder="C:\Program Files\Java\bin\javaw.exe" /></label>}
         </section>
+
+        <section className="settings-section full">
+          <div className="settings-title"><Trash2 /><div><h3>Unused game files</h3><p>Old versions, libraries, natives, Java runtimes and downloaded mods that no instance uses any more.</p></div></div>
+          <div className="update-row update-status-row">
+            <span>
+              <strong>{cleanupPreview ? (cleanupPreview.reclaimedBytes ? `${formatBytes(cleanupPreview.reclaimedBytes)} can be freed` : 'Nothing to clean up') : 'Worlds, mods and settings are never touched'}</strong>
+              <small>{cleanupPreview ? `${cleanupPreview.removed.length} unused items${cleanupPreview.skipped.length ? ` · ${cleanupPreview.skipped.join(', ')} skipped while in use` : ''}` : 'Scan first to see how much space can be reclaimed.'}</small>
+            </span>
+            <button className="secondary" disabled={cleaning} onClick={() => void scanCleanup()}><RefreshCw className={cleaning ? 'spin' : ''} /> Scan</button>
+            {cleanupPreview && cleanupPreview.reclaimedBytes > 0 && <button className="primary" disabled={cleaning} onClick={() => setConfirmCleanup(true)}><Trash2 /> Clean up</button>}
+          </div>
+        </section>
+        <section className="settings-section">
+          <div className="settings-title"><HardDrive /><div><h3>Cached data</h3><p>Copies kept to load faster and work offline. Clearing a cache also recovers from damaged cached data.</p></div></div>
+          {([
+            ['metadata', 'Version and mod metadata', 'Saved version lists, loader lists, Modrinth lookups and news. Offline mode relies on these.'],
+            ['avatars', 'Avatars and skins', 'Player heads, skin and cape images and the saved skin list.'],
+            ['server-icons', 'Server icons', 'Icons and status from recent server checks.'],
+            ['partial-downloads', 'Partial downloads', 'Unfinished downloads that would otherwise resume where they stopped.']
+          ] as const).map(([kind, title, description]) => (
+            <div key={kind} className="setting-row cache-row">
+              <div><strong>{title}</strong><small>{description}</small></div>
+              <span className="cache-size">{cacheSize ? formatBytes(cacheSize.kinds[kind]) : '…'}</span>
+              <button className="secondary" disabled={Boolean(clearingCache) || !cacheSize?.kinds[kind]} onClick={() => void clearCache([kind])}>{clearingCache === kind ? <RefreshCw className="spin" /> : <Trash2 />} Clear</button>
+            </div>
+          ))}
+          <div className="update-row update-status-row">
+            <span><strong>{cacheSize ? `${formatBytes(cacheSize.totalBytes)} cached` : 'Measuring caches…'}</strong><small>Everything cleared is downloaded again when it is next needed.</small></span>
+            <button className="secondary" disabled={Boolean(clearingCache)} onClick={() => void window.mega.app.cacheSize().then(setCacheSize)}><RefreshCw /> Refresh</button>
+            <button className="primary" disabled={Boolean(clearingCache) || !cacheSize?.totalBytes} onClick={() => void clearCache(['metadata', 'avatars', 'server-icons', 'partial-downloads'])}>{clearingCache === 'all' ? <RefreshCw className="spin" /> : <Trash2 />} Clear all</button>
+          </div>
+        </section>
       </div>
+      {confirmCleanup && cleanupPreview && <ConfirmDialog title="Remove unused game files?" message={`This frees about ${formatBytes(cleanupPreview.reclaimedBytes)}. Anything an instance needs again is downloaded on its next launch.`} confirmLabel="Clean up" onCancel={() => setConfirmCleanup(false)} onConfirm={runCleanup} />}
     </div>
   )
 }
@@ -1,5 +1,101 @@
 export {}
 
+type ConnectivityStatus = {
+  state: 'online' | 'unreachable' | 'offline-mode'
+  checkedAt?: string
+}
+
+type CacheKind = 'server-icons' | 'avatars' | 'metadata' | 'partial-downloads'
+
+type CacheSizeReport = {
+  kinds: Record<CacheKind, number>
+  totalBytes: number
+  measuredAt: string
+}
+
+type MeteredPrompt = {
+  id: string
+  instanceId?: string
+  name: string
+  what: string
+  bytes: number
+  closed?: boolean
+}
+
+type ProjectDetails = {
+  id: string
+  slug: string
+  title: string
+  description: string
+  body: string
+  projectType: 'mod' | 'modpack' | 'resourcepack' | 'shader'
+  iconUrl?: string
+  categories: string[]
+  downloads: number
+  followers: number
+  publishedAt?: string
+  updatedAt?: string
+  license?: { id: string; name: string; url?: string }
+  gallery: Array<{ url: string; title?: string; description?: string; featured: boolean }>
+  links: Array<{ kind: 'source' | 'issues' | 'wiki' | 'discord' | 'donation'; label: string; url: string }>
+  versions: Array<{ id: string; name: string; versionNumber: string; type: 'release' | 'beta' | 'alpha'; gameVersions: string[]; loaders: string[]; publishedAt: string; downloads: number; compatible?: boolean }>
+}
+
+type ContentUpdateSummary = {
+  checkedAt: string
+  total: number
+  instances: Array<{
+    instanceId: string
+    name: string
+    mods: Array<{ projectId: string; title: string; currentVersion?: string; latestVersion: string }>
+    modpack?: { projectId: string; title: string; currentVersionId: string; latestVersionId: string; latestVersion: string }
+  }>
+}
+
+type ModSet = {
+  id: string
+  name: string
+  enabled: string[]
+  createdAt: string
+  updatedAt: string
+}
+
+type ModrinthAccount = {
+  id: string
+  username: string
+  avatarUrl?: string
+}
+
+type ModrinthLibrary = Record<'followed' | 'owned', any[]>
+
+type WorldBackup = {
+  id: string
+  worldFolder: string
+  fileName: string
+  createdAt: string
+  sizeBytes: number
+  automatic: boolean
+}
+
+type ShaderLoaderRequirement = {
+  projectId?: string
+  title: string
+  reason: string
+}
+
+type DownloadItem = {
+  id: string
+  url: string
+  fileName: string
+  label?: string
+  status: 'queued' | 'downloading' | 'paused' | 'completed' | 'failed' | 'cancelled'
+  downloaded: number
+  total: number
+  error?: string
+  createdAt: string
+  updatedAt: string
+}
+
 declare global {
   interface Window {
     mega: {
@@ -10,8 +106,13 @@ declare global {
         rendererReady(): Promise<void>
         reload(): Promise<void>
         quit(): Promise<void>
-        getVersions(includeSnapshots: boolean): Promise<Array<{ id: string; type: string }>>
+        getVersions(includeSnapshots: boolean, includeLegacy?: boolean): Promise<Array<{ id: string; type: string }>>
         getLoaderVersions(loader: string, version: string): Promise<string[]>
+        getPatchNotes(version: string): Promise<{ version: string; title: string; type: string; date?: string; imageUrl?: string; summary?: string; body: string } | null>
+        getNews(): Promise<Array<{ id: string; title: string; tag?: string; date?: string; text?: string; imageDataUrl?: string }>>
+        cleanupGameFiles(dryRun: boolean): Promise<{ removed: Array<{ kind: 'version' | 'library' | 'natives' | 'runtime' | 'object'; path: string; bytes: number }>; reclaimedBytes: number; skipped: string[]; dryRun: boolean }>
+        cacheSize(): Promise<CacheSizeReport>
+        clearCache(kinds: CacheKind[]): Promise<{ clearedBytes: number; skipped: CacheKind[]; size: CacheSizeReport }>
         checkUpdates(): Promise<any>
         installUpdate(): Promise<void>
         onBootStatus(callback: (event: { value: number; message: string; detail?: string }) => void): () => void
@@ -29,46 +130,174 @@ declare global {
       }
       instances: {
         create(input: any): Promise<any>
+        chooseLinkedDirectory(): Promise<string | null>
         update(id: string, patch: any): Promise<any>
         delete(id: string): Promise<void>
         select(id: string): Promise<void>
+        getNotes(id: string): Promise<string>
+        setNotes(id: string, notes: string): Promise<string>
+        stats(id: string): Promise<{ lastPlayedAt?: string; lastExitedAt?: string; totalPlaytimeMs: number; launchCount: number }>
+        diskUsage(id: string): Promise<{ instanceId: string; totalBytes: number; sharedBytes: number; categories: Record<'worlds' | 'mods' | 'packs' | 'assets' | 'libraries' | 'versions' | 'logs' | 'other', number>; measuredAt: string }>
+        copyConfiguration(sourceId: string, targetId: string, items?: string[]): Promise<string[]>
+        importMultiMc(): Promise<any | null>
+        importMrpack(): Promise<any | null>
+        exportMrpack(id: string): Promise<{ file: string; linked: number; overrides: number } | null>
+        detectVanilla(): Promise<{ directory: string; profiles: Array<{ id: string; name: string; minecraftVersion?: string; loader: 'vanilla' | 'forge' | 'neoforge' | 'fabric'; loaderVersion?: string; lastUsed?: string; track?: 'release' | 'snapshot' }> } | null>
+        importVanilla(profileId?: string): Promise<any>
+        firstRunImport(): Promise<{ directory: string } | null>
+        detectCurseForge(): Promise<Array<{ folder: string; name: string; minecraftVersion: string; loader: 'vanilla' | 'forge' | 'neoforge' | 'fabric'; loaderVersion?: string; modCount: number }>>
+        importCurseForge(folder?: string): Promise<{ instance: any; skipped: Array<{ name: string; fileName: string; reason: string; pageUrl?: string }> } | null>
+        setSharedFolder(id: string, kind: 'saves' | 'resourcepacks', shared: boolean): Promise<any>
+        archive(id: string): Promise<any>
+        unarchive(id: string): Promise<any>
+        verify(id: string, repair?: boolean): Promise<{ instanceId: string; checkedFiles: number; issues: Array<{ kind: string; file: string; detail: string; repaired: boolean }>; checkedAt: string }>
+        setCustomVersion(id: string): Promise<any | null>
+        installVersionFromUrl(id: string, url: string): Promise<any>
+        clearCustomVersion(id: string): Promise<any>
+        versionProfiles(id: string): Promise<Array<{ id: string; inheritsFrom?: string; hasJar: boolean }>>
+        selectVersionProfile(id: string, versionId: string): Promise<any>
+        setVersionTracking(id: string, track: 'release' | 'snapshot' | null): Promise<any>
+        importVanillaProfiles(ids: string[], link?: boolean): Promise<any[]>
+        exportVanilla(ids?: string[]): Promise<number>
         openFolder(id: string): Promise<void>
         addLocalMod(id: string): Promise<number>
+        importLocalMod(id: string, file: File): Promise<{ fileName: string; title: string; version?: string; description?: string; modIds: string[]; loaders: string[]; warnings: string[] }>
         launch(id: string): Promise<boolean>
         launchServer(id: string, address: string): Promise<boolean>
-        openConsole(): Promise<void>
+        launchWorld(id: string, worldId: string): Promise<boolean>
+        launchSafeMode(id: string): Promise<boolean>
+        startBisect(id: string): Promise<{ candidates: string[]; testing: string[]; rounds: Array<{ testing: string[]; crashed: boolean; at: string }>; startedAt: string; culprit?: string }>
+        bisectStatus(id: string): Promise<{ candidates: string[]; testing: string[]; rounds: Array<{ testing: string[]; crashed: boolean; at: string }>; startedAt: string; culprit?: string } | null>
+        cancelBisect(id: string): Promise<void>
+        prepare(id: string): Promise<boolean>
+        cancelLaunch(id: string): Promise<boolean>
+        stop(id: string): Promise<boolean>
+        running(): Promise<any[]>
+        setRestartOnCrash(id: string, options: { maxRetries: number; withinSeconds: number } | null): Promise<any>
+        setWorldBackups(id: string, policy: { keepLast: number; keepDays: number } | null): Promise<any>
+        clearUnstable(id: string): Promise<any>
+        raiseMemory(id: string): Promise<any>
+        setPriority(id: string, priority: 'below-normal' | 'normal' | 'above-normal', affinity?: number[]): Promise<any>
+        consoleLines(id: string, since?: number): Promise<{ lines: Array<{ seq: number; line: string; kind: string }>; next: number; running: boolean }>
+        launchLogs(id: string): Promise<any[]>
+        readLaunchLog(id: string, name: string): Promise<string>
+        searchLog(id: string, options: any): Promise<any>
+        openConsole(id?: string): Promise<void>
       }
       mods: {
         search(input: any): Promise<any>
-        install(instanceId: string, projectId: string): Promise<any>
+        popular(input: { instanceId?: string; sort?: 'downloads' | 'follows' | 'updated'; limit?: number }): Promise<Record<'mod' | 'shader' | 'resourcepack', any[]>>
+        installCollection(instanceId: string, input: string): Promise<{ collectionId: string; name: string; results: Array<{ projectId: string; title: string; status: 'installed' | 'skipped' | 'failed'; detail?: string }> }>
+        installPlan(projectId: string, instanceId: string): Promise<{
+          projectId: string
+          instanceId: string
+          items: Array<{ projectId: string; versionId: string; title: string; versionNumber: string; fileName: string; size: number; contentType: string; requiredBy?: string; alreadyInstalled: boolean; replaces?: string[] }>
+          downloadBytes: number
+          problems: string[]
+        }>
+        project(idOrSlug: string, instanceId?: string): Promise<ProjectDetails>
+        install(instanceId: string, projectId: string, replace?: boolean): Promise<any>
         installModpack(instanceId: string, projectId: string): Promise<any>
         list(instanceId: string): Promise<any[]>
         setEnabled(instanceId: string, fileName: string, enabled: boolean): Promise<void>
         remove(instanceId: string, fileName: string): Promise<void>
         update(instanceId: string, projectId: string): Promise<any>
-        updateAll(instanceId: string): Promise<number>
+        updateAll(instanceId: string, projectIds?: string[]): Promise<{ instanceId: string; results: Array<{ projectId: string; title: string; status: 'updated' | 'current' | 'failed'; fromVersion?: string; toVersion?: string; error?: string }>; rollbackAvailable: boolean; finishedAt: string }>
+        rollbackUpdate(instanceId: string): Promise<number>
+        updateModpack(instanceId: string): Promise<{ instanceId: string; title: string; fromVersion?: string; toVersion: string; added: number; updated: number; removed: number; keptChanges: string[] }>
+        exportList(instanceId: string): Promise<{ file: string; mods: number; linked: number } | null>
+        importList(instanceId: string): Promise<{ name: string; results: Array<{ title: string; status: 'installed' | 'present' | 'skipped' | 'failed'; detail?: string }> } | null>
+        sets(instanceId: string): Promise<{ sets: ModSet[]; activeId?: string }>
+        saveSet(instanceId: string, name: string): Promise<ModSet>
+        deleteSet(instanceId: string, setId: string): Promise<void>
+        applySet(instanceId: string, setId: string): Promise<{ setId: string; enabled: number; disabled: number; missing: string[] }>
         onProgress(callback: (event: any) => void): () => void
       }
       packs: {
-        install(instanceId: string, projectId: string, type: 'resourcepack' | 'shader'): Promise<any>
+        install(instanceId: string, projectId: string, type: 'resourcepack' | 'shader', installShaderLoader?: boolean): Promise<
+          | { status: 'installed'; pack: any; shaderLoader?: { title: string } }
+          | { status: 'needs-shader-loader'; requirement: ShaderLoaderRequirement }
+        >
+        shaderLoader(instanceId: string): Promise<ShaderLoaderRequirement | null>
         list(instanceId: string, type?: 'resourcepack' | 'shader'): Promise<any[]>
         setEnabled(instanceId: string, fileName: string, type: 'resourcepack' | 'shader', enabled: boolean): Promise<void>
         remove(instanceId: string, fileName: string, type: 'resourcepack' | 'shader'): Promise<void>
+        addLocal(instanceId: string, type: 'resourcepack' | 'shader'): Promise<number>
         openFolder(instanceId: string, type: 'resourcepack' | 'shader'): Promise<void>
       }
+      downloads: {
+        list(): Promise<{ paused: boolean; items: DownloadItem[] }>
+        pause(): Promise<void>
+        resume(): Promise<void>
+        onEvent(callback: (event: DownloadItem) => void): () => void
+      }
+      network: {
+        status(): Promise<ConnectivityStatus>
+        probe(): Promise<ConnectivityStatus>
+        onStatus(callback: (status: ConnectivityStatus) => void): () => void
+        answerMetered(id: string, allowed: boolean): Promise<void>
+        onMeteredPrompt(callback: (prompt: MeteredPrompt) => void): () => void
+      }
+      updates: {
+        contentSummary(): Promise<ContentUpdateSummary | null>
+        checkContent(): Promise<ContentUpdateSummary>
+        onContent(callback: (summary: ContentUpdateSummary) => void): () => void
+      }
+      modrinth: {
+        account(): Promise<ModrinthAccount | null>
+        link(token: string): Promise<ModrinthAccount>
+        unlink(): Promise<void>
+        library(): Promise<ModrinthLibrary>
+        follow(projectId: string, followed: boolean): Promise<void>
+      }
       worlds: {
         list(instanceId: string): Promise<any[]>
         importZip(instanceId: string): Promise<any | null>
         download(instanceId: string, url: string): Promise<any>
         delete(instanceId: string, worldId: string): Promise<void>
         openFolder(instanceId: string, worldId?: string): Promise<void>
+        backups(instanceId: string, worldId?: string): Promise<WorldBackup[]>
+        backup(instanceId: string, worldId: string): Promise<WorldBackup>
+        restoreBackup(instanceId: string, backupId: string): Promise<any>
+        deleteBackup(instanceId: string, backupId: string): Promise<void>
+      }
+      screenshots: {
+        list(instanceId: string): Promise<Array<{ fileName: string; takenAt: string; sizeBytes: number; thumbnailUrl?: string }>>
+        copy(instanceId: string, fileName: string): Promise<void>
+        upload(instanceId: string, fileName: string): Promise<string>
+        show(instanceId: string, fileName: string): Promise<void>
+        openFolder(instanceId: string): Promise<void>
+        hasUploadKey(): Promise<boolean>
+        setUploadKey(value: string | null): Promise<void>
       }
       servers: { copyAddress(address: string): Promise<void>; status(address: string, force?: boolean): Promise<any> }
       settings: { update(patch: any): Promise<any> }
+      logging: { setLevel(level: 'error' | 'warn' | 'info' | 'debug'): Promise<string>; openFile(): Promise<void> }
       launchEvents: {
         onProgress(callback: (event: any) => void): () => void
+        onProgressEvent(callback: (event: {
+          instanceId: string
+          stage: string
+          message: string
+          percent?: number
+          bytes?: { current: number; total: number }
+          items?: { current: number; total: number }
+          speed?: number
+        }) => void): () => void
+        onStage(callback: (event: { instanceId: string; stage: string; previous?: string; at: string; stages: Array<{ stage: string; at: string }> }) => void): () => void
         onError(callback: (event: any) => void): () => void
         onWarning(callback: (event: any) => void): () => void
+        onVersion(callback: (event: { instanceId: string; track: 'release' | 'snapshot'; minecraftVersion: string; previous: string }) => void): () => void
+        onCancelled(callback: (event: { instanceId: string }) => void): () => void
+        onCrashed(callback: (event: { instanceId: string; code: number | null; report: any | null }) => void): () => void
+        onNativeCrash(callback: (event: any) => void): () => void
+        onBisect(callback: (event: { instanceId: string; candidates: string[]; testing: string[]; rounds: Array<{ testing: string[]; crashed: boolean; at: string }>; culprit?: string }) => void): () => void
+        onBisectOffer(callback: (event: { instanceId: string; crashes: number }) => void): () => void
+        onOutOfMemory(callback: (event: { instanceId: string; line: string; memoryMaxMb: number; suggestedMaxMb: number | null }) => void): () => void
+        onCrashLoop(callback: (event: { instanceId: string; since: string; crashes: number; summary?: string; code: number | null; report: any | null; explanations: Array<{ id: string; title: string; detail: string }> }) => void): () => void
+        onStats(callback: (event: { instanceId: string; memoryBytes: number; memoryMaxBytes: number; cpuPercent: number | null; uptimeSeconds: number }) => void): () => void
+        onRestarting(callback: (event: { instanceId: string; attempt: number; maxRetries: number; code: number | null }) => void): () => void
+        onExited(callback: (event: { instanceId: string; code: number | null; stopped: boolean; explanations: Array<{ id: string; title: string; detail: string }> }) => void): () => void
         onClosed(callback: (event: any) => void): () => void
       }
     }